@@ -121,6 +121,11 @@ class LoginResponse(BaseModel):
     token: str
 
 
+class WhoAmIResponse(BaseModel):
+    identity: str
+    scopes: list[str]
+
+
 # --- Health Check ---
 
 
@@ -149,6 +154,13 @@ async def login(request: LoginRequest):
     return LoginResponse(token=expected_token)
 
 
+@app.get("/api/auth/whoami", response_model=WhoAmIResponse)
+async def whoami(_: str = Depends(verify_token)):
+    """Describe the identity behind the bearer token."""
+    # A single shared API token with full access
+    return WhoAmIResponse(identity="api-token", scopes=["read", "write"])
+
+
 # --- Card Endpoints ---
 
 
@@ -224,7 +236,8 @@ async def update_card(
         updates["description"] = card_data.description
     if card_data.priority is not None:
         updates["priority"] = card_data.priority
-    if card_data.due_date is not None:
+    # An explicit null clears the due date; leaving the field out keeps it
+    if "due_date" in card_data.model_fields_set:
         updates["due_date"] = card_data.due_date
     if card_data.tags is not None:
         updates["tags"] = card_data.tags
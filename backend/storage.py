@@ -231,16 +231,16 @@ class Storage:
         card_data = data["cards"][card_id]
         old_column = card_data["column"]
         
+        # None clears a field; callers only pass the fields they mean to change
         for key, value in updates.items():
-            if value is not None:
-                if key == "priority" and isinstance(value, Priority):
-                    card_data[key] = value.value
-                elif key == "column" and isinstance(value, Column):
-                    card_data[key] = value.value
-                elif key == "due_date" and isinstance(value, datetime):
-                    card_data[key] = value.isoformat()
-                else:
-                    card_data[key] = value
+            if key == "priority" and isinstance(value, Priority):
+                card_data[key] = value.value
+            elif key == "column" and isinstance(value, Column):
+                card_data[key] = value.value
+            elif key == "due_date" and isinstance(value, datetime):
+                card_data[key] = value.isoformat()
+            else:
+                card_data[key] = value
         
         card_data["updated_at"] = now_utc().isoformat()
         
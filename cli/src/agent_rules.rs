@@ -0,0 +1,102 @@
+//! Setting `agent_assignable` by rule.
+//!
+//! `[agent_rules]` in config.toml (see README.md) names queries and the
+//! value cards matching them should have, e.g. cards tagged `good-for-agent`
+//! become assignable once they're in todo. Commands that create cards or
+//! change their column (`add`, `quick`, `move`, `edit --column`, `bot`)
+//! apply the rules to the cards they leave behind; `blaze maintenance
+//! agent-rules` catches up on the rest of the board (cards changed in the web
+//! UI, or before a rule existed). Cards no rule matches are left as they are.
+
+use crate::client::Client;
+use crate::config::AgentRule;
+use crate::error::{BlazeError, Result};
+use crate::output::short_id;
+use crate::parsers::Query;
+use crate::types::{Card, CardUpdate};
+use std::collections::BTreeMap;
+
+/// Compiled `[agent_rules]`, in name order
+#[derive(Debug, Default)]
+pub struct AgentRules(Vec<(String, Query, bool)>);
+
+impl AgentRules {
+    pub fn from_config(rules: &BTreeMap<String, AgentRule>) -> Result<Self> {
+        rules
+            .iter()
+            .map(|(name, rule)| {
+                let query = Query::parse(&rule.when)
+                    .map_err(|e| BlazeError::Config(format!("[agent_rules.{}] when: {}", name, e)))?;
+                Ok((name.clone(), query, rule.assignable))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The rule deciding `card`'s value and that value: the last match
+    pub fn decide(&self, card: &Card) -> Option<(&str, bool)> {
+        self.0
+            .iter()
+            .rev()
+            .find(|(_, query, _)| query.matches(card))
+            .map(|(name, _, assignable)| (name.as_str(), *assignable))
+    }
+
+    /// Like [`decide`](Self::decide), but only if the card has the other value
+    pub fn change(&self, card: &Card) -> Option<(&str, bool)> {
+        self.decide(card).filter(|(_, assignable)| *assignable != card.agent_assignable)
+    }
+
+    /// Bring one card in line with the rules; returns it as it is now
+    pub async fn apply(&self, client: &Client, card: Card) -> Result<Card> {
+        let Some((rule, assignable)) = self.change(&card) else {
+            return Ok(card);
+        };
+        eprintln!(
+            "{}: agent_assignable = {} (rule {})",
+            short_id(&card.id),
+            assignable,
+            rule
+        );
+        let update: CardUpdate = CardUpdate::builder().agent_assignable(Some(assignable)).build()?;
+        client.update_card(&card.id, &update).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::json;
+
+    fn card(column: &str, tags: &[&str], assignable: bool) -> Card {
+        testing::card("c1", json!({ "column": column, "tags": tags, "agent_assignable": assignable }))
+    }
+
+    #[test]
+    fn last_matching_rule_decides() {
+        let rule = |when: &str, assignable| AgentRule {
+            when: when.into(),
+            assignable,
+        };
+        let rules = AgentRules::from_config(&BTreeMap::from([
+            ("ready".to_string(), rule("tag:good-for-agent AND column:todo", true)),
+            ("zz-design".to_string(), rule("tag:needs-design", false)),
+        ]))
+        .unwrap();
+
+        assert_eq!(rules.change(&card("todo", &["good-for-agent"], false)), Some(("ready", true)));
+        assert_eq!(rules.change(&card("todo", &["good-for-agent"], true)), None);
+        // Still in the backlog: no rule applies, so nothing changes
+        assert_eq!(rules.decide(&card("backlog", &["good-for-agent"], false)), None);
+        let both = card("todo", &["good-for-agent", "needs-design"], true);
+        assert_eq!(rules.change(&both), Some(("zz-design", false)));
+
+        let bad = BTreeMap::from([("x".to_string(), rule("column:", true))]);
+        assert!(AgentRules::from_config(&bad).unwrap_err().to_string().contains("[agent_rules.x] when"));
+    }
+}
@@ -0,0 +1,561 @@
+//! Pluggable sources for the API token.
+//!
+//! The provider is selected by the `[auth]` section of config.toml:
+//!
+//! ```toml
+//! [auth]
+//! command = "pass show blaze/token"   # or: token = "...", file = "~/secret"
+//!
+//! [auth.oauth]                        # or: OAuth2 client credentials
+//! token_url = "https://id.example.com/oauth/token"
+//! client_id = "blaze-cli"
+//! client_secret_command = "pass show blaze/client-secret"
+//! ```
+//!
+//! Without a provider the token saved by `blaze login` is used. It lives in
+//! the OS keyring, or in ~/.config/blaze/token on machines without one:
+//!
+//! ```toml
+//! [auth]
+//! store = "file"
+//! ```
+//!
+//! `blaze login` also notes which server the token was given for. When
+//! `--url` or the profile later points elsewhere, the saved token is not sent
+//! there, so a typo'd or copied URL can't leak it to another host.
+
+use crate::cache::sha256_hex;
+use crate::client;
+use crate::config::{Config, ConnectionConfig};
+use crate::error::{BlazeError, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Command;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A source of API tokens
+pub trait AuthProvider: Send + Sync {
+    /// Short provider name for diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Resolve the token, or None if the source has nothing to offer
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>>;
+}
+
+/// Keyring entry holding the token saved by `blaze login`
+const KEYRING_SERVICE: &str = "blaze";
+const KEYRING_USER: &str = "api-token";
+
+/// Where `blaze login` keeps the token
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStore {
+    /// OS keychain / secret service
+    #[default]
+    Keyring,
+    /// ~/.config/blaze/token, for headless machines
+    File,
+}
+
+impl TokenStore {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Human-readable location for messages
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TokenStore::Keyring => "OS keyring",
+            TokenStore::File => "token file",
+        }
+    }
+
+    /// Save the token for `profile` (or the default server), replacing any previous one
+    pub async fn save(&self, token: &str, profile: Option<&str>) -> Result<()> {
+        let path = Config::token_path(profile).ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        match self {
+            TokenStore::Keyring => {
+                let token = token.to_string();
+                let user = keyring_user(profile);
+                blocking(move || keyring_entry(&user)?.set_password(&token)).await.map_err(|e| {
+                    BlazeError::Auth(format!(
+                        "Could not save to the OS keyring ({}); set store = \"file\" under [auth] in config.toml",
+                        e
+                    ))
+                })?;
+                // A plaintext copy left over from before would defeat the point
+                match fs::remove_file(&path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                    _ => Ok(()),
+                }
+            }
+            TokenStore::File => {
+                fs::create_dir_all(path.parent().unwrap())?;
+                write_private(&path, token)
+            }
+        }
+    }
+}
+
+/// Write a secret to a file only the owner can read
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        // A new file never exists with wider permissions
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // One that already existed may have them; tighten it before the secret goes in
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// The server a token saved by `blaze login` belongs to
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenBinding {
+    /// Origin of the server it was saved for (scheme://host[:port])
+    server: String,
+    /// SHA-256 of the token, so a token replaced by other means isn't held to a stale server
+    token_sha256: String,
+}
+
+/// Remember that `token` (saved for `profile`) belongs to the server at `url`
+pub fn bind_to_server(token: &str, url: &str, profile: Option<&str>) -> Result<()> {
+    let path = binding_path(profile).ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+    let binding = TokenBinding {
+        server: origin(url),
+        token_sha256: sha256_hex(token.as_bytes()),
+    };
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, serde_json::to_string_pretty(&binding)?)?;
+    Ok(())
+}
+
+/// The server `token` was saved for, if that isn't the one at `url`
+pub fn saved_for_other_server(token: &str, url: &str, profile: Option<&str>) -> Option<String> {
+    let content = fs::read_to_string(binding_path(profile)?).ok()?;
+    let binding: TokenBinding = serde_json::from_str(&content).ok()?;
+    (binding.token_sha256 == sha256_hex(token.as_bytes()) && binding.server != origin(url)).then_some(binding.server)
+}
+
+fn binding_path(profile: Option<&str>) -> Option<PathBuf> {
+    Config::dir().map(|d| d.join(profile_file_name("token-server", "json", profile)))
+}
+
+/// `https://host:443/api` and `https://host` are the same server
+pub fn origin(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) => parsed.origin().ascii_serialization(),
+        Err(_) => url.trim_end_matches('/').to_string(),
+    }
+}
+
+/// `[auth]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Where the token saved by `blaze login` lives
+    #[serde(default, skip_serializing_if = "TokenStore::is_default")]
+    pub store: TokenStore,
+    /// Token stored inline in the config file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Path to a file containing the token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Shell command printing the token on stdout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// OAuth2 client credentials flow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuthConfig>,
+}
+
+impl AuthConfig {
+    pub fn is_empty(&self) -> bool {
+        self.store.is_default() && !self.has_provider()
+    }
+
+    /// Whether a provider other than the saved login token is configured
+    pub fn has_provider(&self) -> bool {
+        self.token.is_some() || self.file.is_some() || self.command.is_some() || self.oauth.is_some()
+    }
+}
+
+/// `[auth.oauth]` config section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    /// Shell command printing the client secret (preferred over `client_secret`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Cache file stems for OAuth access tokens, one per API the token is for
+const OAUTH_CACHE: &str = "oauth-token";
+const GITHUB_OAUTH_CACHE: &str = "github-oauth-token";
+
+/// Build the provider selected by the config; saved tokens and caches are kept per profile
+pub fn provider(cfg: &AuthConfig, profile: Option<&str>, connection: &ConnectionConfig) -> Result<Box<dyn AuthProvider>> {
+    provider_caching_as(cfg, OAUTH_CACHE, profile, connection)
+}
+
+/// Build the `[github.auth]` provider. Its OAuth token is cached apart from the Blaze one,
+/// so neither is ever sent to the other API.
+pub fn github_provider(cfg: &AuthConfig, connection: &ConnectionConfig) -> Result<Box<dyn AuthProvider>> {
+    provider_caching_as(cfg, GITHUB_OAUTH_CACHE, None, connection)
+}
+
+fn provider_caching_as(
+    cfg: &AuthConfig,
+    oauth_cache: &str,
+    profile: Option<&str>,
+    connection: &ConnectionConfig,
+) -> Result<Box<dyn AuthProvider>> {
+    let selected = [
+        cfg.token.is_some(),
+        cfg.file.is_some(),
+        cfg.command.is_some(),
+        cfg.oauth.is_some(),
+    ]
+    .iter()
+    .filter(|s| **s)
+    .count();
+
+    if selected > 1 {
+        return Err(BlazeError::Config(
+            "[auth] must set only one of token, file, command or oauth".into(),
+        ));
+    }
+
+    if let Some(ref token) = cfg.token {
+        return Ok(Box::new(StaticToken(token.clone())));
+    }
+    if let Some(ref file) = cfg.file {
+        return Ok(Box::new(TokenFile(expand_home(file))));
+    }
+    if let Some(ref command) = cfg.command {
+        return Ok(Box::new(CommandToken(command.clone())));
+    }
+    if let Some(ref oauth) = cfg.oauth {
+        let http = client::outside_client(connection)?;
+        return Ok(Box::new(OAuthToken::new(oauth, http, Config::dir().as_deref(), oauth_cache, profile)));
+    }
+
+    let path = Config::token_path(profile)
+        .ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+    match cfg.store {
+        TokenStore::Keyring => Ok(Box::new(KeyringToken {
+            user: keyring_user(profile),
+            fallback: TokenFile(path),
+        })),
+        TokenStore::File => Ok(Box::new(TokenFile(path))),
+    }
+}
+
+/// Token given directly in config
+pub struct StaticToken(pub String);
+
+impl AuthProvider for StaticToken {
+    fn name(&self) -> &'static str {
+        "token"
+    }
+
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move { Ok(Some(self.0.clone())) })
+    }
+}
+
+/// Token read from a file (the default is ~/.config/blaze/token)
+pub struct TokenFile(pub PathBuf);
+
+impl AuthProvider for TokenFile {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move {
+            if !self.0.exists() {
+                return Ok(None);
+            }
+
+            let token = fs::read_to_string(&self.0)
+                .map_err(|e| BlazeError::Config(format!("Failed to read token: {}", e)))?;
+            Ok(non_empty(token))
+        })
+    }
+}
+
+/// Token saved in the OS keyring, falling back to a token file saved before keyring support
+pub struct KeyringToken {
+    pub user: String,
+    pub fallback: TokenFile,
+}
+
+impl AuthProvider for KeyringToken {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move {
+            let user = self.user.clone();
+            match blocking(move || keyring_entry(&user)?.get_password()).await {
+                Ok(token) => Ok(non_empty(token)),
+                // No entry, or no keyring on this machine
+                Err(_) => self.fallback.token().await,
+            }
+        })
+    }
+}
+
+/// Token printed by a shell command (e.g. a password manager)
+pub struct CommandToken(pub String);
+
+impl AuthProvider for CommandToken {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move { run_secret_command(&self.0).map(non_empty) })
+    }
+}
+
+/// Access token obtained with the OAuth2 client credentials grant, cached until expiry
+pub struct OAuthToken {
+    pub config: OAuthConfig,
+    /// Client for the token endpoint, with the configured proxy and CA
+    pub http: reqwest::Client,
+    pub cache: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedToken {
+    access_token: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+impl OAuthToken {
+    /// Provider caching its token in `dir` under `stem`
+    fn new(config: &OAuthConfig, http: reqwest::Client, dir: Option<&Path>, stem: &str, profile: Option<&str>) -> Self {
+        Self {
+            config: config.clone(),
+            http,
+            cache: dir.map(|d| d.join(profile_file_name(stem, "json", profile))),
+        }
+    }
+
+    fn cached(&self) -> Option<String> {
+        let content = fs::read_to_string(self.cache.as_ref()?).ok()?;
+        let cached: CachedToken = serde_json::from_str(&content).ok()?;
+        // Refresh a little early so the token doesn't expire mid-command
+        (cached.expires_at > Utc::now() + Duration::seconds(30)).then_some(cached.access_token)
+    }
+
+    async fn fetch(&self) -> Result<String> {
+        let cfg = &self.config;
+        let secret = match (&cfg.client_secret_command, &cfg.client_secret) {
+            (Some(cmd), _) => run_secret_command(cmd)?,
+            (None, Some(secret)) => secret.clone(),
+            (None, None) => {
+                return Err(BlazeError::Config(
+                    "[auth.oauth] needs client_secret or client_secret_command".into(),
+                ))
+            }
+        };
+
+        let mut form = vec![
+            ("grant_type", "client_credentials".to_string()),
+            ("client_id", cfg.client_id.clone()),
+            ("client_secret", secret),
+        ];
+        if let Some(ref scope) = cfg.scope {
+            form.push(("scope", scope.clone()));
+        }
+
+        let resp = self
+            .http
+            .post(&cfg.token_url)
+            .form(&form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status().as_u16();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(BlazeError::Auth(format!("OAuth token request failed ({}): {}", status, text)));
+        }
+
+        let token: TokenResponse = resp.json().await?;
+        self.store(&CachedToken {
+            access_token: token.access_token.clone(),
+            expires_at: Utc::now() + Duration::seconds(token.expires_in.unwrap_or(3600)),
+        });
+
+        Ok(token.access_token)
+    }
+
+    /// Cache the token, readable only by you; best-effort, as a failure only costs a new
+    /// token request next time
+    fn store(&self, cached: &CachedToken) {
+        if let (Some(path), Ok(json)) = (&self.cache, serde_json::to_string(cached)) {
+            let _ = write_private(path, &json);
+        }
+    }
+}
+
+impl AuthProvider for OAuthToken {
+    fn name(&self) -> &'static str {
+        "oauth"
+    }
+
+    fn token(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        Box::pin(async move {
+            if let Some(token) = self.cached() {
+                return Ok(Some(token));
+            }
+            self.fetch().await.map(Some)
+        })
+    }
+}
+
+/// Run a shell command and return its trimmed stdout
+fn run_secret_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| BlazeError::Auth(format!("Failed to run '{}': {}", command, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut message = format!("'{}' exited with {}", command, output.status);
+        if !stderr.trim().is_empty() {
+            message.push_str(&format!(": {}", stderr.trim()));
+        }
+        return Err(BlazeError::Auth(message));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn keyring_entry(user: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, user)
+}
+
+fn keyring_user(profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("{}:{}", KEYRING_USER, name),
+        None => KEYRING_USER.to_string(),
+    }
+}
+
+/// `stem.ext`, or `stem-<profile>.ext` for a named profile
+fn profile_file_name(stem: &str, ext: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("{}-{}.{}", stem, name, ext),
+        None => format!("{}.{}", stem, ext),
+    }
+}
+
+/// Keyring backends block (and may run their own event loop), so keep them off the runtime
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> keyring::Result<T> + Send + 'static,
+) -> std::result::Result<T, String> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn non_empty(token: String) -> Option<String> {
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_ignore_default_ports_and_paths() {
+        assert_eq!(origin("https://blaze.example.com:443/api/"), "https://blaze.example.com");
+        assert_eq!(origin("http://127.0.0.1:8080"), "http://127.0.0.1:8080");
+        assert_ne!(origin("http://localhost:8080"), origin("http://127.0.0.1:8080"));
+        assert_ne!(origin("http://blaze.example.com"), origin("https://blaze.example.com"));
+    }
+
+    #[test]
+    fn github_and_blaze_oauth_tokens_are_cached_apart() {
+        let dir = std::env::temp_dir().join(format!("blaze-oauth-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = OAuthConfig {
+            token_url: "https://id.example.com/oauth/token".into(),
+            client_id: "blaze-cli".into(),
+            client_secret: Some("secret".into()),
+            client_secret_command: None,
+            scope: None,
+        };
+        let http = reqwest::Client::new();
+        let blaze = OAuthToken::new(&config, http.clone(), Some(&dir), OAUTH_CACHE, None);
+        let github = OAuthToken::new(&config, http, Some(&dir), GITHUB_OAUTH_CACHE, None);
+
+        blaze.store(&CachedToken {
+            access_token: "blaze-token".into(),
+            expires_at: Utc::now() + Duration::hours(1),
+        });
+        assert_eq!(blaze.cached().as_deref(), Some("blaze-token"));
+        assert_eq!(github.cached(), None);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(blaze.cache.as_ref().unwrap()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        github.store(&CachedToken {
+            access_token: "github-token".into(),
+            expires_at: Utc::now() + Duration::hours(1),
+        });
+        assert_eq!(blaze.cached().as_deref(), Some("blaze-token"));
+        assert_eq!(github.cached().as_deref(), Some("github-token"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
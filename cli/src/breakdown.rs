@@ -0,0 +1,184 @@
+//! Task lists in plan files, for `blaze plan breakdown`.
+//!
+//! Every Markdown task item (`- [ ] Title`, also `*`/`+` and nested) is a
+//! task. A trailing `(after: Other task; Third task)` names tasks that must be
+//! done first, by title (case-insensitive; `,` also separates). Checked items
+//! (`- [x]`) are finished: they get no card and don't hold anything back.
+
+use crate::deps;
+use crate::error::{BlazeError, Result};
+use std::collections::HashMap;
+
+/// One task item from a plan file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub title: String,
+    pub done: bool,
+    /// Titles from the `(after: ...)` annotation
+    pub after: Vec<String>,
+    /// File and line, for messages
+    pub source: String,
+}
+
+/// Open tasks in creation order, each with the positions (in that order) of what it waits for
+#[derive(Debug, PartialEq, Eq)]
+pub struct TaskGraph {
+    pub tasks: Vec<Task>,
+    pub blocked_by: Vec<Vec<usize>>,
+}
+
+/// Collect the task items of one file
+pub fn parse_tasks(filename: &str, content: &str) -> Vec<Task> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(n, line)| {
+            let item = line.trim_start().strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?.trim_start();
+            let (done, text) = match item.get(..3)? {
+                "[ ]" => (false, &item[3..]),
+                "[x]" | "[X]" => (true, &item[3..]),
+                _ => return None,
+            };
+            let (title, after) = split_after(text.trim());
+            (!title.is_empty()).then(|| Task {
+                title,
+                done,
+                after,
+                source: format!("{}:{}", filename, n + 1),
+            })
+        })
+        .collect()
+}
+
+/// Split a trailing `(after: a; b)` off a task's text
+fn split_after(text: &str) -> (String, Vec<String>) {
+    let annotation = text.strip_suffix(')').and_then(|rest| {
+        let open = rest.rfind('(')?;
+        let inner = rest[open + 1..].trim();
+        let names = inner.get(..6).filter(|k| k.eq_ignore_ascii_case("after:")).map(|_| &inner[6..])?;
+        Some((rest[..open].trim_end(), names))
+    });
+    match annotation {
+        Some((title, names)) => {
+            let after = names.split([';', ',']).map(str::trim).filter(|n| !n.is_empty()).map(String::from).collect();
+            (title.to_string(), after)
+        }
+        None => (text.to_string(), Vec::new()),
+    }
+}
+
+/// Resolve `after:` titles and order the open tasks so blockers come first.
+///
+/// Fails on unknown or ambiguous titles and on cycles, so nothing is created
+/// from a plan that can't be carried out.
+pub fn plan_graph(tasks: Vec<Task>) -> Result<TaskGraph> {
+    let mut by_title: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, task) in tasks.iter().enumerate() {
+        by_title.entry(task.title.to_lowercase()).or_default().push(i);
+    }
+
+    let open: Vec<usize> = (0..tasks.len()).filter(|&i| !tasks[i].done).collect();
+    let position: HashMap<usize, usize> = open.iter().enumerate().map(|(pos, &i)| (i, pos)).collect();
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); open.len()];
+    for (pos, &i) in open.iter().enumerate() {
+        for name in &tasks[i].after {
+            let blocker = match by_title.get(&name.to_lowercase()).map(Vec::as_slice) {
+                Some([only]) => *only,
+                Some(_) => {
+                    return Err(BlazeError::InvalidInput(format!(
+                        "{}: more than one task is called '{}'",
+                        tasks[i].source, name
+                    )))
+                }
+                None => {
+                    return Err(BlazeError::InvalidInput(format!(
+                        "{}: no task called '{}'",
+                        tasks[i].source, name
+                    )))
+                }
+            };
+            // Finished tasks don't hold anything back
+            if let Some(&p) = position.get(&blocker) {
+                if !preds[pos].contains(&p) {
+                    preds[pos].push(p);
+                }
+            }
+        }
+    }
+
+    let mut succs: Vec<Vec<usize>> = vec![Vec::new(); open.len()];
+    for (pos, ps) in preds.iter().enumerate() {
+        for &p in ps {
+            succs[p].push(pos);
+        }
+    }
+    deps::topological_order(&preds, &succs).map_err(|cycle| {
+        let titles: Vec<&str> = cycle.iter().map(|&pos| tasks[open[pos]].title.as_str()).collect();
+        BlazeError::InvalidInput(format!("Dependency cycle: {}", titles.join(" -> ")))
+    })?;
+    // Any topological order would do; keep file order where dependencies allow
+    let order = stable_order(&preds);
+
+    let rank: HashMap<usize, usize> = order.iter().enumerate().map(|(r, &pos)| (pos, r)).collect();
+    let mut ordered_tasks = Vec::with_capacity(order.len());
+    let mut blocked_by = Vec::with_capacity(order.len());
+    for &pos in &order {
+        ordered_tasks.push(tasks[open[pos]].clone());
+        blocked_by.push(preds[pos].iter().map(|p| rank[p]).collect());
+    }
+    Ok(TaskGraph {
+        tasks: ordered_tasks,
+        blocked_by,
+    })
+}
+
+/// Topological order of an acyclic graph that takes the earliest ready node each time
+fn stable_order(preds: &[Vec<usize>]) -> Vec<usize> {
+    let len = preds.len();
+    let mut placed = vec![false; len];
+    let mut order = Vec::with_capacity(len);
+    while order.len() < len {
+        let next = (0..len)
+            .find(|&i| !placed[i] && preds[i].iter().all(|&p| placed[p]))
+            .expect("graph is acyclic");
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "# Rollout\n\
+        - [ ] Deploy (after: Write migration; review schema)\n\
+        - [x] Review schema\n\
+        * [ ] Write migration\n  \
+          - [ ] Announce (after: Deploy)\n\
+        - not a task\n";
+
+    #[test]
+    fn parses_items_and_annotations() {
+        let tasks = parse_tasks("rollout.md", PLAN);
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(tasks[0].title, "Deploy");
+        assert_eq!(tasks[0].after, ["Write migration", "review schema"]);
+        assert!(tasks[1].done && tasks[1].after.is_empty());
+        assert_eq!(tasks[3].source, "rollout.md:5");
+        assert_eq!(split_after("Fix (the) bug").0, "Fix (the) bug");
+    }
+
+    #[test]
+    fn orders_blockers_first_and_rejects_cycles() {
+        let graph = plan_graph(parse_tasks("rollout.md", PLAN)).unwrap();
+        let titles: Vec<&str> = graph.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Write migration", "Deploy", "Announce"]);
+        assert_eq!(graph.blocked_by, [vec![], vec![0], vec![1]]);
+
+        let cycle = parse_tasks("a.md", "- [ ] A (after: B)\n- [ ] B (after: A)\n");
+        assert!(plan_graph(cycle).unwrap_err().to_string().contains("Dependency cycle"));
+        let unknown = parse_tasks("a.md", "- [ ] A (after: C)\n");
+        assert_eq!(plan_graph(unknown).unwrap_err().to_string(), "Invalid input: a.md:1: no task called 'C'");
+    }
+}
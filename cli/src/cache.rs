@@ -0,0 +1,204 @@
+//! Local cache for downloaded files.
+//!
+//! Files fetched for `open` commands live under ~/.cache/blaze/files/ and are
+//! recorded in ~/.cache/blaze/index.json so `blaze cache clear` can remove
+//! exactly what blaze wrote.
+//!
+//! Every download is also kept once in a content-addressed blob store
+//! (~/.cache/blaze/blobs/<sha256>), shared by plan files and attachments:
+//! content whose hash is already known is read from there instead of being
+//! fetched again, and unchanged content is never written twice.
+
+use crate::error::{BlazeError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A cached download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub path: PathBuf,
+    /// What was downloaded, e.g. `plan:<id>/<file>`
+    pub source: String,
+    pub bytes: u64,
+    pub fetched_at: DateTime<Utc>,
+    /// Hash of the content, naming its blob
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Totals from `cache clear`
+#[derive(Debug, Default, Serialize)]
+pub struct ClearSummary {
+    pub removed: usize,
+    pub bytes: u64,
+}
+
+/// Get the cache directory path (~/.cache/blaze/)
+pub fn dir() -> Result<PathBuf> {
+    dirs::cache_dir()
+        .map(|d| d.join("blaze"))
+        .ok_or_else(|| BlazeError::Config("No cache directory".into()))
+}
+
+fn index_path() -> Result<PathBuf> {
+    Ok(dir()?.join("index.json"))
+}
+
+/// Tracked cache entries
+pub fn entries() -> Result<Vec<CacheEntry>> {
+    let path = index_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_entries(entries: &[CacheEntry]) -> Result<()> {
+    fs::create_dir_all(dir()?)?;
+    fs::write(index_path()?, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+/// Write `content` to the cache at `parts` (joined as path components) and track it
+pub fn store(parts: &[&str], source: &str, content: &[u8]) -> Result<PathBuf> {
+    let mut path = dir()?.join("files");
+    for part in parts {
+        path.push(safe_component(part));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let sha256 = put_blob(content)?;
+    // Leave an unchanged copy alone (an open editor may be holding it)
+    if fs::read(&path).map(|old| sha256_hex(&old)).ok().as_deref() != Some(sha256.as_str()) {
+        fs::write(&path, content)?;
+    }
+
+    let mut index = entries()?;
+    index.retain(|e| e.path != path);
+    index.push(CacheEntry {
+        path: path.clone(),
+        source: source.to_string(),
+        bytes: content.len() as u64,
+        fetched_at: Utc::now(),
+        sha256: Some(sha256),
+    });
+    save_entries(&index)?;
+    Ok(path)
+}
+
+/// Hex SHA-256 of some content, the key of its blob
+pub fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn blobs_dir() -> Result<PathBuf> {
+    Ok(dir()?.join("blobs"))
+}
+
+fn blob_path(sha256: &str) -> Result<PathBuf> {
+    if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(BlazeError::InvalidInput(format!("Invalid SHA-256 '{}'", sha256)));
+    }
+    let sha256 = sha256.to_ascii_lowercase();
+    Ok(blobs_dir()?.join(&sha256[..2]).join(sha256))
+}
+
+/// Keep `content` in the blob store (once) and return its hash
+pub fn put_blob(content: &[u8]) -> Result<String> {
+    let sha256 = sha256_hex(content);
+    let path = blob_path(&sha256)?;
+    if !path.exists() {
+        let parent = path.parent().expect("blob paths have a parent");
+        fs::create_dir_all(parent)?;
+        // Write under a temporary name so a crash never leaves a truncated blob
+        let partial = parent.join(format!("{}.partial", sha256));
+        fs::write(&partial, content)?;
+        fs::rename(&partial, &path)?;
+    }
+    Ok(sha256)
+}
+
+/// Content with this hash, if it's in the blob store and intact
+pub fn get_blob(sha256: &str) -> Result<Option<Vec<u8>>> {
+    let path = blob_path(sha256)?;
+    let content = match fs::read(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if sha256_hex(&content) != sha256.to_ascii_lowercase() {
+        // Corrupted; drop it so the next download replaces it
+        fs::remove_file(&path)?;
+        return Ok(None);
+    }
+    Ok(Some(content))
+}
+
+/// The content last cached for `source`, read from its blob without a download
+pub fn cached(source: &str) -> Result<Option<Vec<u8>>> {
+    let entry = entries()?.into_iter().rev().find(|e| e.source == source);
+    match entry.and_then(|e| e.sha256) {
+        Some(sha256) => get_blob(&sha256),
+        None => Ok(None),
+    }
+}
+
+/// Remove every tracked file
+pub fn clear() -> Result<ClearSummary> {
+    let mut summary = ClearSummary::default();
+    for entry in entries()? {
+        match fs::remove_file(&entry.path) {
+            Ok(()) => {
+                summary.removed += 1;
+                summary.bytes += entry.bytes;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        remove_empty_parents(&entry.path);
+    }
+    save_entries(&[])?;
+
+    let blobs = blobs_dir()?;
+    if blobs.exists() {
+        for shard in fs::read_dir(&blobs)? {
+            for blob in fs::read_dir(shard?.path())? {
+                let blob = blob?;
+                summary.bytes += blob.metadata()?.len();
+                fs::remove_file(blob.path())?;
+            }
+        }
+        fs::remove_dir_all(&blobs)?;
+    }
+    Ok(summary)
+}
+
+/// Clean up directories left empty inside the cache
+fn remove_empty_parents(path: &Path) {
+    let Ok(root) = dir() else { return };
+    let mut current = path.parent();
+    while let Some(d) = current {
+        if d == root || !d.starts_with(&root) || fs::remove_dir(d).is_err() {
+            break;
+        }
+        current = d.parent();
+    }
+}
+
+/// Make a server-provided name safe to use as a single path component
+fn safe_component(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c == '/' || c == '\\' || c == ':' || c.is_control() { '_' } else { c })
+        .collect();
+    match cleaned.trim_start_matches('.') {
+        "" => "_".to_string(),
+        _ if cleaned.starts_with('.') => format!("_{}", cleaned),
+        _ => cleaned,
+    }
+}
@@ -0,0 +1,119 @@
+//! Optional server features and how to tell whether a server has them.
+//!
+//! Cards, the board and auth are always there; agent workflow, archiving,
+//! comments, review threads, attachments, events, shared views and plans may
+//! be missing on older or trimmed-down servers. A 404 from one of their routes
+//! is ambiguous (no such card, or no such route), so the client then sends
+//! one `OPTIONS` to the route with a placeholder ID: a server without the
+//! route answers 404 again, one with it answers 405 or 2xx. The answer is
+//! kept for the rest of the session (the command, or the whole REPL), and
+//! later calls fail right away with [`BlazeError::Unsupported`] instead of a
+//! raw HTTP error.
+
+use crate::error::BlazeError;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Agent,
+    Archive,
+    Comments,
+    Threads,
+    Attachments,
+    Events,
+    Views,
+    Plans,
+}
+
+impl Capability {
+    pub const ALL: &'static [Capability] = &[
+        Capability::Agent,
+        Capability::Archive,
+        Capability::Comments,
+        Capability::Threads,
+        Capability::Attachments,
+        Capability::Events,
+        Capability::Views,
+        Capability::Plans,
+    ];
+
+    /// What the feature is called in messages
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Agent => "agent workflow",
+            Capability::Archive => "archiving",
+            Capability::Comments => "comments",
+            Capability::Threads => "review threads",
+            Capability::Attachments => "attachments",
+            Capability::Events => "events",
+            Capability::Views => "shared views",
+            Capability::Plans => "plans",
+        }
+    }
+
+    /// A route that exists exactly when the feature does
+    pub fn endpoint(self) -> &'static str {
+        match self {
+            Capability::Agent => "/api/agent/ready",
+            Capability::Archive => "/api/cards/{id}/archive",
+            Capability::Comments => "/api/cards/{id}/comments",
+            Capability::Threads => "/api/cards/{id}/threads",
+            Capability::Attachments => "/api/cards/{id}/attachments",
+            Capability::Events => "/api/events",
+            Capability::Views => "/api/views",
+            Capability::Plans => "/api/plans",
+        }
+    }
+
+    /// The endpoint with a placeholder ID, for the `OPTIONS` probe
+    pub fn probe_path(self) -> String {
+        self.endpoint().replace("{id}", "_")
+    }
+
+    /// The optional feature a request path belongs to, if any
+    pub fn of_path(path: &str) -> Option<Self> {
+        let path = path.split('?').next().unwrap_or(path);
+        let segments: Vec<&str> = path.trim_start_matches("/api/").split('/').collect();
+        match segments.as_slice() {
+            ["agent", ..] | ["cards", _, "agent-progress" | "agent-status" | "criteria", ..] => Some(Capability::Agent),
+            ["cards", _, "archive" | "unarchive"] | ["columns", _, "archive"] => Some(Capability::Archive),
+            ["cards", _, "comments", ..] => Some(Capability::Comments),
+            ["cards", _, "threads", ..] => Some(Capability::Threads),
+            ["cards", _, "attachments", ..] => Some(Capability::Attachments),
+            ["events", ..] => Some(Capability::Events),
+            ["views", ..] => Some(Capability::Views),
+            ["plans", ..] => Some(Capability::Plans),
+            _ => None,
+        }
+    }
+
+    /// The error for a server without this feature
+    pub fn unsupported(self) -> BlazeError {
+        BlazeError::Unsupported {
+            feature: self.name(),
+            endpoint: self.endpoint(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_paths_map_to_features() {
+        assert_eq!(Capability::of_path("/api/cards/abc/comments/m1"), Some(Capability::Comments));
+        assert_eq!(Capability::of_path("/api/cards/abc/unarchive"), Some(Capability::Archive));
+        assert_eq!(Capability::of_path("/api/cards/abc/threads/t1/replies"), Some(Capability::Threads));
+        assert_eq!(Capability::of_path("/api/cards/abc/criteria/0/check"), Some(Capability::Agent));
+        assert_eq!(Capability::of_path("/api/events?card_id=abc"), Some(Capability::Events));
+        assert_eq!(Capability::of_path("/api/plans?status=draft"), Some(Capability::Plans));
+        assert_eq!(Capability::of_path("/api/cards/abc"), None);
+        assert_eq!(Capability::of_path("/api/cards/abc/move"), None);
+        assert_eq!(Capability::of_path("/api/board/stats"), None);
+        for capability in Capability::ALL {
+            assert_eq!(Capability::of_path(&capability.probe_path()), Some(*capability));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Recording and replaying API exchanges.
+//!
+//! `--record <dir>` writes every request/response pair to numbered JSON files
+//! (`0001.json`, `0002.json`, ...) with credentials redacted. `--replay <dir>`
+//! answers requests from those files without touching the network, so a
+//! recorded session can be attached to a bug report and re-run offline.
+
+use crate::daemon::{RawRequest, RawResponse};
+use crate::error::{BlazeError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// A recorded request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+    pub method: String,
+    /// Path and query relative to the base URL, so a recording replays against any server URL
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    pub status: u16,
+    pub response: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_link: Option<String>,
+}
+
+/// Writes exchanges to a directory
+pub struct Recorder {
+    dir: PathBuf,
+    next: AtomicUsize,
+    token: Option<String>,
+}
+
+impl Recorder {
+    pub fn new(dir: &Path, token: Option<String>) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        // Continue numbering after the last existing recording, so none is overwritten
+        let last = exchange_files(dir)?.iter().filter_map(|p| sequence(p)).max().unwrap_or(0);
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            next: AtomicUsize::new(last as usize + 1),
+            token,
+        })
+    }
+
+    /// Record one exchange
+    pub fn record(&self, path: &str, req: &RawRequest, resp: &RawResponse) -> Result<()> {
+        let response = serde_json::from_str(&resp.body).unwrap_or_else(|_| Value::String(resp.body.clone()));
+        let exchange = Exchange {
+            method: req.method.clone(),
+            // A token can ride in a query string too
+            path: self.redact_text(path),
+            request: req.body.clone().map(|b| self.redact(b)),
+            status: resp.status,
+            response: self.redact(response),
+            next_link: resp.next_link.as_deref().map(|link| self.redact_text(link)),
+        };
+
+        let seq = self.next.fetch_add(1, Ordering::SeqCst);
+        let file = self.dir.join(format!("{:04}.json", seq));
+        fs::write(file, serde_json::to_string_pretty(&exchange)?)?;
+        Ok(())
+    }
+
+    /// Strip credentials: secret-looking keys and any occurrence of the token itself
+    fn redact(&self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| {
+                        if is_secret_key(&k) {
+                            (k, Value::String(REDACTED.into()))
+                        } else {
+                            (k, self.redact(v))
+                        }
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.redact(v)).collect()),
+            Value::String(s) => Value::String(self.redact_text(&s)),
+            other => other,
+        }
+    }
+
+    /// Replace any occurrence of the token
+    fn redact_text(&self, text: &str) -> String {
+        match self.token {
+            Some(ref token) if !token.is_empty() => text.replace(token.as_str(), REDACTED),
+            _ => text.to_string(),
+        }
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ["token", "password", "secret", "authorization"]
+        .iter()
+        .any(|s| key.contains(s))
+}
+
+/// Serves recorded exchanges back in order
+pub struct Cassette {
+    exchanges: Mutex<Vec<(Exchange, bool)>>,
+}
+
+impl Cassette {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut exchanges = Vec::new();
+        for file in exchange_files(dir)? {
+            let content = fs::read_to_string(&file)?;
+            let exchange: Exchange = serde_json::from_str(&content).map_err(|e| {
+                BlazeError::Replay(format!("{}: {}", file.display(), e))
+            })?;
+            exchanges.push((exchange, false));
+        }
+
+        if exchanges.is_empty() {
+            return Err(BlazeError::Replay(format!("No recordings found in {}", dir.display())));
+        }
+
+        Ok(Self {
+            exchanges: Mutex::new(exchanges),
+        })
+    }
+
+    /// Answer a request with the first unused recording for the same method and path
+    pub fn replay(&self, method: &str, path: &str) -> Result<RawResponse> {
+        let mut exchanges = self.exchanges.lock().unwrap();
+        let (exchange, used) = exchanges
+            .iter_mut()
+            .find(|(e, used)| !used && e.method == method && e.path == path)
+            .ok_or_else(|| BlazeError::Replay(format!("No recorded response for {} {}", method, path)))?;
+
+        *used = true;
+        let body = match exchange.response {
+            Value::String(ref s) => s.clone(),
+            ref other => other.to_string(),
+        };
+        Ok(RawResponse {
+            status: exchange.status,
+            body,
+            next_link: exchange.next_link.clone(),
+        })
+    }
+}
+
+/// Recording files in a directory, in sequence order
+fn exchange_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // By number, as `10000.json` would sort before `9999.json` as a string
+    files.sort_by_key(|p| (sequence(p).unwrap_or(u64::MAX), p.clone()));
+    Ok(files)
+}
+
+fn sequence(path: &Path) -> Option<u64> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blaze-cassette-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn request(method: &str, body: Option<Value>) -> RawRequest {
+        RawRequest {
+            method: method.into(),
+            url: "http://localhost:8080/api/cards".into(),
+            token: Some("s3cr3t".into()),
+            token_withheld: false,
+            body,
+        }
+    }
+
+    fn response(body: Value) -> RawResponse {
+        RawResponse {
+            status: 200,
+            body: body.to_string(),
+            next_link: None,
+        }
+    }
+
+    #[test]
+    fn recordings_redact_secrets_in_both_directions() {
+        let dir = temp_dir("redact");
+        let recorder = Recorder::new(&dir, Some("s3cr3t".into())).unwrap();
+        let req = request("POST", Some(json!({ "title": "Rotate s3cr3t", "api_token": "abc" })));
+        let mut resp = response(json!({ "id": "a", "note": "Bearer s3cr3t", "nested": { "Password": "hunter2" } }));
+        resp.next_link = Some("/api/cards?cursor=2&access_token=s3cr3t".into());
+        recorder.record("/api/cards?access_token=s3cr3t", &req, &resp).unwrap();
+
+        let written = fs::read_to_string(dir.join("0001.json")).unwrap();
+        assert!(!written.contains("s3cr3t") && !written.contains("abc") && !written.contains("hunter2"));
+        let exchange: Exchange = serde_json::from_str(&written).unwrap();
+        assert_eq!(exchange.request.unwrap()["title"], "Rotate [REDACTED]");
+        assert_eq!(exchange.response["nested"]["Password"], REDACTED);
+        assert_eq!(exchange.response["id"], "a");
+        assert_eq!(exchange.path, "/api/cards?access_token=[REDACTED]");
+        assert_eq!(exchange.next_link.as_deref(), Some("/api/cards?cursor=2&access_token=[REDACTED]"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replays_same_requests_in_recorded_order() {
+        let dir = temp_dir("order");
+        let recorder = Recorder::new(&dir, None).unwrap();
+        for n in 1..=2 {
+            recorder.record("/api/cards/a", &request("GET", None), &response(json!({ "n": n }))).unwrap();
+        }
+
+        let cassette = Cassette::load(&dir).unwrap();
+        assert_eq!(cassette.replay("GET", "/api/cards/a").unwrap().body, r#"{"n":1}"#);
+        assert_eq!(cassette.replay("GET", "/api/cards/a").unwrap().body, r#"{"n":2}"#);
+        let err = cassette.replay("GET", "/api/cards/a").unwrap_err().to_string();
+        assert!(err.contains("No recorded response for GET /api/cards/a"), "{}", err);
+        let err = cassette.replay("DELETE", "/api/cards/a").unwrap_err().to_string();
+        assert!(err.contains("No recorded response for DELETE /api/cards/a"), "{}", err);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recording_continues_after_the_highest_number() {
+        let dir = temp_dir("gaps");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["0001.json", "0003.json"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+        let recorder = Recorder::new(&dir, None).unwrap();
+        recorder.record("/api/cards", &request("GET", None), &response(json!([]))).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("0003.json")).unwrap(), "{}");
+        assert!(dir.join("0004.json").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn files_sort_by_sequence_number() {
+        let dir = temp_dir("sort");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["10000.json", "9999.json", "0002.json"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+        let names: Vec<String> = exchange_files(&dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["0002.json", "9999.json", "10000.json"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+//! Detecting card changes between two looks at the board.
+//!
+//! A [`Cursor`] remembers the last seen update time plus each card's column,
+//! which tells moves and deletions apart from edits. Cursors are kept per
+//! profile (or server URL without one) in a JSON file under
+//! ~/.config/blaze/, one file per consumer (`changes`, `notify run`).
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Column};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Moved,
+    Deleted,
+}
+
+/// One changed card
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub change: ChangeKind,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<Column>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_column: Option<Column>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// The card as it is now (none once deleted)
+    #[serde(skip)]
+    pub card: Option<Card>,
+}
+
+/// What was seen last time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    pub last: DateTime<Utc>,
+    pub columns: BTreeMap<String, Column>,
+}
+
+impl Cursor {
+    /// Cursor after seeing `cards`, never moving backwards from `previous`
+    pub fn advance(cards: &[Card], previous: Option<&Cursor>) -> Cursor {
+        let last = cards
+            .iter()
+            .map(|c| c.updated_at)
+            .chain(previous.map(|c| c.last))
+            .max()
+            .unwrap_or_else(Utc::now);
+        Cursor {
+            last,
+            columns: cards.iter().map(|c| (c.id.clone(), c.column.clone())).collect(),
+        }
+    }
+}
+
+/// Cursors of one consumer, keyed by profile or server URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CursorStore {
+    #[serde(default)]
+    servers: BTreeMap<String, Cursor>,
+    #[serde(skip)]
+    file: String,
+}
+
+impl CursorStore {
+    fn path(file: &str) -> Option<PathBuf> {
+        Config::dir().map(|d| d.join(file))
+    }
+
+    /// Load the cursors kept in `file` (e.g. `changes.json`)
+    pub fn load(file: &str) -> Result<Self> {
+        let mut store: Self = match Self::path(file) {
+            Some(path) if path.exists() => serde_json::from_str(&fs::read_to_string(path)?)?,
+            _ => Self::default(),
+        };
+        store.file = file.to_string();
+        Ok(store)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(&self.file), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Cursor> {
+        self.servers.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, cursor: Cursor) {
+        self.servers.insert(key, cursor);
+    }
+}
+
+/// Cards changed after `since` (everything if none).
+///
+/// With a cursor, cards that changed column are reported as moves and cards
+/// that disappeared as deletions.
+pub fn detect(cards: &[Card], cursor: Option<&Cursor>, since: Option<DateTime<Utc>>) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+    for card in cards {
+        if since.is_some_and(|t| card.updated_at <= t) {
+            continue;
+        }
+
+        let previous = cursor.and_then(|c| c.columns.get(&card.id)).cloned();
+        let change = match previous {
+            Some(ref from) if *from != card.column => ChangeKind::Moved,
+            Some(_) => ChangeKind::Updated,
+            None if since.is_none_or(|t| card.created_at > t) => ChangeKind::Created,
+            None => ChangeKind::Updated,
+        };
+
+        changes.push(Change {
+            change,
+            id: card.id.clone(),
+            title: Some(card.title.clone()),
+            column: Some(card.column.clone()),
+            from_column: previous.filter(|_| change == ChangeKind::Moved),
+            updated_at: Some(card.updated_at),
+            card: Some(card.clone()),
+        });
+    }
+    changes.sort_by_key(|c| c.updated_at);
+
+    // Only the cursor knows which cards existed before
+    if let Some(cursor) = cursor {
+        for (id, column) in &cursor.columns {
+            if !cards.iter().any(|c| &c.id == id) {
+                changes.push(Change {
+                    change: ChangeKind::Deleted,
+                    id: id.clone(),
+                    title: None,
+                    column: None,
+                    from_column: Some(column.clone()),
+                    updated_at: None,
+                    card: None,
+                });
+            }
+        }
+    }
+    changes
+}
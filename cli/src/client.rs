@@ -1,147 +1,749 @@
 //! HTTP client for the Blaze API.
 
+use crate::auth;
+use crate::cache;
+use crate::capabilities::Capability;
+use crate::cassette::{Cassette, Recorder};
+use crate::columns;
+use crate::config::ConnectionConfig;
+use crate::confidential::{self, Confidential};
+use crate::daemon::{self, RawRequest, RawResponse};
 use crate::error::{BlazeError, Result};
+use crate::journal::{Change, Journal, Recording};
+use crate::output::print_json;
+use crate::progress::Progress;
+use crate::store::{QueuedRequest, Store};
 use crate::types::*;
-use serde::Serialize;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use reqwest::Client as HttpClient;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{SinkExt, StreamExt};
+use reqwest::{header, Client as HttpClient, Method, StatusCode, Url};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::handshake::client::generate_key;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// Statuses worth another try: rate limiting and gateways in front of a restarting server
+const TRANSIENT_STATUSES: &[u16] = &[429, 502, 503, 504];
+
+/// What `--dry-run` prints in place of a mutating request
+#[derive(Serialize)]
+struct DryRunRequest<'a> {
+    dry_run: bool,
+    method: &'a str,
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a serde_json::Value>,
+}
+
+/// Optional client behavior
+#[derive(Debug, Default, Clone)]
+pub struct ClientOptions {
+    /// Record all exchanges into this directory
+    pub record: Option<PathBuf>,
+    /// Serve all requests from recordings in this directory (no network access)
+    pub replay: Option<PathBuf>,
+    /// Log requests to stderr (1: request lines, 2: also bodies)
+    pub verbosity: u8,
+    /// Print mutating requests instead of sending them
+    pub dry_run: bool,
+    /// Keep cards in the local store, and answer reads from it when the server is unreachable
+    pub store: bool,
+    /// Answer reads from the local store and queue mutations for `blaze sync`
+    pub offline: bool,
+    /// When to retry failed requests
+    pub retry: RetryPolicy,
+    /// Proxy for all requests (otherwise HTTPS_PROXY, HTTP_PROXY and NO_PROXY apply)
+    pub proxy: Option<String>,
+    /// Extra CA certificates (PEM, may be a bundle) to trust besides the built-in roots
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate (PEM) for mTLS
+    pub client_cert: Option<PathBuf>,
+    /// Private key for `client_cert` (PEM), if it isn't in the same file
+    pub client_key: Option<PathBuf>,
+    /// Keys to encrypt confidential cards with
+    pub confidential: Option<Confidential>,
+    /// No token is sent on purpose (the saved one is for another server)
+    pub token_withheld: bool,
+}
+
+impl ClientOptions {
+    /// Whether a proxy, CA or client certificate is set
+    fn custom_connection(&self) -> bool {
+        self.proxy.is_some() || self.ca_cert.is_some() || self.client_cert.is_some() || self.client_key.is_some()
+    }
+}
+
+/// Retries for requests that failed to connect, timed out, or hit a transient status
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Extra attempts after the first one
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each one after
+    pub backoff: Duration,
+    /// Upper bound for the delay
+    pub max_backoff: Duration,
+    /// Randomize each delay between half and all of it, so clients don't retry in lockstep
+    pub jitter: bool,
+    /// Retry POST/PUT/PATCH/DELETE too (they may be applied twice)
+    pub mutations: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            mutations: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait before retry number `attempt` (0-based)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_backoff);
+        if !self.jitter {
+            return delay;
+        }
+        let random = RandomState::new().build_hasher().finish();
+        let half = delay / 2;
+        half + half.mul_f64((random % 1000) as f64 / 1000.0)
+    }
+
+    fn applies_to(&self, method: &Method) -> bool {
+        *method == Method::GET || self.mutations
+    }
+}
 
 /// Blaze API client
 pub struct Client {
     http: HttpClient,
     base_url: String,
     token: Option<String>,
+    token_withheld: bool,
+    /// Daemon socket to delegate requests to, if one is running
+    daemon: Option<PathBuf>,
+    recorder: Option<Recorder>,
+    cassette: Option<Cassette>,
+    retry: RetryPolicy,
+    verbosity: u8,
+    dry_run: bool,
+    store: Option<Store>,
+    offline: bool,
+    /// Whether the "using cached cards" note was printed
+    announced: AtomicBool,
+    /// Optional features found present (true) or missing (false) so far
+    capabilities: Mutex<HashMap<Capability, bool>>,
+    /// Where mutations go in the undo journal, if anywhere
+    recording: Mutex<Option<Recording>>,
+    /// Keys for confidential cards, if configured
+    confidential: Option<Confidential>,
+    /// The board's columns, once asked for
+    columns: tokio::sync::OnceCell<Option<Vec<Column>>>,
+}
+
+/// Encrypt the `description` of a card request body, if it has a non-empty one
+fn seal_description(keys: &Confidential, body: &mut Value) -> Result<()> {
+    if let Some(Value::String(description)) = body.get_mut("description") {
+        if !description.is_empty() && !confidential::is_sealed_text(description) {
+            *description = sealed_within_limit(keys, description)?;
+        }
+    }
+    Ok(())
+}
+
+/// Encrypt a description, failing if the ciphertext is more than the server takes
+fn sealed_within_limit(keys: &Confidential, description: &str) -> Result<String> {
+    let sealed = keys.seal_text(description)?;
+    if sealed.len() > MAX_DESCRIPTION_LEN {
+        // Armor is base64, so each character cut saves about 4/3 of one
+        let over = (sealed.len() - MAX_DESCRIPTION_LEN) * 3 / 4 + 1;
+        return Err(BlazeError::InvalidInput(format!(
+            "Encrypted, the description is {} characters and the server takes at most {}; \
+             shorten it by about {} characters",
+            sealed.len(),
+            MAX_DESCRIPTION_LEN,
+            over
+        )));
+    }
+    Ok(sealed)
+}
+
+/// Build the HTTP client with the proxy and TLS settings
+fn http_client(options: &ClientOptions) -> Result<HttpClient> {
+    http_client_from(HttpClient::builder(), options)
+}
+
+/// Finish `builder` with the proxy and TLS settings
+fn http_client_from(mut builder: reqwest::ClientBuilder, options: &ClientOptions) -> Result<HttpClient> {
+    if let Some(ref url) = options.proxy {
+        let proxy = reqwest::Proxy::all(url)
+            .map_err(|e| BlazeError::Config(format!("Invalid proxy '{}': {}", url, e)))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ref path) = options.ca_cert {
+        // A bundle may hold the whole internal chain
+        let pem = read_pem(path, "CA certificate")?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .ok()
+            .filter(|certs| !certs.is_empty())
+            .ok_or_else(|| BlazeError::Config(format!("No PEM certificates in {}", path.display())))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    match (&options.client_cert, &options.client_key) {
+        (Some(cert), key) => {
+            let mut pem = read_pem(cert, "client certificate")?;
+            if let Some(key) = key {
+                pem.push(b'\n');
+                pem.extend(read_pem(key, "client key")?);
+            }
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| BlazeError::Config(format!("Invalid client certificate or key {}: {}", cert.display(), e)))?;
+            builder = builder.identity(identity);
+        }
+        (None, Some(_)) => return Err(BlazeError::Config("A client key needs a client certificate".into())),
+        (None, None) => {}
+    }
+
+    builder.build().map_err(|e| {
+        // reqwest only says "builder error"; the cause is in the source
+        let cause = std::error::Error::source(&e).map_or_else(|| e.to_string(), |s| s.to_string());
+        BlazeError::Config(format!("Can't set up the HTTP client: {}", cause))
+    })
+}
+
+/// HTTP client for servers other than Blaze's (token endpoints, GitHub): the proxy and
+/// CA apply, but the client certificate is only for the server it was configured for
+pub fn outside_client(connection: &ConnectionConfig) -> Result<HttpClient> {
+    let builder = HttpClient::builder().user_agent(concat!("blaze-cli/", env!("CARGO_PKG_VERSION")));
+    http_client_from(builder, &ClientOptions {
+        proxy: connection.proxy.clone(),
+        ca_cert: connection.ca_cert.clone(),
+        ..ClientOptions::default()
+    })
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| BlazeError::Config(format!("Can't read {} {}: {}", what, path.display(), e)))
 }
 
 impl Client {
     /// Create a new API client
     pub fn new(base_url: &str, token: Option<String>) -> Result<Self> {
-        let http = HttpClient::builder()
-            .build()
-            .map_err(BlazeError::Http)?;
+        Self::with_options(base_url, token, ClientOptions::default())
+    }
+
+    /// Create a new API client with extra options
+    pub fn with_options(base_url: &str, token: Option<String>, options: ClientOptions) -> Result<Self> {
+        let http = http_client(&options)?;
+
+        let recorder = match options.record {
+            Some(ref dir) => Some(Recorder::new(dir, token.clone())?),
+            None => None,
+        };
+        let cassette = match options.replay {
+            Some(ref dir) => Some(Cassette::load(dir)?),
+            None => None,
+        };
+        let store = if options.offline {
+            Some(Store::open(base_url)?)
+        } else if options.store && cassette.is_none() {
+            // The store is a convenience; a broken one shouldn't stop commands
+            Store::open(base_url)
+                .inspect_err(|e| {
+                    if options.verbosity >= 1 {
+                        eprintln!("Local store unavailable: {}", e);
+                    }
+                })
+                .ok()
+        } else {
+            None
+        };
 
         Ok(Self {
             http,
             base_url: base_url.trim_end_matches('/').to_string(),
             token,
+            token_withheld: options.token_withheld,
+            // The daemon's connections don't go through our proxy or use our certificates
+            daemon: daemon::active_socket().filter(|_| !options.custom_connection()),
+            recorder,
+            cassette,
+            retry: options.retry,
+            verbosity: options.verbosity,
+            dry_run: options.dry_run,
+            store,
+            offline: options.offline,
+            announced: AtomicBool::new(false),
+            capabilities: Mutex::new(HashMap::new()),
+            recording: Mutex::new(None),
+            confidential: options.confidential,
+            columns: tokio::sync::OnceCell::new(),
         })
     }
 
-    /// Get headers including auth if token is set
-    fn headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        if let Some(ref token) = self.token {
-            if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
-                headers.insert(AUTHORIZATION, value);
-            }
-        }
-        headers
+    /// The local card store, if enabled
+    pub fn store(&self) -> Option<&Store> {
+        self.store.as_ref()
+    }
+
+    /// Create a client that always talks to the server directly (used by the daemon itself)
+    pub fn direct(base_url: &str, token: Option<String>) -> Result<Self> {
+        let mut client = Self::new(base_url, token)?;
+        client.daemon = None;
+        Ok(client)
     }
 
     /// Make a GET request
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .get(&url)
-            .headers(self.headers())
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+        let resp = self.send(Method::GET, path, None).await?;
+        self.handle_response(resp)
     }
 
     /// Make a POST request with JSON body
-    #[allow(dead_code)]
-    async fn post<B: serde::Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .post(&url)
-            .headers(self.headers())
-            .json(body)
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let resp = self.send(Method::POST, path, Some(serde_json::to_value(body)?)).await?;
+        self.handle_response(resp)
     }
 
     /// Make a PUT request with JSON body
-    #[allow(dead_code)]
-    async fn put<B: serde::Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .put(&url)
-            .headers(self.headers())
-            .json(body)
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+    async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let resp = self.send(Method::PUT, path, Some(serde_json::to_value(body)?)).await?;
+        self.handle_response(resp)
     }
 
     /// Make a PATCH request with JSON body
-    #[allow(dead_code)]
-    async fn patch<B: serde::Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .patch(&url)
-            .headers(self.headers())
-            .json(body)
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+    async fn patch<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let resp = self.send(Method::PATCH, path, Some(serde_json::to_value(body)?)).await?;
+        self.handle_response(resp)
     }
 
     /// Make a DELETE request
-    #[allow(dead_code)]
     async fn delete(&self, path: &str) -> Result<()> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .headers(self.headers())
-            .send()
-            .await?;
+        let resp = self.send(Method::DELETE, path, None).await?;
 
-        if resp.status() == reqwest::StatusCode::NO_CONTENT {
+        if resp.status == reqwest::StatusCode::NO_CONTENT.as_u16() {
             return Ok(());
         }
 
-        let status = resp.status().as_u16();
-        if !resp.status().is_success() {
-            let text = resp.text().await.unwrap_or_default();
+        if !(200..300).contains(&resp.status) {
             return Err(BlazeError::Api {
-                status,
-                message: text,
+                status: resp.status,
+                message: resp.body,
             });
         }
 
         Ok(())
     }
 
-    /// Handle API response, extracting errors
-    async fn handle_response<T: DeserializeOwned>(&self, resp: reqwest::Response) -> Result<T> {
-        let status = resp.status();
+    /// Make a DELETE request that returns a response body
+    async fn delete_with_response<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let resp = self.send(Method::DELETE, path, None).await?;
+        self.handle_response(resp)
+    }
 
-        if status == reqwest::StatusCode::UNAUTHORIZED {
-            return Err(BlazeError::Auth("Invalid or missing token".into()));
+    /// GET a collection, following its pages until the last one (or `request.limit` items)
+    async fn get_collection<T: DeserializeOwned>(&self, path: &str, request: PageRequest) -> Result<Vec<T>> {
+        let mut first = path.to_string();
+        if let Some(limit) = request.limit {
+            first = set_query_param(&first, "limit", &limit.to_string());
+        }
+        if let Some(page) = request.page {
+            first = set_query_param(&first, "page", &page.to_string());
         }
 
-        if !status.is_success() {
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BlazeError::Api {
-                status: status.as_u16(),
-                message: text,
+        let mut items = Vec::new();
+        let mut fetched = HashSet::new();
+        let mut path = first.clone();
+        let progress = Progress::start("fetch", request.limit);
+        loop {
+            let resp = self.send(Method::GET, &path, None).await?;
+            let link = resp.next_link.clone();
+            let page = Page::parse(self.handle_response(resp)?)?;
+            progress.advance_by(page.items.len());
+            items.extend(page.items);
+            fetched.insert(path.clone());
+
+            let enough = request.page.is_some() || request.limit.is_some_and(|limit| items.len() >= limit);
+            let next = match link.map(NextPage::Link).or(page.next) {
+                Some(next) if !enough => next,
+                _ => break,
+            };
+            path = match next {
+                NextPage::Link(url) => self.link_path(&url)?,
+                NextPage::Cursor(cursor) => set_query_param(&path, "cursor", &cursor),
+                NextPage::Page(n) => set_query_param(&path, "page", &n.to_string()),
+            };
+            if fetched.contains(&path) {
+                return Err(BlazeError::Other(format!("Pagination loops back to {}", path)));
+            }
+        }
+        if let Some(limit) = request.limit {
+            items.truncate(limit);
+        }
+
+        // Pages were stored one by one; the whole list also tells the store what's gone
+        if let (Some(store), true, PageRequest { limit: None, page: None }) = (&self.store, fetched.len() > 1, request) {
+            if let Err(e) = store.observe("GET", &first, &Value::Array(items.clone()).to_string()) {
+                if self.verbosity >= 1 {
+                    eprintln!("Local store not updated: {}", e);
+                }
+            }
+        }
+
+        Ok(items.into_iter().map(serde_json::from_value).collect::<serde_json::Result<_>>()?)
+    }
+
+    /// A next-page link as a path relative to the base URL
+    fn link_path(&self, link: &str) -> Result<String> {
+        if let Some(rest) = link.strip_prefix(self.base_url.as_str()) {
+            return Ok(rest.to_string());
+        }
+        if link.starts_with('/') {
+            // Absolute path on the same server; drop the base URL's own path prefix
+            let prefix = Url::parse(&self.base_url).map(|u| u.path().trim_end_matches('/').to_string()).unwrap_or_default();
+            return Ok(link.strip_prefix(prefix.as_str()).unwrap_or(link).to_string());
+        }
+        Err(BlazeError::Other(format!("Next page link {} points to another server", link)))
+    }
+
+    /// Send a request, through the daemon when one is running
+    async fn send(&self, method: Method, path: &str, body: Option<serde_json::Value>) -> Result<RawResponse> {
+        if self.dry_run && method != Method::GET {
+            print_json(&DryRunRequest {
+                dry_run: true,
+                method: method.as_str(),
+                path,
+                body: body.as_ref(),
             });
+            return Err(BlazeError::DryRun);
+        }
+
+        if let Some(ref cassette) = self.cassette {
+            return cassette.replay(method.as_str(), path);
+        }
+
+        if let (true, Some(store)) = (self.offline, &self.store) {
+            if method != Method::GET {
+                print_json(&store.enqueue(method.as_str(), path, body.as_ref())?);
+                return Err(BlazeError::Queued);
+            }
+            if let Some(resp) = store.answer(path)? {
+                self.announce(store, "Offline");
+                return Ok(resp);
+            }
+        }
+
+        let capability = Capability::of_path(path);
+        if let Some(capability) = capability.filter(|c| self.known(*c) == Some(false)) {
+            return Err(capability.unsupported());
+        }
+
+        let started = Instant::now();
+        if self.verbosity >= 2 {
+            if let Some(ref body) = body {
+                eprintln!("> {}", body);
+            }
+        }
+
+        let before = self.prior_state(&method, path).await;
+        let req = self.raw_request(&method, format!("{}{}", self.base_url, path), body);
+
+        let result = self.send_with_retries(&method, &req).await;
+        let resp = match (result, &self.store) {
+            (Ok(resp), _) => resp,
+            (Err(e), Some(store)) if e.is_unreachable() => {
+                if method != Method::GET {
+                    return Err(BlazeError::Other(format!(
+                        "{} (use --offline to queue the change for `blaze sync`)",
+                        e
+                    )));
+                }
+                match store.answer(path)? {
+                    Some(resp) => {
+                        self.announce(store, "Server unreachable");
+                        return Ok(resp);
+                    }
+                    None => return Err(e),
+                }
+            }
+            (Err(e), _) => return Err(e),
+        };
+
+        if self.verbosity >= 1 {
+            eprintln!(
+                "{} {} -> {} ({} ms)",
+                req.method,
+                req.url,
+                resp.status,
+                started.elapsed().as_millis()
+            );
+        }
+        if self.verbosity >= 2 {
+            eprintln!("< {}", resp.body);
+        }
+
+        if let Some(ref recorder) = self.recorder {
+            recorder.record(path, &req, &resp)?;
+        }
+
+        if let Some(ref store) = self.store {
+            if (200..300).contains(&resp.status) {
+                let observed = match resp.next_link {
+                    Some(_) => store.observe_page(path, &resp.body),
+                    None => store.observe(method.as_str(), path, &resp.body),
+                };
+                if let Err(e) = observed {
+                    if self.verbosity >= 1 {
+                        eprintln!("Local store not updated: {}", e);
+                    }
+                }
+            }
         }
 
-        let body = resp.json().await?;
-        Ok(body)
+        if let Some(error) = self.unsupported(capability, resp.status).await {
+            return Err(error);
+        }
+        if method != Method::GET && (200..300).contains(&resp.status) {
+            self.journal(&req, path, before, &resp);
+        }
+        Ok(resp)
+    }
+
+    /// Record the current command's mutations in the undo journal (`None` stops recording)
+    pub fn record_as(&self, recording: Option<Recording>) {
+        *self.recording.lock().unwrap() = recording;
+    }
+
+    /// The card a mutation is about to change, as it is now, when recording
+    async fn prior_state(&self, method: &Method, path: &str) -> Option<Value> {
+        if *method == Method::GET || self.recording.lock().unwrap().is_none() {
+            return None;
+        }
+        let card_id = match path.trim_start_matches("/api/").split(['/', '?']).collect::<Vec<_>>().as_slice() {
+            ["cards", id, ..] => id.to_string(),
+            _ => return None,
+        };
+        // Not self.get: this runs inside send
+        let req = self.raw_request(&Method::GET, format!("{}/api/cards/{}", self.base_url, card_id), None);
+        match self.send_with_retries(&Method::GET, &req).await {
+            Ok(resp) if (200..300).contains(&resp.status) => serde_json::from_str(&resp.body).ok(),
+            _ => None,
+        }
+    }
+
+    /// Add a successful mutation to the undo journal; failing to is only worth a note
+    fn journal(&self, req: &RawRequest, path: &str, before: Option<Value>, resp: &RawResponse) {
+        // Held while writing, so concurrent requests don't overwrite each other's entries
+        let recording = self.recording.lock().unwrap();
+        let Some(ref recording) = *recording else {
+            return;
+        };
+        let change = Change {
+            method: req.method.clone(),
+            path: path.to_string(),
+            body: req.body.clone(),
+            before,
+            after: serde_json::from_str(&resp.body).unwrap_or(Value::Null),
+        };
+        if let Err(e) = Journal::append(recording, change) {
+            if self.verbosity >= 1 {
+                eprintln!("Undo journal not updated: {}", e);
+            }
+        }
+    }
+
+    /// The targeted error for a 404/405 from a feature the server turns out not to have
+    async fn unsupported(&self, capability: Option<Capability>, status: u16) -> Option<BlazeError> {
+        let capability = capability.filter(|_| matches!(status, 404 | 405))?;
+        (!self.supports(capability).await).then(|| capability.unsupported())
+    }
+
+    /// What this session already knows about an optional feature
+    fn known(&self, capability: Capability) -> Option<bool> {
+        self.capabilities.lock().unwrap().get(&capability).copied()
+    }
+
+    /// Whether the server has an optional feature, asked once per session with `OPTIONS`.
+    /// Recordings, the offline store and failed probes count as having it.
+    pub async fn supports(&self, capability: Capability) -> bool {
+        if let Some(known) = self.known(capability) {
+            return known;
+        }
+        if self.offline || self.cassette.is_some() {
+            return true;
+        }
+        let req = self.raw_request(&Method::OPTIONS, format!("{}{}", self.base_url, capability.probe_path()), None);
+        let status = match self.execute(req).await {
+            Ok(resp) => resp.status,
+            Err(_) => return true,
+        };
+        if self.verbosity >= 1 {
+            eprintln!("OPTIONS {}{} -> {}", self.base_url, capability.probe_path(), status);
+        }
+        let supported = status != 404;
+        self.capabilities.lock().unwrap().insert(capability, supported);
+        supported
+    }
+
+    /// Send through the daemon or directly, retrying transient failures per the retry policy
+    async fn send_with_retries(&self, method: &Method, req: &RawRequest) -> Result<RawResponse> {
+        let mut attempt = 0;
+        loop {
+            let result = match self.daemon {
+                // A stale socket just means the daemon went away; fall back to a direct request
+                Some(ref socket) => match daemon::forward(socket, req).await? {
+                    Some(resp) => Ok(resp),
+                    None => self.execute(req.clone()).await,
+                },
+                None => self.execute(req.clone()).await,
+            };
+
+            let failure = match result {
+                Ok(ref resp) if TRANSIENT_STATUSES.contains(&resp.status) => format!("HTTP {}", resp.status),
+                Err(ref e) if e.is_unreachable() => e.to_string(),
+                _ => return result,
+            };
+            if attempt >= self.retry.retries || !self.retry.applies_to(method) {
+                return result;
+            }
+
+            let delay = self.retry.delay(attempt);
+            if self.verbosity >= 1 {
+                eprintln!(
+                    "{} {} failed ({}), retrying in {} ms",
+                    req.method,
+                    req.url,
+                    failure,
+                    delay.as_millis()
+                );
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Say once per command that answers come from the store
+    fn announce(&self, store: &Store, reason: &str) {
+        if self.announced.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        match store.synced_at().ok().flatten() {
+            Some(at) => eprintln!("{}: using cards stored at {}", reason, at.format("%Y-%m-%d %H:%M UTC")),
+            None => eprintln!("{}: using stored cards", reason),
+        }
+    }
+
+    /// Send a request queued with `--offline`
+    pub async fn send_queued(&self, queued: &QueuedRequest) -> Result<serde_json::Value> {
+        self.send_raw(&queued.method, &queued.path, queued.body.clone()).await
+    }
+
+    /// Send a request given as method, path and JSON body
+    pub async fn send_raw(&self, method: &str, path: &str, body: Option<Value>) -> Result<serde_json::Value> {
+        let method = Method::from_bytes(method.as_bytes())
+            .map_err(|_| BlazeError::InvalidInput(format!("Invalid HTTP method '{}'", method)))?;
+        let resp = self.send(method, path, body).await?;
+        if resp.status == reqwest::StatusCode::NO_CONTENT.as_u16() {
+            return Ok(serde_json::Value::Null);
+        }
+        self.handle_response(resp)
+    }
+
+    /// A request as it goes to the daemon or out over HTTP
+    fn raw_request(&self, method: &Method, url: String, body: Option<Value>) -> RawRequest {
+        RawRequest {
+            method: method.to_string(),
+            url,
+            token: self.token.clone(),
+            token_withheld: self.token_withheld,
+            body,
+        }
+    }
+
+    /// Token for a request: its own, else ours (the daemon's) if it goes to our server and the
+    /// caller didn't hold its token back
+    fn bearer<'a>(&'a self, req: &'a RawRequest) -> Option<&'a String> {
+        match req.token {
+            Some(ref token) => Some(token),
+            None if !req.token_withheld && auth::origin(&req.url) == auth::origin(&self.base_url) => {
+                self.token.as_ref()
+            }
+            None => None,
+        }
+    }
+
+    /// Perform a request over HTTP
+    pub async fn execute(&self, req: RawRequest) -> Result<RawResponse> {
+        let method = Method::from_bytes(req.method.as_bytes())
+            .map_err(|_| BlazeError::InvalidInput(format!("Invalid HTTP method '{}'", req.method)))?;
+
+        let mut builder = self.http.request(method, &req.url);
+        if let Some(token) = self.bearer(&req) {
+            builder = builder.bearer_auth(token);
+        }
+        if let Some(ref body) = req.body {
+            builder = builder.json(body);
+        }
+
+        let resp = builder.send().await?;
+        let status = resp.status().as_u16();
+        let next_link = resp
+            .headers()
+            .get_all(header::LINK)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find_map(parse_next_link);
+        let body = resp.text().await?;
+        Ok(RawResponse { status, body, next_link })
+    }
+
+    /// Handle API response, extracting errors
+    fn handle_response<T: DeserializeOwned>(&self, resp: RawResponse) -> Result<T> {
+        if !(200..300).contains(&resp.status) {
+            return Err(status_error(resp.status, resp.body));
+        }
+        Ok(serde_json::from_str(&resp.body)?)
+    }
+
+    /// A request straight to the server, for file content the JSON paths (daemon, recordings,
+    /// offline store) can't carry
+    fn file_request(&self, method: Method, url: Url) -> Result<reqwest::RequestBuilder> {
+        if self.offline || self.cassette.is_some() {
+            return Err(BlazeError::InvalidInput(
+                "Attachments need a live connection (not --offline or --replay)".into(),
+            ));
+        }
+        let mut builder = self.http.request(method, url);
+        if let Some(ref token) = self.token {
+            builder = builder.bearer_auth(token);
+        }
+        Ok(builder)
+    }
+
+    /// URL of a card's attachments, or of one of them
+    fn attachment_url(&self, card_id: &str, name: Option<&str>) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/api/cards/{}/attachments", self.base_url, card_id))
+            .map_err(|e| BlazeError::Config(format!("Invalid server URL: {}", e)))?;
+        if let (Some(name), Ok(mut segments)) = (name, url.path_segments_mut()) {
+            segments.push(name);
+        }
+        Ok(url)
     }
 
     // --- API Methods ---
@@ -151,56 +753,324 @@ impl Client {
         self.get("/health").await
     }
 
+    /// Recorded board events, optionally for one card
+    pub async fn list_events(&self, card_id: Option<&str>) -> Result<Vec<BoardEvent>> {
+        match card_id {
+            Some(id) => self.get(&format!("/api/events?card_id={}", id)).await,
+            None => self.get("/api/events").await,
+        }
+    }
+
+    /// Identity and scopes of the current token
+    pub async fn whoami(&self) -> Result<Identity> {
+        self.get("/api/auth/whoami").await
+    }
+
+    /// The board's columns in order (see `columns.rs`); `None` if they
+    /// can't be found out, in which case any column name is accepted
+    pub async fn columns(&self) -> Option<&[Column]> {
+        self.columns.get_or_init(|| self.fetch_columns()).await.as_deref()
+    }
+
+    async fn fetch_columns(&self) -> Option<Vec<Column>> {
+        if let Some(fresh) = columns::cached(&self.base_url, Some(columns::max_age())) {
+            return Some(fresh);
+        }
+        if self.offline {
+            return columns::cached(&self.base_url, None);
+        }
+        match self.get::<Value>("/api/columns").await {
+            Ok(body) => {
+                let fetched = columns::parse(&body)?;
+                if let Err(e) = columns::remember(&self.base_url, &fetched) {
+                    if self.verbosity >= 1 {
+                        eprintln!("Can't cache the column list: {}", e);
+                    }
+                }
+                Some(fetched)
+            }
+            Err(e) if e.is_endpoint_missing() => Some(Column::DEFAULTS.to_vec()),
+            Err(e) => {
+                if self.verbosity >= 1 {
+                    eprintln!("Can't fetch the column list: {}", e);
+                }
+                columns::cached(&self.base_url, None)
+            }
+        }
+    }
+
+    /// Fail if the board is known not to have `column`
+    pub async fn check_column(&self, column: &Column) -> Result<()> {
+        match self.columns().await {
+            Some(known) => columns::check(column, known).map_err(BlazeError::InvalidInput),
+            None => Ok(()),
+        }
+    }
+
     /// List all cards
     pub async fn list_cards(&self, column: Option<Column>, include_archived: bool) -> Result<Vec<Card>> {
-        let mut params = Vec::new();
-        
-        if let Some(col) = column {
-            params.push(format!("column={}", col));
-        }
-        
-        if include_archived {
-            params.push("include_archived=true".to_string());
-        }
-        
-        let path = if params.is_empty() {
-            "/api/cards".to_string()
-        } else {
-            format!("/api/cards?{}", params.join("&"))
+        let filter = CardFilter {
+            column,
+            include_archived,
+            ..CardFilter::default()
         };
-        
-        self.get(&path).await
+        self.list_cards_where(&filter, PageRequest::default()).await
+    }
+
+    /// List cards matching `filter`, or some of them on huge boards.
+    ///
+    /// The conditions are sent as query parameters; if the server rejects
+    /// them, the whole list is fetched and filtered here instead.
+    pub async fn list_cards_where(&self, filter: &CardFilter, request: PageRequest) -> Result<Vec<Card>> {
+        let mut path = "/api/cards".to_string();
+        if let Some(ref col) = filter.column {
+            path = set_query_param(&path, "column", &col.to_string());
+        }
+        if filter.include_archived {
+            path = set_query_param(&path, "include_archived", "true");
+        }
+
+        let mut narrowed = path.clone();
+        if !filter.priorities.is_empty() {
+            let priorities: Vec<String> = filter.priorities.iter().map(|p| p.to_string()).collect();
+            narrowed = set_query_param(&narrowed, "priority", &priorities.join(","));
+        }
+        if !filter.tags.is_empty() {
+            narrowed = set_query_param(&narrowed, "tags", &filter.tags.join(","));
+        }
+        if filter.overdue {
+            narrowed = set_query_param(&narrowed, "overdue", "true");
+        }
+        if let Some(since) = filter.updated_since {
+            narrowed = set_query_param(&narrowed, "updated_since", &since.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if narrowed == path {
+            let cards = self.get_collection(&path, request).await?;
+            return Ok(cards.into_iter().map(|c| self.open_card(c)).collect());
+        }
+
+        // The limit counts matching cards, which a server ignoring the filters can't do
+        let unlimited = PageRequest { limit: None, ..request };
+        let cards = match self.get_collection::<Card>(&narrowed, unlimited).await {
+            Err(BlazeError::Api { status: 400 | 422, .. }) => {
+                if self.verbosity >= 1 {
+                    eprintln!("Server rejected the list filters; filtering locally");
+                }
+                self.get_collection(&path, unlimited).await?
+            }
+            result => result?,
+        };
+        // Servers that don't know a parameter ignore it, so check again
+        let mut cards: Vec<Card> = cards
+            .into_iter()
+            .filter(|c| filter.matches(c))
+            .map(|c| self.open_card(c))
+            .collect();
+        if let Some(limit) = request.limit {
+            cards.truncate(limit);
+        }
+        Ok(cards)
     }
 
     /// Get a single card
     pub async fn get_card(&self, id: &str) -> Result<Card> {
-        self.get(&format!("/api/cards/{}", id)).await
+        let card = self.get(&format!("/api/cards/{}", id)).await?;
+        Ok(self.open_card(card))
     }
 
     /// Create a new card
-    #[allow(dead_code)]
     pub async fn create_card(&self, card: &CardCreate) -> Result<Card> {
-        self.post("/api/cards", card).await
+        self.check_column(&card.column).await?;
+        let mut body = serde_json::to_value(card)?;
+        if let Some(keys) = self.confidential.as_ref().filter(|keys| keys.covers(&card.tags)) {
+            seal_description(keys, &mut body)?;
+        }
+        let card = self.post("/api/cards", &body).await?;
+        Ok(self.open_card(card))
     }
 
     /// Update a card
-    #[allow(dead_code)]
     pub async fn update_card(&self, id: &str, update: &CardUpdate) -> Result<Card> {
-        self.put(&format!("/api/cards/{}", id), update).await
+        if let Some(ref column) = update.column {
+            self.check_column(column).await?;
+        }
+        let mut body = serde_json::to_value(update)?;
+        if let Some(ref keys) = self.confidential {
+            self.seal_update(keys, id, update, &mut body).await?;
+        }
+        let card = self.put(&format!("/api/cards/{}", id), &body).await?;
+        Ok(self.open_card(card))
+    }
+
+    /// Encrypt the description an update sends to a confidential card, or the
+    /// card's current one when the update makes it confidential
+    async fn seal_update(&self, keys: &Confidential, id: &str, update: &CardUpdate, body: &mut Value) -> Result<()> {
+        let path = format!("/api/cards/{}", id);
+        match (&update.description, &update.tags) {
+            (Some(_), Some(tags)) if keys.covers(tags) => seal_description(keys, body),
+            (Some(_), None) => {
+                let card: Card = self.get(&path).await?;
+                if keys.covers(&card.tags) {
+                    seal_description(keys, body)?;
+                }
+                Ok(())
+            }
+            (None, Some(tags)) if keys.covers(tags) => {
+                let card: Card = self.get(&path).await?;
+                if let Some(description) = card.description.filter(|d| !d.is_empty()) {
+                    body["description"] = Value::String(sealed_within_limit(keys, &description)?);
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// A card with its description encrypted again if it's confidential, for copies
+    /// kept on disk
+    pub fn seal_card(&self, mut card: Card) -> Result<Card> {
+        if let Some(keys) = self.confidential.as_ref().filter(|keys| keys.covers(&card.tags)) {
+            if let Some(description) = card.description.as_mut().filter(|d| !d.is_empty()) {
+                *description = keys.seal_text(description)?;
+            }
+        }
+        Ok(card)
+    }
+
+    /// A card with its description decrypted, if it's encrypted for us
+    fn open_card(&self, mut card: Card) -> Card {
+        if let (Some(keys), Some(description)) = (&self.confidential, &card.description) {
+            if let Some(plain) = keys.open_text(description) {
+                card.description = Some(plain);
+            }
+        }
+        card
     }
 
     /// Move a card to a different column
-    #[allow(dead_code)]
     pub async fn move_card(&self, id: &str, column: Column) -> Result<Card> {
-        self.patch(&format!("/api/cards/{}/move", id), &CardMove { column }).await
+        self.check_column(&column).await?;
+        let card = self.patch(&format!("/api/cards/{}/move", id), &CardMove { column }).await?;
+        Ok(self.open_card(card))
+    }
+
+    /// Archive a card (hidden from lists, keeps its column)
+    pub async fn archive_card(&self, id: &str) -> Result<Card> {
+        self.patch(&format!("/api/cards/{}/archive", id), &serde_json::json!({})).await
     }
 
     /// Delete a card
-    #[allow(dead_code)]
     pub async fn delete_card(&self, id: &str) -> Result<()> {
         self.delete(&format!("/api/cards/{}", id)).await
     }
 
+    /// A card's comments, oldest first
+    pub async fn list_comments(&self, card_id: &str) -> Result<Vec<Comment>> {
+        let mut comments: Vec<Comment> = self.get(&format!("/api/cards/{}/comments", card_id)).await?;
+        comments.sort_by_key(|c| c.created_at);
+        Ok(comments)
+    }
+
+    /// Comment on a card
+    pub async fn add_comment(&self, card_id: &str, comment: &CommentCreate) -> Result<Comment> {
+        self.post(&format!("/api/cards/{}/comments", card_id), comment).await
+    }
+
+    /// Delete a comment
+    pub async fn delete_comment(&self, card_id: &str, comment_id: &str) -> Result<()> {
+        self.delete(&format!("/api/cards/{}/comments/{}", card_id, comment_id)).await
+    }
+
+    /// A card's review threads, oldest first
+    pub async fn list_threads(&self, card_id: &str) -> Result<Vec<Thread>> {
+        let mut threads: Vec<Thread> = self.get(&format!("/api/cards/{}/threads", card_id)).await?;
+        threads.sort_by_key(|t| t.created_at);
+        Ok(threads)
+    }
+
+    /// Start a review thread
+    pub async fn start_thread(&self, card_id: &str, thread: &ThreadCreate) -> Result<Thread> {
+        self.post(&format!("/api/cards/{}/threads", card_id), thread).await
+    }
+
+    /// Reply to a review thread
+    pub async fn reply_to_thread(&self, card_id: &str, thread_id: &str, reply: &CommentCreate) -> Result<Thread> {
+        self.post(&format!("/api/cards/{}/threads/{}/replies", card_id, thread_id), reply).await
+    }
+
+    /// Resolve or reopen a review thread
+    pub async fn update_thread(&self, card_id: &str, thread_id: &str, update: &ThreadUpdate) -> Result<Thread> {
+        self.patch(&format!("/api/cards/{}/threads/{}", card_id, thread_id), update).await
+    }
+
+    /// A card's attachments
+    pub async fn list_attachments(&self, card_id: &str) -> Result<Vec<Attachment>> {
+        self.get(&format!("/api/cards/{}/attachments", card_id)).await
+    }
+
+    /// Upload a file to a card (multipart, field `file`), encrypted if the card is confidential
+    pub async fn upload_attachment(&self, card_id: &str, name: &str, mut content: Vec<u8>) -> Result<Attachment> {
+        if let Some(ref keys) = self.confidential {
+            let card: Card = self.get(&format!("/api/cards/{}", card_id)).await?;
+            if keys.covers(&card.tags) {
+                content = keys.seal_bytes(&content)?;
+            }
+        }
+        let url = self.attachment_url(card_id, None)?;
+        if self.dry_run {
+            print_json(&DryRunRequest {
+                dry_run: true,
+                method: "POST",
+                path: url.path(),
+                body: Some(&serde_json::json!({ "file": name, "size": content.len() })),
+            });
+            return Err(BlazeError::DryRun);
+        }
+        let part = reqwest::multipart::Part::bytes(content).file_name(name.to_string());
+        let form = reqwest::multipart::Form::new().part("file", part);
+        let resp = self.file_request(Method::POST, url)?.multipart(form).send().await?;
+        let status = resp.status().as_u16();
+        if let Some(error) = self.unsupported(Some(Capability::Attachments), status).await {
+            return Err(error);
+        }
+        let body = resp.text().await?;
+        self.handle_response(RawResponse { status, body, next_link: None })
+    }
+
+    /// Download an attachment's content. With the `sha256` the server reported for it, a copy
+    /// in the blob cache is used instead; downloads are kept there as the server sends them
+    /// (still encrypted, for confidential cards).
+    pub async fn download_attachment(&self, card_id: &str, name: &str, sha256: Option<&str>) -> Result<Vec<u8>> {
+        // A bad hash from the server just means no cache hit
+        let cached = sha256.and_then(|sha256| cache::get_blob(sha256).ok().flatten());
+        let content = match cached {
+            Some(content) => content,
+            None => {
+                let url = self.attachment_url(card_id, Some(name))?;
+                let resp = self.file_request(Method::GET, url)?.send().await?;
+                let status = resp.status().as_u16();
+                if let Some(error) = self.unsupported(Some(Capability::Attachments), status).await {
+                    return Err(error);
+                }
+                if !(200..300).contains(&status) {
+                    return Err(status_error(status, resp.text().await?));
+                }
+                let content = resp.bytes().await?.to_vec();
+                // The cache is a convenience; a broken one shouldn't stop downloads
+                if let Err(e) = cache::put_blob(&content) {
+                    if self.verbosity >= 1 {
+                        eprintln!("Blob cache unavailable: {}", e);
+                    }
+                }
+                content
+            }
+        };
+        match self.confidential.as_ref().and_then(|keys| keys.open_bytes(&content)) {
+            Some(plain) => Ok(plain),
+            None => Ok(content),
+        }
+    }
+
     /// Get board statistics
     pub async fn stats(&self) -> Result<BoardStats> {
         self.get("/api/board/stats").await
@@ -210,7 +1080,8 @@ impl Client {
 
     /// List cards ready for agent work
     pub async fn list_agent_ready(&self) -> Result<Vec<Card>> {
-        self.get("/api/agent/ready").await
+        let cards: Vec<Card> = self.get("/api/agent/ready").await?;
+        Ok(cards.into_iter().map(|c| self.open_card(c)).collect())
     }
 
     /// Add a progress entry to a card
@@ -246,11 +1117,16 @@ impl Client {
 
     /// List all plans
     pub async fn list_plans(&self, status: Option<PlanStatus>) -> Result<Vec<Plan>> {
+        self.list_plans_paged(status, PageRequest::default()).await
+    }
+
+    /// List plans, or some of them
+    pub async fn list_plans_paged(&self, status: Option<PlanStatus>, request: PageRequest) -> Result<Vec<Plan>> {
         let path = match status {
             Some(s) => format!("/api/plans?status={}", s),
             None => "/api/plans".to_string(),
         };
-        self.get(&path).await
+        self.get_collection(&path, request).await
     }
 
     /// Get a single plan
@@ -259,25 +1135,21 @@ impl Client {
     }
 
     /// Create a new plan
-    #[allow(dead_code)]
     pub async fn create_plan(&self, plan: &PlanCreate) -> Result<Plan> {
         self.post("/api/plans", plan).await
     }
 
     /// Update a plan
-    #[allow(dead_code)]
     pub async fn update_plan(&self, id: &str, update: &PlanUpdate) -> Result<Plan> {
         self.patch(&format!("/api/plans/{}", id), update).await
     }
 
     /// Delete a plan
-    #[allow(dead_code)]
     pub async fn delete_plan(&self, id: &str) -> Result<()> {
         self.delete(&format!("/api/plans/{}", id)).await
     }
 
     /// Add a file to a plan
-    #[allow(dead_code)]
     pub async fn add_plan_file(&self, plan_id: &str, file: &PlanFileCreate) -> Result<Plan> {
         self.post(&format!("/api/plans/{}/files", plan_id), file).await
     }
@@ -288,29 +1160,511 @@ impl Client {
     }
 
     /// Update a file in a plan
-    #[allow(dead_code)]
     pub async fn update_plan_file(&self, plan_id: &str, filename: &str, update: &PlanFileUpdate) -> Result<Plan> {
         self.patch(&format!("/api/plans/{}/files/{}", plan_id, filename), update).await
     }
 
     /// Delete a file from a plan
-    #[allow(dead_code)]
     pub async fn delete_plan_file(&self, plan_id: &str, filename: &str) -> Result<Plan> {
         self.delete_with_response(&format!("/api/plans/{}/files/{}", plan_id, filename)).await
     }
+
+    // --- View Methods ---
+
+    /// List views shared on the server
+    pub async fn list_views(&self) -> Result<Vec<SavedView>> {
+        self.get("/api/views").await
+    }
+
+    /// Create or replace a shared view
+    pub async fn put_view(&self, view: &SavedView) -> Result<SavedView> {
+        self.put(&format!("/api/views/{}", view.name), view).await
+    }
+}
+
+/// Conditions for listing cards (any of the priorities, any of the tags)
+#[derive(Debug, Default, Clone)]
+pub struct CardFilter {
+    pub column: Option<Column>,
+    pub include_archived: bool,
+    pub priorities: Vec<Priority>,
+    pub tags: Vec<String>,
+    /// Past its due date
+    pub overdue: bool,
+    pub updated_since: Option<DateTime<Utc>>,
+}
+
+impl CardFilter {
+    /// Whether `card` meets the conditions the server may not apply (all but column and archived)
+    pub fn matches(&self, card: &Card) -> bool {
+        (self.priorities.is_empty() || self.priorities.contains(&card.priority))
+            && (self.tags.is_empty() || self.tags.iter().any(|t| card.tags.contains(t)))
+            && (!self.overdue || card.due_date.is_some_and(|due| due < Utc::now()))
+            && self.updated_since.is_none_or(|since| card.updated_at >= since)
+    }
+}
+
+// --- Pagination ---
+
+/// Which part of a collection to fetch; the default is all of it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    /// Stop after this many items (also sent as `limit`, the page size)
+    pub limit: Option<usize>,
+    /// Fetch only this page (`page=N`) instead of following the pages
+    pub page: Option<u64>,
+}
+
+/// One page of a collection response
+#[derive(Debug)]
+pub struct Page {
+    pub items: Vec<Value>,
+    pub next: Option<NextPage>,
+}
+
+/// Where the rest of a collection is
+#[derive(Debug, PartialEq)]
+pub enum NextPage {
+    /// URL or path to fetch as is
+    Link(String),
+    /// Repeat the request with `cursor=...`
+    Cursor(String),
+    /// Repeat the request with `page=N`
+    Page(u64),
+}
+
+impl Page {
+    /// Split a collection body. A plain array is a whole collection; an
+    /// envelope holds `items` (or `data`, `results`) and points at the next
+    /// page with `next`, `next_cursor`, or `page` and `pages`/`total_pages`.
+    pub fn parse(body: Value) -> Result<Self> {
+        let mut envelope = match body {
+            Value::Array(items) => return Ok(Self { items, next: None }),
+            Value::Object(map) => map,
+            other => return Err(BlazeError::Other(format!("Expected a list, got {}", other))),
+        };
+        let items = ["items", "data", "results"]
+            .iter()
+            .find_map(|key| match envelope.remove(*key) {
+                Some(Value::Array(items)) => Some(items),
+                _ => None,
+            })
+            .ok_or_else(|| BlazeError::Other("Expected a list or an object with `items`".into()))?;
+
+        let text = |key: &str| envelope.get(key).and_then(Value::as_str).filter(|s| !s.is_empty()).map(String::from);
+        let number = |key: &str| envelope.get(key).and_then(Value::as_u64);
+        let next = if let Some(link) = text("next") {
+            Some(NextPage::Link(link))
+        } else if let Some(cursor) = text("next_cursor") {
+            Some(NextPage::Cursor(cursor))
+        } else {
+            match (number("page"), number("pages").or_else(|| number("total_pages"))) {
+                (Some(page), Some(pages)) if page < pages && !items.is_empty() => Some(NextPage::Page(page + 1)),
+                _ => None,
+            }
+        };
+        Ok(Self { items, next })
+    }
+}
+
+/// The error an unsuccessful response reports
+fn status_error(status: u16, body: String) -> BlazeError {
+    if status == StatusCode::UNAUTHORIZED.as_u16() {
+        return BlazeError::Auth("Invalid or missing token".into());
+    }
+    BlazeError::Api { status, message: body }
+}
+
+/// The `rel="next"` target of a Link header
+fn parse_next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params.split(';').any(|param| {
+            param
+                .trim()
+                .strip_prefix("rel=")
+                .is_some_and(|rel| rel.trim_matches('"').split_whitespace().any(|r| r.eq_ignore_ascii_case("next")))
+        });
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+/// `path` with query parameter `name` set to `value` (replacing any earlier value)
+fn set_query_param(path: &str, name: &str, value: &str) -> String {
+    let Ok(mut url) = Url::parse(&format!("http://blaze{}", path)) else {
+        return path.to_string();
+    };
+    let kept: Vec<(String, String)> = url.query_pairs().filter(|(k, _)| k != name).map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+    url.query_pairs_mut().clear().extend_pairs(kept).append_pair(name, value);
+    format!("{}?{}", url.path(), url.query().unwrap_or_default())
+}
+
+// --- Event stream ---
+
+/// How `blaze events` connects to the server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventTransport {
+    /// Server-sent events, or the WebSocket if the server has no event stream
+    #[default]
+    Auto,
+    /// Server-sent events from /api/events/stream
+    Sse,
+    /// The board WebSocket at /ws
+    #[value(name = "websocket", alias = "ws")]
+    WebSocket,
+}
+
+/// A live connection delivering board events as they happen
+pub struct EventStream {
+    source: EventSource,
+    /// Parsed events not handed out yet (one read can hold several)
+    pending: VecDeque<BoardEvent>,
+}
+
+enum EventSource {
+    Sse {
+        response: reqwest::Response,
+        buffer: Vec<u8>,
+        /// The last byte read was a CR, so a following LF belongs to it
+        after_cr: bool,
+    },
+    WebSocket {
+        socket: WebSocketStream<reqwest::Upgraded>,
+        keepalive: tokio::time::Interval,
+    },
+}
+
+impl EventStream {
+    fn new(source: EventSource) -> Self {
+        Self {
+            source,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Wait for the next event; `None` when the server closes the stream
+    pub async fn next(&mut self) -> Result<Option<BoardEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            match self.source {
+                EventSource::Sse {
+                    ref mut response,
+                    ref mut buffer,
+                    ref mut after_cr,
+                } => {
+                    let Some(chunk) = response.chunk().await? else {
+                        return Ok(None);
+                    };
+                    // Line endings may be CRLF, LF or CR; blank lines end an event
+                    for &byte in chunk.iter() {
+                        if !(byte == b'\n' && *after_cr) {
+                            buffer.push(if byte == b'\r' { b'\n' } else { byte });
+                        }
+                        *after_cr = byte == b'\r';
+                    }
+                    while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                        let block: Vec<u8> = buffer.drain(..end + 2).collect();
+                        self.pending.extend(parse_sse_block(&String::from_utf8_lossy(&block)));
+                    }
+                }
+                EventSource::WebSocket {
+                    ref mut socket,
+                    ref mut keepalive,
+                } => {
+                    tokio::select! {
+                        message = socket.next() => match message {
+                            None | Some(Ok(Message::Close(_))) => return Ok(None),
+                            Some(Ok(Message::Text(text))) => self.pending.extend(parse_event(&text, None)),
+                            Some(Ok(_)) => {}
+                            Some(Err(e)) => return Err(BlazeError::Other(format!("WebSocket: {}", e))),
+                        },
+                        _ = keepalive.tick() => {
+                            // The server answers "ping" with "pong"; idle proxies would otherwise drop us
+                            socket
+                                .send(Message::text("ping"))
+                                .await
+                                .map_err(|e| BlazeError::Other(format!("WebSocket: {}", e)))?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One SSE event: `data:` lines hold the JSON, `event:` may name its type
+fn parse_sse_block(block: &str) -> Option<BoardEvent> {
+    let mut name = None;
+    let mut data = Vec::new();
+    for line in block.lines() {
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => name = Some(value),
+            "data" => data.push(value),
+            // Comments (keep-alives) and ids
+            _ => {}
+        }
+    }
+    if data.is_empty() {
+        return None;
+    }
+    parse_event(&data.join("\n"), name)
+}
+
+/// Accept both recorded events (as from /api/events) and board broadcasts
+/// like `{"type": "card_moved", "card": {...}}`; anything else is skipped
+fn parse_event(text: &str, name: Option<&str>) -> Option<BoardEvent> {
+    let mut value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if value.get("timestamp").is_some() {
+        return serde_json::from_value(value).ok();
+    }
+
+    let object = value.as_object_mut()?;
+    let kind = match object.remove("type") {
+        Some(serde_json::Value::String(kind)) => kind,
+        _ => name?.to_string(),
+    };
+    let card = object.get("card");
+    let card_id = card
+        .and_then(|c| c.get("id"))
+        .or_else(|| object.get("card_id"))
+        .and_then(|id| id.as_str())
+        .map(String::from);
+    let timestamp = card
+        .and_then(|c| c.get("updated_at"))
+        .and_then(|t| serde_json::from_value(t.clone()).ok())
+        .unwrap_or_else(Utc::now);
+    Some(BoardEvent {
+        // Same spelling as recorded events: card_moved -> card.moved
+        kind: if kind.contains('.') { kind } else { kind.replacen('_', ".", 1) },
+        card_id,
+        timestamp,
+        data: value,
+    })
 }
 
 impl Client {
-    /// Make a DELETE request that returns a response body
-    async fn delete_with_response<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .headers(self.headers())
+    /// Open a live event stream. Streams bypass the daemon, recordings and the local store.
+    pub async fn event_stream(&self, transport: EventTransport) -> Result<EventStream> {
+        if self.offline || self.cassette.is_some() {
+            return Err(BlazeError::InvalidInput(
+                "Live events need the server (not --offline or --replay)".into(),
+            ));
+        }
+        match transport {
+            EventTransport::Sse => self.sse_stream().await,
+            EventTransport::WebSocket => self.websocket_stream().await,
+            EventTransport::Auto => match self.sse_stream().await {
+                Err(e) if e.is_endpoint_missing() => self.websocket_stream().await,
+                other => other,
+            },
+        }
+    }
+
+    async fn sse_stream(&self) -> Result<EventStream> {
+        let url = format!("{}/api/events/stream", self.base_url);
+        let response = self
+            .stream_request(&url)
+            .header(header::ACCEPT, "text/event-stream")
             .send()
             .await?;
+        let response = self.check_stream(response, &url, StatusCode::OK).await?;
+
+        // A web UI answering every path isn't an event stream
+        let is_stream = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !is_stream {
+            return Err(BlazeError::Api {
+                status: 404,
+                message: "no event stream at /api/events/stream".into(),
+            });
+        }
+        Ok(EventStream::new(EventSource::Sse {
+            response,
+            buffer: Vec::new(),
+            after_cr: false,
+        }))
+    }
+
+    async fn websocket_stream(&self) -> Result<EventStream> {
+        let url = format!("{}/ws", self.base_url);
+        let key = generate_key();
+        let response = self
+            .stream_request(&url)
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_VERSION, "13")
+            .header(header::SEC_WEBSOCKET_KEY, &key)
+            .send()
+            .await?;
+        let response = self.check_stream(response, &url, StatusCode::SWITCHING_PROTOCOLS).await?;
+
+        let accept = response
+            .headers()
+            .get(header::SEC_WEBSOCKET_ACCEPT)
+            .and_then(|v| v.to_str().ok());
+        if accept != Some(derive_accept_key(key.as_bytes()).as_str()) {
+            return Err(BlazeError::Other("WebSocket handshake failed: wrong Sec-WebSocket-Accept".into()));
+        }
+
+        let socket = WebSocketStream::from_raw_socket(response.upgrade().await?, Role::Client, None).await;
+        let period = Duration::from_secs(30);
+        let keepalive = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        Ok(EventStream::new(EventSource::WebSocket { socket, keepalive }))
+    }
+
+    fn stream_request(&self, url: &str) -> reqwest::RequestBuilder {
+        let builder = self.http.get(url);
+        match self.token {
+            Some(ref token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    /// Turn anything but the `expected` status into an error
+    async fn check_stream(&self, response: reqwest::Response, url: &str, expected: StatusCode) -> Result<reqwest::Response> {
+        let status = response.status();
+        if self.verbosity >= 1 {
+            eprintln!("GET {} -> {} (stream)", url, status.as_u16());
+        }
+        if status == expected {
+            return Ok(response);
+        }
+        Err(match status {
+            StatusCode::UNAUTHORIZED => BlazeError::Auth("Invalid or missing token".into()),
+            _ => BlazeError::Api {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn retry_delays_double_up_to_the_cap() {
+        let fixed = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let delays: Vec<u128> = (0..7).map(|n| fixed.delay(n).as_millis()).collect();
+        assert_eq!(delays, [200, 400, 800, 1600, 3200, 5000, 5000]);
+        assert_eq!(fixed.delay(40), fixed.max_backoff);
+
+        let jittered = RetryPolicy::default();
+        for attempt in 0..4 {
+            let delay = jittered.delay(attempt);
+            assert!(delay >= fixed.delay(attempt) / 2 && delay <= fixed.delay(attempt));
+        }
+        assert!(!jittered.applies_to(&Method::POST));
+        assert!(jittered.applies_to(&Method::GET));
+    }
+
+    #[test]
+    fn sealed_descriptions_must_fit_the_server_limit() {
+        let config = crate::config::EncryptionConfig {
+            recipients: vec![age::x25519::Identity::generate().to_public().to_string()],
+            identity: None,
+            tag: None,
+        };
+        let keys = Confidential::from_config(&config).unwrap().unwrap();
+
+        let mut body = serde_json::json!({ "description": "bands and names" });
+        seal_description(&keys, &mut body).unwrap();
+        assert!(confidential::is_sealed_text(body["description"].as_str().unwrap()));
+
+        // Fits in plain text, but not once encrypted
+        let mut body = serde_json::json!({ "description": "x".repeat(1500) });
+        let err = seal_description(&keys, &mut body).unwrap_err();
+        assert!(matches!(err, BlazeError::InvalidInput(ref msg) if msg.contains("shorten it")), "{}", err);
+    }
+
+    #[test]
+    fn daemon_token_stays_with_its_server() {
+        let daemon = Client::direct("https://blaze.example.com", Some("daemon".into())).unwrap();
+        let req = |url: &str, token: Option<&str>, token_withheld: bool| RawRequest {
+            method: "GET".into(),
+            url: url.into(),
+            token: token.map(String::from),
+            token_withheld,
+            body: None,
+        };
+        let bearer = |req: &RawRequest| daemon.bearer(req).cloned();
+
+        assert_eq!(bearer(&req("https://blaze.example.com:443/api/cards", None, false)).as_deref(), Some("daemon"));
+        assert_eq!(bearer(&req("https://other.example.com/api/cards", Some("own"), false)).as_deref(), Some("own"));
+        assert_eq!(bearer(&req("https://other.example.com/api/cards", None, false)), None);
+        assert_eq!(bearer(&req("https://blaze.example.com/api/cards", None, true)), None);
+    }
+
+    #[test]
+    fn pages_point_at_the_next_one() {
+        let page = |body: &str| Page::parse(serde_json::from_str(body).unwrap()).unwrap().next;
+        assert_eq!(page(r#"[{"id": "a"}]"#), None);
+        assert_eq!(page(r#"{"items": [{"id": "a"}], "next_cursor": "b2"}"#), Some(NextPage::Cursor("b2".into())));
+        assert_eq!(page(r#"{"data": [{"id": "a"}], "page": 1, "total_pages": 3}"#), Some(NextPage::Page(2)));
+        assert_eq!(page(r#"{"results": [{"id": "a"}], "page": 3, "pages": 3}"#), None);
+        assert_eq!(page(r#"{"items": [], "next_cursor": ""}"#), None);
+        assert!(Page::parse(serde_json::json!({"detail": "nope"})).is_err());
+
+        assert_eq!(
+            parse_next_link(r#"<https://b.example/api/cards?page=1>; rel="prev", <https://b.example/api/cards?page=3>; rel="next""#),
+            Some("https://b.example/api/cards?page=3".into())
+        );
+        assert_eq!(parse_next_link(r#"</api/cards?page=3>; rel="last""#), None);
+
+        assert_eq!(set_query_param("/api/cards?column=todo&cursor=x", "cursor", "a b"), "/api/cards?column=todo&cursor=a+b");
+        assert_eq!(set_query_param("/api/plans", "limit", "50"), "/api/plans?limit=50");
+    }
+
+    #[test]
+    fn card_filter_rechecks_what_servers_ignore() {
+        let card = testing::card("a1", serde_json::json!({
+            "priority": "high", "due_date": "2020-01-01T00:00:00Z", "tags": ["bug", "ui"],
+            "updated_at": "2026-01-05T10:00:00Z",
+        }));
+        let filter = |f: CardFilter| f.matches(&card);
+        assert!(filter(CardFilter::default()));
+        assert!(filter(CardFilter {
+            priorities: vec![Priority::Urgent, Priority::High],
+            tags: vec!["ui".into()],
+            overdue: true,
+            ..Default::default()
+        }));
+        assert!(!filter(CardFilter { priorities: vec![Priority::Low], ..Default::default() }));
+        assert!(!filter(CardFilter { tags: vec!["docs".into()], ..Default::default() }));
+        let since = |s: &str| Some(s.parse().unwrap());
+        assert!(filter(CardFilter { updated_since: since("2026-01-05T10:00:00Z"), ..Default::default() }));
+        assert!(!filter(CardFilter { updated_since: since("2026-01-06T00:00:00Z"), ..Default::default() }));
+    }
+
+    #[test]
+    fn stream_events_from_both_shapes() {
+        let moved = parse_sse_block("event: update\ndata: {\"type\": \"card_moved\",\ndata: \"card\": {\"id\": \"a1\"}}\n").unwrap();
+        assert_eq!(moved.kind, "card.moved");
+        assert_eq!(moved.card_id.as_deref(), Some("a1"));
+
+        let deleted = parse_event(r#"{"type": "card_deleted", "card_id": "b2"}"#, None).unwrap();
+        assert_eq!((deleted.kind.as_str(), deleted.card_id.as_deref()), ("card.deleted", Some("b2")));
+
+        let recorded = parse_event(
+            r#"{"type": "card.created", "card_id": "c3", "timestamp": "2026-01-05T10:00:00Z"}"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(recorded.kind, "card.created");
 
-        self.handle_response(resp).await
+        assert!(parse_event("pong", None).is_none());
+        assert!(parse_sse_block(": keep-alive\n").is_none());
     }
 }
@@ -0,0 +1,141 @@
+//! The board's columns, as the server lists them.
+//!
+//! Servers may rename the default columns or add their own. `GET
+//! /api/columns` lists them in board order, either as names (`["backlog",
+//! "qa"]`) or as objects with a `name` (or `id`), optionally wrapped in
+//! `{"columns": [...]}`. A server without the route has the five defaults.
+//!
+//! The list is kept in ~/.cache/blaze/columns.json per server for an hour;
+//! when the server can't be asked, the cached list is used however old it
+//! is. `Client::columns` is the way in.
+
+use crate::cache;
+use crate::error::Result;
+use crate::types::{Card, Column};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+
+const CACHE_FILE: &str = "columns.json";
+
+/// How long a fetched list is trusted
+pub fn max_age() -> Duration {
+    Duration::hours(1)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cached {
+    fetched_at: DateTime<Utc>,
+    columns: Vec<Column>,
+}
+
+fn load() -> BTreeMap<String, Cached> {
+    cache::dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join(CACHE_FILE)).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// The cached list for a server, if there is one younger than `max_age`
+pub fn cached(server: &str, max_age: Option<Duration>) -> Option<Vec<Column>> {
+    let entry = load().remove(server)?;
+    match max_age {
+        Some(age) if Utc::now() - entry.fetched_at > age => None,
+        _ => Some(entry.columns),
+    }
+}
+
+/// Keep a freshly fetched list
+pub fn remember(server: &str, columns: &[Column]) -> Result<()> {
+    let dir = cache::dir()?;
+    fs::create_dir_all(&dir)?;
+    let mut all = load();
+    all.insert(
+        server.to_string(),
+        Cached {
+            fetched_at: Utc::now(),
+            columns: columns.to_vec(),
+        },
+    );
+    fs::write(dir.join(CACHE_FILE), serde_json::to_string_pretty(&all)?)?;
+    Ok(())
+}
+
+/// Columns from a `/api/columns` response; `None` if it isn't a column list
+pub fn parse(body: &Value) -> Option<Vec<Column>> {
+    let list = match body {
+        Value::Object(map) => map.get("columns")?.as_array()?,
+        Value::Array(list) => list,
+        _ => return None,
+    };
+    let columns: Vec<Column> = list
+        .iter()
+        .map(|entry| match entry {
+            Value::String(name) => Some(Column::from(name.clone())),
+            Value::Object(map) => map
+                .get("name")
+                .or_else(|| map.get("id"))
+                .and_then(Value::as_str)
+                .map(|name| Column::from(name.to_string())),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    (!columns.is_empty()).then_some(columns)
+}
+
+/// Columns to show for `cards`: the board's (or the defaults), then any
+/// others the cards are in, so nothing goes missing from a summary
+pub fn in_order(known: Option<&[Column]>, cards: &[Card]) -> Vec<Column> {
+    let mut columns = known.unwrap_or(&Column::DEFAULTS).to_vec();
+    let mut extra: Vec<Column> = cards.iter().map(|c| c.column.clone()).filter(|c| !columns.contains(c)).collect();
+    extra.sort_by(|a, b| a.rank().cmp(&b.rank()).then_with(|| a.to_string().cmp(&b.to_string())));
+    extra.dedup();
+    columns.extend(extra);
+    columns
+}
+
+/// `column` as a choice among `columns`, or why it isn't one
+pub fn check(column: &Column, columns: &[Column]) -> std::result::Result<(), String> {
+    if columns.contains(column) {
+        return Ok(());
+    }
+    let names: Vec<String> = columns.iter().map(Column::to_string).collect();
+    Err(format!("Unknown column '{}' (this board has: {})", column, names.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_names_and_objects() {
+        let renamed = parse(&json!(["backlog", "doing", "done"])).unwrap();
+        assert_eq!(renamed, [Column::Backlog, Column::Other("doing".into()), Column::Done]);
+        let wrapped = parse(&json!({"columns": [{"name": "qa_signoff", "title": "QA"}, {"id": "todo"}]})).unwrap();
+        assert_eq!(wrapped, [Column::Other("qa_signoff".into()), Column::Todo]);
+        assert_eq!(parse(&json!({"detail": "Not Found"})), None);
+        assert_eq!(parse(&json!([1, 2])), None);
+
+        assert!(check(&Column::Done, &renamed).is_ok());
+        let error = check(&Column::InProgress, &renamed).unwrap_err();
+        assert_eq!(error, "Unknown column 'in_progress' (this board has: backlog, doing, done)");
+    }
+
+    #[test]
+    fn typed_names_and_display() {
+        assert_eq!("In Progress".parse::<Column>(), Ok(Column::InProgress));
+        assert_eq!("in-progress".parse::<Column>(), Ok(Column::InProgress));
+        assert_eq!("QA-Signoff".parse::<Column>(), Ok(Column::Other("qa_signoff".into())));
+        assert!("on hold!".parse::<Column>().is_err());
+        assert_eq!(Column::Other("qa_signoff".into()).display_name(), "Qa Signoff");
+
+        // Unknown names from the server survive a round trip
+        let column: Column = serde_json::from_value(json!("blocked_external")).unwrap();
+        assert_eq!(serde_json::to_value(&column).unwrap(), json!("blocked_external"));
+        assert!(Column::Other("x".into()).rank() > Column::Done.rank());
+    }
+}
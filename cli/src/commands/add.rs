@@ -1,10 +1,16 @@
-//! `blaze add` - Create a new card.
+//! `blaze add` - Create a new card, or many with `--batch`.
 
-use crate::client::Client;
-use crate::error::Result;
-use crate::output::print_card_detail;
-use crate::types::{CardCreate, Column, Priority};
-use chrono::{NaiveDate, TimeZone, Utc};
+use crate::agent_rules::AgentRules;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_card_detail, print_json_line, short_id};
+use crate::parsers::parse_due_date;
+use crate::types::{Card, CardCreate, CardCreateBuilder, Column, Priority};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Read;
 
 pub struct AddOptions {
     pub title: String,
@@ -13,32 +19,175 @@ pub struct AddOptions {
     pub priority: Priority,
     pub tags: Vec<String>,
     pub due: Option<String>,
+    pub agent: bool,
+    pub criteria: Vec<String>,
+    pub blocked_by: Vec<String>,
+    pub estimate: Option<f64>,
+    pub expires: Option<DateTime<Utc>>,
+    pub assignee: Option<String>,
 }
 
-pub async fn run(client: &Client, options: AddOptions) -> Result<()> {
+pub async fn run(ctx: &CommandContext, options: AddOptions) -> Result<()> {
+    let rules = AgentRules::from_config(&ctx.config.agent_rules)?;
     // Parse due date if provided
-    let due_date = match options.due {
-        Some(ref s) => {
-            let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
-                .map_err(|_| crate::error::BlazeError::InvalidInput(
-                    format!("Invalid date format '{}'. Use YYYY-MM-DD", s)
-                ))?;
-            // Set to end of day UTC
-            Some(Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap()))
+    let due_date = options.due.as_deref().map(|due| parse_due_date(due, ctx.tz)).transpose()?;
+
+    // Store full IDs so dependencies resolve regardless of how they were typed
+    let mut builder = CardCreate::builder();
+    for id in &options.blocked_by {
+        builder = builder.blocked_by(ctx.resolve_card_id(id).await?);
+    }
+
+    let card = builder
+        .title(options.title)
+        .maybe_description(options.description)
+        .column(options.column)
+        .priority(options.priority)
+        .tags(options.tags)
+        .maybe_due_date(due_date)
+        .agent_assignable(options.agent)
+        .criteria(options.criteria)
+        .estimate(options.estimate)
+        .expires_at(options.expires)
+        .assignee(options.assignee)
+        .build()?;
+
+    // An explicit --agent wins over the rules
+    let created = create(ctx, &rules, &card).await?;
+    ctx.remember_card(&created.id);
+    print_card_detail(ctx, &created);
+    Ok(())
+}
+
+/// Result of one `--batch` item (also used by `blaze quick --stdin`)
+#[derive(Serialize)]
+pub struct BatchResult {
+    /// 1-based position in the input (the line number for NDJSON)
+    item: usize,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BatchResult {
+    pub fn created(item: usize, card: Card) -> Self {
+        Self {
+            item,
+            ok: true,
+            id: Some(card.id),
+            title: Some(card.title),
+            error: None,
+        }
+    }
+
+    pub fn failed(item: usize, error: &BlazeError) -> Self {
+        Self {
+            item,
+            ok: false,
+            id: None,
+            title: None,
+            error: Some(error.to_string()),
         }
-        None => None,
-    };
-
-    let card = CardCreate {
-        title: options.title,
-        description: options.description,
-        column: options.column,
-        priority: options.priority,
-        tags: options.tags,
-        due_date,
-    };
-
-    let created = client.create_card(&card).await?;
-    print_card_detail(&created);
+    }
+}
+
+/// Create cards from NDJSON or a JSON array of card objects on stdin
+pub async fn run_batch(ctx: &CommandContext) -> Result<()> {
+    let rules = AgentRules::from_config(&ctx.config.agent_rules)?;
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+
+    let items = parse_batch(&input)?;
+    let total = items.len();
+    let mut failed = 0;
+
+    for (item, parsed) in items {
+        let card = parsed.and_then(|value| card_from_json(ctx, value));
+
+        let result = match card {
+            Ok(card) => create(ctx, &rules, &card).await,
+            Err(e) => Err(e),
+        };
+
+        let result = match result {
+            Ok(created) => BatchResult::created(item, created),
+            // The request was printed (or queued) instead of sent
+            Err(BlazeError::DryRun | BlazeError::Queued) => continue,
+            Err(e) => {
+                failed += 1;
+                BatchResult::failed(item, &e)
+            }
+        };
+        print_batch_result(ctx, &result);
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    if ctx.offline {
+        return Err(BlazeError::Queued);
+    }
+    if failed > 0 {
+        return Err(BlazeError::PartialFailure { failed, total });
+    }
     Ok(())
 }
+
+/// Create a card and apply the rules to it, unless it asks to be agent-assignable (also used by
+/// `blaze quick` and `blaze bot`)
+pub async fn create(ctx: &CommandContext, rules: &AgentRules, card: &CardCreate) -> Result<Card> {
+    let created = ctx.client.create_card(card).await?;
+    if card.agent_assignable {
+        return Ok(created);
+    }
+    rules.apply(&ctx.client, created).await
+}
+
+/// A card to create from a JSON object with `CardCreate`'s fields (also used by `blaze bot`)
+pub fn card_from_json(ctx: &CommandContext, mut value: Value) -> Result<CardCreate> {
+    // Dates without an offset are in the configured time zone, as with --due
+    if let Some(Value::String(due)) = value.get_mut("due_date") {
+        *due = parse_due_date(due, ctx.tz)?.to_rfc3339();
+    }
+    let builder: CardCreateBuilder = serde_json::from_value(value)?;
+    Ok(builder.build()?)
+}
+
+/// Split batch input into numbered items; a bad NDJSON line fails only that item
+fn parse_batch(input: &str) -> Result<Vec<(usize, Result<Value>)>> {
+    if input.trim_start().starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(input)?;
+        return Ok(values.into_iter().enumerate().map(|(i, v)| (i + 1, Ok(v))).collect());
+    }
+
+    Ok(input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, serde_json::from_str(line).map_err(BlazeError::from)))
+        .collect())
+}
+
+pub fn print_batch_result(ctx: &CommandContext, result: &BatchResult) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json_line(result),
+        OutputFormat::Quiet => {
+            if let Some(ref id) = result.id {
+                outln!("{}", id);
+            }
+        }
+        OutputFormat::Table | OutputFormat::Markdown => match (&result.id, &result.error) {
+            (Some(id), _) => outln!(
+                "ok      {}  {}",
+                short_id(id),
+                result.title.as_deref().unwrap_or_default()
+            ),
+            (None, Some(error)) => outln!("failed  item {}: {}", result.item, error),
+            (None, None) => {}
+        },
+    }
+}
@@ -1,55 +1,81 @@
 //! `blaze agent` - Agent workflow commands.
 
-use crate::client::Client;
+use crate::context::CommandContext;
+use crate::deps::DependencyIndex;
 use crate::error::Result;
-use crate::output::print_cards;
+use crate::output::{print_card_detail, print_cards, print_held_back};
 use crate::types::{AgentStatus, Card};
-use serde::Serialize;
 
-/// List cards ready for agent work
-pub async fn list(client: &Client) -> Result<()> {
-    let cards = client.list_agent_ready().await?;
-    print_cards(&cards);
+/// List cards ready for agent work, leaving out cards whose dependencies aren't done
+pub async fn list(ctx: &CommandContext, show_blocked_by: bool) -> Result<()> {
+    let cards = ctx.client.list_agent_ready().await?;
+    if cards.iter().all(|c| c.blocked_by.is_empty()) {
+        if show_blocked_by {
+            print_held_back(ctx, &[]);
+        } else {
+            print_cards(ctx, &cards);
+        }
+        return Ok(());
+    }
+
+    let all = ctx.client.list_cards(None, true).await?;
+    let index = DependencyIndex::new(&all);
+    if show_blocked_by {
+        let held: Vec<_> = cards
+            .iter()
+            .filter(|c| !index.is_unblocked(c))
+            .map(|c| index.held_back(c))
+            .collect();
+        print_held_back(ctx, &held);
+    } else {
+        let ready: Vec<Card> = cards.into_iter().filter(|c| index.is_unblocked(c)).collect();
+        print_cards(ctx, &ready);
+    }
     Ok(())
 }
 
 /// Start working on a card (set status to in_progress)
-pub async fn start(client: &Client, card_id: &str) -> Result<()> {
-    let card = client.update_agent_status(card_id, AgentStatus::InProgress, None).await?;
+pub async fn start(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+    ctx.client.update_agent_status(card_id, AgentStatus::InProgress, None).await?;
     // Add initial progress entry
-    let card = client.add_agent_progress(card_id, "Started work").await?;
-    println!("{}", serde_json::to_string_pretty(&card)?);
+    let card = ctx.client.add_agent_progress(card_id, "Started work").await?;
+    print_card_detail(ctx, &card);
     Ok(())
 }
 
 /// Add a progress entry
-pub async fn progress(client: &Client, card_id: &str, message: &str) -> Result<()> {
-    let card = client.add_agent_progress(card_id, message).await?;
-    println!("{}", serde_json::to_string_pretty(&card)?);
+pub async fn progress(ctx: &CommandContext, card_id: &str, message: &str) -> Result<()> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.add_agent_progress(card_id, message).await?;
+    print_card_detail(ctx, &card);
     Ok(())
 }
 
 /// Mark card as blocked
-pub async fn block(client: &Client, card_id: &str, reason: &str) -> Result<()> {
-    let card = client.update_agent_status(card_id, AgentStatus::Blocked, Some(reason.to_string())).await?;
+pub async fn block(ctx: &CommandContext, card_id: &str, reason: &str) -> Result<()> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+    ctx.client.update_agent_status(card_id, AgentStatus::Blocked, Some(reason.to_string())).await?;
     // Add progress entry about blocking
-    let card = client.add_agent_progress(card_id, &format!("Blocked: {}", reason)).await?;
-    println!("{}", serde_json::to_string_pretty(&card)?);
+    let card = ctx.client.add_agent_progress(card_id, &format!("Blocked: {}", reason)).await?;
+    print_card_detail(ctx, &card);
     Ok(())
 }
 
 /// Mark card as done (set status to needs_review)
-pub async fn done(client: &Client, card_id: &str) -> Result<()> {
+pub async fn done(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
     // Add completion progress entry
-    let card = client.add_agent_progress(card_id, "Completed work").await?;
-    let card = client.update_agent_status(card_id, AgentStatus::NeedsReview, None).await?;
-    println!("{}", serde_json::to_string_pretty(&card)?);
+    ctx.client.add_agent_progress(card_id, "Completed work").await?;
+    let card = ctx.client.update_agent_status(card_id, AgentStatus::NeedsReview, None).await?;
+    print_card_detail(ctx, &card);
     Ok(())
 }
 
 /// Check/uncheck an acceptance criterion
-pub async fn check(client: &Client, card_id: &str, index: usize, checked: bool) -> Result<()> {
-    let card = client.toggle_criterion(card_id, index, checked).await?;
-    println!("{}", serde_json::to_string_pretty(&card)?);
+pub async fn check(ctx: &CommandContext, card_id: &str, index: usize, checked: bool) -> Result<()> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.toggle_criterion(card_id, index, checked).await?;
+    print_card_detail(ctx, &card);
     Ok(())
 }
@@ -0,0 +1,160 @@
+//! `blaze analytics` - Flow metrics over a time window.
+//!
+//! Cycle time, lead time, weekly throughput and WIP over time for cards
+//! finished between `--since` (default: 12 weeks ago) and `--until` (default:
+//! now); see `flow.rs` for how they're worked out. CSV has one row per week
+//! for graphing; `--cards` lists the finished cards with their times instead.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::flow::{self, Durations, Flow, FlowReport};
+use crate::outln;
+use crate::output::{format_time, print_csv, print_json, print_markdown, print_table, short_id};
+use chrono::{DateTime, Duration, Utc};
+
+/// Window when `--since` isn't given
+const DEFAULT_WEEKS: i64 = 12;
+
+pub async fn run(
+    ctx: &CommandContext,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    cards: bool,
+) -> Result<()> {
+    let until = until.unwrap_or_else(Utc::now);
+    let since = since.unwrap_or(until - Duration::weeks(DEFAULT_WEEKS));
+    if since >= until {
+        return Err(BlazeError::InvalidInput("--since must be before --until".into()));
+    }
+
+    // Finished cards are often archived soon after
+    let all = ctx.client.list_cards(None, true).await?;
+    let events = match ctx.client.list_events(None).await {
+        Ok(events) => Some(events),
+        Err(e) if e.is_endpoint_missing() => {
+            eprintln!("The server keeps no events: no cycle times or WIP, and finish times are guesses");
+            None
+        }
+        Err(e) => return Err(e),
+    };
+    let flows = flow::flows(&all, events.as_deref());
+
+    if cards {
+        let mut finished: Vec<&Flow> =
+            flows.iter().filter(|f| f.finished_at.is_some_and(|at| since <= at && at < until)).collect();
+        finished.sort_by_key(|f| f.finished_at);
+        print_flows(ctx, &finished);
+    } else {
+        print_report(ctx, &flow::report(&flows, since, until, ctx.tz, events.is_some()));
+    }
+    Ok(())
+}
+
+fn print_report(ctx: &CommandContext, report: &FlowReport) {
+    let number = |n: Option<f64>| n.map(|n| n.to_string()).unwrap_or_default();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(report),
+        OutputFormat::Quiet => outln!("{}", report.finished),
+        OutputFormat::Csv => {
+            let rows = report
+                .weeks
+                .iter()
+                .map(|w| {
+                    vec![
+                        w.week.clone(),
+                        w.throughput.to_string(),
+                        w.wip.map(|n| n.to_string()).unwrap_or_default(),
+                        number(w.median_lead_days),
+                        number(w.median_cycle_days),
+                    ]
+                })
+                .collect();
+            print_csv(&["week", "throughput", "wip", "median_lead_days", "median_cycle_days"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let times = |name: &str, durations: &Option<Durations>| match durations {
+                Some(d) => vec![
+                    name.to_string(),
+                    d.cards.to_string(),
+                    format!("{}d", d.median),
+                    format!("{}d", d.p85),
+                    format!("{}d", d.mean),
+                ],
+                None => vec![name.to_string(), "0".to_string(), "-".into(), "-".into(), "-".into()],
+            };
+            let summary = vec![times("lead time", &report.lead_time), times("cycle time", &report.cycle_time)];
+            let weeks = report
+                .weeks
+                .iter()
+                .map(|w| {
+                    let wip = w.wip.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+                    let median = |days: Option<f64>| days.map(|d| format!("{}d", d)).unwrap_or_else(|| "-".into());
+                    vec![w.week.clone(), w.throughput.to_string(), wip, median(w.median_cycle_days)]
+                })
+                .collect();
+            let summary_headers = ["TIME", "CARDS", "MEDIAN", "P85", "MEAN"];
+            let week_headers = ["WEEK", "DONE", "WIP", "CYCLE"];
+            if ctx.format == OutputFormat::Markdown {
+                print_markdown(&summary_headers, summary);
+                outln!();
+                print_markdown(&week_headers, weeks);
+            } else {
+                print_table(&summary_headers, summary);
+                outln!();
+                print_table(&week_headers, weeks);
+                eprintln!(
+                    "{} cards finished {} – {}",
+                    report.finished,
+                    format_time(ctx, report.since),
+                    format_time(ctx, report.until)
+                );
+            }
+        }
+    }
+}
+
+fn print_flows(ctx: &CommandContext, flows: &[&Flow]) {
+    let time = |at: Option<DateTime<Utc>>| at.map(|t| t.to_rfc3339()).unwrap_or_default();
+    let days = |days: Option<f64>| days.map(|d| d.to_string()).unwrap_or_default();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&flows),
+        OutputFormat::Quiet => flows.iter().for_each(|f| outln!("{}", f.id)),
+        OutputFormat::Csv => {
+            let rows = flows
+                .iter()
+                .map(|f| {
+                    vec![
+                        f.id.clone(),
+                        f.title.clone(),
+                        f.created_at.to_rfc3339(),
+                        time(f.started_at),
+                        time(f.finished_at),
+                        days(f.lead_days()),
+                        days(f.cycle_days()),
+                    ]
+                })
+                .collect();
+            let headers = ["id", "title", "created_at", "started_at", "finished_at", "lead_days", "cycle_days"];
+            print_csv(&headers, rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = flows
+                .iter()
+                .map(|f| {
+                    vec![
+                        short_id(&f.id).to_string(),
+                        f.finished_at.map(|t| format_time(ctx, t)).unwrap_or_default(),
+                        days(f.lead_days()),
+                        days(f.cycle_days()),
+                        f.title.clone(),
+                    ]
+                })
+                .collect();
+            let headers = ["ID", "FINISHED", "LEAD (D)", "CYCLE (D)", "TITLE"];
+            match ctx.format {
+                OutputFormat::Markdown => print_markdown(&headers, rows),
+                _ => print_table(&headers, rows),
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! `blaze attach` / `blaze attachment` - Files on a card.
+//!
+//! Uploads and downloads go straight to the server: they aren't queued with
+//! `--offline`, recorded with `--record` or sent through the daemon.
+//! Downloads are kept in the blob cache (see [`crate::cache`]), and when the
+//! server reports an attachment's hash, a cached copy is used instead of
+//! downloading it again. `open` keeps a copy under the cache's files like
+//! `plan file open`. `blaze show` lists a card's attachments. Files on
+//! confidential cards are encrypted on the way up and decrypted on the way
+//! down (see [`crate::confidential`]).
+
+use crate::cache;
+use crate::context::{CommandContext, OutputFormat};
+use crate::editor;
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_json, print_table};
+use crate::types::Attachment;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Upload files to a card
+pub async fn attach(ctx: &CommandContext, card_id: &str, files: &[PathBuf]) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut uploaded = Vec::new();
+    for path in files {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| BlazeError::InvalidInput(format!("{} is not a file name", path.display())))?;
+        let content = fs::read(path)
+            .map_err(|e| BlazeError::InvalidInput(format!("Can't read {}: {}", path.display(), e)))?;
+        match ctx.client.upload_attachment(&card_id, name, content).await {
+            Ok(attachment) => uploaded.push(attachment),
+            Err(BlazeError::DryRun) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    print_attachments(ctx, &uploaded);
+    Ok(())
+}
+
+/// List a card's attachments
+pub async fn list(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let attachments = ctx.client.list_attachments(&card_id).await?;
+    print_attachments(ctx, &attachments);
+    Ok(())
+}
+
+/// Download an attachment into `output` (a directory or file name; default: the current directory)
+pub async fn get(ctx: &CommandContext, card_id: &str, name: &str, output: Option<PathBuf>, force: bool) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    // The server names the file; never let that name climb out of the target directory
+    let file_name = Path::new(name)
+        .file_name()
+        .ok_or_else(|| BlazeError::InvalidInput(format!("'{}' is not a file name", name)))?;
+    let target = match output {
+        Some(path) if path.is_dir() => path.join(file_name),
+        Some(path) => path,
+        None => PathBuf::from(file_name),
+    };
+    if target.exists() && !force {
+        return Err(BlazeError::InvalidInput(format!(
+            "{} already exists; pass --force to overwrite it",
+            target.display()
+        )));
+    }
+
+    let content = fetch(ctx, &card_id, name).await?;
+    fs::write(&target, &content)?;
+    eprintln!("Saved {} ({} bytes)", target.display(), content.len());
+    if ctx.format == OutputFormat::Quiet {
+        outln!("{}", target.display());
+    }
+    Ok(())
+}
+
+/// Download an attachment to the cache and open it with the default application
+pub async fn open(ctx: &CommandContext, card_id: &str, name: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let content = fetch(ctx, &card_id, name).await?;
+    let source = format!("attachment:{}/{}", card_id, name);
+    let path = cache::store(&["attachments", &card_id, name], &source, &content)?;
+
+    editor::open_path(&path)?;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => {
+            print_json(&serde_json::json!({ "source": source, "path": path }))
+        }
+        _ => outln!("{}", path.display()),
+    }
+    Ok(())
+}
+
+/// An attachment's content, from the cache if the server's hash for it is there
+async fn fetch(ctx: &CommandContext, card_id: &str, name: &str) -> Result<Vec<u8>> {
+    let attachments = ctx.client.list_attachments(card_id).await?;
+    let attachment = attachments
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| BlazeError::InvalidInput(format!("Card {} has no attachment '{}'", card_id, name)))?;
+    ctx.client.download_attachment(card_id, name, attachment.sha256.as_deref()).await
+}
+
+fn print_attachments(ctx: &CommandContext, attachments: &[Attachment]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(attachments),
+        OutputFormat::Quiet => attachments.iter().for_each(|a| outln!("{}", a.name)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = attachments
+                .iter()
+                .map(|a| {
+                    vec![
+                        a.name.clone(),
+                        a.size.to_string(),
+                        a.content_type.clone().unwrap_or_default(),
+                        a.uploaded_at.map(|t| format_time(ctx, t)).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["NAME", "BYTES", "TYPE", "UPLOADED"], rows);
+        }
+    }
+}
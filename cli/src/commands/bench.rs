@@ -0,0 +1,265 @@
+//! `blaze bench` - Measure API latency from this client.
+//!
+//! Sends `--requests` requests per operation, `--concurrency` at a time, and
+//! reports latency percentiles and error rates. Cards made by the `create`
+//! run are tagged `blaze-bench` and deleted afterwards. Timings include the
+//! client's retries, as a real command would see them.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table};
+use crate::progress::Progress;
+use crate::types::{Card, CardCreate, Column, Priority};
+use clap::ValueEnum;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Tag on the cards `create` makes
+const BENCH_TAG: &str = "blaze-bench";
+
+/// Requests `blaze bench` can measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BenchOp {
+    /// GET /api/cards
+    List,
+    /// GET /api/cards/{id}
+    Get,
+    /// POST /api/cards (deleted again afterwards)
+    Create,
+}
+
+impl BenchOp {
+    fn name(self) -> &'static str {
+        match self {
+            BenchOp::List => "list",
+            BenchOp::Get => "get",
+            BenchOp::Create => "create",
+        }
+    }
+}
+
+/// Options for `blaze bench`
+pub struct BenchOptions {
+    /// Requests per operation
+    pub requests: usize,
+    /// Requests in flight at once
+    pub concurrency: usize,
+    /// Operations to measure (all if empty)
+    pub ops: Vec<BenchOp>,
+}
+
+/// Latency summary for one operation, in milliseconds
+#[derive(Debug, Serialize)]
+struct OpStats {
+    op: &'static str,
+    requests: usize,
+    errors: usize,
+    error_rate: f64,
+    min_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+    mean_ms: f64,
+    requests_per_sec: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_error: Option<String>,
+}
+
+pub async fn run(ctx: &CommandContext, options: BenchOptions) -> Result<()> {
+    if ctx.offline || ctx.dry_run {
+        return Err(BlazeError::InvalidInput("Benchmarks need to reach the server (no --offline or --dry-run)".into()));
+    }
+    if options.requests == 0 {
+        return Err(BlazeError::InvalidInput("--requests must be at least 1".into()));
+    }
+    let ops = if options.ops.is_empty() {
+        vec![BenchOp::List, BenchOp::Get, BenchOp::Create]
+    } else {
+        options.ops.clone()
+    };
+
+    // `get` needs a card to fetch; make one if the board is empty
+    let mut scratch: Option<String> = None;
+    let target = if ops.contains(&BenchOp::Get) {
+        match ctx.client.list_cards(None, false).await?.into_iter().next() {
+            Some(card) => Some(card.id),
+            None => {
+                let card = ctx.client.create_card(&bench_card("bench target")?).await?;
+                scratch = Some(card.id.clone());
+                Some(card.id)
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut stats = Vec::new();
+    let mut created = Vec::new();
+    for op in ops {
+        eprintln!("{}: {} requests, {} at a time", op.name(), options.requests, options.concurrency);
+        let (summary, cards) = match op {
+            BenchOp::List => {
+                measure(op, &options, |_| async { ctx.client.list_cards(None, false).await.map(|_| None) }).await
+            }
+            BenchOp::Get => {
+                let id = target.as_deref().unwrap_or_default();
+                measure(op, &options, |_| async move { ctx.client.get_card(id).await.map(|_| None) }).await
+            }
+            BenchOp::Create => {
+                measure(op, &options, |n| async move {
+                    ctx.client.create_card(&bench_card(&format!("bench {}", n))?).await.map(Some)
+                })
+                .await
+            }
+        };
+        stats.push(summary);
+        created.extend(cards);
+    }
+
+    // Clean up, without timing it
+    let leftovers = created.iter().map(|c| c.id.clone()).chain(scratch);
+    let mut failed = 0;
+    for id in leftovers {
+        if ctx.client.delete_card(&id).await.is_err() {
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        eprintln!("Could not delete {} benchmark cards; find them with `blaze list --tag {}`", failed, BENCH_TAG);
+    }
+
+    print_stats(ctx, &stats);
+    Ok(())
+}
+
+fn bench_card(title: &str) -> Result<CardCreate> {
+    let card = CardCreate::builder()
+        .title(title)
+        .priority(Priority::Low)
+        .column(Column::Backlog)
+        .tag(BENCH_TAG)
+        .build()?;
+    Ok(card)
+}
+
+/// Run `request` `options.requests` times and summarize; returns any cards it created
+async fn measure<F, Fut>(op: BenchOp, options: &BenchOptions, request: F) -> (OpStats, Vec<Card>)
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = Result<Option<Card>>>,
+{
+    let progress = &Progress::start(format!("bench {}", op.name()), Some(options.requests));
+    let started = Instant::now();
+    let results: Vec<(Duration, Result<Option<Card>>)> = stream::iter(0..options.requests)
+        .map(|n| {
+            let fut = request(n);
+            async move {
+                let sent = Instant::now();
+                let result = fut.await;
+                let latency = sent.elapsed();
+                progress.advance();
+                (latency, result)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect()
+        .await;
+    let elapsed = started.elapsed();
+
+    let mut latencies: Vec<Duration> = Vec::with_capacity(results.len());
+    let mut cards = Vec::new();
+    let mut errors = 0;
+    let mut first_error = None;
+    for (latency, result) in results {
+        match result {
+            Ok(card) => {
+                latencies.push(latency);
+                cards.extend(card);
+            }
+            Err(e) => {
+                errors += 1;
+                first_error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+    latencies.sort();
+
+    let ms = |d: Duration| (d.as_secs_f64() * 1000.0 * 10.0).round() / 10.0;
+    let mean = match latencies.len() {
+        0 => Duration::ZERO,
+        n => latencies.iter().sum::<Duration>() / n as u32,
+    };
+    let stats = OpStats {
+        op: op.name(),
+        requests: options.requests,
+        errors,
+        error_rate: errors as f64 / options.requests as f64,
+        min_ms: ms(latencies.first().copied().unwrap_or_default()),
+        p50_ms: ms(percentile(&latencies, 50.0)),
+        p90_ms: ms(percentile(&latencies, 90.0)),
+        p99_ms: ms(percentile(&latencies, 99.0)),
+        max_ms: ms(latencies.last().copied().unwrap_or_default()),
+        mean_ms: ms(mean),
+        requests_per_sec: (options.requests as f64 / elapsed.as_secs_f64() * 10.0).round() / 10.0,
+        first_error,
+    };
+    (stats, cards)
+}
+
+/// Nearest-rank percentile of sorted latencies (zero if there are none)
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn print_stats(ctx: &CommandContext, stats: &[OpStats]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(stats),
+        OutputFormat::Quiet => stats.iter().for_each(|s| outln!("{} {}", s.op, s.p50_ms)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = stats
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.op.to_string(),
+                        s.requests.to_string(),
+                        format!("{:.1}%", s.error_rate * 100.0),
+                        format!("{:.1}", s.p50_ms),
+                        format!("{:.1}", s.p90_ms),
+                        format!("{:.1}", s.p99_ms),
+                        format!("{:.1}", s.max_ms),
+                        format!("{:.1}", s.requests_per_sec),
+                    ]
+                })
+                .collect();
+            print_table(&["OP", "REQUESTS", "ERRORS", "P50 MS", "P90 MS", "P99 MS", "MAX MS", "REQ/S"], rows);
+            for s in stats {
+                if let Some(ref error) = s.first_error {
+                    eprintln!("{}: first error: {}", s.op, error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&sorted, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&sorted[..1], 90.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+}
@@ -1,11 +1,208 @@
 //! `blaze board` - Show board overview (column summary).
+//!
+//! Each column shows its count, a trend arrow against the latest `blaze
+//! metrics snapshot` of the board (when there is one), and a bar of its
+//! cards' priorities weighted by urgency (see `Priority::weight`).
+//!
+//! `--by tag|assignee|priority` splits the counts into swimlanes, one row per
+//! workstream, with the board's totals (and WIP limits) in a last row. Cards
+//! with several tags are counted in each of their lanes.
 
-use crate::client::Client;
+use crate::columns;
+use crate::commands::metrics as metrics_cmd;
+use crate::context::{CommandContext, OutputFormat};
 use crate::error::Result;
-use crate::output::print_board_summary;
+use crate::metrics::{MetricsDb, Snapshot};
+use crate::outln;
+use crate::output::{print_board_summary, print_csv, print_json, print_markdown, print_table};
+use crate::types::{Card, Column, Priority};
+use crate::wip::WipLimits;
+use clap::ValueEnum;
+use serde::Serialize;
 
-pub async fn run(client: &Client) -> Result<()> {
-    let cards = client.list_cards(None, false).await?;
-    print_board_summary(&cards);
+/// What `board --by` groups cards into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Swimlane {
+    Tag,
+    Assignee,
+    Priority,
+}
+
+/// One swimlane's cards per column
+#[derive(Debug, Serialize)]
+struct Lane {
+    lane: String,
+    total: usize,
+    columns: Vec<LaneCount>,
+}
+
+#[derive(Debug, Serialize)]
+struct LaneCount {
+    column: Column,
+    count: usize,
+}
+
+pub async fn run(ctx: &CommandContext, by: Option<Swimlane>) -> Result<()> {
+    let limits = WipLimits::from_config(&ctx.config.wip)?;
+    let cards = ctx.client.list_cards(None, false).await?;
+    let columns = columns::in_order(ctx.client.columns().await, &cards);
+    match by {
+        Some(by) => print_lanes(ctx, &lanes(&cards, &columns, by), &cards, &columns, &limits),
+        None => print_board_summary(ctx, &cards, &columns, &limits, previous_snapshot(ctx).as_ref()),
+    }
     Ok(())
 }
+
+/// The latest `metrics snapshot` of this board, if there's a database with one
+fn previous_snapshot(ctx: &CommandContext) -> Option<Snapshot> {
+    let path = metrics_cmd::db_path(ctx, None).ok().filter(|p| p.exists())?;
+    // Trends are a nicety; a database that can't be read just means none
+    let latest = MetricsDb::open_read_only(&path).and_then(|db| db.latest(metrics_cmd::board(ctx)));
+    match latest {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            if ctx.verbosity >= 1 {
+                eprintln!("No trends: {}", e);
+            }
+            None
+        }
+    }
+}
+
+/// Lanes in a fixed order: priorities most urgent first, otherwise by name
+/// with cards lacking a tag or assignee last
+fn lanes(cards: &[Card], columns: &[Column], by: Swimlane) -> Vec<Lane> {
+    let mut names: Vec<String> = match by {
+        Swimlane::Priority => Priority::value_variants().iter().rev().map(|p| p.to_string()).collect(),
+        Swimlane::Tag => cards.iter().flat_map(|c| c.tags.iter().cloned()).collect(),
+        Swimlane::Assignee => cards.iter().filter_map(|c| c.assignee.clone()).collect(),
+    };
+    if by != Swimlane::Priority {
+        names.sort_by_key(|n| n.to_lowercase());
+        names.dedup();
+    }
+    let none = match by {
+        Swimlane::Tag => Some("(untagged)"),
+        Swimlane::Assignee => Some("(unassigned)"),
+        Swimlane::Priority => None,
+    };
+
+    let in_lane = |card: &Card, lane: Option<&str>| match (by, lane) {
+        (Swimlane::Priority, Some(name)) => card.priority.to_string() == name,
+        (Swimlane::Tag, Some(name)) => card.tags.iter().any(|t| t == name),
+        (Swimlane::Tag, None) => card.tags.is_empty(),
+        (Swimlane::Assignee, Some(name)) => card.assignee.as_deref() == Some(name),
+        (Swimlane::Assignee, None) => card.assignee.is_none(),
+        (Swimlane::Priority, None) => false,
+    };
+    let lane = |label: &str, lane: Option<&str>| {
+        let members: Vec<&Card> = cards.iter().filter(|c| in_lane(c, lane)).collect();
+        Lane {
+            lane: label.to_string(),
+            total: members.len(),
+            columns: columns
+                .iter()
+                .map(|column| LaneCount {
+                    column: column.clone(),
+                    count: members.iter().filter(|c| c.column == *column).count(),
+                })
+                .collect(),
+        }
+    };
+
+    let mut lanes: Vec<Lane> = names.iter().map(|name| lane(name, Some(name))).collect();
+    if let Some(label) = none {
+        lanes.push(lane(label, None));
+    }
+    // Empty lanes only clutter the view (priorities keep theirs, there are four)
+    lanes.retain(|l| l.total > 0 || by == Swimlane::Priority);
+    lanes
+}
+
+fn print_lanes(ctx: &CommandContext, lanes: &[Lane], cards: &[Card], columns: &[Column], limits: &WipLimits) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(lanes),
+        OutputFormat::Quiet => lanes.iter().for_each(|l| outln!("{}", l.total)),
+        OutputFormat::Csv => {
+            let rows = lanes
+                .iter()
+                .flat_map(|l| {
+                    l.columns
+                        .iter()
+                        .map(|c| vec![l.lane.clone(), c.column.to_string(), c.count.to_string()])
+                })
+                .collect();
+            print_csv(&["lane", "column", "count"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let names: Vec<String> = columns.iter().map(|c| c.display_name().into_owned()).collect();
+            let mut headers: Vec<&str> = vec!["LANE"];
+            headers.extend(names.iter().map(String::as_str));
+            headers.push("TOTAL");
+
+            let mut rows: Vec<Vec<String>> = lanes
+                .iter()
+                .map(|l| {
+                    let mut row = vec![l.lane.clone()];
+                    row.extend(l.columns.iter().map(|c| c.count.to_string()));
+                    row.push(l.total.to_string());
+                    row
+                })
+                .collect();
+            // The whole board, with WIP limits as in the plain summary
+            let mut all = vec!["All".to_string()];
+            all.extend(columns.iter().map(|column| {
+                let count = cards.iter().filter(|c| c.column == *column).count();
+                match limits.limit(column) {
+                    Some(limit) if count > limit => ctx.theme.alert(&format!("{}/{}", count, limit)),
+                    Some(limit) => format!("{}/{}", count, limit),
+                    None => count.to_string(),
+                }
+            }));
+            all.push(cards.len().to_string());
+            rows.push(all);
+
+            match ctx.format {
+                OutputFormat::Markdown => print_markdown(&headers, rows),
+                _ => print_table(&headers, rows),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::json;
+
+    fn card(column: &str, tags: &[&str], assignee: Option<&str>) -> Card {
+        testing::card("c", json!({ "priority": "high", "column": column, "tags": tags, "assignee": assignee }))
+    }
+
+    #[test]
+    fn splits_columns_into_lanes() {
+        let cards = [
+            card("todo", &["payments", "api"], Some("dana")),
+            card("todo", &["payments"], None),
+            card("done", &[], Some("dana")),
+        ];
+        let columns = [Column::Todo, Column::Done];
+        let counts = |lane: &Lane| -> Vec<usize> { lane.columns.iter().map(|c| c.count).collect() };
+
+        let by_tag = lanes(&cards, &columns, Swimlane::Tag);
+        let names: Vec<&str> = by_tag.iter().map(|l| l.lane.as_str()).collect();
+        assert_eq!(names, ["api", "payments", "(untagged)"]);
+        assert_eq!(counts(&by_tag[1]), [2, 0]);
+        assert_eq!(counts(&by_tag[2]), [0, 1]);
+
+        let by_assignee = lanes(&cards, &columns, Swimlane::Assignee);
+        assert_eq!(by_assignee[0].lane, "dana");
+        assert_eq!(counts(&by_assignee[0]), [1, 1]);
+        assert_eq!(by_assignee[1].lane, "(unassigned)");
+
+        let by_priority = lanes(&cards, &columns, Swimlane::Priority);
+        assert_eq!(by_priority.len(), 4);
+        assert_eq!((by_priority[0].lane.as_str(), by_priority[1].total), ("urgent", 3));
+    }
+}
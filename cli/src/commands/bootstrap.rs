@@ -0,0 +1,386 @@
+//! `blaze bootstrap` - Give a fresh board a starting point.
+//!
+//! A template creates a handful of example cards (which bring the template's
+//! tags with them), saved views over those tags, and with `--plan` a plan
+//! with the usual files. Views are saved locally and shared through the
+//! server when it supports it.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table, short_id};
+use crate::types::{CardCreate, Column, PlanCreate, PlanFileCreate, Priority, SavedView};
+use crate::views::ViewStore;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Starter board layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// Features, bugs and releases
+    Software,
+    /// Home, errands and goals
+    Personal,
+    /// Customer tickets and escalations
+    Support,
+}
+
+struct StarterCard {
+    title: &'static str,
+    description: &'static str,
+    column: Column,
+    priority: Priority,
+    tags: &'static [&'static str],
+    criteria: &'static [&'static str],
+}
+
+struct StarterView {
+    name: &'static str,
+    query: &'static str,
+    description: &'static str,
+}
+
+struct StarterPlan {
+    title: &'static str,
+    files: &'static [(&'static str, &'static str)],
+}
+
+struct Starter {
+    cards: &'static [StarterCard],
+    views: &'static [StarterView],
+    plan: StarterPlan,
+}
+
+const SOFTWARE: Starter = Starter {
+    cards: &[
+        StarterCard {
+            title: "Set up CI for the main branch",
+            description: "Build and run the tests on every push.",
+            column: Column::Todo,
+            priority: Priority::High,
+            tags: &["infra"],
+            criteria: &["Tests run on every push", "Failures block merging"],
+        },
+        StarterCard {
+            title: "Write the README quick start",
+            description: "How to install, configure and run it in five minutes.",
+            column: Column::Backlog,
+            priority: Priority::Medium,
+            tags: &["docs"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Example bug: crash on empty input",
+            description: "Steps to reproduce, expected and actual behavior.",
+            column: Column::Todo,
+            priority: Priority::High,
+            tags: &["bug"],
+            criteria: &["Regression test added"],
+        },
+        StarterCard {
+            title: "Example feature: export to CSV",
+            description: "Who needs it and why.",
+            column: Column::Backlog,
+            priority: Priority::Low,
+            tags: &["feature"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Cut the first release",
+            description: "Changelog, version bump, tag and publish.",
+            column: Column::Backlog,
+            priority: Priority::Medium,
+            tags: &["release"],
+            criteria: &["Changelog updated", "Tag pushed"],
+        },
+    ],
+    views: &[
+        StarterView { name: "bugs", query: "tag:bug", description: "Open bugs" },
+        StarterView { name: "release", query: "tag:release", description: "Release checklist" },
+        StarterView {
+            name: "hot",
+            query: "priority:high,urgent AND NOT column:done",
+            description: "High-priority work not done yet",
+        },
+    ],
+    plan: StarterPlan {
+        title: "Project plan",
+        files: &[
+            ("overview.md", "# Overview\n\nWhat we're building and for whom.\n"),
+            ("architecture.md", "# Architecture\n\nComponents and how they talk to each other.\n"),
+            ("milestones.md", "# Milestones\n\n- [ ] First release\n"),
+        ],
+    },
+};
+
+const PERSONAL: Starter = Starter {
+    cards: &[
+        StarterCard {
+            title: "Plan this week",
+            description: "Pick the three things that matter most.",
+            column: Column::Todo,
+            priority: Priority::High,
+            tags: &["weekly"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Groceries",
+            description: "",
+            column: Column::Todo,
+            priority: Priority::Medium,
+            tags: &["errand"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Renew passport",
+            description: "Check the expiry date and book an appointment.",
+            column: Column::Backlog,
+            priority: Priority::Medium,
+            tags: &["admin"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Read one book this month",
+            description: "",
+            column: Column::Backlog,
+            priority: Priority::Low,
+            tags: &["goal"],
+            criteria: &[],
+        },
+    ],
+    views: &[
+        StarterView { name: "errands", query: "tag:errand", description: "Things to pick up or drop off" },
+        StarterView { name: "goals", query: "tag:goal", description: "Longer-term goals" },
+    ],
+    plan: StarterPlan {
+        title: "Personal goals",
+        files: &[
+            ("goals.md", "# Goals\n\n## This year\n\n## This quarter\n"),
+            ("review.md", "# Weekly review\n\n- What went well?\n- What didn't?\n- What's next?\n"),
+        ],
+    },
+};
+
+const SUPPORT: Starter = Starter {
+    cards: &[
+        StarterCard {
+            title: "Example ticket: can't log in",
+            description: "Customer, account, what they tried, screenshots.",
+            column: Column::Todo,
+            priority: Priority::High,
+            tags: &["ticket"],
+            criteria: &["Customer replied to", "Root cause noted"],
+        },
+        StarterCard {
+            title: "Example escalation: data export failing",
+            description: "Escalated to engineering with logs attached.",
+            column: Column::InProgress,
+            priority: Priority::Urgent,
+            tags: &["ticket", "escalated"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Update the FAQ with this week's top questions",
+            description: "",
+            column: Column::Backlog,
+            priority: Priority::Medium,
+            tags: &["kb"],
+            criteria: &[],
+        },
+        StarterCard {
+            title: "Write canned replies for common requests",
+            description: "",
+            column: Column::Backlog,
+            priority: Priority::Low,
+            tags: &["kb"],
+            criteria: &[],
+        },
+    ],
+    views: &[
+        StarterView {
+            name: "queue",
+            query: "tag:ticket AND column:todo",
+            description: "Tickets nobody has picked up",
+        },
+        StarterView { name: "escalated", query: "tag:escalated", description: "Waiting on engineering" },
+        StarterView { name: "kb", query: "tag:kb", description: "Knowledge base work" },
+    ],
+    plan: StarterPlan {
+        title: "Support playbook",
+        files: &[
+            ("triage.md", "# Triage\n\nHow to set priority, and when to escalate.\n"),
+            ("escalation.md", "# Escalation\n\nWho to contact, and what to include.\n"),
+            ("sla.md", "# Response times\n\n| Priority | First reply |\n|---|---|\n| urgent | 1 h |\n"),
+        ],
+    },
+};
+
+impl Template {
+    fn starter(self) -> &'static Starter {
+        match self {
+            Template::Software => &SOFTWARE,
+            Template::Personal => &PERSONAL,
+            Template::Support => &SUPPORT,
+        }
+    }
+}
+
+/// Options for `blaze bootstrap`
+pub struct BootstrapOptions {
+    pub template: Template,
+    /// Also create the template's plan
+    pub plan: bool,
+    /// Add the starter cards even if the board isn't empty
+    pub force: bool,
+}
+
+/// Something `blaze bootstrap` created
+#[derive(Debug, Serialize)]
+struct Created {
+    kind: &'static str,
+    id: String,
+    title: String,
+}
+
+pub async fn run(ctx: &CommandContext, options: BootstrapOptions) -> Result<()> {
+    if ctx.offline {
+        return Err(BlazeError::InvalidInput("Can't bootstrap with --offline".into()));
+    }
+    let existing = ctx.client.list_cards(None, true).await?;
+    if !existing.is_empty() && !options.force {
+        return Err(BlazeError::InvalidInput(format!(
+            "The board already has {} cards; use --force to add the starter cards anyway",
+            existing.len()
+        )));
+    }
+
+    let starter = options.template.starter();
+    let mut created = Vec::new();
+
+    for card in starter.cards {
+        let create = CardCreate::builder()
+            .title(card.title)
+            .maybe_description((!card.description.is_empty()).then(|| card.description.to_string()))
+            .priority(card.priority)
+            .column(card.column.clone())
+            .tags(card.tags.iter().copied())
+            .criteria(card.criteria.iter().copied())
+            .build()?;
+        match ctx.client.create_card(&create).await {
+            Ok(card) => created.push(Created {
+                kind: "card",
+                id: card.id,
+                title: card.title,
+            }),
+            Err(BlazeError::DryRun) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    if options.plan {
+        let create = PlanCreate {
+            title: starter.plan.title.to_string(),
+            files: starter
+                .plan
+                .files
+                .iter()
+                .map(|(name, content)| PlanFileCreate {
+                    name: name.to_string(),
+                    content: content.to_string(),
+                })
+                .collect(),
+        };
+        match ctx.client.create_plan(&create).await {
+            Ok(plan) => created.push(Created {
+                kind: "plan",
+                id: plan.id,
+                title: plan.title,
+            }),
+            Err(BlazeError::DryRun) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+
+    save_views(ctx, starter.views, &mut created).await?;
+    print_created(ctx, &created);
+    Ok(())
+}
+
+/// Save the views locally (keeping any of the same name) and share them
+async fn save_views(ctx: &CommandContext, views: &[StarterView], created: &mut Vec<Created>) -> Result<()> {
+    let mut store = ViewStore::load()?;
+    let mut added = Vec::new();
+    for view in views {
+        if store.get(view.name).is_ok() {
+            eprintln!("Keeping your existing view '{}'", view.name);
+            continue;
+        }
+        let view = SavedView {
+            name: view.name.to_string(),
+            query: view.query.to_string(),
+            description: Some(view.description.to_string()),
+        };
+        store.insert(view.clone());
+        added.push(view);
+    }
+    store.save()?;
+
+    for view in &added {
+        match ctx.client.put_view(view).await {
+            Ok(_) => {}
+            Err(e) if e.is_endpoint_missing() => {
+                eprintln!("Server does not support shared views; keeping views local only");
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    created.extend(added.into_iter().map(|v| Created {
+        kind: "view",
+        id: v.name,
+        title: v.description.unwrap_or_default(),
+    }));
+    Ok(())
+}
+
+fn print_created(ctx: &CommandContext, created: &[Created]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(created),
+        OutputFormat::Quiet => created.iter().for_each(|c| outln!("{}", c.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = created
+                .iter()
+                .map(|c| {
+                    let id = if c.kind == "view" { c.id.as_str() } else { short_id(&c.id) };
+                    vec![c.kind.to_string(), id.to_string(), c.title.clone()]
+                })
+                .collect();
+            print_table(&["KIND", "ID", "TITLE"], rows);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::Query;
+
+    #[test]
+    fn template_views_parse_and_match_their_cards() {
+        for template in [Template::Software, Template::Personal, Template::Support] {
+            let starter = template.starter();
+            for view in starter.views {
+                Query::parse(view.query).unwrap_or_else(|e| panic!("{}: {}", view.name, e));
+                // Every view's tag is introduced by some starter card
+                let tag = view.query.split_whitespace().find_map(|t| t.strip_prefix("tag:"));
+                if let Some(tag) = tag {
+                    assert!(starter.cards.iter().any(|c| c.tags.contains(&tag)), "{}", view.name);
+                }
+            }
+        }
+    }
+}
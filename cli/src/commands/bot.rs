@@ -0,0 +1,179 @@
+//! `blaze bot` - Drive blaze from another process over a line protocol.
+//!
+//! With `--transport stdjson`, each line on stdin is one JSON command and
+//! each command gets exactly one JSON line back on stdout, in order:
+//!
+//! ```text
+//! {"id": 1, "cmd": "list", "column": "todo", "assignee": "dana"}
+//! {"id": 1, "ok": true, "result": [...cards]}
+//! {"id": 2, "cmd": "move", "card": "3f2a", "column": "done"}
+//! {"id": 2, "ok": false, "error": "Card not found: 3f2a"}
+//! ```
+//!
+//! `id` is optional and echoed back as given. Commands are `list` (column,
+//! priority, tag, assignee, include_archived, limit), `add` (the fields of
+//! `add --batch`), `move` (card, column) and `show` (card); cards may be given
+//! by ID prefix. A failed command doesn't end the session; end of input does.
+//! Each command is its own operation for `blaze undo`.
+
+use crate::agent_rules::AgentRules;
+use crate::client::{CardFilter, PageRequest};
+use crate::commands::add;
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::types::{Column, Priority};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead};
+
+/// How `blaze bot` talks to the process driving it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BotTransport {
+    /// One JSON command per line on stdin, one JSON reply per line on stdout
+    #[default]
+    Stdjson,
+}
+
+/// One line of output
+#[derive(Serialize)]
+struct Reply {
+    #[serde(skip_serializing_if = "Value::is_null")]
+    id: Value,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListArgs {
+    #[serde(default)]
+    column: Option<Column>,
+    #[serde(default)]
+    priority: Vec<Priority>,
+    #[serde(default)]
+    tag: Vec<String>,
+    #[serde(default)]
+    assignee: Option<String>,
+    #[serde(default)]
+    include_archived: bool,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MoveArgs {
+    card: String,
+    column: Column,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShowArgs {
+    card: String,
+}
+
+pub async fn run(ctx: &CommandContext, transport: BotTransport) -> Result<()> {
+    match transport {
+        BotTransport::Stdjson => run_stdjson(ctx).await,
+    }
+}
+
+async fn run_stdjson(ctx: &CommandContext) -> Result<()> {
+    // Both print requests to stdout, between the replies
+    if ctx.dry_run || ctx.offline {
+        return Err(BlazeError::InvalidInput("blaze bot can't run with --dry-run or --offline".into()));
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, result) = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(mut command)) => {
+                let id = command.remove("id").unwrap_or(Value::Null);
+                (id, execute(ctx, command).await)
+            }
+            Ok(_) => (Value::Null, Err(BlazeError::InvalidInput("Commands must be JSON objects".into()))),
+            Err(e) => (Value::Null, Err(e.into())),
+        };
+        let reply = match result {
+            Ok(result) => Reply {
+                id,
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => Reply {
+                id,
+                ok: false,
+                result: None,
+                error: Some(e.to_string()),
+            },
+        };
+        // Not print_json: --query mustn't reshape the protocol
+        outln!("{}", serde_json::to_string(&reply)?);
+    }
+    Ok(())
+}
+
+/// Run one command; its arguments are the object's other fields
+async fn execute(ctx: &CommandContext, mut command: serde_json::Map<String, Value>) -> Result<Value> {
+    let name = match command.remove("cmd") {
+        Some(Value::String(name)) => name,
+        _ => return Err(BlazeError::InvalidInput("Missing \"cmd\" (list, add, move or show)".into())),
+    };
+    let args = Value::Object(command);
+    ctx.begin_operation(&format!("bot {}", name));
+
+    match name.as_str() {
+        "list" => {
+            let args: ListArgs = serde_json::from_value(args)?;
+            let filter = CardFilter {
+                column: args.column,
+                include_archived: args.include_archived,
+                priorities: args.priority,
+                tags: args.tag,
+                ..CardFilter::default()
+            };
+            // The assignee is matched here, so the server can't stop at the limit
+            let request = PageRequest {
+                limit: args.limit.filter(|_| args.assignee.is_none()),
+                page: None,
+            };
+            let mut cards = ctx.client.list_cards_where(&filter, request).await?;
+            if let Some(ref name) = args.assignee {
+                cards.retain(|card| card.assignee.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(name)));
+            }
+            cards.truncate(args.limit.unwrap_or(usize::MAX));
+            Ok(serde_json::to_value(cards)?)
+        }
+        "add" => {
+            let card = add::card_from_json(ctx, args)?;
+            let created = add::create(ctx, &AgentRules::from_config(&ctx.config.agent_rules)?, &card).await?;
+            Ok(serde_json::to_value(created)?)
+        }
+        "move" => {
+            let args: MoveArgs = serde_json::from_value(args)?;
+            let rules = AgentRules::from_config(&ctx.config.agent_rules)?;
+            let card_id = ctx.resolve_card_id(&args.card).await?;
+            let moved = ctx.client.move_card(&card_id, args.column).await?;
+            Ok(serde_json::to_value(rules.apply(&ctx.client, moved).await?)?)
+        }
+        "show" => {
+            let args: ShowArgs = serde_json::from_value(args)?;
+            let card_id = ctx.resolve_card_id(&args.card).await?;
+            Ok(serde_json::to_value(ctx.client.get_card(&card_id).await?)?)
+        }
+        other => Err(BlazeError::InvalidInput(format!(
+            "Unknown command '{}' (expected list, add, move or show)",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,217 @@
+//! Applying one change to several cards with a per-card summary.
+//!
+//! Operations on more cards than `[bulk] confirm_above` (default 20) must be
+//! confirmed by typing a phrase like `delete 25 cards` (or passing it with
+//! `--confirm`; `--yes`/`--force` don't count), and are recorded in
+//! ~/.config/blaze/audit.jsonl before anything is sent.
+
+use crate::config::Config;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table, short_id};
+use crate::progress::Progress;
+use crate::types::Card;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::{self, Write};
+
+/// Default for `[bulk] confirm_above`
+const DEFAULT_CONFIRM_ABOVE: usize = 20;
+
+/// Outcome for one card
+#[derive(Serialize)]
+pub struct BulkOutcome {
+    pub id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of a multi-card operation
+#[derive(Serialize)]
+pub struct BulkSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkOutcome>,
+}
+
+/// One confirmed bulk operation in the audit log
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    at: DateTime<Utc>,
+    operation: &'a str,
+    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<&'a str>,
+    url: &'a str,
+    ids: &'a [String],
+}
+
+/// Whether an operation on `count` cards needs the typed confirmation
+pub fn needs_phrase(ctx: &CommandContext, count: usize) -> bool {
+    let limit = ctx.config.bulk.confirm_above.unwrap_or(DEFAULT_CONFIRM_ABOVE);
+    !ctx.dry_run && limit > 0 && count > limit
+}
+
+/// Require the confirmation phrase for large operations, then log them to the audit file
+fn guard(ctx: &CommandContext, operation: &str, ids: &[String]) -> Result<()> {
+    if !needs_phrase(ctx, ids.len()) {
+        return Ok(());
+    }
+
+    let phrase = format!("{} {} cards", operation, ids.len());
+    let confirmed = match ctx.confirm {
+        Some(ref given) => given.trim() == phrase,
+        None => {
+            eprint!("This will {} {} cards. Type \"{}\" to continue: ", operation, ids.len(), phrase);
+            io::stderr().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            input.trim() == phrase
+        }
+    };
+    if !confirmed {
+        return Err(BlazeError::InvalidInput(format!(
+            "Not confirmed; type (or pass --confirm) \"{}\" exactly",
+            phrase
+        )));
+    }
+
+    let entry = AuditEntry {
+        at: Utc::now(),
+        operation,
+        count: ids.len(),
+        profile: ctx.profile(),
+        url: &ctx.url,
+        ids,
+    };
+    // Nothing is sent unless the record was written
+    let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+    std::fs::create_dir_all(&dir)?;
+    let mut log = OpenOptions::new().create(true).append(true).open(dir.join("audit.jsonl"))?;
+    writeln!(log, "{}", serde_json::to_string(&entry)?)?;
+    Ok(())
+}
+
+/// Run `op` for every card ID, continuing past failures, then print a summary.
+///
+/// `operation` is the verb shown in the confirmation phrase (e.g. `delete`).
+/// Returns an error if any card failed so the exit status reflects it.
+pub async fn apply<F, Fut>(ctx: &CommandContext, operation: &str, ids: &[String], op: F) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Card>>,
+{
+    apply_concurrent(ctx, operation, ids, 1, op).await
+}
+
+/// Like [`apply`], with up to `concurrency` cards in flight at once.
+///
+/// Results are reported in the order of `ids`.
+pub async fn apply_concurrent<F, Fut>(
+    ctx: &CommandContext,
+    operation: &str,
+    ids: &[String],
+    concurrency: usize,
+    op: F,
+) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Card>>,
+{
+    guard(ctx, operation, ids)?;
+
+    let progress = &Progress::start(operation, Some(ids.len()));
+    let outcomes: Vec<(&String, Result<Card>)> = stream::iter(ids)
+        .map(|id| {
+            let fut = op(id.clone());
+            async move {
+                let outcome = fut.await;
+                progress.advance();
+                (id, outcome)
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut results = Vec::with_capacity(ids.len());
+    for (id, outcome) in outcomes {
+        let outcome = match outcome {
+            Ok(card) => BulkOutcome {
+                id: card.id,
+                ok: true,
+                title: Some(card.title),
+                error: None,
+            },
+            // The request was printed (or queued) instead of sent; keep going for the rest
+            Err(BlazeError::DryRun | BlazeError::Queued) => continue,
+            Err(e) => BulkOutcome {
+                id: id.clone(),
+                ok: false,
+                title: None,
+                error: Some(e.to_string()),
+            },
+        };
+        results.push(outcome);
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    if ctx.offline {
+        return Err(BlazeError::Queued);
+    }
+
+    let succeeded = results.iter().filter(|r| r.ok).count();
+    let summary = BulkSummary {
+        profile: ctx.profile().map(String::from),
+        total: results.len(),
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    };
+    print_summary(ctx, &summary);
+
+    if summary.failed > 0 {
+        return Err(BlazeError::PartialFailure {
+            failed: summary.failed,
+            total: summary.total,
+        });
+    }
+    Ok(())
+}
+
+fn print_summary(ctx: &CommandContext, summary: &BulkSummary) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(summary),
+        OutputFormat::Quiet => summary
+            .results
+            .iter()
+            .filter(|r| r.ok)
+            .for_each(|r| outln!("{}", r.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = summary
+                .results
+                .iter()
+                .map(|r| {
+                    vec![
+                        short_id(&r.id).to_string(),
+                        if r.ok { "ok" } else { "failed" }.to_string(),
+                        r.title.clone().or_else(|| r.error.clone()).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "RESULT", "DETAIL"], rows);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! `blaze cache` - Inspect and clear downloaded files.
+
+use crate::cache;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::Result;
+use crate::outln;
+use crate::output::{format_time, print_json, print_table};
+
+/// List cached downloads
+pub async fn list(ctx: &CommandContext) -> Result<()> {
+    let entries = cache::entries()?;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&entries),
+        OutputFormat::Quiet => entries.iter().for_each(|e| outln!("{}", e.path.display())),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = entries
+                .iter()
+                .map(|e| {
+                    vec![
+                        e.source.clone(),
+                        e.bytes.to_string(),
+                        format_time(ctx, e.fetched_at),
+                        e.path.display().to_string(),
+                    ]
+                })
+                .collect();
+            print_table(&["SOURCE", "BYTES", "FETCHED", "PATH"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Remove all cached downloads
+pub async fn clear(ctx: &CommandContext) -> Result<()> {
+    let summary = cache::clear()?;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&summary),
+        _ => eprintln!("Removed {} cached file(s), {} bytes", summary.removed, summary.bytes),
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! `blaze changes` - Cards created, updated, moved or deleted since a point in time.
+//!
+//! `--since last` continues from where the previous `--since last` run left
+//! off. The cursor (last seen update time plus each card's column, to tell
+//! moves and deletions apart from edits) is kept per profile (or server URL
+//! without one) in ~/.config/blaze/changes.json.
+
+use crate::changes::{detect, Change, Cursor, CursorStore};
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::Result;
+use crate::outln;
+use crate::output::{format_time, print_json_line, print_table, short_id};
+use crate::parsers::Since;
+
+pub async fn run(ctx: &CommandContext, since: Since) -> Result<()> {
+    let cards = ctx.client.list_cards(None, true).await?;
+    let mut state = CursorStore::load("changes.json")?;
+    let key = ctx.profile().unwrap_or(&ctx.url).to_string();
+
+    let cursor = match since {
+        Since::Last => state.get(&key),
+        Since::At(_) => None,
+    };
+    let since_time = match since {
+        Since::At(at) => Some(at),
+        Since::Last => cursor.map(|c| c.last),
+    };
+
+    let changes = detect(&cards, cursor, since_time);
+    print_changes(ctx, &changes);
+
+    if since == Since::Last {
+        let cursor = Cursor::advance(&cards, cursor);
+        state.insert(key, cursor);
+        state.save()?;
+    }
+    Ok(())
+}
+
+/// Print changes as JSON lines, IDs or a table (shared with `blaze watch`)
+pub fn print_changes(ctx: &CommandContext, changes: &[Change]) {
+    match ctx.format {
+        // One compact object per line, so pollers can stream it
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => {
+            for change in changes {
+                print_json_line(change);
+            }
+        }
+        OutputFormat::Quiet => changes.iter().for_each(|c| outln!("{}", c.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = changes
+                .iter()
+                .map(|c| {
+                    let column = match (&c.from_column, &c.column) {
+                        (Some(from), Some(to)) => format!("{} → {}", from.display_name(), to.display_name()),
+                        (None, Some(to)) => to.display_name().to_string(),
+                        (Some(from), None) => from.display_name().to_string(),
+                        (None, None) => String::new(),
+                    };
+                    vec![
+                        format!("{:?}", c.change).to_lowercase(),
+                        short_id(&c.id).to_string(),
+                        column,
+                        c.updated_at.map(|t| format_time(ctx, t)).unwrap_or_default(),
+                        c.title.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["CHANGE", "ID", "COLUMN", "UPDATED", "TITLE"], rows);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! `blaze comment` - Discussion on a card.
+//!
+//! Comments are signed with `--author`, `author` in config.toml or `$USER`;
+//! the server only knows the API token. `blaze show --comments` prints them
+//! below the card, oldest first.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_json, print_table, short_id};
+use crate::types::{Comment, CommentCreate};
+
+/// Comment on a card
+pub async fn add(ctx: &CommandContext, card_id: &str, body: &str, author: Option<String>) -> Result<()> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(BlazeError::InvalidInput("Comment must not be empty".into()));
+    }
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let comment = CommentCreate {
+        body: body.to_string(),
+        author: author_or_default(ctx, author),
+    };
+    let comment = ctx.client.add_comment(&card_id, &comment).await?;
+    print_comments(ctx, std::slice::from_ref(&comment));
+    Ok(())
+}
+
+/// List a card's comments, oldest first
+pub async fn list(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let comments = ctx.client.list_comments(&card_id).await?;
+    print_comments(ctx, &comments);
+    Ok(())
+}
+
+/// Delete a comment by ID or unique ID prefix
+pub async fn rm(ctx: &CommandContext, card_id: &str, comment_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let comments = ctx.client.list_comments(&card_id).await?;
+    let matches: Vec<&Comment> = comments.iter().filter(|c| c.id.starts_with(comment_id)).collect();
+    let comment = match matches.as_slice() {
+        [comment] => *comment,
+        [] => {
+            return Err(BlazeError::InvalidInput(format!(
+                "Card {} has no comment {}",
+                card_id, comment_id
+            )))
+        }
+        _ => {
+            return Err(BlazeError::InvalidInput(format!(
+                "Comment ID '{}' matches {} comments; use more characters",
+                comment_id,
+                matches.len()
+            )))
+        }
+    };
+
+    ctx.client.delete_comment(&card_id, &comment.id).await?;
+    eprintln!("Deleted comment {} by {}", short_id(&comment.id), comment.author);
+    Ok(())
+}
+
+/// `author` if given, else `author` in config.toml, else $USER
+pub fn author_or_default(ctx: &CommandContext, author: Option<String>) -> Option<String> {
+    author
+        .or_else(|| ctx.config.author.clone())
+        .or_else(|| std::env::var("USER").ok())
+        .filter(|a| !a.trim().is_empty())
+}
+
+fn print_comments(ctx: &CommandContext, comments: &[Comment]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(comments),
+        OutputFormat::Quiet => comments.iter().for_each(|c| outln!("{}", c.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = comments
+                .iter()
+                .map(|c| {
+                    vec![
+                        short_id(&c.id).to_string(),
+                        format_time(ctx, c.created_at),
+                        c.author.clone(),
+                        c.body.replace('\n', " "),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "WHEN", "AUTHOR", "COMMENT"], rows);
+        }
+    }
+}
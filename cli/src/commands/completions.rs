@@ -0,0 +1,20 @@
+//! `blaze completions` - Print a shell completion script.
+//!
+//! The script asks blaze itself for candidates on every tab press, so card
+//! IDs complete from the live board. Install it with e.g.
+//! `blaze completions bash >> ~/.bashrc` or
+//! `blaze completions fish > ~/.config/fish/completions/blaze.fish`.
+
+use crate::error::{BlazeError, Result};
+use clap_complete::env::Shells;
+use clap_complete::Shell;
+use std::io;
+
+pub fn run(shell: Shell) -> Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell.to_string())
+        .ok_or_else(|| BlazeError::InvalidInput(format!("Dynamic completion doesn't support {}", shell)))?;
+    completer.write_registration("COMPLETE", "blaze", "blaze", "blaze", &mut io::stdout())?;
+    Ok(())
+}
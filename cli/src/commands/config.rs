@@ -0,0 +1,237 @@
+//! `blaze config` - Inspect and edit ~/.config/blaze/config.toml.
+//!
+//! These commands run before the command context is built, so they work
+//! even when the config points at an unreachable server or a missing token.
+
+use crate::auth::TokenStore;
+use crate::client::Client;
+use crate::config::{Config, Profile};
+use crate::context::OutputFormat;
+use crate::error::{BlazeError, Result};
+use crate::output::print_json;
+use crate::{out, outln};
+use std::fs;
+use std::io::{self, Write};
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, TableLike};
+
+/// Print the config file path
+pub fn path() -> Result<()> {
+    let path = Config::path().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+    outln!("{}", path.display());
+    Ok(())
+}
+
+/// Print the whole config, or the value at a dotted key such as `profiles.work.url`
+pub fn get(format: OutputFormat, key: Option<&str>) -> Result<()> {
+    let table = read_table()?;
+    let value = match key {
+        Some(key) => lookup(&table, key)
+            .cloned()
+            .ok_or_else(|| BlazeError::InvalidInput(format!("'{}' is not set", key)))?,
+        None => Value::Table(table),
+    };
+
+    match (format, value) {
+        (OutputFormat::Json | OutputFormat::Ndjson, value) => print_json(&value),
+        (_, Value::String(s)) => outln!("{}", s),
+        (_, Value::Table(t)) => out!("{}", toml::to_string_pretty(&t).unwrap_or_default()),
+        (_, value) => outln!("{}", value),
+    }
+    Ok(())
+}
+
+/// Set a dotted key, validating the result before saving
+pub fn set(key: &str, value: &str) -> Result<()> {
+    let mut document = read_document()?;
+    set_in(&mut document, key, value)?;
+    write_document(&document)
+}
+
+/// Remove a dotted key
+pub fn unset(key: &str) -> Result<()> {
+    let mut document = read_document()?;
+    unset_in(&mut document, key)?;
+    write_document(&document)
+}
+
+/// Set `key` in the document, leaving comments and everything else as they are
+fn set_in(document: &mut DocumentMut, key: &str, value: &str) -> Result<()> {
+    let (parent, last) = parent_mut(document.as_table_mut(), key)?;
+    parent.insert(last, toml_edit::Item::Value(parse_value(value)));
+    let config = check(document)?;
+
+    // A key Config doesn't know would be written, but never read
+    let known = Table::try_from(&config).map_err(|e| BlazeError::Config(e.to_string()))?;
+    if lookup(&known, key).is_none() {
+        return Err(BlazeError::InvalidInput(format!("Unknown config key '{}'", key)));
+    }
+    Ok(())
+}
+
+fn unset_in(document: &mut DocumentMut, key: &str) -> Result<()> {
+    let (parent, last) = parent_mut(document.as_table_mut(), key)?;
+    if parent.remove(last).is_none() {
+        return Err(BlazeError::InvalidInput(format!("'{}' is not set", key)));
+    }
+    check(document)?;
+    Ok(())
+}
+
+/// Ask for the server URL, token storage and token, then write the config.
+///
+/// With `--profile`, the answers go into `[profiles.<name>]` instead.
+pub async fn init(profile: Option<&str>) -> Result<()> {
+    let mut config = Config::load(None)?;
+    let current = match profile {
+        Some(name) => config.profiles.get(name).and_then(|p| p.url.clone()),
+        None => config.url.clone(),
+    };
+
+    let default_url = current.unwrap_or_else(|| "http://localhost:8080".to_string());
+    let url = prompt(&format!("Server URL [{}]: ", default_url))?;
+    let url = if url.is_empty() { default_url } else { url.trim_end_matches('/').to_string() };
+
+    let client = Client::new(&url, None)?;
+    if let Err(e) = client.health().await {
+        eprintln!("Warning: {} is not reachable right now ({})", url, e);
+    }
+
+    let keyring = prompt("Store tokens in the OS keyring? [Y/n] ")?;
+    let store = if keyring.eq_ignore_ascii_case("n") {
+        TokenStore::File
+    } else {
+        TokenStore::Keyring
+    };
+
+    match profile {
+        Some(name) => {
+            config.profiles.entry(name.to_string()).or_insert_with(Profile::default).url = Some(url.clone());
+        }
+        None => config.url = Some(url.clone()),
+    }
+    config.auth.store = store;
+    config.save()?;
+    eprintln!("Wrote {}", Config::path().unwrap().display());
+
+    let token = rpassword::prompt_password("API token (leave empty to skip): ")?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Ok(());
+    }
+    let identity = Client::new(&url, Some(token.to_string()))?.whoami().await?;
+    config.profile = profile.map(String::from);
+    config.save_token(token, &url).await?;
+    eprintln!("Logged in as {} ({})", identity.identity, store.describe());
+    Ok(())
+}
+
+fn prompt(question: &str) -> Result<String> {
+    eprint!("{}", question);
+    io::stderr().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+fn read_table() -> Result<Table> {
+    match Config::path() {
+        Some(path) if path.exists() => Ok(toml::from_str(&fs::read_to_string(path)?)?),
+        _ => Ok(Table::new()),
+    }
+}
+
+fn read_document() -> Result<DocumentMut> {
+    match Config::path() {
+        Some(path) if path.exists() => fs::read_to_string(path)?
+            .parse()
+            .map_err(|e: toml_edit::TomlError| BlazeError::Config(format!("Invalid config: {}", e.message()))),
+        _ => Ok(DocumentMut::new()),
+    }
+}
+
+/// Write the edited file as is, so hand-written comments and layout survive
+fn write_document(document: &DocumentMut) -> Result<()> {
+    let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+    fs::create_dir_all(&dir)?;
+    fs::write(Config::path().unwrap(), document.to_string())?;
+    Ok(())
+}
+
+/// Check an edited document is a valid config
+fn check(document: &DocumentMut) -> Result<Config> {
+    toml::from_str(&document.to_string())
+        .map_err(|e: toml::de::Error| BlazeError::Config(format!("Invalid config: {}", e.message())))
+}
+
+fn lookup<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let mut parts = key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// The table holding the last component of `key`, creating intermediate tables
+fn parent_mut<'a, 'k>(table: &'a mut dyn TableLike, key: &'k str) -> Result<(&'a mut dyn TableLike, &'k str)> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().filter(|p| !p.is_empty());
+    let last = last.ok_or_else(|| BlazeError::InvalidInput(format!("Invalid key '{}'", key)))?;
+
+    let mut current = table;
+    for part in parts {
+        current = current
+            .entry(part)
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| BlazeError::InvalidInput(format!("'{}' in '{}' is not a table", part, key)))?;
+    }
+    Ok((current, last))
+}
+
+/// Values are TOML literals when they parse as one (`true`, `30`), strings otherwise
+fn parse_value(input: &str) -> toml_edit::Value {
+    input
+        .parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| toml_edit::Value::from(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAND_WRITTEN: &str = "# my server\nurl = \"http://localhost:8080\"\n\n[profiles.work]\nurl = \"https://work.example.com\" # VPN only\n";
+
+    #[test]
+    fn set_edits_nested_keys_in_place() {
+        let mut document: DocumentMut = HAND_WRITTEN.parse().unwrap();
+        set_in(&mut document, "profiles.work.connection.proxy", "http://proxy:3128").unwrap();
+        set_in(&mut document, "retry.retries", "5").unwrap();
+
+        let text = document.to_string();
+        assert!(text.contains("# my server") && text.contains("# VPN only"), "{}", text);
+        let config = check(&document).unwrap();
+        assert_eq!(config.profiles["work"].connection.proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(config.retry.retries, Some(5));
+    }
+
+    #[test]
+    fn unset_of_a_missing_key_fails() {
+        let mut document: DocumentMut = HAND_WRITTEN.parse().unwrap();
+        let err = unset_in(&mut document, "profiles.work.connection.proxy").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid input: 'profiles.work.connection.proxy' is not set");
+
+        unset_in(&mut document, "profiles.work.url").unwrap();
+        assert!(document.to_string().contains("# my server"));
+    }
+
+    #[test]
+    fn set_rejects_unknown_keys() {
+        let mut document: DocumentMut = HAND_WRITTEN.parse().unwrap();
+        let err = set_in(&mut document, "profiles.work.colour", "red").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid input: Unknown config key 'profiles.work.colour'");
+        assert!(matches!(set_in(&mut document, "url.path", "x"), Err(BlazeError::InvalidInput(_))));
+        assert!(matches!(set_in(&mut document, "retry.", "x"), Err(BlazeError::InvalidInput(_))));
+    }
+}
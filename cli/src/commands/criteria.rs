@@ -0,0 +1,208 @@
+//! `blaze criteria` - Reusable acceptance criteria sets.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::criteria::{
+    criteria_to_subtasks, missing_criteria, parse_set_name, subtasks_to_criteria, CriteriaSet, CriteriaStore,
+};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_card_detail, print_json, print_table};
+use crate::types::CardUpdate;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// List local criteria sets
+pub async fn list(ctx: &CommandContext) -> Result<()> {
+    let sets = CriteriaStore::load()?.all();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&sets),
+        OutputFormat::Quiet => sets.iter().for_each(|s| outln!("{}", s.name)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = sets
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.name.clone(),
+                        s.criteria.len().to_string(),
+                        s.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["NAME", "CRITERIA", "DESCRIPTION"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Show a criteria set
+pub async fn show(ctx: &CommandContext, name: &str) -> Result<()> {
+    let set = CriteriaStore::load()?.get(name)?;
+    print_set(ctx, &set);
+    Ok(())
+}
+
+/// Save a set from the given criteria and/or an existing card's criteria
+pub async fn save_set(
+    ctx: &CommandContext,
+    name: String,
+    criteria: Vec<String>,
+    from_card: Option<&str>,
+    description: Option<String>,
+) -> Result<()> {
+    let mut all = Vec::new();
+    if let Some(card_id) = from_card {
+        let card_id = ctx.resolve_card_id(card_id).await?;
+        all.extend(ctx.client.get_card(&card_id).await?.acceptance_criteria);
+    }
+    for criterion in criteria {
+        let criterion = criterion.trim().to_string();
+        if criterion.is_empty() {
+            return Err(BlazeError::InvalidInput("Criteria must not be empty".into()));
+        }
+        if !all.contains(&criterion) {
+            all.push(criterion);
+        }
+    }
+    if all.is_empty() {
+        return Err(BlazeError::InvalidInput(
+            "No criteria to save; pass --criterion or --from-card".into(),
+        ));
+    }
+
+    let set = CriteriaSet {
+        name,
+        criteria: all,
+        description,
+    };
+    let mut store = CriteriaStore::load()?;
+    store.insert(set.clone());
+    store.save()?;
+    print_set(ctx, &set);
+    Ok(())
+}
+
+/// Append a set's criteria to a card, skipping ones it already has
+pub async fn apply_set(ctx: &CommandContext, card_id: &str, name: &str) -> Result<()> {
+    let set = CriteriaStore::load()?.get(name)?;
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.get_card(&card_id).await?;
+
+    let added = missing_criteria(&card.acceptance_criteria, &set);
+    if added.is_empty() {
+        eprintln!("Card already has every criterion in '{}'", name);
+        print_card_detail(ctx, &card);
+        return Ok(());
+    }
+
+    let mut criteria = card.acceptance_criteria;
+    criteria.extend(added);
+    let update = CardUpdate::builder().acceptance_criteria(Some(criteria)).build()?;
+    let card = ctx.client.update_card(&card_id, &update).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Move a card's acceptance criteria to its subtasks, checked ones as done
+pub async fn to_subtasks(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.get_card(&card_id).await?;
+    if card.acceptance_criteria.is_empty() {
+        return Err(BlazeError::InvalidInput(format!("Card {} has no acceptance criteria", card_id)));
+    }
+
+    let update = CardUpdate::builder()
+        .subtasks(Some(criteria_to_subtasks(&card)))
+        .acceptance_criteria(Some(Vec::new()))
+        .build()?;
+    let card = ctx.client.update_card(&card_id, &update).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Move a card's subtasks to its acceptance criteria, done ones as checked
+pub async fn from_subtasks(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut card = ctx.client.get_card(&card_id).await?;
+    if card.subtasks.is_empty() {
+        return Err(BlazeError::InvalidInput(format!("Card {} has no subtasks", card_id)));
+    }
+
+    let (criteria, checked) = subtasks_to_criteria(&card);
+    let update = CardUpdate::builder()
+        .acceptance_criteria(Some(criteria))
+        .subtasks(Some(Vec::new()))
+        .build()?;
+    card = ctx.client.update_card(&card_id, &update).await?;
+    // Replacing the criteria unchecks them all
+    for index in checked {
+        card = ctx.client.toggle_criterion(&card_id, index, true).await?;
+    }
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Remove a criteria set
+pub async fn rm_set(name: &str) -> Result<()> {
+    let mut store = CriteriaStore::load()?;
+    if !store.remove(name) {
+        return Err(BlazeError::InvalidInput(format!("Unknown criteria set '{}'", name)));
+    }
+    store.save()?;
+    eprintln!("Removed criteria set {}", name);
+    Ok(())
+}
+
+/// Print sets as a JSON array (all of them if no names are given)
+pub async fn export(names: &[String]) -> Result<()> {
+    let store = CriteriaStore::load()?;
+    let sets = if names.is_empty() {
+        store.all()
+    } else {
+        names.iter().map(|n| store.get(n)).collect::<Result<Vec<_>>>()?
+    };
+    print_json(&sets);
+    Ok(())
+}
+
+/// Read sets exported with `criteria export`, replacing local sets with the same name
+pub async fn import(file: Option<&Path>) -> Result<()> {
+    let content = match file {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path)?,
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let sets: Vec<CriteriaSet> = serde_json::from_str(&content)?;
+
+    let mut store = CriteriaStore::load()?;
+    let mut updated = 0;
+    for set in &sets {
+        if parse_set_name(&set.name).is_err() || set.criteria.iter().any(|c| c.trim().is_empty()) {
+            eprintln!("Skipping invalid criteria set '{}'", set.name);
+            continue;
+        }
+        if store.insert(set.clone()) {
+            updated += 1;
+        }
+    }
+    store.save()?;
+    eprintln!("Imported {} criteria set(s), {} updated", sets.len(), updated);
+    Ok(())
+}
+
+fn print_set(ctx: &CommandContext, set: &CriteriaSet) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(set),
+        OutputFormat::Quiet => outln!("{}", set.name),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            match set.description {
+                Some(ref desc) => outln!("{}: {}", set.name, desc),
+                None => outln!("{}:", set.name),
+            }
+            set.criteria.iter().for_each(|c| outln!("  - {}", c));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! `blaze daemon` - Manage the background connection daemon.
+
+use crate::context::CommandContext;
+use crate::daemon::{self, DaemonRequest, DaemonResponse};
+use crate::error::{BlazeError, Result};
+use crate::output::print_json;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct DaemonState {
+    running: bool,
+    socket: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<daemon::DaemonStatus>,
+}
+
+fn socket() -> Result<PathBuf> {
+    daemon::socket_path().ok_or_else(|| BlazeError::Config("No config directory".into()))
+}
+
+/// Query a running daemon, returning None if nothing is listening
+async fn query(socket: &Path) -> Option<daemon::DaemonStatus> {
+    match daemon::call(socket, &DaemonRequest::Status).await {
+        Ok(DaemonResponse::Status(status)) => Some(status),
+        _ => None,
+    }
+}
+
+/// Start the daemon in the background
+pub async fn start(ctx: &CommandContext) -> Result<()> {
+    let socket = socket()?;
+
+    if let Some(status) = query(&socket).await {
+        print_json(&DaemonState {
+            running: true,
+            socket,
+            status: Some(status),
+        });
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(["--url", &ctx.url]);
+    // The daemon lends its token to sessions without one, so it must be this profile's
+    if let Some(profile) = ctx.profile() {
+        cmd.args(["--profile", profile]);
+    }
+    cmd.args(["daemon", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Detach from the terminal's process group so Ctrl-C doesn't take the daemon down
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    cmd.spawn()?;
+
+    // Wait for the socket to come up
+    for _ in 0..50 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Some(status) = query(&socket).await {
+            print_json(&DaemonState {
+                running: true,
+                socket,
+                status: Some(status),
+            });
+            return Ok(());
+        }
+    }
+
+    Err(BlazeError::Daemon("Daemon did not start within 5 seconds".into()))
+}
+
+/// Stop a running daemon
+pub async fn stop() -> Result<()> {
+    let socket = socket()?;
+
+    if query(&socket).await.is_some() {
+        daemon::call(&socket, &DaemonRequest::Shutdown).await?;
+    } else if socket.exists() {
+        // Stale socket from a daemon that died
+        std::fs::remove_file(&socket)?;
+    }
+
+    print_json(&DaemonState {
+        running: false,
+        socket,
+        status: None,
+    });
+    Ok(())
+}
+
+/// Report whether the daemon is running
+pub async fn status() -> Result<()> {
+    let socket = socket()?;
+    let status = query(&socket).await;
+
+    print_json(&DaemonState {
+        running: status.is_some(),
+        socket,
+        status,
+    });
+    Ok(())
+}
+
+/// Run the daemon in the foreground
+pub async fn run(ctx: &CommandContext) -> Result<()> {
+    let socket = socket()?;
+    daemon::serve(&socket, &ctx.url, ctx.token.clone()).await
+}
@@ -0,0 +1,171 @@
+//! `blaze dep` - Dependencies between cards.
+//!
+//! `dep add <id> --blocked-by <other>` makes the card wait for `other`;
+//! `--blocks <other>` makes `other` wait for the card. Both are stored in the
+//! waiting card's `blocked_by`. Links that would close a cycle are refused.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::deps::{BlockerRef, DependencyIndex};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table, short_id};
+use crate::types::{Card, CardUpdate};
+use serde::Serialize;
+
+/// Both sides of a card's dependencies
+#[derive(Debug, Serialize)]
+struct Dependencies {
+    id: String,
+    title: String,
+    blocked_by: Vec<BlockerRef>,
+    blocks: Vec<BlockerRef>,
+}
+
+/// Make `card_id` wait for `blocked_by`, and `blocks` wait for `card_id`
+pub async fn add(ctx: &CommandContext, card_id: &str, blocks: &[String], blocked_by: &[String]) -> Result<()> {
+    let (card_id, blocks, blocked_by) = resolve(ctx, card_id, blocks, blocked_by).await?;
+    let all = ctx.client.list_cards(None, true).await?;
+    let index = DependencyIndex::new(&all);
+
+    // (waiting card, card it waits for)
+    let links = blocked_by
+        .iter()
+        .map(|other| (card_id.as_str(), other.as_str()))
+        .chain(blocks.iter().map(|other| (other.as_str(), card_id.as_str())));
+    let mut added: Vec<(&str, &str)> = Vec::new();
+    for (waiting, blocker) in links {
+        if waiting == blocker {
+            return Err(BlazeError::InvalidInput("A card can't depend on itself".into()));
+        }
+        if index.depends_on(blocker, waiting) || added.contains(&(blocker, waiting)) {
+            return Err(BlazeError::InvalidInput(format!(
+                "{} already waits on {}; this would be a dependency cycle",
+                short_id(blocker),
+                short_id(waiting)
+            )));
+        }
+        added.push((waiting, blocker));
+    }
+
+    change(ctx, &all, &added, |ids, blocker| {
+        if !ids.iter().any(|id| id == blocker) {
+            ids.push(blocker.to_string());
+        }
+    })
+    .await?;
+    list(ctx, &card_id).await
+}
+
+/// Undo `dep add`
+pub async fn rm(ctx: &CommandContext, card_id: &str, blocks: &[String], blocked_by: &[String]) -> Result<()> {
+    let (card_id, blocks, blocked_by) = resolve(ctx, card_id, blocks, blocked_by).await?;
+    let all = ctx.client.list_cards(None, true).await?;
+
+    let removed: Vec<(&str, &str)> = blocked_by
+        .iter()
+        .map(|other| (card_id.as_str(), other.as_str()))
+        .chain(blocks.iter().map(|other| (other.as_str(), card_id.as_str())))
+        .collect();
+    change(ctx, &all, &removed, |ids, blocker| ids.retain(|id| id != blocker)).await?;
+    list(ctx, &card_id).await
+}
+
+/// Show what a card waits for and what waits for it
+pub async fn list(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let all = ctx.client.list_cards(None, true).await?;
+    let card = match all.iter().find(|c| c.id == card_id) {
+        Some(card) => card.clone(),
+        None => ctx.client.get_card(&card_id).await?,
+    };
+    let index = DependencyIndex::new(&all);
+
+    let by_id = |id: &String| all.iter().find(|c| &c.id == id).map(blocker_ref);
+    let deps = Dependencies {
+        blocked_by: card.blocked_by.iter().filter_map(by_id).collect(),
+        blocks: index.blocks(&card).into_iter().map(blocker_ref).collect(),
+        id: card.id,
+        title: card.title,
+    };
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&deps),
+        OutputFormat::Quiet => deps.blocked_by.iter().for_each(|b| outln!("{}", b.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = [("blocked by", &deps.blocked_by), ("blocks", &deps.blocks)]
+                .into_iter()
+                .flat_map(|(relation, cards)| {
+                    cards.iter().map(move |b| {
+                        vec![
+                            relation.to_string(),
+                            short_id(&b.id).to_string(),
+                            ctx.theme.column(&b.column),
+                            b.title.clone(),
+                        ]
+                    })
+                })
+                .collect();
+            print_table(&["RELATION", "ID", "COLUMN", "TITLE"], rows);
+        }
+    }
+    Ok(())
+}
+
+async fn resolve(
+    ctx: &CommandContext,
+    card_id: &str,
+    blocks: &[String],
+    blocked_by: &[String],
+) -> Result<(String, Vec<String>, Vec<String>)> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut resolved = (Vec::new(), Vec::new());
+    for id in blocks {
+        resolved.0.push(ctx.resolve_card_id(id).await?);
+    }
+    for id in blocked_by {
+        resolved.1.push(ctx.resolve_card_id(id).await?);
+    }
+    Ok((card_id, resolved.0, resolved.1))
+}
+
+/// Apply `edit(blocked_by, blocker)` for each (waiting, blocker) pair, one update per waiting card
+async fn change(
+    ctx: &CommandContext,
+    all: &[Card],
+    pairs: &[(&str, &str)],
+    edit: impl Fn(&mut Vec<String>, &str),
+) -> Result<()> {
+    let mut waiting: Vec<&str> = pairs.iter().map(|(w, _)| *w).collect();
+    waiting.sort_unstable();
+    waiting.dedup();
+    for id in waiting {
+        let current = match all.iter().find(|c| c.id == id) {
+            Some(card) => card.blocked_by.clone(),
+            None => ctx.client.get_card(id).await?.blocked_by,
+        };
+        let mut blocked_by = current.clone();
+        for (_, blocker) in pairs.iter().filter(|(w, _)| *w == id) {
+            edit(&mut blocked_by, blocker);
+        }
+        if blocked_by == current {
+            continue;
+        }
+        let update = CardUpdate::builder().blocked_by(Some(blocked_by)).build()?;
+        match ctx.client.update_card(id, &update).await {
+            Ok(_) | Err(BlazeError::DryRun | BlazeError::Queued) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    Ok(())
+}
+
+fn blocker_ref(card: &Card) -> BlockerRef {
+    BlockerRef {
+        id: card.id.clone(),
+        title: card.title.clone(),
+        column: card.column.clone(),
+    }
+}
@@ -1,13 +1,18 @@
-//! `blaze edit` - Update an existing card.
+//! `blaze edit` - Update existing cards.
 
-use crate::client::Client;
-use crate::error::Result;
+use crate::agent_rules::AgentRules;
+use crate::commands::{bulk, comment};
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::escalation;
+use crate::goals::{self, GoalStore};
 use crate::output::print_card_detail;
-use crate::types::{CardUpdate, Column, Priority};
-use chrono::{NaiveDate, TimeZone, Utc};
+use crate::parsers::parse_due_date;
+use crate::types::{Card, CardUpdate, Column, CommentCreate, Priority, ValidationError};
+use chrono::{DateTime, Utc};
 
 pub struct EditOptions {
-    pub card_id: String,
+    pub card_ids: Vec<String>,
     pub title: Option<String>,
     pub description: Option<String>,
     pub column: Option<Column>,
@@ -16,13 +21,91 @@ pub struct EditOptions {
     pub tags_remove: Vec<String>,
     pub due: Option<String>,
     pub clear_due: bool,
+    pub agent: Option<bool>,
+    pub criteria_add: Vec<String>,
+    pub blocked_by_add: Vec<String>,
+    pub estimate: Option<f64>,
+    /// When `blaze maintenance expire` may close the card
+    pub expires: Option<DateTime<Utc>>,
+    /// Link to a goal from `blaze goal add`
+    pub goal: Option<String>,
+    /// Why the priority goes up, recorded as a comment
+    pub reason: Option<String>,
+    pub assignee: Option<String>,
+    pub unassign: bool,
 }
 
-pub async fn run(client: &Client, options: EditOptions) -> Result<()> {
-    // If we're modifying tags, fetch current card first
-    let tags = if !options.tags_add.is_empty() || !options.tags_remove.is_empty() {
-        let current = client.get_card(&options.card_id).await?;
-        let mut tags = current.tags;
+pub async fn run(ctx: &CommandContext, options: EditOptions) -> Result<()> {
+    let due_date = options.due.as_deref().map(|due| parse_due_date(due, ctx.tz)).transpose()?;
+
+    // Validate once up front so a bad flag doesn't fail every card separately
+    let tags_add = (!options.tags_add.is_empty()).then(|| options.tags_add.clone());
+    let update = build_update(&options, due_date, tags_add, None, None)?;
+
+    // Check if any fields are being updated
+    if update.is_empty()
+        && options.tags_remove.is_empty()
+        && options.criteria_add.is_empty()
+        && options.blocked_by_add.is_empty()
+        && options.goal.is_none()
+    {
+        return Err(ValidationError::NoChanges.into());
+    }
+
+    if let Some(ref goal) = options.goal {
+        GoalStore::load()?.get(goal)?;
+    }
+    if options.reason.as_deref().is_some_and(|r| r.trim().is_empty()) {
+        return Err(BlazeError::InvalidInput("--reason must not be empty".into()));
+    }
+
+    let rules = &AgentRules::from_config(&ctx.config.agent_rules)?;
+
+    // Resolve dependencies to full IDs once for all cards
+    let mut blockers = Vec::new();
+    for id in &options.blocked_by_add {
+        blockers.push(ctx.resolve_card_id(id).await?);
+    }
+
+    if let [card_id] = options.card_ids.as_slice() {
+        let updated = edit_one(ctx, card_id, &options, due_date, &blockers, rules).await?;
+        print_card_detail(ctx, &updated);
+        return Ok(());
+    }
+
+    let (options, blockers) = (&options, &blockers);
+    bulk::apply(ctx, "edit", &options.card_ids, |id| async move {
+        edit_one(ctx, &id, options, due_date, blockers, rules).await
+    })
+    .await
+}
+
+/// Update one card, merging tag and criteria changes with its current values
+async fn edit_one(
+    ctx: &CommandContext,
+    card_id: &str,
+    options: &EditOptions,
+    due_date: Option<DateTime<Utc>>,
+    blockers: &[String],
+    rules: &AgentRules,
+) -> Result<Card> {
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+
+    // If we're modifying tags, criteria, dependencies or priority, fetch current card first
+    let current = if !options.tags_add.is_empty()
+        || !options.tags_remove.is_empty()
+        || !options.criteria_add.is_empty()
+        || !blockers.is_empty()
+        || options.goal.is_some()
+        || options.priority.is_some()
+    {
+        Some(ctx.client.get_card(card_id).await?)
+    } else {
+        None
+    };
+
+    let tags = if !options.tags_add.is_empty() || !options.tags_remove.is_empty() || options.goal.is_some() {
+        let mut tags = current.as_ref().map(|c| c.tags.clone()).unwrap_or_default();
         
         // Add new tags
         for tag in &options.tags_add {
@@ -33,54 +116,103 @@ pub async fn run(client: &Client, options: EditOptions) -> Result<()> {
         
         // Remove tags
         tags.retain(|t| !options.tags_remove.contains(t));
+
+        // A card belongs to one goal at a time
+        if let Some(ref goal) = options.goal {
+            tags.retain(|t| !t.starts_with(goals::GOAL_TAG_PREFIX));
+            tags.push(goals::goal_tag(goal));
+        }
         
         Some(tags)
     } else {
         None
     };
 
-    // Parse due date if provided
-    let due_date = if options.clear_due {
-        // Explicitly clear the due date - but we need a way to signal this
-        // For now, we'll skip it if clear_due is set and let the API handle it
-        None
-    } else {
-        match options.due {
-            Some(ref s) => {
-                let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
-                    .map_err(|_| crate::error::BlazeError::InvalidInput(
-                        format!("Invalid date format '{}'. Use YYYY-MM-DD", s)
-                    ))?;
-                Some(Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap()))
+    // (from, to) when this edit raises the priority
+    let raise = match (&current, options.priority) {
+        (Some(card), Some(to)) if escalation::is_raise(card.priority, to) => Some((card.priority, to)),
+        _ => None,
+    };
+    if let (Some((_, Priority::Urgent)), None) = (raise, &options.reason) {
+        if ctx.config.escalation.require_reason.unwrap_or(true) {
+            return Err(BlazeError::InvalidInput(format!(
+                "Raising {} to urgent needs --reason (or set escalation.require_reason = false)",
+                card_id
+            )));
+        }
+    }
+
+    let blocked_by = match current {
+        Some(ref card) if !blockers.is_empty() => {
+            if blockers.contains(&card.id) {
+                return Err(BlazeError::InvalidInput(format!("Card {} cannot block itself", card.id)));
             }
-            None => None,
+            let mut blocked_by = card.blocked_by.clone();
+            for id in blockers {
+                if !blocked_by.contains(id) {
+                    blocked_by.push(id.clone());
+                }
+            }
+            Some(blocked_by)
         }
+        _ => None,
     };
 
-    let update = CardUpdate {
-        title: options.title,
-        description: options.description,
-        column: options.column,
-        priority: options.priority,
-        tags,
-        due_date,
+    // Criteria are replaced as a whole, so append to the existing list
+    let criteria = match current {
+        Some(card) if !options.criteria_add.is_empty() => {
+            if card.acceptance_checked.iter().any(|c| *c) {
+                eprintln!("Note: adding criteria resets the checked state of existing criteria");
+            }
+            let mut criteria = card.acceptance_criteria;
+            criteria.extend(options.criteria_add.iter().cloned());
+            Some(criteria)
+        }
+        _ => None,
     };
 
-    // Check if any fields are being updated
-    if update.title.is_none()
-        && update.description.is_none()
-        && update.column.is_none()
-        && update.priority.is_none()
-        && update.tags.is_none()
-        && update.due_date.is_none()
-        && !options.clear_due
-    {
-        return Err(crate::error::BlazeError::InvalidInput(
-            "No fields to update. Specify at least one option.".into()
-        ));
+    let update = build_update(options, due_date, tags, criteria, blocked_by)?;
+    let mut card = ctx.client.update_card(card_id, &update).await?;
+    // A moved card gets the rules, as with `blaze move`; an explicit --agent wins
+    if options.column.is_some() && options.agent.is_none() {
+        card = rules.apply(&ctx.client, card).await?;
     }
 
-    let updated = client.update_card(&options.card_id, &update).await?;
-    print_card_detail(&updated);
-    Ok(())
+    if let (Some((from, to)), Some(reason)) = (raise, &options.reason) {
+        let comment = CommentCreate {
+            body: escalation::comment_body(from, to, reason),
+            author: comment::author_or_default(ctx, None),
+        };
+        // The priority is already changed; a lost reason shouldn't look like a failed edit
+        if let Err(e) = ctx.client.add_comment(card_id, &comment).await {
+            eprintln!("Warning: reason for {} not recorded: {}", card_id, e);
+        }
+    }
+    Ok(card)
+}
+
+fn build_update(
+    options: &EditOptions,
+    due_date: Option<DateTime<Utc>>,
+    tags: Option<Vec<String>>,
+    criteria: Option<Vec<String>>,
+    blocked_by: Option<Vec<String>>,
+) -> Result<CardUpdate> {
+    let update = CardUpdate::builder()
+        .title(options.title.clone())
+        .description(options.description.clone())
+        .column(options.column.clone())
+        .priority(options.priority)
+        .tags(tags)
+        .due_date(due_date)
+        .clear_due_date(options.clear_due)
+        .agent_assignable(options.agent)
+        .acceptance_criteria(criteria)
+        .blocked_by(blocked_by)
+        .estimate(options.estimate)
+        .expires_at(options.expires)
+        .assignee(options.assignee.clone())
+        .unassign(options.unassign)
+        .build()?;
+    Ok(update)
 }
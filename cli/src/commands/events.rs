@@ -0,0 +1,172 @@
+//! `blaze events` - Follow board changes live.
+//!
+//! Events are printed one compact JSON object per line as they arrive. The
+//! connection is reopened when it drops; events sent while disconnected are
+//! missed (`blaze changes --since` can fill the gap).
+
+use crate::client::EventTransport;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_json_line, short_id};
+use crate::parsers::Query;
+use crate::types::{BoardEvent, Card};
+use std::time::Duration;
+
+/// Longest wait between reconnection attempts
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Options for `blaze events`
+pub struct EventsOptions {
+    /// `key=value` conditions, all of which must hold
+    pub filters: Vec<String>,
+    pub transport: EventTransport,
+}
+
+pub async fn run(ctx: &CommandContext, options: EventsOptions) -> Result<()> {
+    let filter = EventFilter::parse(&options.filters)?;
+    let mut connected_once = false;
+    let mut delay = Duration::from_secs(1);
+
+    loop {
+        match ctx.client.event_stream(options.transport).await {
+            Ok(mut stream) => {
+                if connected_once {
+                    eprintln!("Reconnected");
+                }
+                connected_once = true;
+                delay = Duration::from_secs(1);
+                loop {
+                    match stream.next().await {
+                        Ok(Some(event)) if filter.matches(&event) => print_event(ctx, &event),
+                        Ok(Some(_)) => {}
+                        Ok(None) => {
+                            eprintln!("Event stream closed by the server; reconnecting");
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!("Event stream interrupted ({}); reconnecting", e);
+                            break;
+                        }
+                    }
+                }
+            }
+            // Bad tokens and missing endpoints won't fix themselves
+            Err(e) if !connected_once || !e.is_unreachable() => return Err(e),
+            Err(e) => eprintln!("Reconnect failed ({}); retrying in {} s", e, delay.as_secs()),
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
+
+fn print_event(ctx: &CommandContext, event: &BoardEvent) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json_line(event),
+        OutputFormat::Quiet => {
+            if let Some(ref id) = event.card_id {
+                outln!("{}", id);
+            }
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let title = event_card(event).map(|c| c.title).unwrap_or_default();
+            outln!(
+                "{}  {:<16}  {:<8}  {}",
+                format_time(ctx, event.timestamp),
+                event.kind,
+                event.card_id.as_deref().map(short_id).unwrap_or("-"),
+                title
+            );
+        }
+    }
+}
+
+/// The card carried by the event, if any
+fn event_card(event: &BoardEvent) -> Option<Card> {
+    serde_json::from_value(event.data.get("card")?.clone()).ok()
+}
+
+/// `--filter` conditions
+#[derive(Debug, Default)]
+struct EventFilter {
+    /// Event types, e.g. `card.moved` or just `moved`
+    kinds: Vec<String>,
+    /// Card ID prefixes
+    cards: Vec<String>,
+    /// Conditions on the card in the event, as in `list --filter`
+    query: Option<Query>,
+}
+
+impl EventFilter {
+    fn parse(filters: &[String]) -> Result<Self> {
+        let mut filter = EventFilter::default();
+        for item in filters {
+            let (key, value) = item.split_once('=').ok_or_else(|| {
+                BlazeError::InvalidInput(format!("Filter '{}' should look like key=value, e.g. column=in_progress", item))
+            })?;
+            let values = value.split(',').map(str::trim).filter(|v| !v.is_empty());
+            match key.trim() {
+                "type" => filter.kinds.extend(values.map(|v| v.replace('_', "."))),
+                "card" | "id" => filter.cards.extend(values.map(String::from)),
+                // column, priority, tag, agent, title, due
+                field => {
+                    let term = Query::parse(&format!("{}:{}", field, value))?;
+                    filter.query = Some(match filter.query.take() {
+                        Some(query) => Query::And(Box::new(query), Box::new(term)),
+                        None => term,
+                    });
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    fn matches(&self, event: &BoardEvent) -> bool {
+        let kind_ok = self.kinds.is_empty()
+            || self
+                .kinds
+                .iter()
+                .any(|k| event.kind == *k || event.kind.ends_with(&format!(".{}", k)));
+        let card_ok = self.cards.is_empty()
+            || event
+                .card_id
+                .as_deref()
+                .is_some_and(|id| self.cards.iter().any(|prefix| id.starts_with(prefix.as_str())));
+        let query_ok = match self.query {
+            Some(ref query) => event_card(event).is_some_and(|card| query.matches(&card)),
+            None => true,
+        };
+        kind_ok && card_ok && query_ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn moved(column: &str) -> BoardEvent {
+        serde_json::from_value(serde_json::json!({
+            "type": "card.moved",
+            "card_id": "84eb0f2a11b7",
+            "timestamp": "2026-01-05T10:00:00Z",
+            "data": { "card": testing::card("84eb0f2a11b7", serde_json::json!({ "column": column, "tags": ["auth"] })) }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn filters_combine_type_card_and_fields() {
+        let filter = EventFilter::parse(&["column=in_progress".into(), "type=moved".into()]).unwrap();
+        assert!(filter.matches(&moved("in_progress")));
+        assert!(!filter.matches(&moved("review")));
+
+        let filter = EventFilter::parse(&["card=84eb".into(), "tag=auth,ui".into()]).unwrap();
+        assert!(filter.matches(&moved("todo")));
+        assert!(!EventFilter::parse(&["card=ffff".into()]).unwrap().matches(&moved("todo")));
+        assert!(!EventFilter::parse(&["type=card_deleted".into()]).unwrap().matches(&moved("todo")));
+
+        assert!(EventFilter::parse(&["column".into()]).is_err());
+        assert!(EventFilter::parse(&["colour=red".into()]).is_err());
+    }
+}
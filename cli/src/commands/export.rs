@@ -0,0 +1,390 @@
+//! `blaze export` / `blaze import` - Move a whole board between servers.
+//!
+//! The export is a single JSON or YAML document holding every card (and,
+//! optionally, plans) as the API returns them. Import recreates them through
+//! the normal endpoints, so cards get new IDs: `blocked_by` is rewritten to
+//! the new IDs, subtasks and links are set again, and agent progress entries
+//! are replayed in order (they get the import time as their timestamp).
+
+use crate::client::Client;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::output::{print_json, print_table, short_id};
+use crate::progress::Progress;
+use crate::types::{Card, CardCreate, CardUpdate, Plan, PlanCreate, PlanFileCreate, PlanStatus, PlanUpdate};
+use crate::{out, outln};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Bumped when the document layout changes incompatibly
+const EXPORT_VERSION: u32 = 1;
+
+/// The exported board
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoardExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// URL of the server the board came from
+    pub source: String,
+    pub cards: Vec<Card>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plans: Vec<Plan>,
+}
+
+impl BoardExport {
+    /// The board as of now; confidential descriptions stay encrypted, as on the server
+    fn new(client: &Client, source: String, cards: Vec<Card>, plans: Vec<Plan>) -> Result<Self> {
+        Ok(Self {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            source,
+            cards: cards.into_iter().map(|c| client.seal_card(c)).collect::<Result<_>>()?,
+            plans,
+        })
+    }
+}
+
+/// Options for `blaze export`
+pub struct ExportOptions {
+    /// Include plans and their files
+    pub plans: bool,
+    /// Include archived cards (they are imported as active cards)
+    pub include_archived: bool,
+    /// Write YAML instead of JSON
+    pub yaml: bool,
+    /// File to write (stdout if not set)
+    pub output: Option<std::path::PathBuf>,
+}
+
+pub async fn export(ctx: &CommandContext, options: ExportOptions) -> Result<()> {
+    let mut cards = ctx.client.list_cards(None, options.include_archived).await?;
+    cards.sort_by_key(|c| (c.column.rank(), c.position, c.created_at));
+    let plans = if options.plans {
+        ctx.client.list_plans(None).await?
+    } else {
+        Vec::new()
+    };
+
+    let board = BoardExport::new(&ctx.client, ctx.url.clone(), cards, plans)?;
+
+    // A .yaml/.yml output file implies --yaml
+    let yaml = options.yaml
+        || options
+            .output
+            .as_deref()
+            .and_then(|p| p.extension())
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    let document = if yaml {
+        serde_yaml::to_string(&board)?
+    } else {
+        serde_json::to_string_pretty(&board)? + "\n"
+    };
+
+    match options.output {
+        Some(path) => {
+            fs::write(&path, document)?;
+            eprintln!(
+                "Exported {} cards and {} plans to {}",
+                board.cards.len(),
+                board.plans.len(),
+                path.display()
+            );
+        }
+        None => out!("{}", document),
+    }
+    Ok(())
+}
+
+/// One card or plan created by `blaze import`
+#[derive(Debug, Serialize)]
+struct Imported {
+    kind: &'static str,
+    source_id: String,
+    id: String,
+    title: String,
+}
+
+/// Recreate an exported board (`-` or no file reads stdin)
+pub async fn import(ctx: &CommandContext, file: Option<&Path>, skip_plans: bool) -> Result<()> {
+    if ctx.offline {
+        return Err(BlazeError::InvalidInput("Can't import with --offline".into()));
+    }
+
+    let content = match file {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path)?,
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let board = parse_export(&content)?;
+
+    let mut imported = Vec::new();
+    // Exported ID -> ID on this server
+    let mut ids: HashMap<String, String> = HashMap::new();
+
+    let progress = Progress::start("import cards", Some(board.cards.len()));
+    for card in &board.cards {
+        let created = match ctx.client.create_card(&card_create(card)).await {
+            Ok(created) => created,
+            Err(BlazeError::DryRun) => {
+                progress.advance();
+                continue;
+            }
+            Err(e) => return Err(import_failed(&imported, &card.title, e)),
+        };
+        restore_agent_fields(ctx, card, &created.id)
+            .await
+            .map_err(|e| import_failed(&imported, &card.title, e))?;
+        ids.insert(card.id.clone(), created.id.clone());
+        imported.push(Imported {
+            kind: "card",
+            source_id: card.id.clone(),
+            id: created.id,
+            title: created.title,
+        });
+        progress.advance();
+    }
+
+    // Dependencies can only be set once every card has its new ID
+    let dependent: Vec<&Card> = board.cards.iter().filter(|c| !c.blocked_by.is_empty()).collect();
+    let progress = Progress::start("import dependencies", Some(dependent.len()));
+    for card in dependent {
+        let Some(id) = ids.get(&card.id) else {
+            progress.advance();
+            continue;
+        };
+        let blocked_by: Vec<String> = card
+            .blocked_by
+            .iter()
+            .filter_map(|b| match ids.get(b) {
+                Some(new_id) => Some(new_id.clone()),
+                None => {
+                    eprintln!("Dropping dependency of '{}' on {}: not in the export", card.title, short_id(b));
+                    None
+                }
+            })
+            .collect();
+        let update = CardUpdate {
+            blocked_by: Some(blocked_by),
+            ..Default::default()
+        };
+        ctx.client.update_card(id, &update).await?;
+        progress.advance();
+    }
+
+    if !skip_plans {
+        let progress = Progress::start("import plans", Some(board.plans.len()));
+        for plan in &board.plans {
+            let created = import_plan(ctx, plan).await;
+            progress.advance();
+            match created {
+                Ok(created) => imported.push(Imported {
+                    kind: "plan",
+                    source_id: plan.id.clone(),
+                    id: created.id,
+                    title: created.title,
+                }),
+                Err(BlazeError::DryRun) => continue,
+                Err(e) => return Err(import_failed(&imported, &plan.title, e)),
+            }
+        }
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+
+    print_imported(ctx, &imported);
+    Ok(())
+}
+
+/// Parse a JSON or YAML export
+fn parse_export(content: &str) -> Result<BoardExport> {
+    let board: BoardExport = if content.trim_start().starts_with('{') {
+        serde_json::from_str(content)?
+    } else {
+        serde_yaml::from_str(content)?
+    };
+    if board.version > EXPORT_VERSION {
+        return Err(BlazeError::InvalidInput(format!(
+            "Export version {} is newer than this blaze supports ({})",
+            board.version, EXPORT_VERSION
+        )));
+    }
+    Ok(board)
+}
+
+pub fn card_create(card: &Card) -> CardCreate {
+    CardCreate {
+        title: card.title.clone(),
+        description: card.description.clone(),
+        priority: card.priority,
+        column: card.column.clone(),
+        due_date: card.due_date,
+        tags: card.tags.clone(),
+        agent_assignable: card.agent_assignable,
+        acceptance_criteria: card.acceptance_criteria.clone(),
+        blocked_by: Vec::new(),
+        estimate: card.estimate,
+        expires_at: card.expires_at,
+        assignee: card.assignee.clone(),
+    }
+}
+
+/// Replay subtasks, links, criteria checks, agent progress and agent status onto a new card
+pub async fn restore_agent_fields(ctx: &CommandContext, card: &Card, id: &str) -> Result<()> {
+    // Creating a card can't set its subtasks or links
+    let update = CardUpdate::builder()
+        .subtasks(Some(card.subtasks.clone()).filter(|s| !s.is_empty()))
+        .links(Some(card.links.clone()).filter(|l| !l.is_empty()))
+        .build()?;
+    if !update.is_empty() {
+        ctx.client.update_card(id, &update).await?;
+    }
+    for (index, _) in card.acceptance_checked.iter().enumerate().filter(|(_, checked)| **checked) {
+        ctx.client.toggle_criterion(id, index, true).await?;
+    }
+    for entry in &card.agent_progress {
+        ctx.client.add_agent_progress(id, &entry.message).await?;
+    }
+    if let Some(status) = card.agent_status {
+        ctx.client
+            .update_agent_status(id, status, card.blocked_reason.clone())
+            .await?;
+    }
+    Ok(())
+}
+
+async fn import_plan(ctx: &CommandContext, plan: &Plan) -> Result<Plan> {
+    let create = PlanCreate {
+        title: plan.title.clone(),
+        files: plan
+            .files
+            .iter()
+            .map(|f| PlanFileCreate {
+                name: f.name.clone(),
+                content: f.content.clone(),
+            })
+            .collect(),
+    };
+    let mut created = ctx.client.create_plan(&create).await?;
+
+    // New plans start as drafts; approved ones have to pass through ready
+    let steps: &[PlanStatus] = match plan.status {
+        PlanStatus::Draft => &[],
+        PlanStatus::Ready => &[PlanStatus::Ready],
+        PlanStatus::Approved => &[PlanStatus::Ready, PlanStatus::Approved],
+    };
+    for status in steps {
+        let update = PlanUpdate {
+            status: Some(*status),
+            ..Default::default()
+        };
+        created = ctx.client.update_plan(&created.id, &update).await?;
+    }
+    Ok(created)
+}
+
+/// Say how far the import got before `error`, so a retry can start from a clean board
+fn import_failed(imported: &[Imported], title: &str, error: BlazeError) -> BlazeError {
+    BlazeError::Other(format!(
+        "Import stopped at '{}' after creating {} items: {}",
+        title,
+        imported.len(),
+        error
+    ))
+}
+
+fn print_imported(ctx: &CommandContext, imported: &[Imported]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(imported),
+        OutputFormat::Quiet => imported.iter().for_each(|i| outln!("{}", i.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = imported
+                .iter()
+                .map(|i| {
+                    vec![
+                        i.kind.to_string(),
+                        short_id(&i.source_id).to_string(),
+                        short_id(&i.id).to_string(),
+                        i.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["KIND", "FROM", "ID", "TITLE"], rows);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientOptions;
+    use crate::testing;
+
+    #[test]
+    fn exported_confidential_cards_stay_sealed() {
+        let config = crate::config::EncryptionConfig {
+            recipients: vec![age::x25519::Identity::generate().to_public().to_string()],
+            identity: None,
+            tag: None,
+        };
+        let options = ClientOptions {
+            confidential: crate::confidential::Confidential::from_config(&config).unwrap(),
+            ..ClientOptions::default()
+        };
+        let client = Client::with_options("http://localhost:8080", None, options).unwrap();
+        let cards = vec![
+            testing::card("a", serde_json::json!({ "description": "bands and names", "tags": ["confidential"] })),
+            testing::card("b", serde_json::json!({ "description": "release notes" })),
+        ];
+
+        let board = BoardExport::new(&client, "http://localhost:8080".into(), cards, Vec::new()).unwrap();
+        let sealed = board.cards[0].description.as_deref().unwrap();
+        assert!(crate::confidential::is_sealed_text(sealed) && !sealed.contains("bands"));
+        assert_eq!(board.cards[1].description.as_deref(), Some("release notes"));
+    }
+
+    #[test]
+    fn json_and_yaml_exports_round_trip() {
+        let board: BoardExport = serde_json::from_value(serde_json::json!({
+            "version": 1,
+            "exported_at": "2026-03-01T12:00:00Z",
+            "source": "http://old:8080",
+            "cards": [{
+                "id": "c1",
+                "title": "Ship it",
+                "priority": "high",
+                "column": "review",
+                "tags": ["release"],
+                "due_date": "2026-03-05T23:59:59Z",
+                "created_at": "2026-02-02T09:00:00Z",
+                "updated_at": "2026-02-04T09:00:00Z",
+                "agent_assignable": true,
+                "agent_status": "needs_review",
+                "acceptance_criteria": ["tests pass"],
+                "acceptance_checked": [true],
+            }],
+        }))
+        .unwrap();
+
+        for document in [serde_json::to_string(&board).unwrap(), serde_yaml::to_string(&board).unwrap()] {
+            let parsed = parse_export(&document).unwrap();
+            let card = &parsed.cards[0];
+            assert_eq!(card.column, crate::types::Column::Review);
+            assert_eq!(card.tags, ["release"]);
+            assert_eq!(card.due_date, board.cards[0].due_date);
+            assert_eq!(card.acceptance_checked, [true]);
+            assert!(parsed.plans.is_empty());
+        }
+
+        let newer = serde_json::to_string(&BoardExport { version: 2, ..board }).unwrap();
+        assert!(parse_export(&newer).is_err());
+    }
+}
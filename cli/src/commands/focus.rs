@@ -0,0 +1,204 @@
+//! `blaze focus` - What's being worked on right now.
+//!
+//! Every open card in progress, whether it's in the In Progress column or an
+//! agent has it in progress or blocked, most urgent first. Each one comes with
+//! what holds it back (the agent's blocked reason, unfinished dependencies),
+//! its due date, how many acceptance criteria are checked, and the latest
+//! `--entries` progress entries.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::deps::DependencyIndex;
+use crate::error::Result;
+use crate::output::{format_due, format_time, print_csv, print_json, short_id};
+use crate::types::{AgentProgressEntry, AgentStatus, Card, Column, Priority};
+use crate::{out, outln};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// One card in progress
+#[derive(Debug, Serialize)]
+struct Focus {
+    id: String,
+    title: String,
+    column: Column,
+    priority: Priority,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_status: Option<AgentStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocked_reason: Option<String>,
+    /// IDs of dependencies that aren't done yet
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    waiting_on: Vec<String>,
+    criteria_checked: usize,
+    criteria_total: usize,
+    /// The latest progress entries, oldest first
+    progress: Vec<AgentProgressEntry>,
+}
+
+pub async fn run(ctx: &CommandContext, entries: usize) -> Result<()> {
+    let cards = ctx.client.list_cards(None, false).await?;
+    let focus = if cards.iter().any(|c| !c.blocked_by.is_empty()) {
+        // Archived cards can still be blockers
+        let all = ctx.client.list_cards(None, true).await?;
+        in_focus(&cards, &DependencyIndex::new(&all), entries)
+    } else {
+        in_focus(&cards, &DependencyIndex::new(&cards), entries)
+    };
+    if focus.is_empty() {
+        eprintln!("Nothing in progress");
+        return Ok(());
+    }
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&focus),
+        OutputFormat::Quiet => focus.iter().for_each(|f| outln!("{}", f.id)),
+        OutputFormat::Csv => {
+            let rows = focus
+                .iter()
+                .map(|f| {
+                    vec![
+                        f.id.clone(),
+                        f.title.clone(),
+                        f.column.to_string(),
+                        f.priority.to_string(),
+                        f.assignee.clone().unwrap_or_default(),
+                        f.agent_status.map(|s| s.to_string()).unwrap_or_default(),
+                        f.due_date.map(|d| d.to_rfc3339()).unwrap_or_default(),
+                        format!("{}/{}", f.criteria_checked, f.criteria_total),
+                        blockers(f).join("; "),
+                        f.progress.last().map(|e| e.message.clone()).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            let headers = [
+                "id", "title", "column", "priority", "assignee", "agent_status", "due_date", "criteria", "blocked",
+                "latest_progress",
+            ];
+            print_csv(&headers, rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let now = Utc::now();
+            let out: Vec<String> = focus.iter().map(|f| card_text(ctx, f, now)).collect();
+            out!("{}", out.join("\n"));
+        }
+    }
+    Ok(())
+}
+
+/// Open cards someone is working on, most urgent (then soonest due) first
+fn in_focus(cards: &[Card], index: &DependencyIndex, entries: usize) -> Vec<Focus> {
+    let mut working: Vec<&Card> = cards
+        .iter()
+        .filter(|c| c.column != Column::Done)
+        .filter(|c| {
+            let agent_working = matches!(c.agent_status, Some(AgentStatus::InProgress | AgentStatus::Blocked));
+            c.column == Column::InProgress || agent_working
+        })
+        .collect();
+    working.sort_by_key(|c| (std::cmp::Reverse(c.priority.weight()), c.due_date.is_none(), c.due_date));
+
+    working
+        .into_iter()
+        .map(|card| {
+            let criteria_total = card.acceptance_criteria.len();
+            let skip = card.agent_progress.len().saturating_sub(entries);
+            Focus {
+                id: card.id.clone(),
+                title: card.title.clone(),
+                column: card.column.clone(),
+                priority: card.priority,
+                assignee: card.assignee.clone(),
+                agent_status: card.agent_status,
+                due_date: card.due_date,
+                blocked_reason: card.blocked_reason.clone(),
+                waiting_on: index.open_blockers(card).iter().map(|b| b.id.clone()).collect(),
+                // `acceptance_checked` can be shorter or longer than the criteria
+                criteria_checked: card.acceptance_checked.iter().take(criteria_total).filter(|c| **c).count(),
+                criteria_total,
+                progress: card.agent_progress[skip..].to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Why a card isn't moving, if anything is known
+fn blockers(focus: &Focus) -> Vec<String> {
+    let mut reasons: Vec<String> = focus.blocked_reason.iter().cloned().collect();
+    if !focus.waiting_on.is_empty() {
+        let ids: Vec<&str> = focus.waiting_on.iter().map(|id| short_id(id)).collect();
+        reasons.push(format!("waits on {}", ids.join(", ")));
+    }
+    reasons
+}
+
+/// A few lines per card: title, one line of facts, blockers, then progress
+fn card_text(ctx: &CommandContext, focus: &Focus, now: DateTime<Utc>) -> String {
+    let mut facts = vec![ctx.theme.column(&focus.column), ctx.theme.priority_label(focus.priority)];
+    if let Some(ref assignee) = focus.assignee {
+        facts.push(assignee.clone());
+    }
+    if let Some(status) = focus.agent_status {
+        facts.push(format!("agent {}", ctx.theme.with_emoji(status.emoji(), status)));
+    }
+    if let Some(due) = focus.due_date {
+        let due_text = format!("due {}", format_due(ctx, due));
+        facts.push(if due < now { ctx.theme.alert(&due_text) } else { due_text });
+    }
+    if focus.criteria_total > 0 {
+        facts.push(format!("criteria {}/{}", focus.criteria_checked, focus.criteria_total));
+    }
+
+    let mut out = format!("{}  {}\n  {}\n", short_id(&focus.id), focus.title, facts.join(" · "));
+    for reason in blockers(focus) {
+        let _ = writeln!(out, "  {}", ctx.theme.alert(&format!("blocked: {}", reason)));
+    }
+    for entry in &focus.progress {
+        let _ = writeln!(out, "  {}  {}", format_time(ctx, entry.timestamp), entry.message);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::{json, Value};
+
+    fn card(id: &str, column: &str, priority: &str, mut fields: Value) -> Card {
+        fields["column"] = column.into();
+        fields["priority"] = priority.into();
+        testing::card(id, fields)
+    }
+
+    #[test]
+    fn picks_cards_in_progress_with_their_state() {
+        let progress: Vec<Value> = (1..=4)
+            .map(|i| json!({ "timestamp": format!("2026-01-0{}T10:00:00Z", i), "message": format!("step {}", i) }))
+            .collect();
+        let cards = [
+            card("human", "in_progress", "medium", json!({
+                "acceptance_criteria": ["a", "b", "c"], "acceptance_checked": [true, false, true, true],
+                "blocked_by": ["dep"],
+            })),
+            card("agent", "todo", "urgent", json!({
+                "agent_status": "blocked", "blocked_reason": "needs keys", "agent_progress": progress,
+            })),
+            card("dep", "todo", "low", json!({})),
+            card("finished", "done", "high", json!({ "agent_status": "in_progress" })),
+        ];
+        let focus = in_focus(&cards, &DependencyIndex::new(&cards), 2);
+
+        let ids: Vec<&str> = focus.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, ["agent", "human"]);
+        let messages: Vec<&str> = focus[0].progress.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["step 3", "step 4"]);
+        assert_eq!(blockers(&focus[0]), ["needs keys"]);
+        assert_eq!((focus[1].criteria_checked, focus[1].criteria_total), (2, 3));
+        assert_eq!(focus[1].waiting_on, ["dep"]);
+    }
+}
@@ -0,0 +1,289 @@
+//! `blaze sync github-project` - Two-way sync with a GitHub Projects board.
+//!
+//! Runs once (for cron) or every `--watch` interval. With `--dry-run` the
+//! planned steps are printed and neither side is touched. See `github.rs`
+//! for how changes and conflicts are decided.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::github::{ConflictPolicy, GithubClient, Item, Link, Project, ProjectState, StatusMap, Step, SyncState};
+use crate::outln;
+use crate::output::{print_json, print_table, short_id};
+use crate::parsers::Query;
+use crate::types::{Card, CardCreate, CardUpdate};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Options for `blaze sync github-project`
+pub struct ProjectSyncOptions {
+    /// Project node ID (`PVT_...`)
+    pub project: String,
+    /// Overrides `[github] conflicts`
+    pub conflicts: Option<ConflictPolicy>,
+    /// Only cards matching this get new items
+    pub filter: Option<Query>,
+    /// Keep syncing at this interval
+    pub watch: Option<Duration>,
+}
+
+/// What happened to one step
+#[derive(Debug, Serialize)]
+struct Outcome {
+    action: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<String>,
+    detail: String,
+    result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn run(ctx: &CommandContext, options: ProjectSyncOptions) -> Result<()> {
+    if ctx.offline {
+        return Err(BlazeError::InvalidInput("Can't sync with GitHub with --offline".into()));
+    }
+    let settings = &ctx.config.github;
+    let github = GithubClient::from_config(settings, &ctx.config.connection()).await?;
+    let map = StatusMap::from_config(&settings.columns)?;
+    let policy = options.conflicts.or(settings.conflicts).unwrap_or_default();
+
+    let Some(interval) = options.watch else {
+        return sync_once(ctx, &github, &map, policy, &options).await;
+    };
+    eprintln!("Syncing with {} every {} s (Ctrl-C to stop)", options.project, interval.as_secs());
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        // One bad round (GitHub down, a card that won't save) shouldn't end the watch
+        if let Err(e) = sync_once(ctx, &github, &map, policy, &options).await {
+            eprintln!("Sync failed: {}; trying again in {} s", e, interval.as_secs());
+        }
+    }
+}
+
+async fn sync_once(
+    ctx: &CommandContext,
+    github: &GithubClient,
+    map: &StatusMap,
+    policy: ConflictPolicy,
+    options: &ProjectSyncOptions,
+) -> Result<()> {
+    let project = github.project(&options.project).await?;
+    let cards = ctx.client.list_cards(None, false).await?;
+    let mut state = SyncState::load()?;
+    let server = ctx.profile().unwrap_or(&ctx.url).to_string();
+    let project_state = state.project(&server, &project.id);
+    let steps = crate::github::plan(&cards, &project.items, project_state, map, policy, options.filter.as_ref());
+
+    if ctx.dry_run {
+        let outcomes: Vec<Outcome> = steps.iter().map(|s| outcome(s, "planned", None)).collect();
+        print_outcomes(ctx, &outcomes, options.watch.is_some());
+        return Ok(());
+    }
+
+    let mut cards: HashMap<String, Card> = cards.into_iter().map(|c| (c.id.clone(), c)).collect();
+    let mut items: HashMap<String, Item> = project.items.iter().map(|i| (i.id.clone(), i.clone())).collect();
+    // Pairs whose snapshot must stay as it was, so the difference is seen again next time
+    let mut unsettled: HashSet<String> = HashSet::new();
+    let mut outcomes = Vec::with_capacity(steps.len());
+    let mut failed = 0;
+    for step in &steps {
+        let result = apply(ctx, github, &project, step, &mut cards, &mut items, project_state).await;
+        let (result, created, error) = match result {
+            Ok(_) if matches!(step, Step::Conflict { .. }) => ("skipped", None, None),
+            Ok(created) => ("done", created, None),
+            Err(e) => {
+                failed += 1;
+                ("failed", None, Some(e.to_string()))
+            }
+        };
+        if let Some(item) = step_item(step).filter(|_| result != "done") {
+            unsettled.insert(item.to_string());
+        }
+        let mut outcome = outcome(step, result, error);
+        outcome.card = outcome.card.or(created);
+        outcomes.push(outcome);
+    }
+
+    for (item_id, link) in project_state.links.iter_mut() {
+        if unsettled.contains(item_id) {
+            continue;
+        }
+        if let (Some(card), Some(item)) = (cards.get(&link.card), items.get(item_id)) {
+            *link = Link::new(card, item);
+        }
+    }
+    state.save()?;
+
+    print_outcomes(ctx, &outcomes, options.watch.is_some());
+    if failed > 0 {
+        return Err(BlazeError::PartialFailure {
+            failed,
+            total: steps.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Carry out one step, keeping `cards`, `items` and `state` up to date. Returns the ID of a new card.
+async fn apply(
+    ctx: &CommandContext,
+    github: &GithubClient,
+    project: &Project,
+    step: &Step,
+    cards: &mut HashMap<String, Card>,
+    items: &mut HashMap<String, Item>,
+    state: &mut ProjectState,
+) -> Result<Option<String>> {
+    let links = &mut state.links;
+    match step {
+        Step::Link { card, item } => {
+            links.insert(item.clone(), Link::new(&cards[card], &items[item]));
+        }
+        Step::CreateCard { item, title, column } => {
+            let create = CardCreate::builder().title(title.clone()).column(column.clone()).build()?;
+            let card = ctx.client.create_card(&create).await?;
+            let id = card.id.clone();
+            links.insert(item.clone(), Link::new(&card, &items[item]));
+            cards.insert(id.clone(), card);
+            return Ok(Some(id));
+        }
+        Step::CreateItem { card, title, status } => {
+            let item = github.add_draft(project, title).await?;
+            let id = item.id.clone();
+            // Link first: the draft exists even if its status can't be set (it's retried next time)
+            links.insert(id.clone(), Link::new(&cards[card], &item));
+            items.insert(id.clone(), item);
+            github.set_status(project, &id, status).await?;
+            items.get_mut(&id).expect("just added").status = Some(status.clone());
+        }
+        Step::Push {
+            item, title, status, ..
+        } => {
+            let current = items.get_mut(item).expect("planned from this item");
+            if let Some(title) = title {
+                github.set_title(current, title).await?;
+                current.title = title.clone();
+            }
+            if let Some(status) = status {
+                github.set_status(project, item, status).await?;
+                current.status = Some(status.clone());
+            }
+        }
+        Step::Pull {
+            card, title, column, ..
+        } => {
+            if let Some(title) = title {
+                let update = CardUpdate::builder().title(Some(title.clone())).build()?;
+                let updated = ctx.client.update_card(card, &update).await?;
+                cards.insert(card.clone(), updated);
+            }
+            if let Some(column) = column {
+                let updated = ctx.client.move_card(card, column.clone()).await?;
+                cards.insert(card.clone(), updated);
+            }
+        }
+        Step::Conflict { .. } => {}
+        Step::Unlink { card, item, gone } => {
+            links.remove(item);
+            // Don't bring back what was deleted or archived on the other side
+            let survivor = if *gone == "card" { item } else { card };
+            state.detached.insert(survivor.clone());
+        }
+    }
+    Ok(None)
+}
+
+/// The project item a step is about, if it existed before the sync
+fn step_item(step: &Step) -> Option<&str> {
+    match step {
+        Step::Link { item, .. }
+        | Step::CreateCard { item, .. }
+        | Step::Push { item, .. }
+        | Step::Pull { item, .. }
+        | Step::Conflict { item, .. }
+        | Step::Unlink { item, .. } => Some(item),
+        Step::CreateItem { .. } => None,
+    }
+}
+
+fn outcome(step: &Step, result: &'static str, error: Option<String>) -> Outcome {
+    let changes = |title: &Option<String>, target: Option<String>| {
+        let mut parts = Vec::new();
+        if let Some(title) = title {
+            parts.push(format!("title \"{}\"", title));
+        }
+        parts.extend(target);
+        parts.join(", ")
+    };
+    let (action, card, item, detail) = match step {
+        Step::Link { card, item } => ("link", Some(card), Some(item), "same title".to_string()),
+        Step::CreateCard { item, title, column } => {
+            ("create-card", None, Some(item), format!("\"{}\" in {}", title, column.display_name()))
+        }
+        Step::CreateItem { card, title, status } => {
+            ("create-item", Some(card), None, format!("\"{}\" in {}", title, status))
+        }
+        Step::Push {
+            card,
+            item,
+            title,
+            status,
+        } => {
+            let target = status.as_ref().map(|s| format!("status {}", s));
+            ("push", Some(card), Some(item), changes(title, target))
+        }
+        Step::Pull {
+            card,
+            item,
+            title,
+            column,
+        } => {
+            let target = column.as_ref().map(|c| format!("column {}", c.display_name()));
+            ("pull", Some(card), Some(item), changes(title, target))
+        }
+        Step::Conflict { card, item } => ("conflict", Some(card), Some(item), "changed on both sides".to_string()),
+        Step::Unlink { card, item, gone } => ("unlink", Some(card), Some(item), format!("{} is gone", gone)),
+    };
+    Outcome {
+        action,
+        card: card.cloned(),
+        item: item.cloned(),
+        detail,
+        result,
+        error,
+    }
+}
+
+fn print_outcomes(ctx: &CommandContext, outcomes: &[Outcome], watching: bool) {
+    let machine = matches!(ctx.format, OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv);
+    // A quiet round isn't worth a line when watching
+    if outcomes.is_empty() && (watching || !machine) {
+        if !watching {
+            eprintln!("Already in sync");
+        }
+        return;
+    }
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(outcomes),
+        OutputFormat::Quiet => outcomes.iter().filter_map(|o| o.card.as_ref()).for_each(|id| outln!("{}", id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = outcomes
+                .iter()
+                .map(|o| {
+                    vec![
+                        o.action.to_string(),
+                        o.card.as_deref().map(short_id).unwrap_or_default().to_string(),
+                        o.item.clone().unwrap_or_default(),
+                        o.detail.clone(),
+                        o.error.as_ref().map_or(o.result.to_string(), |e| format!("{}: {}", o.result, e)),
+                    ]
+                })
+                .collect();
+            print_table(&["ACTION", "CARD", "ITEM", "DETAIL", "RESULT"], rows);
+        }
+    }
+}
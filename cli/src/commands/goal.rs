@@ -0,0 +1,106 @@
+//! `blaze goal` - Goals that cards roll up to.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::goals::{self, Goal, GoalStore};
+use crate::outln;
+use crate::output::{format_date, print_json, print_table};
+use crate::parsers::parse_due_date;
+
+/// Add (or replace) a goal
+pub async fn add(
+    ctx: &CommandContext,
+    id: String,
+    title: String,
+    description: Option<String>,
+    due: Option<&str>,
+) -> Result<()> {
+    let goal = Goal {
+        id,
+        title,
+        description,
+        due: due.map(|due| parse_due_date(due, ctx.tz)).transpose()?,
+    };
+    let mut store = GoalStore::load()?;
+    store.insert(goal.clone());
+    store.save()?;
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&goal),
+        OutputFormat::Quiet => outln!("{}", goal.id),
+        OutputFormat::Table | OutputFormat::Markdown => eprintln!(
+            "Saved goal '{}'; link cards with `blaze edit <id> --goal {}`",
+            goal.id, goal.id
+        ),
+    }
+    Ok(())
+}
+
+/// List goals
+pub async fn list(ctx: &CommandContext) -> Result<()> {
+    let goals = GoalStore::load()?.all();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&goals),
+        OutputFormat::Quiet => goals.iter().for_each(|g| outln!("{}", g.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = goals
+                .iter()
+                .map(|g| {
+                    vec![
+                        g.id.clone(),
+                        g.title.clone(),
+                        g.due.map(|d| format_date(ctx, d)).unwrap_or_default(),
+                        g.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "TITLE", "DUE", "DESCRIPTION"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Remove a goal (cards keep their `goal:` tag)
+pub async fn rm(id: &str) -> Result<()> {
+    let mut store = GoalStore::load()?;
+    if !store.remove(id) {
+        return Err(BlazeError::InvalidInput(format!("Unknown goal '{}'", id)));
+    }
+    store.save()?;
+    eprintln!("Removed goal '{}'", id);
+    Ok(())
+}
+
+/// Card completion per goal (all goals, or one)
+pub async fn progress(ctx: &CommandContext, id: Option<&str>) -> Result<()> {
+    let store = GoalStore::load()?;
+    let goals = match id {
+        Some(id) => vec![store.get(id)?],
+        None => store.all(),
+    };
+    // Done cards that were archived still count
+    let cards = ctx.client.list_cards(None, true).await?;
+    let progress = goals::progress(&goals, &cards);
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&progress),
+        OutputFormat::Quiet => progress.iter().for_each(|p| outln!("{}", p.goal)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = progress
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.goal.clone(),
+                        format!("{}/{}", p.done, p.cards),
+                        p.in_progress.to_string(),
+                        format!("{:.0}%", p.percent),
+                        p.due.map(|d| format_date(ctx, d)).unwrap_or_default(),
+                        p.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["GOAL", "DONE", "ACTIVE", "PROGRESS", "DUE", "TITLE"], rows);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! `blaze link` - External links on a card (CI runs, docs, designs).
+//!
+//! Links are shown in `blaze show`, as clickable hyperlinks on terminals that
+//! support them. Adding a URL the card already links to updates its title and
+//! kind instead of adding it twice.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_card_detail, print_json, print_table};
+use crate::types::{CardLink, CardUpdate, LinkKind};
+
+/// Add a link to a card, or update the one with the same URL
+pub async fn add(
+    ctx: &CommandContext,
+    card_id: &str,
+    url: String,
+    title: Option<String>,
+    kind: LinkKind,
+) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.get_card(&card_id).await?;
+
+    let mut links = card.links;
+    let title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    match links.iter_mut().find(|l| l.url == url) {
+        Some(link) => {
+            link.title = title.or(link.title.take());
+            link.kind = kind;
+        }
+        None => links.push(CardLink { url, title, kind }),
+    }
+    let update = CardUpdate::builder().links(Some(links)).build()?;
+    let card = ctx.client.update_card(&card_id, &update).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// List a card's links
+pub async fn list(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let links = ctx.client.get_card(&card_id).await?.links;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&links),
+        OutputFormat::Quiet => links.iter().for_each(|l| outln!("{}", l.url)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = links
+                .iter()
+                .enumerate()
+                .map(|(i, l)| {
+                    vec![
+                        i.to_string(),
+                        l.kind.to_string(),
+                        l.title.clone().unwrap_or_default(),
+                        l.url.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["#", "KIND", "TITLE", "URL"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Remove a link by its index (from `link list`) or URL
+pub async fn rm(ctx: &CommandContext, card_id: &str, link: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.get_card(&card_id).await?;
+
+    let mut links = card.links;
+    let index = match link.parse::<usize>() {
+        Ok(i) if i < links.len() => Some(i),
+        Ok(_) => None,
+        Err(_) => links.iter().position(|l| l.url == link),
+    };
+    let Some(index) = index else {
+        return Err(BlazeError::InvalidInput(format!("Card {} has no link {}", card_id, link)));
+    };
+    links.remove(index);
+
+    let update = CardUpdate::builder().links(Some(links)).build()?;
+    let card = ctx.client.update_card(&card_id, &update).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
@@ -1,69 +1,270 @@
 //! `blaze list` - List cards with optional filters.
 
-use crate::client::Client;
-use crate::error::Result;
-use crate::output::print_cards;
+use crate::client::{CardFilter, PageRequest};
+use crate::commands::{comment, thread};
+use crate::context::{CommandContext, OutputFormat};
+use crate::deps::DependencyIndex;
+use crate::error::{BlazeError, Result};
+use crate::history;
+use crate::output::{print_cards_as, print_held_back, short_id, CardField};
+use crate::parsers::Query;
 use crate::types::{Card, Column, Priority};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use std::cmp::Ordering;
+
+/// Warn about cards expiring this soon
+const EXPIRY_WARNING_HOURS: i64 = 48;
+
+/// Expiry warnings listed before summarizing the rest
+const EXPIRY_WARNINGS_SHOWN: usize = 5;
+
+/// The identity the bundled server reports for every token
+const SHARED_IDENTITY: &str = "api-token";
+
+/// `list --sort` keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    /// Soonest first; cards without a due date last
+    Due,
+    /// Most urgent first
+    Priority,
+    /// Oldest first
+    Created,
+    /// Most recently changed first
+    Updated,
+    /// Alphabetical, ignoring case
+    Title,
+}
 
 /// Filter options for listing cards
+#[derive(Default)]
 pub struct ListFilters {
     pub column: Option<Column>,
     pub priorities: Vec<Priority>,
     pub tags: Vec<String>,
     pub overdue: bool,
+    /// Only cards changed at or after this time
+    pub updated_since: Option<DateTime<Utc>>,
     pub include_archived: bool,
+    pub query: Option<Query>,
+    /// Only cards assigned to this name (case-insensitive)
+    pub assignee: Option<String>,
+    /// Only cards assigned to the identity `blaze whoami` reports
+    pub mine: bool,
+    /// Only cards with open review threads
+    pub unresolved_threads: bool,
+    /// Only cards that aren't done and have no unfinished dependencies
+    pub ready: bool,
+    /// Only cards that aren't done and wait on unfinished dependencies
+    pub blocked: bool,
+    /// Only cards without unfinished dependencies (done ones included)
+    pub unblocked: bool,
+    /// Show held-back cards with the cards blocking them
+    pub show_blocked_by: bool,
+    /// CSV columns (defaults if empty)
+    pub fields: Vec<CardField>,
+    /// Order of the cards (the server's board order if not set)
+    pub sort: Option<SortKey>,
+    /// Reverse the sort order
+    pub desc: bool,
+    /// Show at most this many cards
+    pub limit: Option<usize>,
+    /// Fetch only this page of the server's list
+    pub page: Option<u64>,
 }
 
-impl Default for ListFilters {
-    fn default() -> Self {
-        Self {
-            column: None,
-            priorities: Vec::new(),
-            tags: Vec::new(),
-            overdue: false,
-            include_archived: false,
-        }
+pub async fn run(ctx: &CommandContext, filters: ListFilters) -> Result<()> {
+    // Priority, tag, overdue and age conditions go to the server; the query, assignee,
+    // threads, dependency checks and sorting happen here, so the server can't stop at the limit.
+    let assignee = if filters.mine {
+        Some(me(ctx).await?)
+    } else {
+        filters.assignee.clone()
+    };
+    let by_deps = filters.ready || filters.blocked || filters.unblocked || filters.show_blocked_by;
+    let filtered_here = filters.query.is_some()
+        || assignee.is_some()
+        || filters.unresolved_threads
+        || by_deps
+        || filters.sort.is_some();
+    let request = PageRequest {
+        limit: filters.limit.filter(|_| !filtered_here),
+        page: filters.page,
+    };
+    if let Some(ref column) = filters.column {
+        ctx.client.check_column(column).await?;
     }
-}
+    let server_filter = CardFilter {
+        column: filters.column.clone(),
+        include_archived: filters.include_archived,
+        priorities: filters.priorities.clone(),
+        tags: filters.tags.clone(),
+        overdue: filters.overdue,
+        updated_since: filters.updated_since,
+    };
+    let cards = ctx.client.list_cards_where(&server_filter, request).await?;
 
-pub async fn run(client: &Client, filters: ListFilters) -> Result<()> {
-    // Fetch cards (API supports column and include_archived filters)
-    let cards = client.list_cards(filters.column, filters.include_archived).await?;
-
-    // Apply client-side filters
-    let filtered: Vec<Card> = cards
-        .into_iter()
-        .filter(|card| {
-            // Priority filter
-            if !filters.priorities.is_empty() && !filters.priorities.contains(&card.priority) {
-                return false;
-            }
+    let mut filtered: Vec<Card> = match filters.query {
+        Some(ref query) => cards.into_iter().filter(|card| query.matches(card)).collect(),
+        None => cards,
+    };
+    if let Some(ref name) = assignee {
+        filtered.retain(|card| card.assignee.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(name)));
+    }
+    // Last, so only the cards left cost a request
+    if filters.unresolved_threads {
+        filtered = thread::with_unresolved(ctx, filtered).await?;
+    }
+    if let Some(key) = filters.sort {
+        sort_cards(&mut filtered, key, filters.desc);
+    }
+    let limit = filters.limit.unwrap_or(usize::MAX);
+    if ctx.format != OutputFormat::Quiet {
+        warn_expiring(&filtered[..filtered.len().min(limit)], Utc::now());
+    }
 
-            // Tag filter (card must have at least one matching tag)
-            if !filters.tags.is_empty() {
-                let has_matching_tag = filters.tags.iter().any(|t| card.tags.contains(t));
-                if !has_matching_tag {
-                    return false;
-                }
-            }
+    // Only full JSON, or output asking for column entry times, pays for the events lookup
+    let wants_entered = match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson if filters.fields.is_empty() => true,
+        OutputFormat::Quiet => false,
+        _ => filters.fields.contains(&CardField::EnteredColumn),
+    };
+    if wants_entered {
+        history::fill_entered_column_at(&ctx.client, &mut filtered).await?;
+    }
+
+    if !by_deps {
+        filtered.truncate(limit);
+        print_cards_as(ctx, &filtered, &filters.fields);
+        return Ok(());
+    }
 
-            // Overdue filter
-            if filters.overdue {
-                match &card.due_date {
-                    Some(due) => {
-                        if due >= &Utc::now() {
-                            return false;
-                        }
-                    }
-                    None => return false, // No due date = not overdue
-                }
+    // Dependencies may live in other columns or be archived
+    let all = ctx.client.list_cards(None, true).await?;
+    let index = DependencyIndex::new(&all);
+
+    if filters.show_blocked_by {
+        let held: Vec<_> = filtered
+            .iter()
+            .filter(|c| c.column != Column::Done && !index.is_unblocked(c))
+            .map(|c| index.held_back(c))
+            .take(limit)
+            .collect();
+        print_held_back(ctx, &held);
+    } else {
+        let keep = |c: &Card| {
+            if filters.unblocked {
+                index.is_unblocked(c)
+            } else {
+                c.column != Column::Done && index.is_unblocked(c) != filters.blocked
             }
+        };
+        let kept: Vec<Card> = filtered.into_iter().filter(|c| keep(c)).take(limit).collect();
+        print_cards_as(ctx, &kept, &filters.fields);
+    }
+    Ok(())
+}
 
-            true
-        })
+/// Who `--mine` means: the token's identity, unless it's one every token shares (the bundled
+/// server says `api-token` for all of them); then the name comments are signed with
+async fn me(ctx: &CommandContext) -> Result<String> {
+    let identity = match ctx.client.whoami().await {
+        Ok(identity) => Some(identity.identity).filter(|name| name != SHARED_IDENTITY),
+        Err(e) if e.is_endpoint_missing() => None,
+        Err(e) => return Err(e),
+    };
+    identity.or_else(|| comment::author_or_default(ctx, None)).ok_or_else(|| {
+        BlazeError::InvalidInput("The token doesn't say who you are; set `author` in config.toml for --mine".into())
+    })
+}
+
+/// Warn on stderr about open cards that expired or will within `EXPIRY_WARNING_HOURS`
+fn warn_expiring(cards: &[Card], now: DateTime<Utc>) {
+    let soon = now + chrono::Duration::hours(EXPIRY_WARNING_HOURS);
+    let mut expiring: Vec<(&Card, DateTime<Utc>)> = cards
+        .iter()
+        .filter(|c| c.column != Column::Done)
+        .filter_map(|c| c.expires_at.filter(|at| *at <= soon).map(|at| (c, at)))
         .collect();
+    if expiring.is_empty() {
+        return;
+    }
+    expiring.sort_by_key(|(_, at)| *at);
 
-    print_cards(&filtered);
-    Ok(())
+    for (card, at) in expiring.iter().take(EXPIRY_WARNINGS_SHOWN) {
+        let hours = (*at - now).num_hours();
+        let when = match hours {
+            _ if *at <= now => "expired".to_string(),
+            0 => "expires within the hour".to_string(),
+            h => format!("expires in {}h", h),
+        };
+        eprintln!("Warning: {} {} ({})", short_id(&card.id), card.title, when);
+    }
+    if expiring.len() > EXPIRY_WARNINGS_SHOWN {
+        eprintln!("Warning: and {} more", expiring.len() - EXPIRY_WARNINGS_SHOWN);
+    }
+    if expiring.iter().any(|(_, at)| *at <= now) {
+        eprintln!("Run `blaze maintenance expire` to close expired cards");
+    }
+}
+
+/// Sort by `key` (`desc` reverses it); ties go to the more urgent card, then the one due first
+pub fn sort_cards(cards: &mut [Card], key: SortKey, desc: bool) {
+    let directed = |order: Ordering| if desc { order.reverse() } else { order };
+    cards.sort_by(|a, b| {
+        let primary = match key {
+            // Cards without a due date stay last either way
+            SortKey::Due => match (a.due_date, b.due_date) {
+                (Some(x), Some(y)) => directed(x.cmp(&y)),
+                (x, y) => x.is_none().cmp(&y.is_none()),
+            },
+            SortKey::Priority => directed((b.priority as u8).cmp(&(a.priority as u8))),
+            SortKey::Created => directed(a.created_at.cmp(&b.created_at)),
+            SortKey::Updated => directed(b.updated_at.cmp(&a.updated_at)),
+            SortKey::Title => directed(a.title.to_lowercase().cmp(&b.title.to_lowercase())),
+        };
+        primary
+            .then_with(|| (b.priority as u8).cmp(&(a.priority as u8)))
+            .then_with(|| a.due_date.is_none().cmp(&b.due_date.is_none()))
+            .then_with(|| a.due_date.cmp(&b.due_date))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(title: &str, priority: &str, due: Option<&str>) -> Card {
+        testing::card(title, serde_json::json!({ "priority": priority, "due_date": due }))
+    }
+
+    fn titles(cards: &[Card]) -> Vec<&str> {
+        cards.iter().map(|c| c.title.as_str()).collect()
+    }
+
+    #[test]
+    fn sorts_with_priority_then_due_tie_breaks() {
+        let mut cards = vec![
+            card("a", "low", Some("2026-02-01T00:00:00Z")),
+            card("b", "urgent", None),
+            card("c", "high", Some("2026-03-01T00:00:00Z")),
+            card("d", "high", Some("2026-02-01T00:00:00Z")),
+            card("e", "urgent", Some("2026-02-01T00:00:00Z")),
+        ];
+
+        sort_cards(&mut cards, SortKey::Priority, false);
+        assert_eq!(titles(&cards), ["e", "b", "d", "c", "a"]);
+
+        sort_cards(&mut cards, SortKey::Due, false);
+        assert_eq!(titles(&cards), ["e", "d", "a", "c", "b"]);
+        // Undated cards stay last when reversed
+        sort_cards(&mut cards, SortKey::Due, true);
+        assert_eq!(titles(&cards), ["c", "e", "d", "a", "b"]);
+
+        sort_cards(&mut cards, SortKey::Title, true);
+        assert_eq!(titles(&cards), ["e", "d", "c", "b", "a"]);
+    }
 }
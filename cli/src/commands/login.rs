@@ -0,0 +1,80 @@
+//! `blaze login` / `blaze whoami` - Store and inspect the API token.
+
+use crate::client::Client;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::print_json;
+use crate::types::Identity;
+use serde::Serialize;
+use std::io::{self, Read};
+
+#[derive(Serialize)]
+struct WhoAmI<'a> {
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<&'a str>,
+    #[serde(flatten)]
+    identity: &'a Identity,
+}
+
+/// Verify a token against the server and save it to the token file
+pub async fn login(ctx: &CommandContext, with_token: bool) -> Result<()> {
+    let token = if with_token {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        rpassword::prompt_password(format!("Token for {}: ", ctx.url))?
+    };
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(BlazeError::InvalidInput("No token given".into()));
+    }
+
+    let identity = Client::new(&ctx.url, Some(token.to_string()))?.whoami().await?;
+
+    let auth = ctx.config.auth();
+    let store = auth.store.describe();
+    if ctx.dry_run {
+        eprintln!("Would save token for {} to the {}", identity.identity, store);
+    } else {
+        ctx.config.save_token(token, &ctx.url).await?;
+    }
+    if auth.has_provider() {
+        eprintln!("Note: the [auth] provider in config.toml takes precedence over the {}", store);
+    }
+
+    print_identity(ctx, &identity);
+    Ok(())
+}
+
+/// Show who the current token authenticates as
+pub async fn whoami(ctx: &CommandContext) -> Result<()> {
+    if ctx.token.is_none() {
+        return Err(BlazeError::Auth("Not logged in (run `blaze login`)".into()));
+    }
+    let identity = ctx.client.whoami().await?;
+    print_identity(ctx, &identity);
+    Ok(())
+}
+
+fn print_identity(ctx: &CommandContext, identity: &Identity) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&WhoAmI {
+            url: &ctx.url,
+            profile: ctx.profile(),
+            identity,
+        }),
+        OutputFormat::Quiet => outln!("{}", identity.identity),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            match ctx.profile() {
+                Some(profile) => outln!("{} @ {} (profile {})", identity.identity, ctx.url, profile),
+                None => outln!("{} @ {}", identity.identity, ctx.url),
+            }
+            if !identity.scopes.is_empty() {
+                outln!("Scopes: {}", identity.scopes.join(", "));
+            }
+        }
+    }
+}
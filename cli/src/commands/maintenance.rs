@@ -0,0 +1,92 @@
+//! `blaze maintenance` - Housekeeping tasks meant for cron.
+//!
+//! `expire` closes (moves to done) or archives every card whose `expires_at`
+//! has passed, such as time-boxed spikes nobody got back to. `agent-rules`
+//! sets `agent_assignable` wherever `[agent_rules]` disagree with a card.
+
+use crate::agent_rules::AgentRules;
+use crate::commands::bulk;
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Column};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+/// What `blaze maintenance expire` does with expired cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExpireAction {
+    /// Move to done
+    #[default]
+    Close,
+    /// Archive, keeping the column (done cards included)
+    Archive,
+}
+
+pub async fn expire(ctx: &CommandContext, action: ExpireAction) -> Result<()> {
+    let cards = ctx.client.list_cards(None, false).await?;
+    let ids: Vec<String> = expired(&cards, action, Utc::now()).map(|c| c.id.clone()).collect();
+    if ids.is_empty() {
+        eprintln!("No expired cards");
+        return Ok(());
+    }
+
+    bulk::apply(ctx, "expire", &ids, |id| async move {
+        match action {
+            ExpireAction::Close => ctx.client.move_card(&id, Column::Done).await,
+            ExpireAction::Archive => ctx.client.archive_card(&id).await,
+        }
+    })
+    .await
+}
+
+pub async fn agent_rules(ctx: &CommandContext) -> Result<()> {
+    let rules = &AgentRules::from_config(&ctx.config.agent_rules)?;
+    if rules.is_empty() {
+        return Err(BlazeError::Config("No [agent_rules] in config.toml".into()));
+    }
+    let cards = ctx.client.list_cards(None, false).await?;
+    let ids: Vec<String> = cards.iter().filter(|c| rules.change(c).is_some()).map(|c| c.id.clone()).collect();
+    if ids.is_empty() {
+        eprintln!("Every card already follows the agent rules");
+        return Ok(());
+    }
+
+    let cards = &cards;
+    bulk::apply(ctx, "agent-rules", &ids, |id| async move {
+        let card = cards.iter().find(|c| c.id == id).cloned().expect("selected from these cards");
+        rules.apply(&ctx.client, card).await
+    })
+    .await
+}
+
+/// Cards past their expiry that `action` would still change
+fn expired(cards: &[Card], action: ExpireAction, now: DateTime<Utc>) -> impl Iterator<Item = &Card> {
+    cards
+        .iter()
+        .filter(move |c| c.expires_at.is_some_and(|at| at <= now))
+        .filter(move |c| action == ExpireAction::Archive || c.column != Column::Done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, column: Column, expires_at: Option<&str>) -> Card {
+        testing::card(id, serde_json::json!({ "column": column, "expires_at": expires_at }))
+    }
+
+    #[test]
+    fn selects_cards_past_expiry() {
+        let now = "2026-03-01T12:00:00Z".parse().unwrap();
+        let cards = [
+            card("past", Column::Todo, Some("2026-03-01T11:00:00Z")),
+            card("future", Column::Todo, Some("2026-03-02T00:00:00Z")),
+            card("never", Column::Todo, None),
+            card("done", Column::Done, Some("2026-02-01T00:00:00Z")),
+        ];
+        let ids = |action| expired(&cards, action, now).map(|c| c.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids(ExpireAction::Close), ["past"]);
+        assert_eq!(ids(ExpireAction::Archive), ["past", "done"]);
+    }
+}
@@ -0,0 +1,217 @@
+//! `blaze metrics` - Board stats history in a local SQLite database.
+//!
+//! `snapshot` appends the current stats (run it from cron); `query` shows
+//! them over time for the current board, optionally one row per day, week
+//! or month, or runs read-only SQL against the database (see `metrics.rs`
+//! for the schema).
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::metrics::{self, Every, MetricsDb, Snapshot};
+use crate::outln;
+use crate::output::{format_time, print_csv, print_json, print_markdown, print_table};
+use crate::types::Column;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// What `metrics query` asks for
+#[derive(Debug, Default)]
+pub struct MetricsQuery {
+    /// Metric names; `total`, `overdue` and the columns if empty
+    pub metrics: Vec<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub every: Option<Every>,
+    /// Raw SQL instead of the above
+    pub sql: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Recorded<'a> {
+    id: i64,
+    board: &'a str,
+    taken_at: DateTime<Utc>,
+    database: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<String>,
+    taken_at: DateTime<Utc>,
+    metrics: BTreeMap<String, Option<i64>>,
+}
+
+/// Record the board's stats now
+pub async fn snapshot(ctx: &CommandContext, db: Option<PathBuf>) -> Result<()> {
+    let path = db_path(ctx, db)?;
+    let stats = ctx.client.stats().await?;
+    let board = board(ctx);
+    let taken_at = Utc::now();
+    let id = MetricsDb::open(&path)?.record(board, taken_at, &stats)?;
+
+    let recorded = Recorded {
+        id,
+        board,
+        taken_at,
+        database: path.display().to_string(),
+    };
+    match ctx.format {
+        OutputFormat::Quiet => outln!("{}", id),
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&recorded),
+        _ => outln!(
+            "Recorded {} cards ({} overdue) for {} in {}",
+            stats.total_cards, stats.overdue_count, board, recorded.database
+        ),
+    }
+    Ok(())
+}
+
+/// Show recorded metrics, or the result of `--sql`
+pub fn query(ctx: &CommandContext, db: Option<PathBuf>, query: MetricsQuery) -> Result<()> {
+    let path = db_path(ctx, db)?;
+    let db = MetricsDb::open_read_only(&path)?;
+    if let Some(ref sql) = query.sql {
+        let (names, rows) = db.query(sql)?;
+        return print_sql(ctx, &names, rows);
+    }
+
+    let snapshots = db.history(board(ctx), query.since)?;
+    if snapshots.is_empty() {
+        eprintln!("No snapshots of {} in {}", board(ctx), path.display());
+        return Ok(());
+    }
+    let names = if query.metrics.is_empty() {
+        default_metrics(&snapshots)
+    } else {
+        check_metrics(&query.metrics, &snapshots)?;
+        query.metrics.clone()
+    };
+    let periods: Vec<(Option<String>, Snapshot)> = match query.every {
+        Some(every) => metrics::thin_out(snapshots, every, ctx.tz)
+            .into_iter()
+            .map(|(period, s)| (Some(period), s))
+            .collect(),
+        None => snapshots.into_iter().map(|s| (None, s)).collect(),
+    };
+    let rows: Vec<Row> = periods
+        .into_iter()
+        .map(|(period, s)| Row {
+            period,
+            taken_at: s.taken_at,
+            metrics: names.iter().map(|n| (n.clone(), s.metrics.get(n).copied())).collect(),
+        })
+        .collect();
+    print_rows(ctx, &names, &rows);
+    Ok(())
+}
+
+/// `--db`, else `[metrics] db`, else the default
+pub fn db_path(ctx: &CommandContext, db: Option<PathBuf>) -> Result<PathBuf> {
+    db.or_else(|| ctx.config.metrics.db.clone())
+        .or_else(metrics::default_path)
+        .ok_or_else(|| BlazeError::Config("No config directory; pass --db".into()))
+}
+
+/// Snapshots are kept per profile, or per server without one
+pub fn board(ctx: &CommandContext) -> &str {
+    ctx.profile().unwrap_or(&ctx.url)
+}
+
+/// `total`, `overdue`, then every column seen, in board order
+fn default_metrics(snapshots: &[Snapshot]) -> Vec<String> {
+    let mut columns: Vec<Column> = snapshots
+        .iter()
+        .flat_map(|s| s.metrics.keys())
+        .filter_map(|name| name.strip_prefix("column:"))
+        .map(|name| Column::from(name.to_string()))
+        .collect();
+    columns.sort_by(|a, b| a.rank().cmp(&b.rank()).then_with(|| a.to_string().cmp(&b.to_string())));
+    columns.dedup();
+    ["total".to_string(), "overdue".to_string()]
+        .into_iter()
+        .chain(columns.iter().map(|c| format!("column:{}", c)))
+        .collect()
+}
+
+fn check_metrics(wanted: &[String], snapshots: &[Snapshot]) -> Result<()> {
+    let known: std::collections::BTreeSet<&String> = snapshots.iter().flat_map(|s| s.metrics.keys()).collect();
+    match wanted.iter().find(|w| !known.contains(w)) {
+        Some(unknown) => Err(BlazeError::InvalidInput(format!(
+            "No metric '{}' recorded (known: {})",
+            unknown,
+            known.into_iter().cloned().collect::<Vec<_>>().join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn print_rows(ctx: &CommandContext, names: &[String], rows: &[Row]) {
+    let values = |row: &Row| -> Vec<String> {
+        names
+            .iter()
+            .map(|n| row.metrics[n].map(|v| v.to_string()).unwrap_or_default())
+            .collect()
+    };
+    let by_period = rows.first().is_some_and(|r| r.period.is_some());
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(rows),
+        OutputFormat::Quiet => rows.iter().for_each(|r| outln!("{}", values(r).first().cloned().unwrap_or_default())),
+        OutputFormat::Csv => {
+            let mut headers = vec!["taken_at".to_string()];
+            if by_period {
+                headers.insert(0, "period".to_string());
+            }
+            headers.extend(names.iter().cloned());
+            let rows = rows
+                .iter()
+                .map(|r| {
+                    let mut row: Vec<String> = r.period.iter().cloned().collect();
+                    row.push(r.taken_at.to_rfc3339());
+                    row.extend(values(r));
+                    row
+                })
+                .collect();
+            print_csv(&headers, rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let first = if by_period { "PERIOD" } else { "TIME" };
+            let headers: Vec<String> =
+                std::iter::once(first.to_string()).chain(names.iter().map(|n| n.to_uppercase())).collect();
+            let rows = rows
+                .iter()
+                .map(|r| {
+                    let when = r.period.clone().unwrap_or_else(|| format_time(ctx, r.taken_at));
+                    std::iter::once(when).chain(values(r)).collect()
+                })
+                .collect();
+            if ctx.format == OutputFormat::Markdown {
+                print_markdown(&headers, rows);
+            } else {
+                let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+                print_table(&headers, rows);
+            }
+        }
+    }
+}
+
+fn print_sql(ctx: &CommandContext, names: &[String], rows: Vec<Vec<String>>) -> Result<()> {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let objects: Vec<BTreeMap<&str, &str>> = rows
+                .iter()
+                .map(|row| names.iter().map(String::as_str).zip(row.iter().map(String::as_str)).collect())
+                .collect();
+            print_json(&objects);
+        }
+        OutputFormat::Quiet => rows.iter().for_each(|r| outln!("{}", r.first().cloned().unwrap_or_default())),
+        OutputFormat::Csv => print_csv(names, rows),
+        OutputFormat::Markdown => print_markdown(names, rows),
+        OutputFormat::Table => {
+            let headers: Vec<&str> = names.iter().map(String::as_str).collect();
+            print_table(&headers, rows);
+        }
+    }
+    Ok(())
+}
@@ -2,12 +2,60 @@
 
 pub mod add;
 pub mod agent;
+pub mod analytics;
+pub mod attachment;
+pub mod backup;
+pub mod bench;
 pub mod board;
+pub mod bootstrap;
+pub mod bot;
+pub mod bulk;
+pub mod cache;
+pub mod changes;
+pub mod comment;
+pub mod completions;
+pub mod config;
+pub mod criteria;
+pub mod daemon;
+pub mod dep;
 pub mod edit;
+pub mod events;
+pub mod export;
+pub mod focus;
+pub mod github_project;
+pub mod goal;
+pub mod link;
 pub mod list;
+pub mod login;
+pub mod maintenance;
+pub mod metrics;
 pub mod move_card;
+pub mod notify;
+pub mod notifyd;
 pub mod ping;
 pub mod plan;
+pub mod plugin;
+pub mod poker;
+pub mod publish;
+pub mod quick;
+pub mod repl;
+pub mod replace;
+pub mod report;
 pub mod rm;
+pub mod script;
+pub mod settings;
 pub mod show;
+pub mod simulate;
 pub mod stats;
+pub mod sync;
+pub mod tag;
+pub mod task;
+pub mod team;
+pub mod thread;
+pub mod timeline;
+pub mod trash;
+pub mod undo;
+pub mod use_card;
+pub mod view;
+pub mod watch;
+pub mod wizard;
@@ -1,17 +1,92 @@
 //! `blaze move` and `blaze done` - Move cards between columns.
+//!
+//! Both check the target column's WIP limit first (see [`crate::wip`]) and
+//! apply `[agent_rules]` to the moved cards (see [`crate::agent_rules`]).
+//! `done` also refuses cards whose dependencies aren't done yet; blockers
+//! finished in the same command don't count. `--force` skips the checks.
 
-use crate::client::Client;
-use crate::error::Result;
-use crate::output::print_card_detail;
+use crate::agent_rules::AgentRules;
+use crate::commands::bulk;
+use crate::context::CommandContext;
+use crate::deps::DependencyIndex;
+use crate::error::{BlazeError, Result};
+use crate::output::{print_card_detail, short_id};
 use crate::types::Column;
+use crate::wip::{self, WipLimits};
 
-pub async fn run(client: &Client, card_id: &str, column: Column) -> Result<()> {
-    let moved = client.move_card(card_id, column).await?;
-    print_card_detail(&moved);
-    Ok(())
+pub async fn run(ctx: &CommandContext, card_ids: &[String], column: Column, force: bool) -> Result<()> {
+    check_wip(ctx, card_ids, &column, force).await?;
+    let rules = &AgentRules::from_config(&ctx.config.agent_rules)?;
+    if let [card_id] = card_ids {
+        let card_id = ctx.resolve_card_id(card_id).await?;
+        let moved = ctx.client.move_card(&card_id, column).await?;
+        let moved = rules.apply(&ctx.client, moved).await?;
+        print_card_detail(ctx, &moved);
+        return Ok(());
+    }
+
+    let column = &column;
+    bulk::apply(ctx, "move", card_ids, |id| async move {
+        let id = ctx.resolve_card_id(&id).await?;
+        let moved = ctx.client.move_card(&id, column.clone()).await?;
+        rules.apply(&ctx.client, moved).await
+    })
+    .await
 }
 
 /// Shortcut for moving to done
-pub async fn run_done(client: &Client, card_id: &str) -> Result<()> {
-    run(client, card_id, Column::Done).await
+pub async fn run_done(ctx: &CommandContext, card_ids: &[String], force: bool) -> Result<()> {
+    if !force {
+        check_blockers(ctx, card_ids).await?;
+    }
+    run(ctx, card_ids, Column::Done, force).await
+}
+
+/// Fail (or warn) if the move would take `column` over its WIP limit
+async fn check_wip(ctx: &CommandContext, card_ids: &[String], column: &Column, force: bool) -> Result<()> {
+    let limits = WipLimits::from_config(&ctx.config.wip)?;
+    if limits.limit(column).is_none() {
+        return Ok(());
+    }
+    let there = ctx.client.list_cards(Some(column.clone()), false).await?;
+    let mut incoming = 0;
+    for id in card_ids {
+        let id = ctx.resolve_card_id(id).await?;
+        if !there.iter().any(|c| c.id == id) {
+            incoming += 1;
+        }
+    }
+    let enforce = ctx.config.wip.enforce.unwrap_or_default();
+    wip::check_move(&limits, enforce, column, there.len(), incoming, force)
+}
+
+/// Fail if any card waits on an unfinished card outside `card_ids`
+async fn check_blockers(ctx: &CommandContext, card_ids: &[String]) -> Result<()> {
+    let mut ids = Vec::with_capacity(card_ids.len());
+    for id in card_ids {
+        ids.push(ctx.resolve_card_id(id).await?);
+    }
+    let all = ctx.client.list_cards(None, true).await?;
+    let index = DependencyIndex::new(&all);
+
+    let mut held = 0;
+    for card in all.iter().filter(|c| ids.contains(&c.id)) {
+        let open: Vec<&str> = index
+            .open_blockers(card)
+            .into_iter()
+            .filter(|b| !ids.contains(&b.id))
+            .map(|b| short_id(&b.id))
+            .collect();
+        if !open.is_empty() {
+            eprintln!("  {} waits on {}", short_id(&card.id), open.join(", "));
+            held += 1;
+        }
+    }
+    if held > 0 {
+        return Err(BlazeError::InvalidInput(format!(
+            "{} card(s) have unfinished dependencies; finish those first or pass --force",
+            held
+        )));
+    }
+    Ok(())
 }
@@ -0,0 +1,117 @@
+//! `blaze notify run` - Send recent changes through the routes in notify.toml.
+//!
+//! Meant for cron or a systemd timer: with `--since last` (the default) each
+//! run picks up where the previous one left off. The very first run only
+//! records where the board is, so nothing old gets sent.
+
+use crate::changes::{detect, Cursor, CursorStore};
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::notify::{self, NotifyConfig};
+use crate::output::{print_json, print_table, short_id};
+use crate::parsers::Since;
+use serde::Serialize;
+
+/// One notification sent (or attempted)
+#[derive(Debug, Serialize)]
+struct Delivery {
+    channel: String,
+    change: String,
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn run(ctx: &CommandContext, since: Since) -> Result<()> {
+    let config = NotifyConfig::load()?;
+    if config.rules.is_empty() {
+        return Err(BlazeError::Config(format!(
+            "No notify rules; add some to {}",
+            NotifyConfig::path().map(|p| p.display().to_string()).unwrap_or_default()
+        )));
+    }
+
+    let cards = ctx.client.list_cards(None, true).await?;
+    let mut state = CursorStore::load("notify-state.json")?;
+    let key = ctx.profile().unwrap_or(&ctx.url).to_string();
+
+    let cursor = match since {
+        Since::Last => state.get(&key),
+        Since::At(_) => None,
+    };
+    let changes = match (since, cursor) {
+        (Since::At(at), _) => detect(&cards, None, Some(at)),
+        (Since::Last, Some(cursor)) => detect(&cards, Some(cursor), Some(cursor.last)),
+        (Since::Last, None) => {
+            eprintln!("First run: recorded the current board, future changes will be sent");
+            Vec::new()
+        }
+    };
+
+    let mut deliveries = Vec::new();
+    for change in &changes {
+        let message = notify::describe(change);
+        for name in config.route(change) {
+            let result = if ctx.dry_run {
+                eprintln!("[dry-run] {} <- {}", name, message);
+                Ok(())
+            } else {
+                notify::send(&config.channels[name], &message).await
+            };
+            deliveries.push(Delivery {
+                channel: name.to_string(),
+                change: format!("{:?}", change.change).to_lowercase(),
+                id: change.id.clone(),
+                title: change.title.clone(),
+                ok: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+    }
+
+    if since == Since::Last && !ctx.dry_run {
+        let cursor = Cursor::advance(&cards, cursor);
+        state.insert(key, cursor);
+        state.save()?;
+    }
+
+    print_deliveries(ctx, &deliveries);
+
+    let failed = deliveries.iter().filter(|d| !d.ok).count();
+    if failed > 0 {
+        return Err(BlazeError::Other(format!(
+            "{} of {} notifications failed",
+            failed,
+            deliveries.len()
+        )));
+    }
+    Ok(())
+}
+
+fn print_deliveries(ctx: &CommandContext, deliveries: &[Delivery]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(deliveries),
+        OutputFormat::Quiet => {}
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = deliveries
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.channel.clone(),
+                        d.change.clone(),
+                        short_id(&d.id).to_string(),
+                        match d.error {
+                            Some(ref e) => format!("failed: {}", e),
+                            None => "sent".to_string(),
+                        },
+                        d.title.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["CHANNEL", "CHANGE", "ID", "RESULT", "TITLE"], rows);
+        }
+    }
+}
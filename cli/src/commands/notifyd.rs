@@ -0,0 +1,315 @@
+//! `blaze notifyd` - Desktop alerts for due, overdue and blocked cards.
+//!
+//! Polls the board every `interval_secs` and raises a desktop notification
+//! when an open card becomes due soon, overdue, or blocked (by an agent, or
+//! by dependencies that aren't done). How
+//! far ahead "due soon" starts depends on the card's priority:
+//!
+//! ```toml
+//! [notifyd]
+//! interval_secs = 120
+//! due_soon_hours = { urgent = 48, low = 0 }   # 0: no due-soon alert
+//! ```
+//!
+//! Each alert fires once, until the card leaves that state and enters it
+//! again; what has fired is kept in `notifyd-state.json`, so a restart
+//! doesn't repeat it. Runs in the foreground (for systemd or launchd, one
+//! JSON line per alert on stdout) unless `--detach`ed; `--stop` ends a
+//! detached one.
+
+use crate::config::{Config, NotifydConfig};
+use crate::context::CommandContext;
+use crate::deps::DependencyIndex;
+use crate::error::{BlazeError, Result};
+use crate::notify::{self, Channel};
+use crate::output::{format_due, print_json_line, short_id};
+use crate::types::{AgentStatus, Card, Column, Priority};
+use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const STATE_FILE: &str = "notifyd-state.json";
+const PID_FILE: &str = "notifyd.pid";
+
+/// Seconds between polls without `interval_secs`
+const DEFAULT_INTERVAL: u64 = 300;
+
+/// Hours of warning before the due date, per priority, without `due_soon_hours`
+const DEFAULT_DUE_SOON: [(Priority, u32); 4] = [
+    (Priority::Urgent, 48),
+    (Priority::High, 24),
+    (Priority::Medium, 12),
+    (Priority::Low, 4),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Alert {
+    DueSoon,
+    Overdue,
+    Blocked,
+}
+
+/// One notification, as logged on stdout
+#[derive(Serialize)]
+struct Fired<'a> {
+    at: DateTime<Utc>,
+    alert: Alert,
+    id: &'a str,
+    title: &'a str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Alerts already shown, per server and card
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    #[serde(default)]
+    servers: BTreeMap<String, BTreeMap<String, Vec<Alert>>>,
+}
+
+impl State {
+    fn load() -> Result<Self> {
+        match Config::dir().map(|d| d.join(STATE_FILE)) {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(STATE_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Due-soon warning per priority, from config over the defaults
+struct Thresholds(Vec<(Priority, Duration)>);
+
+impl Thresholds {
+    fn from_config(config: &NotifydConfig) -> Result<Self> {
+        let mut hours = DEFAULT_DUE_SOON.to_vec();
+        for (name, &value) in &config.due_soon_hours {
+            let priority = Priority::from_str(name, true)
+                .map_err(|_| BlazeError::Config(format!("[notifyd] due_soon_hours: unknown priority '{}'", name)))?;
+            for entry in hours.iter_mut().filter(|(p, _)| *p == priority) {
+                entry.1 = value;
+            }
+        }
+        Ok(Self(hours.into_iter().map(|(p, h)| (p, Duration::hours(h.into()))).collect()))
+    }
+
+    fn due_soon(&self, priority: Priority) -> Duration {
+        self.0.iter().find(|(p, _)| *p == priority).map_or(Duration::zero(), |(_, d)| *d)
+    }
+}
+
+pub async fn run(ctx: &CommandContext, interval: Option<std::time::Duration>, once: bool) -> Result<()> {
+    let config = &ctx.config.notifyd;
+    let thresholds = Thresholds::from_config(config)?;
+    let interval = interval.unwrap_or(std::time::Duration::from_secs(config.interval_secs.unwrap_or(DEFAULT_INTERVAL)));
+    let server = ctx.profile().unwrap_or(&ctx.url).to_string();
+
+    loop {
+        match poll(ctx, &server, &thresholds).await {
+            Ok(()) => {}
+            Err(e) if once => return Err(e),
+            // A server that's down for a while shouldn't end the daemon
+            Err(e) => eprintln!("{}: poll failed: {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), e),
+        }
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Look at the board once and notify about what's new
+async fn poll(ctx: &CommandContext, server: &str, thresholds: &Thresholds) -> Result<()> {
+    let cards = ctx.client.list_cards(None, false).await?;
+    // Archived cards can still be blockers
+    let all = if cards.iter().any(|c| !c.blocked_by.is_empty()) {
+        ctx.client.list_cards(None, true).await?
+    } else {
+        Vec::new()
+    };
+    let index = DependencyIndex::new(if all.is_empty() { &cards } else { &all });
+    let now = Utc::now();
+    let mut state = State::load()?;
+    let shown = state.servers.entry(server.to_string()).or_default();
+
+    let mut current = BTreeMap::new();
+    for card in &cards {
+        let alerts = alerts(card, &index, thresholds, now);
+        let before = shown.get(&card.id).cloned().unwrap_or_default();
+        for &alert in alerts.iter().filter(|a| !before.contains(a)) {
+            let message = describe(ctx, card, &index, alert);
+            let result = if ctx.dry_run {
+                eprintln!("[dry-run] desktop <- {}", message);
+                Ok(())
+            } else {
+                notify::send(&Channel::Desktop, &message).await
+            };
+            print_json_line(&Fired {
+                at: now,
+                alert,
+                id: &card.id,
+                title: &card.title,
+                message,
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        if !alerts.is_empty() {
+            current.insert(card.id.clone(), alerts);
+        }
+    }
+
+    // Cards that left every state (or the board) may alert again later
+    if *shown != current && !ctx.dry_run {
+        *shown = current;
+        state.save()?;
+    }
+    Ok(())
+}
+
+/// The states an open card is in right now
+fn alerts(card: &Card, index: &DependencyIndex, thresholds: &Thresholds, now: DateTime<Utc>) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    if card.column == Column::Done {
+        return alerts;
+    }
+    if let Some(due) = card.due_date {
+        if due < now {
+            alerts.push(Alert::Overdue);
+        } else if due - now < thresholds.due_soon(card.priority) {
+            alerts.push(Alert::DueSoon);
+        }
+    }
+    if card.agent_status == Some(AgentStatus::Blocked) || !index.is_unblocked(card) {
+        alerts.push(Alert::Blocked);
+    }
+    alerts
+}
+
+fn describe(ctx: &CommandContext, card: &Card, index: &DependencyIndex, alert: Alert) -> String {
+    let due = card.due_date.map(|d| format_due(ctx, d)).unwrap_or_default();
+    let what = match alert {
+        Alert::DueSoon => format!("Due {}", due),
+        Alert::Overdue => format!("Overdue since {}", due),
+        Alert::Blocked => {
+            let waiting_on: Vec<&str> = index.open_blockers(card).iter().map(|b| short_id(&b.id)).collect();
+            match (&card.blocked_reason, waiting_on.is_empty()) {
+                (Some(reason), _) => format!("Blocked: {}", reason),
+                (None, false) => format!("Waiting on {}", waiting_on.join(", ")),
+                (None, true) => "Blocked".to_string(),
+            }
+        }
+    };
+    format!("{}: {} ({}, {})", what, card.title, short_id(&card.id), card.priority)
+}
+
+fn pid_path() -> Result<PathBuf> {
+    Config::dir()
+        .map(|d| d.join(PID_FILE))
+        .ok_or_else(|| BlazeError::Config("No config directory".into()))
+}
+
+/// Start `blaze notifyd` in the background
+pub fn detach(ctx: &CommandContext, interval: Option<std::time::Duration>) -> Result<()> {
+    let pid_path = pid_path()?;
+    let mut cmd = Command::new(std::env::current_exe()?);
+    match ctx.profile() {
+        Some(profile) => cmd.args(["--profile", profile]),
+        None => cmd.args(["--url", &ctx.url]),
+    };
+    cmd.arg("notifyd");
+    if let Some(interval) = interval {
+        cmd.args(["--interval", &interval.as_secs().to_string()]);
+    }
+    // The environment keeps the token out of `ps`
+    if let Some(ref token) = ctx.token {
+        cmd.env("BLAZE_TOKEN", token);
+    }
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    // Detach from the terminal's process group so Ctrl-C doesn't take it down
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let child = cmd.spawn()?;
+    if let Some(dir) = pid_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&pid_path, child.id().to_string())?;
+    eprintln!("notifyd running as pid {}; `blaze notifyd --stop` ends it", child.id());
+    Ok(())
+}
+
+/// Stop a detached `blaze notifyd`
+pub fn stop() -> Result<()> {
+    let pid_path = pid_path()?;
+    let pid = match fs::read_to_string(&pid_path) {
+        Ok(pid) => pid.trim().to_string(),
+        Err(_) => return Err(BlazeError::InvalidInput("No detached notifyd is running".into())),
+    };
+    let status = Command::new("kill").arg(&pid).stderr(Stdio::null()).status()?;
+    fs::remove_file(&pid_path)?;
+    if status.success() {
+        eprintln!("Stopped notifyd (pid {})", pid);
+    } else {
+        // It was gone already; the pid file was stale
+        eprintln!("notifyd (pid {}) wasn't running", pid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(priority: &str, due: Option<&str>, agent_status: Option<&str>) -> Card {
+        testing::card("c1", serde_json::json!({ "priority": priority, "due_date": due, "agent_status": agent_status }))
+    }
+
+    #[test]
+    fn due_soon_depends_on_priority() {
+        let now: DateTime<Utc> = "2026-03-10T12:00:00Z".parse().unwrap();
+        let config = NotifydConfig {
+            interval_secs: None,
+            due_soon_hours: [("low".to_string(), 0)].into(),
+        };
+        let thresholds = Thresholds::from_config(&config).unwrap();
+        let index = DependencyIndex::new(&[]);
+        let tomorrow = Some("2026-03-11T06:00:00Z");
+
+        assert_eq!(alerts(&card("urgent", tomorrow, None), &index, &thresholds, now), [Alert::DueSoon]);
+        assert_eq!(alerts(&card("medium", tomorrow, None), &index, &thresholds, now), []);
+        assert_eq!(alerts(&card("low", Some("2026-03-10T12:30:00Z"), None), &index, &thresholds, now), []);
+        let late_and_blocked = card("low", Some("2026-03-09T00:00:00Z"), Some("blocked"));
+        assert_eq!(alerts(&late_and_blocked, &index, &thresholds, now), [Alert::Overdue, Alert::Blocked]);
+
+        // Waiting on a card that isn't done is blocked too, whatever the agent says
+        let mut waiting = card("medium", None, None);
+        waiting.blocked_by = vec!["dep".into()];
+        let mut dep = card("medium", None, None);
+        dep.id = "dep".into();
+        let cards = [waiting.clone(), dep];
+        assert_eq!(alerts(&waiting, &DependencyIndex::new(&cards), &thresholds, now), [Alert::Blocked]);
+
+        let typo = NotifydConfig {
+            interval_secs: None,
+            due_soon_hours: [("critical".to_string(), 1)].into(),
+        };
+        assert!(Thresholds::from_config(&typo).is_err());
+    }
+}
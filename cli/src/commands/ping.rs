@@ -1,8 +1,10 @@
 //! `blaze ping` - Check API connectivity.
 
-use crate::client::Client;
+use crate::capabilities::Capability;
+use crate::context::CommandContext;
 use crate::error::Result;
 use crate::output::print_json;
+use futures::future::join_all;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -10,22 +12,36 @@ struct PingResult {
     ok: bool,
     url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    /// Optional features, with `--capabilities`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    capabilities: Vec<CapabilityStatus>,
+}
+
+#[derive(Serialize)]
+struct CapabilityStatus {
+    feature: Capability,
+    endpoint: &'static str,
+    supported: bool,
 }
 
-pub async fn run(url: &str) -> Result<()> {
-    let client = Client::new(url, None)?;
+pub async fn run(ctx: &CommandContext, capabilities: bool) -> Result<()> {
+    let url = &ctx.url;
 
-    match client.health().await {
+    match ctx.client.health().await {
         Ok(resp) => {
             let result = PingResult {
                 ok: resp.status == "ok",
                 url: url.to_string(),
+                profile: ctx.profile().map(String::from),
                 error: if resp.status != "ok" {
                     Some(format!("Unexpected status: {}", resp.status))
                 } else {
                     None
                 },
+                capabilities: if capabilities { probe(ctx).await } else { Vec::new() },
             };
             print_json(&result);
             Ok(())
@@ -34,10 +50,24 @@ pub async fn run(url: &str) -> Result<()> {
             let result = PingResult {
                 ok: false,
                 url: url.to_string(),
+                profile: ctx.profile().map(String::from),
                 error: Some(e.to_string()),
+                capabilities: Vec::new(),
             };
             print_json(&result);
             Err(e)
         }
     }
 }
+
+/// Ask the server about every optional feature at once
+async fn probe(ctx: &CommandContext) -> Vec<CapabilityStatus> {
+    let checks = Capability::ALL.iter().map(|&feature| async move {
+        CapabilityStatus {
+            feature,
+            endpoint: feature.endpoint(),
+            supported: ctx.client.supports(feature).await,
+        }
+    });
+    join_all(checks).await
+}
@@ -1,26 +1,38 @@
 //! `blaze plan` - Plan management commands.
 
-use crate::client::Client;
-use crate::error::Result;
-use crate::output::print_json;
-use crate::types::{PlanCreate, PlanFileCreate, PlanFileUpdate, PlanStatus, PlanUpdate};
+use crate::breakdown;
+use crate::cache;
+use crate::client::PageRequest;
+use crate::context::{CommandContext, OutputFormat};
+use crate::editor;
+use crate::error::{BlazeError, Result};
+use crate::merge::{self, Chunk};
+use crate::output::{print_cards, print_json, print_plan_detail, print_plans, print_table};
+use crate::plan_lint::{self, Severity};
+use crate::plan_sign;
+use crate::types::{CardCreate, Column, Plan, PlanCreate, PlanFileCreate, PlanFileUpdate, PlanStatus, PlanUpdate};
+use crate::{out, outln};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
 
 /// List plans with optional status filter
-pub async fn list(client: &Client, status: Option<PlanStatus>) -> Result<()> {
-    let plans = client.list_plans(status).await?;
-    print_json(&plans);
+pub async fn list(ctx: &CommandContext, status: Option<PlanStatus>, request: PageRequest) -> Result<()> {
+    let plans = ctx.client.list_plans_paged(status, request).await?;
+    print_plans(ctx, &plans);
     Ok(())
 }
 
 /// Show a single plan
-pub async fn show(client: &Client, plan_id: &str) -> Result<()> {
-    let plan = client.get_plan(plan_id).await?;
-    print_json(&plan);
+pub async fn show(ctx: &CommandContext, plan_id: &str) -> Result<()> {
+    let plan = ctx.client.get_plan(plan_id).await?;
+    print_plan_detail(ctx, &plan);
     Ok(())
 }
 
 /// Create a new plan
-pub async fn add(client: &Client, title: String, file: Option<String>) -> Result<()> {
+pub async fn add(ctx: &CommandContext, title: String, file: Option<String>) -> Result<()> {
     let files = if let Some(filename) = file {
         vec![PlanFileCreate {
             name: filename,
@@ -30,29 +42,249 @@ pub async fn add(client: &Client, title: String, file: Option<String>) -> Result
         Vec::new()
     };
 
-    let plan = client
+    let plan = ctx
+        .client
         .create_plan(&PlanCreate { title, files })
         .await?;
-    print_json(&plan);
+    print_plan_detail(ctx, &plan);
+    Ok(())
+}
+
+/// Options for `blaze plan breakdown`
+pub struct BreakdownOptions {
+    pub plan_id: String,
+    /// Only this file's tasks (all files if not set)
+    pub file: Option<String>,
+    pub column: Column,
+    pub tags: Vec<String>,
+}
+
+/// Create a card per open task in a plan's task lists, with `(after: ...)` as dependencies
+pub async fn breakdown(ctx: &CommandContext, options: BreakdownOptions) -> Result<()> {
+    let plan = ctx.client.get_plan(&options.plan_id).await?;
+    if let Some(ref name) = options.file {
+        if !plan.files.iter().any(|f| f.name == *name) {
+            return Err(BlazeError::NotFound(format!("File '{}' in plan {}", name, plan.id)));
+        }
+    }
+    let tasks: Vec<breakdown::Task> = plan
+        .files
+        .iter()
+        .filter(|f| options.file.as_ref().is_none_or(|name| f.name == *name))
+        .flat_map(|f| breakdown::parse_tasks(&f.name, &f.content))
+        .collect();
+    // Everything is checked before the first card is created
+    let graph = breakdown::plan_graph(tasks)?;
+    if graph.tasks.is_empty() {
+        eprintln!("No open tasks (`- [ ] ...`) in plan {}", plan.id);
+        return Ok(());
+    }
+
+    // Tasks that already have a card (e.g. from an earlier run) are linked, not created again
+    let existing: HashMap<String, String> = ctx
+        .client
+        .list_cards(None, false)
+        .await?
+        .into_iter()
+        .map(|c| (c.title.to_lowercase(), c.id))
+        .collect();
+
+    let mut ids: Vec<Option<String>> = Vec::with_capacity(graph.tasks.len());
+    let mut created = Vec::new();
+    for (task, blockers) in graph.tasks.iter().zip(&graph.blocked_by) {
+        if let Some(id) = existing.get(&task.title.to_lowercase()) {
+            eprintln!("Keeping existing card {} for '{}'", id, task.title);
+            ids.push(Some(id.clone()));
+            continue;
+        }
+        let mut builder = CardCreate::builder()
+            .title(task.title.clone())
+            .description(format!("From plan \"{}\" ({})", plan.title, task.source))
+            .column(options.column.clone())
+            .tags(options.tags.clone());
+        for id in blockers.iter().filter_map(|&b| ids[b].as_ref()) {
+            builder = builder.blocked_by(id.clone());
+        }
+        match ctx.client.create_card(&builder.build()?).await {
+            Ok(card) => {
+                ids.push(Some(card.id.clone()));
+                created.push(card);
+            }
+            Err(BlazeError::DryRun) => ids.push(None),
+            Err(e) => {
+                if !created.is_empty() {
+                    eprintln!("Created {} of {} cards before the error", created.len(), graph.tasks.len());
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    print_cards(ctx, &created);
     Ok(())
 }
 
 /// Update a plan
 pub async fn edit(
-    client: &Client,
+    ctx: &CommandContext,
     plan_id: &str,
     title: Option<String>,
     status: Option<PlanStatus>,
+    force: bool,
 ) -> Result<()> {
+    // Catch invalid status changes locally instead of surfacing a server error
+    if let Some(to) = status {
+        let current = ctx.client.get_plan(plan_id).await?;
+        current.status.check_transition(to)?;
+        if to == PlanStatus::Approved && current.status != to && !force {
+            check_lint(ctx, &current)?;
+        }
+    }
+
     let update = PlanUpdate { title, status };
-    let plan = client.update_plan(plan_id, &update).await?;
-    print_json(&plan);
+    let plan = ctx.client.update_plan(plan_id, &update).await?;
+    print_plan_detail(ctx, &plan);
     Ok(())
 }
 
+/// Refuse approval while `plan lint` finds errors
+fn check_lint(ctx: &CommandContext, plan: &Plan) -> Result<()> {
+    let findings = plan_lint::lint(&plan.files, PlanStatus::Approved, &ctx.config.plan_lint);
+    let errors: Vec<_> = findings.iter().filter(|f| f.severity == Severity::Error).collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    for f in &errors {
+        match f.file {
+            Some(_) => eprintln!("  {}: {}", f.location(), f.message),
+            None => eprintln!("  {}", f.message),
+        }
+    }
+    Err(BlazeError::InvalidInput(format!(
+        "Plan {} has {} lint error(s); fix them or approve with --force",
+        plan.id,
+        errors.len()
+    )))
+}
+
+/// Approve a plan, optionally with a signature over its files
+pub async fn approve(ctx: &CommandContext, plan_id: &str, sign: bool, key: Option<&str>, force: bool) -> Result<()> {
+    let plan = ctx.client.get_plan(plan_id).await?;
+    let approved = plan.status == PlanStatus::Approved;
+    if !approved {
+        plan.status.check_transition(PlanStatus::Approved)?;
+        if !force {
+            check_lint(ctx, &plan)?;
+        }
+    } else if !sign {
+        eprintln!("Plan {} is already approved", plan_id);
+        return Ok(());
+    }
+
+    if sign {
+        let now = Utc::now();
+        let signed = plan_sign::sign(&plan_sign::manifest(&plan, now), key)?;
+        let file = PlanFileCreate {
+            name: plan_sign::approval_name(&plan, now),
+            content: signed,
+        };
+        match ctx.client.add_plan_file(&plan.id, &file).await {
+            Ok(_) => eprintln!("Signed approval stored as {}", file.name),
+            Err(BlazeError::DryRun) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let plan = if approved {
+        ctx.client.get_plan(&plan.id).await?
+    } else {
+        let update = PlanUpdate {
+            title: None,
+            status: Some(PlanStatus::Approved),
+        };
+        ctx.client.update_plan(&plan.id, &update).await?
+    };
+    print_plan_detail(ctx, &plan);
+    Ok(())
+}
+
+/// Check a plan's signed approvals against its files
+pub async fn verify(ctx: &CommandContext, plan_id: &str) -> Result<()> {
+    let plan = ctx.client.get_plan(plan_id).await?;
+    let verifications = plan_sign::verify(&plan)?;
+    if verifications.is_empty() {
+        return Err(BlazeError::InvalidInput(format!(
+            "Plan {} has no signed approvals (`plan approve --sign` adds one)",
+            plan_id
+        )));
+    }
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&verifications),
+        OutputFormat::Quiet => verifications.iter().filter(|v| v.holds()).for_each(|v| outln!("{}", v.file)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = verifications
+                .iter()
+                .map(|v| {
+                    vec![
+                        v.file.clone(),
+                        v.signer.clone().unwrap_or_else(|| "-".into()),
+                        v.signature.describe().to_string(),
+                        if v.differences.is_empty() { "none".into() } else { v.differences.join(", ") },
+                    ]
+                })
+                .collect();
+            print_table(&["FILE", "SIGNER", "SIGNATURE", "CHANGES SINCE"], rows);
+        }
+    }
+
+    if verifications.iter().any(|v| v.holds()) {
+        Ok(())
+    } else {
+        Err(BlazeError::Other(format!("No signed approval of plan {} holds", plan_id)))
+    }
+}
+
+/// Check a plan as it would be judged at `status` (default: its current status)
+pub async fn lint(ctx: &CommandContext, plan_id: &str, status: Option<PlanStatus>) -> Result<()> {
+    let plan = ctx.client.get_plan(plan_id).await?;
+    let status = status.unwrap_or(plan.status);
+    let findings = plan_lint::lint(&plan.files, status, &ctx.config.plan_lint);
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&findings),
+        OutputFormat::Quiet => {}
+        OutputFormat::Table | OutputFormat::Markdown => {
+            if findings.is_empty() {
+                eprintln!("No problems found ({} plan)", status);
+            } else {
+                let rows = findings
+                    .iter()
+                    .map(|f| {
+                        let level = match f.severity {
+                            Severity::Error => "error",
+                            Severity::Warning => "warning",
+                        };
+                        vec![level.to_string(), f.location(), f.message.clone()]
+                    })
+                    .collect();
+                print_table(&["LEVEL", "WHERE", "PROBLEM"], rows);
+            }
+        }
+    }
+
+    match findings.iter().filter(|f| f.severity == Severity::Error).count() {
+        0 => Ok(()),
+        errors => Err(BlazeError::Other(format!("Plan {} has {} lint error(s)", plan_id, errors))),
+    }
+}
+
 /// Delete a plan
-pub async fn rm(client: &Client, plan_id: &str, force: bool) -> Result<()> {
-    if !force {
+pub async fn rm(ctx: &CommandContext, plan_id: &str, force: bool) -> Result<()> {
+    if !force && !ctx.dry_run {
         eprintln!("Delete plan {}? [y/N] ", plan_id);
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
@@ -62,49 +294,196 @@ pub async fn rm(client: &Client, plan_id: &str, force: bool) -> Result<()> {
         }
     }
 
-    client.delete_plan(plan_id).await?;
+    ctx.client.delete_plan(plan_id).await?;
     eprintln!("Deleted plan {}", plan_id);
     Ok(())
 }
 
 /// Add a file to a plan
-pub async fn file_add(client: &Client, plan_id: &str, filename: &str) -> Result<()> {
+pub async fn file_add(ctx: &CommandContext, plan_id: &str, filename: &str) -> Result<()> {
     let file = PlanFileCreate {
         name: filename.to_string(),
         content: String::new(),
     };
-    let plan = client.add_plan_file(plan_id, &file).await?;
-    print_json(&plan);
+    let plan = ctx.client.add_plan_file(plan_id, &file).await?;
+    print_plan_detail(ctx, &plan);
     Ok(())
 }
 
 /// Show a file from a plan
-pub async fn file_show(client: &Client, plan_id: &str, filename: &str) -> Result<()> {
-    let file = client.get_plan_file(plan_id, filename).await?;
-    print_json(&file);
+pub async fn file_show(ctx: &CommandContext, plan_id: &str, filename: &str) -> Result<()> {
+    let file = ctx.client.get_plan_file(plan_id, filename).await?;
+    match ctx.format {
+        OutputFormat::Table | OutputFormat::Markdown => out!("{}", file.content),
+        _ => print_json(&file),
+    }
+    Ok(())
+}
+
+/// Options for editing a plan file
+pub struct FileEditOptions {
+    pub new_name: Option<String>,
+    pub content: Option<String>,
+    /// The version the edit started from (defaults to the copy `plan file open` cached when
+    /// the new content is given, else the current server copy)
+    pub base: Option<PathBuf>,
+    pub watch_conflicts: bool,
+}
+
+/// Download a plan file to the cache and open it with the default application
+pub async fn file_open(ctx: &CommandContext, plan_id: &str, filename: &str) -> Result<()> {
+    let file = ctx.client.get_plan_file(plan_id, filename).await?;
+    let source = cache_source(plan_id, &file.name);
+    let path = cache::store(&["plans", plan_id, &file.name], &source, file.content.as_bytes())?;
+
+    editor::open_path(&path)?;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => {
+            print_json(&serde_json::json!({ "source": source, "path": path }))
+        }
+        _ => outln!("{}", path.display()),
+    }
     Ok(())
 }
 
 /// Update a file in a plan (content from stdin or argument)
 pub async fn file_edit(
-    client: &Client,
+    ctx: &CommandContext,
     plan_id: &str,
     filename: &str,
-    new_name: Option<String>,
-    content: Option<String>,
+    options: FileEditOptions,
 ) -> Result<()> {
+    let content = if options.watch_conflicts {
+        match merge_edit(ctx, plan_id, filename, options.content, options.base).await? {
+            Some(content) => Some(content),
+            None => return Ok(()),
+        }
+    } else if options.content.is_none() && options.new_name.is_none() {
+        Some(read_stdin()?)
+    } else {
+        options.content
+    };
+
     let update = PlanFileUpdate {
-        name: new_name,
+        name: options.new_name,
         content,
     };
-    let plan = client.update_plan_file(plan_id, filename, &update).await?;
-    print_json(&plan);
+    let plan = ctx.client.update_plan_file(plan_id, filename, &update).await?;
+    print_plan_detail(ctx, &plan);
     Ok(())
 }
 
+/// Cache source name of a plan file
+fn cache_source(plan_id: &str, filename: &str) -> String {
+    format!("plan:{}/{}", plan_id, filename)
+}
+
+/// Produce the content to save, merged with any changes made on the server
+/// since the edit started. Returns `None` if the user aborts.
+async fn merge_edit(
+    ctx: &CommandContext,
+    plan_id: &str,
+    filename: &str,
+    content: Option<String>,
+    base: Option<PathBuf>,
+) -> Result<Option<String>> {
+    // Content given up front was most likely edited from the opened copy
+    let opened = match content {
+        Some(_) => cache::cached(&cache_source(plan_id, filename))?.and_then(|c| String::from_utf8(c).ok()),
+        None => None,
+    };
+    let base = match (base, opened) {
+        (Some(path), _) => std::fs::read_to_string(path)?,
+        (None, Some(opened)) => opened,
+        (None, None) => ctx.client.get_plan_file(plan_id, filename).await?.content,
+    };
+
+    let local = match content {
+        Some(content) => content,
+        None if std::io::stdin().is_terminal() => editor::edit_text(&base, filename)?,
+        None => read_stdin()?,
+    };
+
+    // Re-fetch right before saving to pick up concurrent edits
+    let remote = ctx.client.get_plan_file(plan_id, filename).await?.content;
+    if remote == base {
+        return Ok(Some(local));
+    }
+
+    let chunks = merge::merge(&base, &local, &remote);
+    let conflicts = merge::conflict_count(&chunks);
+    eprintln!(
+        "{} changed on the server while editing; merged ({} conflict(s))",
+        filename, conflicts
+    );
+    if conflicts == 0 {
+        return Ok(merge::merged_text(&chunks));
+    }
+
+    if !std::io::stdin().is_terminal() {
+        for chunk in &chunks {
+            if let Chunk::Conflict { base, local, remote } = chunk {
+                eprint!("{}", merge::conflict_markers(base, local, remote));
+            }
+        }
+        return Err(BlazeError::Conflict(format!(
+            "{} conflicting region(s) in {}; rerun in a terminal to resolve them",
+            conflicts, filename
+        )));
+    }
+
+    resolve_conflicts(chunks, conflicts)
+}
+
+/// Ask the user to pick a side for each conflict
+fn resolve_conflicts(chunks: Vec<Chunk>, total: usize) -> Result<Option<String>> {
+    let mut text = String::new();
+    let mut seen = 0;
+
+    for chunk in chunks {
+        let (base, local, remote) = match chunk {
+            Chunk::Resolved(lines) => {
+                lines.iter().for_each(|l| text.push_str(l));
+                continue;
+            }
+            Chunk::Conflict { base, local, remote } => (base, local, remote),
+        };
+
+        seen += 1;
+        eprintln!("\nConflict {} of {}:", seen, total);
+        eprint!("{}", merge::conflict_markers(&base, &local, &remote));
+
+        loop {
+            eprint!("Keep [l]ocal, [r]emote, [b]oth, or [a]bort? ");
+            let mut input = String::new();
+            std::io::stdin().read_line(&mut input)?;
+            let keep: Vec<&String> = match input.trim().to_ascii_lowercase().as_str() {
+                "l" | "local" => local.iter().collect(),
+                "r" | "remote" => remote.iter().collect(),
+                "b" | "both" => local.iter().chain(remote.iter()).collect(),
+                "a" | "abort" | "" => {
+                    eprintln!("Aborted.");
+                    return Ok(None);
+                }
+                _ => continue,
+            };
+            keep.into_iter().for_each(|l| text.push_str(l));
+            break;
+        }
+    }
+
+    Ok(Some(text))
+}
+
+fn read_stdin() -> Result<String> {
+    let mut content = String::new();
+    std::io::stdin().read_to_string(&mut content)?;
+    Ok(content)
+}
+
 /// Delete a file from a plan
-pub async fn file_rm(client: &Client, plan_id: &str, filename: &str) -> Result<()> {
-    let plan = client.delete_plan_file(plan_id, filename).await?;
-    print_json(&plan);
+pub async fn file_rm(ctx: &CommandContext, plan_id: &str, filename: &str) -> Result<()> {
+    let plan = ctx.client.delete_plan_file(plan_id, filename).await?;
+    print_plan_detail(ctx, &plan);
     Ok(())
 }
@@ -0,0 +1,161 @@
+//! `blaze plugin` - Git-style external subcommands.
+//!
+//! `blaze foo args...` runs the first `blaze-foo` executable on PATH with
+//! `args...`, after the global options before `foo` have been applied. The
+//! plugin gets the resolved settings in its environment:
+//!
+//! - `BLAZE_URL`: API base URL
+//! - `BLAZE_FORMAT`: output format (`json`, `table`, `quiet`, `csv`, `ndjson`, `markdown`)
+//! - `BLAZE_COLOR`: `auto`, `always` or `never` (`NO_COLOR` is set too for `never`)
+//! - `BLAZE_PROFILE`: active profile, if any
+//! - `BLAZE_CONFIG`: path of config.toml
+//! - `BLAZE_TOKEN_FILE`: token file, if the token is kept in one
+//! - `BLAZE_TOKEN`: only when given with `--token` or already set
+//! - `BLAZE_BIN`: this blaze binary, for calling back into it (it knows how to get the token)
+
+use crate::config::Config;
+use crate::context::{GlobalArgs, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table, ColorMode};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PREFIX: &str = "blaze-";
+
+/// An executable found on PATH
+#[derive(Debug, Serialize)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// List plugins on PATH (the first of each name wins, as when running them)
+pub fn list(format: OutputFormat) -> Result<()> {
+    let plugins = discover();
+    match format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&plugins),
+        OutputFormat::Quiet => plugins.iter().for_each(|p| outln!("{}", p.name)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = plugins
+                .iter()
+                .map(|p| vec![p.name.clone(), p.path.display().to_string()])
+                .collect();
+            print_table(&["NAME", "PATH"], rows);
+        }
+    }
+    if plugins.is_empty() && !matches!(format, OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv) {
+        eprintln!("No blaze-* executables on PATH");
+    }
+    Ok(())
+}
+
+fn discover() -> Vec<Plugin> {
+    let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let path = env::var_os("PATH").unwrap_or_default();
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = dir.read_dir() else { continue };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str().and_then(|n| n.strip_prefix(PREFIX)) else {
+                continue;
+            };
+            // Windows executables carry an extension
+            let name = name.strip_suffix(env::consts::EXE_SUFFIX).unwrap_or(name);
+            if !name.is_empty() && is_executable(&entry.path()) {
+                found.entry(name.to_string()).or_insert_with(|| entry.path());
+            }
+        }
+    }
+    found.into_iter().map(|(name, path)| Plugin { name, path }).collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Settings handed to a plugin
+pub struct PluginEnv {
+    pub url: String,
+    pub profile: Option<String>,
+    pub format: OutputFormat,
+    pub color: ColorMode,
+    /// Only a token given explicitly; plugins can ask `BLAZE_BIN` for the stored one
+    pub token: Option<String>,
+}
+
+impl PluginEnv {
+    /// Resolve the settings from global options and the config file
+    pub fn from_args(global: &GlobalArgs) -> Result<Self> {
+        let config = Config::load(global.profile.as_deref())?;
+        let url = global
+            .url
+            .clone()
+            .or(config.url().map(String::from))
+            .unwrap_or_else(|| "http://localhost:8080".to_string());
+        Ok(Self {
+            url,
+            profile: config.profile,
+            format: global.format,
+            color: if global.no_color { ColorMode::Never } else { global.color },
+            token: global.token.clone(),
+        })
+    }
+}
+
+/// Run `blaze-<name>` and return its exit code
+pub fn run(args: &[String], settings: &PluginEnv) -> Result<i32> {
+    let (name, plugin_args) = args
+        .split_first()
+        .ok_or_else(|| BlazeError::InvalidInput("Missing command".into()))?;
+    let plugin = discover().into_iter().find(|p| p.name == *name).ok_or_else(|| {
+        BlazeError::InvalidInput(format!(
+            "Unknown command '{}' (no {}{} on PATH; see `blaze --help` and `blaze plugin list`)",
+            name, PREFIX, name
+        ))
+    })?;
+
+    let mut command = Command::new(&plugin.path);
+    command.args(plugin_args).env("BLAZE_URL", &settings.url);
+    if let Some(format) = settings.format.to_possible_value() {
+        command.env("BLAZE_FORMAT", format.get_name());
+    }
+    if let Some(color) = settings.color.to_possible_value() {
+        command.env("BLAZE_COLOR", color.get_name());
+    }
+    if settings.color == ColorMode::Never {
+        command.env("NO_COLOR", "1");
+    }
+    if let Some(ref profile) = settings.profile {
+        command.env("BLAZE_PROFILE", profile);
+    }
+    if let Some(path) = Config::path() {
+        command.env("BLAZE_CONFIG", path);
+    }
+    if let Some(path) = Config::token_path(settings.profile.as_deref()).filter(|p| p.exists()) {
+        command.env("BLAZE_TOKEN_FILE", path);
+    }
+    if let Some(ref token) = settings.token {
+        command.env("BLAZE_TOKEN", token);
+    }
+    if let Ok(bin) = env::current_exe() {
+        command.env("BLAZE_BIN", bin);
+    }
+
+    let status = command
+        .status()
+        .map_err(|e| BlazeError::Other(format!("Failed to run {}: {}", plugin.path.display(), e)))?;
+    // Killed by a signal if there's no code
+    Ok(status.code().unwrap_or(1))
+}
@@ -0,0 +1,263 @@
+//! `blaze poker` - Estimate a card together.
+//!
+//! Each participant enters an estimate without seeing the others'. Once
+//! everyone has voted, the votes are revealed, the agreed value is stored as
+//! the card's estimate, and the session is recorded as a comment on the card.
+//!
+//! Votes come from the terminal (typed blind, one participant at a time) or
+//! from stdin as `name=value` tokens, e.g. collected in chat:
+//!
+//! ```text
+//! echo "ana=3 ben=5 chris=3" | blaze poker 84eb
+//! ```
+//!
+//! A vote of `?` abstains.
+
+use crate::commands::comment;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table, short_id};
+use crate::types::{CardUpdate, CommentCreate};
+use serde::Serialize;
+use std::io::{self, IsTerminal, Read, Write};
+
+/// Options for `blaze poker`
+pub struct PokerOptions {
+    pub card_id: String,
+    /// Who votes, in order (asked for interactively when empty)
+    pub participants: Vec<String>,
+    /// Value to record when the votes differ, instead of asking (or the median)
+    pub estimate: Option<f64>,
+}
+
+/// One participant's vote (`None` abstains)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Vote {
+    pub participant: String,
+    pub estimate: Option<f64>,
+}
+
+/// Outcome of a session
+#[derive(Debug, Serialize)]
+struct PokerResult {
+    card_id: String,
+    votes: Vec<Vote>,
+    unanimous: bool,
+    median: f64,
+    estimate: f64,
+}
+
+pub async fn run(ctx: &CommandContext, options: PokerOptions) -> Result<()> {
+    let card_id = ctx.resolve_card_id(&options.card_id).await?;
+    let card = ctx.client.get_card(&card_id).await?;
+
+    let interactive = io::stdin().is_terminal();
+    let votes = if interactive {
+        eprintln!("Estimating {}  {}", short_id(&card.id), card.title);
+        collect_interactive(&options.participants)?
+    } else {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        parse_votes(&input)?
+    };
+
+    let values: Vec<f64> = votes.iter().filter_map(|v| v.estimate).collect();
+    let median = median(&values).ok_or_else(|| BlazeError::InvalidInput("No estimates given".into()))?;
+    let unanimous = values.iter().all(|v| *v == values[0]);
+
+    reveal(ctx, &votes, median);
+    let estimate = match options.estimate {
+        _ if unanimous => values[0],
+        Some(estimate) => estimate,
+        None if interactive => ask_estimate(median)?,
+        None => median,
+    };
+
+    let result = PokerResult {
+        card_id: card.id.clone(),
+        votes,
+        unanimous,
+        median,
+        estimate,
+    };
+
+    // Record the outcome; in dry-run mode show both requests
+    let update = CardUpdate::builder().estimate(Some(estimate)).build()?;
+    let recorded = ctx.client.update_card(&card.id, &update).await;
+    let session = CommentCreate {
+        body: session_log(&result),
+        author: comment::author_or_default(ctx, None),
+    };
+    let logged = ctx.client.add_comment(&card.id, &session).await;
+    match (recorded, logged) {
+        (Ok(_), Ok(_)) => {}
+        (Err(e), _) | (_, Err(e)) => return Err(e),
+    }
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&result),
+        OutputFormat::Quiet => outln!("{}", format_estimate(estimate)),
+        OutputFormat::Table | OutputFormat::Markdown => eprintln!(
+            "Recorded estimate {} on {}",
+            format_estimate(estimate),
+            short_id(&card.id)
+        ),
+    }
+    Ok(())
+}
+
+/// Ask each participant for a hidden vote
+fn collect_interactive(participants: &[String]) -> Result<Vec<Vote>> {
+    let mut votes: Vec<Vote> = Vec::new();
+    let mut names = participants.iter().cloned();
+    loop {
+        let participant = match names.next() {
+            Some(name) => name,
+            None if !participants.is_empty() => break,
+            None => {
+                let name = prompt("Participant (empty to reveal): ")?;
+                if name.is_empty() {
+                    break;
+                }
+                name
+            }
+        };
+        if votes.iter().any(|v| v.participant == participant) {
+            eprintln!("{} has already voted", participant);
+            continue;
+        }
+        let estimate = loop {
+            let input = rpassword::prompt_password(format!("{}'s estimate (hidden, ? to abstain): ", participant))?;
+            match parse_estimate(&input) {
+                Ok(estimate) => break estimate,
+                Err(e) => eprintln!("{}", e),
+            }
+        };
+        votes.push(Vote { participant, estimate });
+    }
+    Ok(votes)
+}
+
+fn prompt(question: &str) -> Result<String> {
+    eprint!("{}", question);
+    io::stderr().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Ask for the value to record when the votes differ
+fn ask_estimate(median: f64) -> Result<f64> {
+    loop {
+        let input = prompt(&format!("Votes differ; estimate to record [{}]: ", format_estimate(median)))?;
+        if input.is_empty() {
+            return Ok(median);
+        }
+        match parse_estimate(&input) {
+            Ok(Some(estimate)) => return Ok(estimate),
+            Ok(None) => eprintln!("Enter a number"),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn reveal(ctx: &CommandContext, votes: &[Vote], median: f64) {
+    // Machine-readable formats get everything in the final result
+    if !matches!(ctx.format, OutputFormat::Table | OutputFormat::Markdown) {
+        return;
+    }
+    let rows = votes
+        .iter()
+        .map(|v| vec![v.participant.clone(), v.estimate.map(format_estimate).unwrap_or_else(|| "?".into())])
+        .collect();
+    print_table(&["PARTICIPANT", "ESTIMATE"], rows);
+    outln!("\nMedian: {}", format_estimate(median));
+}
+
+/// Parse `name=value` (or `name:value`) tokens separated by whitespace
+pub fn parse_votes(input: &str) -> Result<Vec<Vote>> {
+    let mut votes: Vec<Vote> = Vec::new();
+    for token in input.split_whitespace() {
+        let (name, value) = token
+            .split_once('=')
+            .or_else(|| token.split_once(':'))
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| BlazeError::InvalidInput(format!("Vote '{}' should look like name=estimate", token)))?;
+        if votes.iter().any(|v| v.participant == name) {
+            return Err(BlazeError::InvalidInput(format!("{} voted more than once", name)));
+        }
+        votes.push(Vote {
+            participant: name.to_string(),
+            estimate: parse_estimate(value)?,
+        });
+    }
+    if votes.is_empty() {
+        return Err(BlazeError::InvalidInput("No votes on stdin (expected name=estimate ...)".into()));
+    }
+    Ok(votes)
+}
+
+fn parse_estimate(input: &str) -> Result<Option<f64>> {
+    match input.trim() {
+        "?" => Ok(None),
+        value => value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .map(Some)
+            .ok_or_else(|| BlazeError::InvalidInput(format!("Invalid estimate '{}'", value))),
+    }
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(sorted[mid]),
+        _ => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+    }
+}
+
+fn format_estimate(value: f64) -> String {
+    format!("{}", value)
+}
+
+/// Timeline entry describing the session
+fn session_log(result: &PokerResult) -> String {
+    let votes: Vec<String> = result
+        .votes
+        .iter()
+        .map(|v| format!("{} {}", v.participant, v.estimate.map(format_estimate).unwrap_or_else(|| "?".into())))
+        .collect();
+    format!(
+        "Estimation poker: {} -> {}{}",
+        votes.join(", "),
+        format_estimate(result.estimate),
+        if result.unanimous { " (unanimous)" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piped_votes_parse_and_median() {
+        let votes = parse_votes("ana=3 ben:5\nchris=?  dee=8").unwrap();
+        assert_eq!(votes.len(), 4);
+        assert_eq!(votes[2], Vote { participant: "chris".into(), estimate: None });
+
+        let values: Vec<f64> = votes.iter().filter_map(|v| v.estimate).collect();
+        assert_eq!(median(&values), Some(5.0));
+        assert_eq!(median(&[3.0, 5.0]), Some(4.0));
+        assert_eq!(median(&[]), None);
+
+        assert!(parse_votes("ana=3 ana=5").is_err());
+        assert!(parse_votes("ana").is_err());
+        assert!(parse_votes("ana=-1").is_err());
+        assert!(parse_votes("").is_err());
+    }
+}
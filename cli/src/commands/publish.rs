@@ -0,0 +1,63 @@
+//! `blaze publish` - Serve a read-only board snapshot for status pages.
+
+use crate::columns;
+use crate::context::CommandContext;
+use crate::error::Result;
+use crate::publish::{self, Rendered, SharedRendered, Snapshot};
+use crate::types::Column;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Serve snapshots on `listen`, refetching the board every `refresh`
+pub async fn run(ctx: &CommandContext, listen: &str, refresh: Duration) -> Result<()> {
+    let listener = TcpListener::bind(listen).await?;
+    eprintln!(
+        "Publishing a read-only board snapshot on http://{} (refresh every {}s)",
+        listener.local_addr()?,
+        refresh.as_secs()
+    );
+
+    let rendered: SharedRendered = Arc::new(RwLock::new(Rendered::default()));
+    let mut snapshot: Option<Snapshot> = None;
+    let mut ticker = tokio::time::interval(refresh);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                snapshot = Some(match ctx.client.list_cards(None, false).await {
+                    Ok(cards) => Snapshot::new(&cards, &columns::in_order(ctx.client.columns().await, &cards)),
+                    // Keep showing the last good board, marked as stale
+                    Err(e) => {
+                        eprintln!("Refresh failed: {}", e);
+                        let mut stale = snapshot.take().unwrap_or_else(|| Snapshot::new(&[], &Column::DEFAULTS));
+                        stale.stale = true;
+                        stale
+                    }
+                });
+                if let Some(ref snapshot) = snapshot {
+                    *rendered.write().unwrap() = Rendered {
+                        html: snapshot.to_html(refresh.as_secs()),
+                        json: serde_json::to_string(snapshot)?,
+                    };
+                }
+            }
+            accepted = listener.accept() => {
+                // Running out of file descriptors and the like passes; keep serving
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        eprintln!("Accept failed: {}", e);
+                        // Without a pause this would spin on the same error
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                let rendered = Arc::clone(&rendered);
+                tokio::spawn(async move {
+                    let _ = publish::handle(stream, rendered).await;
+                });
+            }
+        }
+    }
+}
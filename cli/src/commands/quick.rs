@@ -0,0 +1,80 @@
+//! `blaze quick` - Create a card from one line of inline syntax.
+//!
+//! `blaze quick 'Fix login bug !high #backend @friday ^review'` is the same
+//! as `blaze add "Fix login bug" -p high -t backend --due <friday> -c review`;
+//! see `parsers::parse_quick` for the markers.
+//!
+//! With `--stdin`, every non-blank line is a card. They are created
+//! concurrently and each result is printed as it comes in, like `add --batch`
+//! (`item` is the line number).
+
+use crate::agent_rules::AgentRules;
+use crate::commands::add::{self, print_batch_result, BatchResult};
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::output::print_card_detail;
+use crate::parsers::parse_quick;
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use std::io::Read;
+
+pub async fn run(ctx: &CommandContext, line: &str) -> Result<()> {
+    let rules = AgentRules::from_config(&ctx.config.agent_rules)?;
+    let card = parse_quick(line, Utc::now().with_timezone(&ctx.tz).date_naive(), ctx.tz)?;
+    let created = add::create(ctx, &rules, &card).await?;
+    ctx.remember_card(&created.id);
+    print_card_detail(ctx, &created);
+    Ok(())
+}
+
+/// Create a card for each line on stdin, up to `concurrency` at once
+pub async fn run_stdin(ctx: &CommandContext, concurrency: usize) -> Result<()> {
+    let rules = &AgentRules::from_config(&ctx.config.agent_rules)?;
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+
+    // One date for the whole run, so `@friday` means the same on every line
+    let today = Utc::now().with_timezone(&ctx.tz).date_naive();
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line))
+        .collect();
+    let total = lines.len();
+
+    let mut results = stream::iter(lines)
+        .map(|(item, line)| async move {
+            let created = match parse_quick(line, today, ctx.tz) {
+                Ok(card) => add::create(ctx, rules, &card).await,
+                Err(e) => Err(e),
+            };
+            (item, created)
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    let mut failed = 0;
+    while let Some((item, created)) = results.next().await {
+        let result = match created {
+            Ok(card) => BatchResult::created(item, card),
+            // The request was printed (or queued) instead of sent
+            Err(BlazeError::DryRun | BlazeError::Queued) => continue,
+            Err(e) => {
+                failed += 1;
+                BatchResult::failed(item, &e)
+            }
+        };
+        print_batch_result(ctx, &result);
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+    if ctx.offline {
+        return Err(BlazeError::Queued);
+    }
+    if failed > 0 {
+        return Err(BlazeError::PartialFailure { failed, total });
+    }
+    Ok(())
+}
@@ -0,0 +1,356 @@
+//! `blaze repl` - Interactive shell sharing one client between commands.
+//!
+//! Each line is a blaze command line without the leading `blaze`. The
+//! connection, token and config are set up once, so commands start instantly.
+//! `$name` expands variables set with `set name value`; `$last_id` is the card
+//! most recently created or referenced. When stdin isn't a terminal, lines are
+//! run as a script that stops at the first failing command.
+
+use crate::config::Config;
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::journal::command_line;
+use crate::commands::plugin::{self, PluginEnv};
+use crate::outln;
+use crate::{dispatch, run_config, run_settings, Cli, Commands, PluginCommands};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal};
+
+/// Global options that can't change without rebuilding the client
+const SESSION_OPTIONS: &[&str] = &[
+    "profile",
+    "url",
+    "token",
+    "verbose",
+    "dry_run",
+    "offline",
+    "retries",
+    "retry_mutations",
+    "record",
+    "replay",
+];
+
+const BUILTINS_HELP: &str = "\
+Repl commands:
+  set NAME VALUE  Set a variable, used as $NAME ($last_id is set automatically)
+  unset NAME      Remove a variable
+  vars            Show variables
+  exit, quit      Leave the repl (or press Ctrl-D)";
+
+/// What to do after a line
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Continue,
+    Failed,
+    Exit,
+}
+
+pub async fn run(ctx: &mut CommandContext) -> Result<()> {
+    let mut vars = BTreeMap::new();
+    if !io::stdin().is_terminal() {
+        return run_script(ctx, &mut vars).await;
+    }
+
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new().map_err(readline_error)?;
+    editor.set_helper(Some(ReplHelper));
+    let history = Config::dir().map(|d| d.join("repl_history"));
+    if let Some(ref path) = history {
+        // Missing on first use
+        let _ = editor.load_history(path);
+    }
+
+    let prompt = match ctx.profile() {
+        Some(profile) => format!("blaze ({})> ", profile),
+        None => "blaze> ".to_string(),
+    };
+    eprintln!("Connected to {}. Type `help` for commands, Ctrl-D to exit.", ctx.url);
+
+    loop {
+        // Completion may fetch card IDs with its own runtime, which can't start on a runtime thread
+        let prompt = prompt.clone();
+        let (line, returned) = tokio::task::spawn_blocking(move || {
+            let line = editor.readline(&prompt);
+            (line, editor)
+        })
+        .await
+        .map_err(|e| BlazeError::Other(e.to_string()))?;
+        editor = returned;
+
+        match line {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                if execute(ctx, &mut vars, &line).await == Step::Exit {
+                    break;
+                }
+            }
+            // Ctrl-C abandons the current line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(readline_error(e)),
+        }
+    }
+
+    if let Some(path) = history {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(&path);
+    }
+    Ok(())
+}
+
+/// Run lines from stdin, stopping at the first failure
+async fn run_script(ctx: &mut CommandContext, vars: &mut BTreeMap<String, String>) -> Result<()> {
+    for (number, line) in io::stdin().lock().lines().enumerate() {
+        match execute(ctx, vars, &line?).await {
+            Step::Continue => {}
+            Step::Exit => break,
+            Step::Failed => return Err(BlazeError::Other(format!("Script stopped at line {}", number + 1))),
+        }
+    }
+    Ok(())
+}
+
+/// Run one line, reporting errors without ending the session
+async fn execute(ctx: &mut CommandContext, vars: &mut BTreeMap<String, String>, line: &str) -> Step {
+    match try_execute(ctx, vars, line).await {
+        Ok(step) => step,
+        // The request was printed (or queued) instead of sent
+        Err(BlazeError::DryRun | BlazeError::Queued) => Step::Continue,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            Step::Failed
+        }
+    }
+}
+
+async fn try_execute(ctx: &mut CommandContext, vars: &mut BTreeMap<String, String>, line: &str) -> Result<Step> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(Step::Continue);
+    }
+
+    let mut known = vars.clone();
+    if let Some(id) = ctx.last_card() {
+        known.insert("last_id".to_string(), id);
+    }
+    let words = split_line(line, &known)?;
+
+    match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["exit" | "quit"] => return Ok(Step::Exit),
+        ["set", "last_id", _] => {
+            return Err(BlazeError::InvalidInput("$last_id is set automatically".into()));
+        }
+        ["set", name, value] => {
+            if !is_var_name(name) {
+                return Err(BlazeError::InvalidInput(format!("Invalid variable name '{}'", name)));
+            }
+            vars.insert(name.to_string(), value.to_string());
+            return Ok(Step::Continue);
+        }
+        ["set", ..] => return Err(BlazeError::InvalidInput("Usage: set NAME VALUE (quote values with spaces)".into())),
+        ["unset", name] => {
+            vars.remove(*name);
+            return Ok(Step::Continue);
+        }
+        ["vars"] => {
+            known.iter().for_each(|(name, value)| outln!("{}={}", name, value));
+            return Ok(Step::Continue);
+        }
+        ["help"] => {
+            Cli::command().print_help()?;
+            outln!("\n{}", BUILTINS_HELP);
+            return Ok(Step::Continue);
+        }
+        _ => {}
+    }
+
+    let matches = match Cli::command().no_binary_name(true).try_get_matches_from(&words) {
+        Ok(matches) => matches,
+        Err(e) => {
+            // Help and version requests come back as errors too
+            let _ = e.print();
+            return Ok(if e.use_stderr() { Step::Failed } else { Step::Continue });
+        }
+    };
+    for id in SESSION_OPTIONS {
+        if matches.value_source(id) == Some(ValueSource::CommandLine) {
+            return Err(BlazeError::InvalidInput(format!(
+                "--{} is fixed for the session; restart `blaze repl` with it",
+                id.replace('_', "-")
+            )));
+        }
+    }
+    let cli = Cli::from_arg_matches(&matches).map_err(|e| BlazeError::InvalidInput(e.to_string()))?;
+
+    // --format and --tz apply to this line only
+    let (format, tz) = (ctx.format, ctx.tz);
+    if matches.value_source("format") == Some(ValueSource::CommandLine) {
+        ctx.format = cli.global.format;
+    }
+    if let Some(line_tz) = cli.global.tz {
+        ctx.tz = line_tz;
+    }
+
+    let result = match cli.command {
+        Commands::Repl | Commands::Completions { .. } => {
+            Err(BlazeError::InvalidInput(format!("`{}` isn't available inside the repl", words[0])))
+        }
+        Commands::Config { action } => run_config(action, ctx.profile(), ctx.format).await,
+        Commands::Settings { action } => run_settings(action, ctx.dry_run),
+        Commands::Plugin { action } => match action {
+            PluginCommands::List => plugin::list(ctx.format),
+        },
+        Commands::External(args) => {
+            let settings = PluginEnv {
+                url: ctx.url.clone(),
+                profile: ctx.profile().map(String::from),
+                format: ctx.format,
+                color: ctx.color,
+                token: None,
+            };
+            match plugin::run(&args, &settings)? {
+                0 => Ok(()),
+                code => Err(BlazeError::Other(format!("{} exited with status {}", args[0], code))),
+            }
+        }
+        command => {
+            // Cards may have been added since the last line
+            ctx.refresh_ids();
+            ctx.begin_operation(&command_line(&words));
+            dispatch(ctx, command).await
+        }
+    };
+
+    ctx.format = format;
+    ctx.tz = tz;
+    result.map(|()| Step::Continue)
+}
+
+/// Split a line into words like a shell does, then expand `$name` in each
+fn split_line(line: &str, vars: &BTreeMap<String, String>) -> Result<Vec<String>> {
+    let words = shlex::split(line).ok_or_else(|| BlazeError::InvalidInput("Unbalanced quotes".into()))?;
+    words.iter().map(|word| expand(word, vars)).collect()
+}
+
+fn expand(word: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = word;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..len];
+        if name.is_empty() {
+            // A lone `$` is literal
+            expanded.push('$');
+        } else {
+            let value = vars.get(name).ok_or_else(|| match name {
+                "last_id" => BlazeError::InvalidInput("$last_id is unset: no card created or referenced yet".into()),
+                _ => BlazeError::InvalidInput(format!("Unknown variable ${}", name)),
+            })?;
+            expanded.push_str(value);
+        }
+        rest = &after[len..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn readline_error(e: ReadlineError) -> BlazeError {
+    BlazeError::Other(format!("Line editor: {}", e))
+}
+
+/// Completes subcommands, flags and card IDs the same way shell completion does
+struct ReplHelper;
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &rustyline::Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let mut args: Vec<OsString> = std::iter::once(OsString::from("blaze"))
+            .chain(before.split_whitespace().map(OsString::from))
+            .collect();
+        let current = if before.is_empty() || before.ends_with(char::is_whitespace) {
+            args.push(OsString::new());
+            ""
+        } else {
+            before.split_whitespace().last().unwrap_or_default()
+        };
+
+        let index = args.len() - 1;
+        let candidates = clap_complete::engine::complete(&mut Cli::command(), args, index, None).unwrap_or_default();
+        let pairs = candidates
+            .into_iter()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| {
+                let value = c.get_value().to_string_lossy().into_owned();
+                let display = match c.get_help() {
+                    Some(help) => format!("{}  {}", value, help),
+                    None => value.clone(),
+                };
+                Pair {
+                    display,
+                    replacement: value,
+                }
+            })
+            .collect();
+        Ok((pos - current.len(), pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_split_and_expand() {
+        let vars = BTreeMap::from([
+            ("last_id".to_string(), "84eb0f2a11b7".to_string()),
+            ("col".to_string(), "review".to_string()),
+        ]);
+        assert_eq!(
+            split_line("move $last_id $col", &vars).unwrap(),
+            ["move", "84eb0f2a11b7", "review"]
+        );
+        assert_eq!(
+            split_line("add 'Fix $ sign in $col' --tag a", &vars).unwrap(),
+            ["add", "Fix $ sign in review", "--tag", "a"]
+        );
+        assert_eq!(
+            split_line("show $nope", &vars).unwrap_err().to_string(),
+            "Invalid input: Unknown variable $nope"
+        );
+        assert!(split_line("add \"unterminated", &vars).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! `blaze replace` - Find and replace text across cards.
+//!
+//! Every change is previewed per card (`-` old, `+` new) before anything is
+//! sent. In tags the text is replaced inside each tag; tags that end up
+//! empty are dropped and duplicates merged.
+
+use crate::commands::bulk;
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::output::short_id;
+use crate::parsers::Query;
+use crate::types::{Card, CardUpdate};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Card fields `replace` can change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReplaceField {
+    #[value(alias = "title")]
+    Titles,
+    #[value(alias = "description")]
+    Descriptions,
+    #[value(alias = "tag")]
+    Tags,
+}
+
+/// Options for `blaze replace`
+pub struct ReplaceOptions {
+    pub find: String,
+    pub replace: String,
+    /// Fields to search (all if empty)
+    pub fields: Vec<ReplaceField>,
+    /// Cards to consider (all active cards if not set)
+    pub filter: Option<Query>,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Maximum number of updates in flight
+    pub concurrency: usize,
+}
+
+/// New values for the fields a card would change
+#[derive(Debug, Default, PartialEq)]
+struct Replacement {
+    title: Option<String>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+impl Replacement {
+    fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.tags.is_none()
+    }
+}
+
+pub async fn run(ctx: &CommandContext, options: ReplaceOptions) -> Result<()> {
+    if options.find.is_empty() {
+        return Err(BlazeError::InvalidInput("--find must not be empty".into()));
+    }
+    let fields = if options.fields.is_empty() {
+        vec![ReplaceField::Titles, ReplaceField::Descriptions, ReplaceField::Tags]
+    } else {
+        options.fields.clone()
+    };
+
+    let changes: HashMap<String, (Card, Replacement)> = ctx
+        .client
+        .list_cards(None, false)
+        .await?
+        .into_iter()
+        .filter(|c| options.filter.as_ref().is_none_or(|q| q.matches(c)))
+        .filter_map(|card| {
+            let replacement = replace_in(&card, &options.find, &options.replace, &fields);
+            (!replacement.is_empty()).then(|| (card.id.clone(), (card, replacement)))
+        })
+        .collect();
+    if changes.is_empty() {
+        eprintln!("No cards contain '{}'", options.find);
+        return Ok(());
+    }
+
+    let mut ids: Vec<String> = changes.keys().cloned().collect();
+    ids.sort_by_key(|id| {
+        let card = &changes[id].0;
+        (card.column.rank(), card.position, card.created_at)
+    });
+    for id in &ids {
+        let (card, replacement) = &changes[id];
+        print_preview(card, replacement);
+    }
+
+    // Large operations get the typed confirmation instead
+    if !ctx.dry_run && !options.yes && !bulk::needs_phrase(ctx, ids.len()) {
+        eprint!("Replace '{}' with '{}' in {} card(s)? [y/N] ", options.find, options.replace, ids.len());
+        io::stderr().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let changes = &changes;
+    bulk::apply_concurrent(ctx, "replace", &ids, options.concurrency, |id| async move {
+        let (_, replacement) = &changes[&id];
+        let update = CardUpdate::builder()
+            .title(replacement.title.clone())
+            .description(replacement.description.clone())
+            .tags(replacement.tags.clone())
+            .build()?;
+        ctx.client.update_card(&id, &update).await
+    })
+    .await
+}
+
+/// What replacing `find` in the chosen fields would change on `card`
+fn replace_in(card: &Card, find: &str, replace: &str, fields: &[ReplaceField]) -> Replacement {
+    let replaced = |text: &str| Some(text.replace(find, replace)).filter(|new| new != text);
+    let mut replacement = Replacement::default();
+    if fields.contains(&ReplaceField::Titles) {
+        replacement.title = replaced(&card.title);
+    }
+    if fields.contains(&ReplaceField::Descriptions) {
+        replacement.description = card.description.as_deref().and_then(replaced);
+    }
+    if fields.contains(&ReplaceField::Tags) && card.tags.iter().any(|t| replaced(t).is_some()) {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in card.tags.iter().map(|t| t.replace(find, replace)) {
+            let tag = tag.trim().to_string();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        replacement.tags = Some(tags);
+    }
+    replacement
+}
+
+fn print_preview(card: &Card, replacement: &Replacement) {
+    eprintln!("{}  {}", short_id(&card.id), card.title);
+    if let Some(ref title) = replacement.title {
+        eprintln!("  title: - {}", card.title);
+        eprintln!("  title: + {}", title);
+    }
+    if let Some(ref description) = replacement.description {
+        let old = card.description.as_deref().unwrap_or_default();
+        // Only the lines that change
+        for (before, after) in old.lines().zip(description.lines()).filter(|(b, a)| b != a) {
+            eprintln!("  description: - {}", before);
+            eprintln!("  description: + {}", after);
+        }
+    }
+    if let Some(ref tags) = replacement.tags {
+        eprintln!("  tags: - {}", card.tags.join(", "));
+        eprintln!("  tags: + {}", tags.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card() -> Card {
+        testing::card("a1", serde_json::json!({
+            "title": "Deploy old-service", "description": "Step 1\nRestart old-service\nDone",
+            "tags": ["old-service", "new-service", "ops"],
+        }))
+    }
+
+    #[test]
+    fn replaces_in_chosen_fields_and_merges_tags() {
+        let all = [ReplaceField::Titles, ReplaceField::Descriptions, ReplaceField::Tags];
+        let replacement = replace_in(&card(), "old-service", "new-service", &all);
+        assert_eq!(replacement.title.as_deref(), Some("Deploy new-service"));
+        assert_eq!(replacement.description.as_deref(), Some("Step 1\nRestart new-service\nDone"));
+        assert_eq!(replacement.tags, Some(vec!["new-service".to_string(), "ops".to_string()]));
+
+        let titles_only = replace_in(&card(), "old-service", "new-service", &[ReplaceField::Titles]);
+        assert!(titles_only.description.is_none() && titles_only.tags.is_none());
+
+        // Emptied tags go away
+        let stripped = replace_in(&card(), "ops", "", &[ReplaceField::Tags]);
+        assert_eq!(stripped.tags, Some(vec!["old-service".to_string(), "new-service".to_string()]));
+
+        assert!(replace_in(&card(), "missing", "x", &all).is_empty());
+    }
+}
@@ -0,0 +1,532 @@
+//! `blaze report` - Analyses over the whole board, or several.
+//!
+//! `rollup` compares boards side by side. Boards are profiles from
+//! config.toml (`default` is the top-level server); one that can't be reached
+//! gets an error row instead of failing the report.
+//!
+//! `escalations` lists priority raises recorded by `edit --reason`, for retros.
+//!
+//! `wip` lists in-progress and review cards per assignee for standups. With
+//! `--payload` it prints a ready-made Slack message instead, for cron:
+//! `blaze report wip --payload slack-blocks | curl -d @- $SLACK_WEBHOOK`.
+
+use crate::client::Client;
+use crate::context::{CommandContext, OutputFormat};
+use crate::deps::{self, DependencyIndex};
+use crate::error::{BlazeError, Result};
+use crate::escalation::{self, Escalation};
+use crate::history;
+use crate::outln;
+use crate::output::{format_relative, format_time, print_csv, print_json, print_markdown, print_table, short_id};
+use crate::types::{AgentStatus, Card, Column, Priority};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use futures::{future, stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// One board's line in `report rollup`
+#[derive(Debug, Default, Serialize)]
+struct BoardRollup {
+    board: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    url: String,
+    total: usize,
+    open: usize,
+    overdue: usize,
+    /// Open cards waiting on open dependencies or marked blocked by an agent
+    blocked: usize,
+    /// Cards that reached done within the window
+    throughput: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Stats for several boards plus their totals
+#[derive(Debug, Serialize)]
+struct Rollup {
+    window_days: u32,
+    boards: Vec<BoardRollup>,
+    totals: BoardRollup,
+}
+
+/// Longest dependency chain to completion, with slack for every other card
+pub async fn critical_path(ctx: &CommandContext, default_estimate: f64) -> Result<()> {
+    // Archived cards may still be blockers
+    let cards = ctx.client.list_cards(None, true).await?;
+    let schedule = deps::critical_path(&cards, default_estimate)?;
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&schedule),
+        OutputFormat::Quiet => schedule.critical_path.iter().for_each(|c| outln!("{}", c.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            outln!("Critical path ({}):", format_amount(schedule.total));
+            let rows = schedule
+                .critical_path
+                .iter()
+                .map(|c| {
+                    vec![
+                        short_id(&c.id).to_string(),
+                        format_amount(c.estimate),
+                        format_amount(c.start),
+                        format_amount(c.finish),
+                        c.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "EST", "START", "FINISH", "TITLE"], rows);
+
+            let rest: Vec<_> = schedule.cards.iter().filter(|c| c.slack > 0.0).collect();
+            if !rest.is_empty() {
+                outln!("\nSlack:");
+                let rows = rest
+                    .iter()
+                    .map(|c| {
+                        vec![
+                            short_id(&c.id).to_string(),
+                            format_amount(c.estimate),
+                            format_amount(c.slack),
+                            c.title.clone(),
+                        ]
+                    })
+                    .collect();
+                print_table(&["ID", "EST", "SLACK", "TITLE"], rows);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print whole numbers without a trailing `.0`
+fn format_amount(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// Totals, overdue, blocked and throughput for several boards side by side
+pub async fn rollup(ctx: &CommandContext, boards: &[String], days: u32) -> Result<()> {
+    if days == 0 {
+        return Err(BlazeError::InvalidInput("--days must be at least 1".into()));
+    }
+    let boards: Vec<String> = if boards.is_empty() {
+        std::iter::once("default".to_string()).chain(ctx.config.profiles.keys().cloned()).collect()
+    } else {
+        boards.to_vec()
+    };
+
+    let now = Utc::now();
+    let since = now - chrono::Duration::days(days.into());
+    let rows = future::join_all(boards.iter().map(|board| async move {
+        let mut row = BoardRollup {
+            board: board.clone(),
+            ..BoardRollup::default()
+        };
+        // The board in use keeps its flags and environment (--url, BLAZE_TOKEN, ...)
+        let fetched = if ctx.profile().unwrap_or("default") == board {
+            row.url = ctx.url.clone();
+            board_cards(&ctx.client).await
+        } else {
+            match ctx.board_client(board).await {
+                Ok((url, client)) => {
+                    row.url = url;
+                    board_cards(&client).await
+                }
+                Err(e) => Err(e),
+            }
+        };
+        match fetched {
+            Ok(cards) => summarize(&mut row, &cards, since, now),
+            Err(e) => row.error = Some(e.to_string()),
+        }
+        row
+    }))
+    .await;
+
+    let totals = rows.iter().fold(
+        BoardRollup {
+            board: "total".to_string(),
+            ..BoardRollup::default()
+        },
+        |mut acc, row| {
+            acc.total += row.total;
+            acc.open += row.open;
+            acc.overdue += row.overdue;
+            acc.blocked += row.blocked;
+            acc.throughput += row.throughput;
+            acc
+        },
+    );
+    print_rollup(
+        ctx,
+        &Rollup {
+            window_days: days,
+            boards: rows,
+            totals,
+        },
+    );
+    Ok(())
+}
+
+/// Comment fetches in flight at once for `escalations`
+const COMMENT_FETCHES: usize = 8;
+
+/// Priority raises with their reasons from the last `days` days, newest first
+pub async fn escalations(ctx: &CommandContext, days: u32) -> Result<()> {
+    if days == 0 {
+        return Err(BlazeError::InvalidInput("--days must be at least 1".into()));
+    }
+    let since = Utc::now() - chrono::Duration::days(days.into());
+    // A raise updates the card, so older cards can't have one in the window
+    let cards: Vec<Card> = ctx
+        .client
+        .list_cards(None, true)
+        .await?
+        .into_iter()
+        .filter(|c| c.updated_at >= since)
+        .collect();
+
+    let per_card: Vec<Vec<Escalation>> = stream::iter(&cards)
+        .map(|card| async move {
+            let comments = ctx.client.list_comments(&card.id).await?;
+            Ok::<_, BlazeError>(
+                comments
+                    .iter()
+                    .filter(|c| c.created_at >= since)
+                    .filter_map(|c| escalation::parse(card, c))
+                    .collect(),
+            )
+        })
+        .buffer_unordered(COMMENT_FETCHES)
+        .try_collect()
+        .await?;
+    let mut raises: Vec<Escalation> = per_card.into_iter().flatten().collect();
+    raises.sort_by_key(|e| std::cmp::Reverse(e.at));
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&raises),
+        OutputFormat::Quiet => raises.iter().for_each(|e| outln!("{}", e.card_id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = raises
+                .iter()
+                .map(|e| {
+                    vec![
+                        format_time(ctx, e.at),
+                        short_id(&e.card_id).to_string(),
+                        format!("{} → {}", e.from, e.to),
+                        e.author.clone(),
+                        e.title.clone(),
+                        e.reason.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["WHEN", "ID", "PRIORITY", "BY", "TITLE", "REASON"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// A chat message `report wip` can print instead of the report itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WipPayload {
+    /// Slack Block Kit message: a header, a section per assignee and a summary
+    SlackBlocks,
+    /// Slack message with the same content as plain mrkdwn text
+    SlackText,
+}
+
+/// Slack's limits: blocks per message, characters per section
+const SLACK_BLOCKS: usize = 50;
+const SLACK_SECTION: usize = 3000;
+
+/// A card in progress or in review
+#[derive(Debug, Serialize)]
+struct WipCard {
+    id: String,
+    title: String,
+    column: Column,
+    priority: Priority,
+    /// When it entered its column, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<DateTime<Utc>>,
+    /// In its column longer than `--stale-days`
+    stale: bool,
+}
+
+/// One assignee's cards (`None` for unassigned ones)
+#[derive(Debug, Serialize)]
+struct WipGroup {
+    assignee: Option<String>,
+    cards: Vec<WipCard>,
+}
+
+/// Cards in progress or in review, per assignee
+pub async fn wip(ctx: &CommandContext, stale_days: u32, payload: Option<WipPayload>) -> Result<()> {
+    let cards = board_cards(&ctx.client).await?;
+    let now = Utc::now();
+    let groups = wip_groups(cards, now - chrono::Duration::days(stale_days.into()));
+
+    if let Some(payload) = payload {
+        print_json(&slack_message(&groups, payload, stale_days, now));
+        return Ok(());
+    }
+    let rows = || {
+        groups.iter().flat_map(|group| {
+            group.cards.iter().map(|card| {
+                let since = card.since.map(|t| format_relative(t, now)).unwrap_or_default();
+                vec![
+                    group.assignee.clone().unwrap_or_else(|| "-".into()),
+                    short_id(&card.id).to_string(),
+                    card.column.display_name().to_string(),
+                    if card.stale { format!("{} (stale)", since) } else { since },
+                    card.title.clone(),
+                ]
+            })
+        })
+    };
+    let headers = ["ASSIGNEE", "ID", "COLUMN", "SINCE", "TITLE"];
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&groups),
+        OutputFormat::Quiet => groups.iter().flat_map(|g| &g.cards).for_each(|c| outln!("{}", c.id)),
+        OutputFormat::Csv => print_csv(&headers.map(str::to_lowercase), rows().collect()),
+        OutputFormat::Markdown => print_markdown(&headers, rows().collect()),
+        OutputFormat::Table => print_table(&headers, rows().collect()),
+    }
+    Ok(())
+}
+
+/// Group in-progress and review cards by assignee (unassigned last), longest waiting first
+fn wip_groups(cards: Vec<Card>, stale_before: DateTime<Utc>) -> Vec<WipGroup> {
+    let mut groups: Vec<WipGroup> = Vec::new();
+    let mut cards: Vec<Card> = cards
+        .into_iter()
+        .filter(|c| matches!(c.column, Column::InProgress | Column::Review))
+        .collect();
+    cards.sort_by(|a, b| {
+        let key = |c: &Card| (c.assignee.is_none(), c.assignee.as_ref().map(|a| a.to_lowercase()));
+        let waiting = |c: &Card| (c.entered_column_at.is_none(), c.entered_column_at);
+        key(a).cmp(&key(b)).then(waiting(a).cmp(&waiting(b)))
+    });
+    for card in cards {
+        let since = card.entered_column_at;
+        let card_assignee = card.assignee.clone();
+        let wip = WipCard {
+            id: card.id,
+            title: card.title,
+            column: card.column,
+            priority: card.priority,
+            since,
+            stale: since.is_some_and(|t| t < stale_before),
+        };
+        match groups.last_mut() {
+            Some(group) if eq_assignee(&group.assignee, &card_assignee) => group.cards.push(wip),
+            _ => groups.push(WipGroup {
+                assignee: card_assignee,
+                cards: vec![wip],
+            }),
+        }
+    }
+    groups
+}
+
+fn eq_assignee(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// The report as a Slack message body, ready to POST to an incoming webhook
+fn slack_message(groups: &[WipGroup], payload: WipPayload, stale_days: u32, now: DateTime<Utc>) -> Value {
+    let total: usize = groups.iter().map(|g| g.cards.len()).sum();
+    let stale = groups.iter().flat_map(|g| &g.cards).filter(|c| c.stale).count();
+    let mut summary = format!("{} card{} in progress or review", total, if total == 1 { "" } else { "s" });
+    if stale > 0 {
+        summary.push_str(&format!(", {} in the same column for over {}d", stale, stale_days));
+    }
+    let sections: Vec<String> = groups.iter().map(|g| slack_section(g, now)).collect();
+
+    match payload {
+        WipPayload::SlackText => {
+            let mut text = format!("*Work in progress*: {}", summary);
+            for section in &sections {
+                text.push_str("\n\n");
+                text.push_str(section);
+            }
+            json!({ "text": text })
+        }
+        WipPayload::SlackBlocks => {
+            let mut blocks = vec![json!({
+                "type": "header",
+                "text": { "type": "plain_text", "text": "Work in progress" },
+            })];
+            // Header and summary take two of the blocks
+            let room = SLACK_BLOCKS - 2;
+            let shown = if sections.len() > room { room - 1 } else { sections.len() };
+            for section in &sections[..shown] {
+                blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": section } }));
+            }
+            if shown < sections.len() {
+                let more = format!("…and {} more assignees", sections.len() - shown);
+                blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": more } }));
+            }
+            blocks.push(json!({
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": summary }],
+            }));
+            // `text` is what notifications show
+            json!({ "text": format!("Work in progress: {}", summary), "blocks": blocks })
+        }
+    }
+}
+
+/// One assignee's cards as mrkdwn, cut to fit a section
+fn slack_section(group: &WipGroup, now: DateTime<Utc>) -> String {
+    let name = group.assignee.as_deref().map_or_else(|| "Unassigned".to_string(), slack_escape);
+    let mut text = format!("*{}* ({})", name, group.cards.len());
+    for (shown, card) in group.cards.iter().enumerate() {
+        let mut line = format!(
+            "\n• `{}` {} · {}",
+            short_id(&card.id),
+            slack_escape(&card.title),
+            card.column.display_name()
+        );
+        if let Some(since) = card.since {
+            line.push_str(&format!(" since {}", format_relative(since, now)));
+        }
+        if card.stale {
+            line.push_str(" :hourglass:");
+        }
+        let more = format!("\n…and {} more", group.cards.len() - shown);
+        if text.len() + line.len() + more.len() > SLACK_SECTION {
+            text.push_str(&more);
+            break;
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+/// Slack treats `&`, `<` and `>` as markup in message text
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Active cards with the time each entered its column filled in
+async fn board_cards(client: &Client) -> Result<Vec<Card>> {
+    let mut cards = client.list_cards(None, false).await?;
+    history::fill_entered_column_at(client, &mut cards).await?;
+    Ok(cards)
+}
+
+fn summarize(row: &mut BoardRollup, cards: &[Card], since: DateTime<Utc>, now: DateTime<Utc>) {
+    let index = DependencyIndex::new(cards);
+    let open: Vec<&Card> = cards.iter().filter(|c| c.column != Column::Done).collect();
+    row.total = cards.len();
+    row.open = open.len();
+    row.overdue = open.iter().filter(|c| c.due_date.is_some_and(|due| due < now)).count();
+    row.blocked = open
+        .iter()
+        .filter(|c| c.agent_status == Some(AgentStatus::Blocked) || !index.is_unblocked(c))
+        .count();
+    // Without a recorded move, the last update is the best guess for when a card was finished
+    row.throughput = cards
+        .iter()
+        .filter(|c| c.column == Column::Done)
+        .filter(|c| c.entered_column_at.unwrap_or(c.updated_at) >= since)
+        .count();
+}
+
+fn print_rollup(ctx: &CommandContext, rollup: &Rollup) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(rollup),
+        OutputFormat::Quiet => rollup.boards.iter().for_each(|b| outln!("{} {}", b.board, b.open)),
+        OutputFormat::Table | OutputFormat::Csv | OutputFormat::Markdown => {
+            let throughput = format!("DONE ({}D)", rollup.window_days);
+            let headers = ["BOARD", "TOTAL", "OPEN", "OVERDUE", "BLOCKED", throughput.as_str()];
+            let rows = rollup
+                .boards
+                .iter()
+                .chain(std::iter::once(&rollup.totals))
+                .map(|b| {
+                    let counts = [b.total, b.open, b.overdue, b.blocked, b.throughput];
+                    let cells = counts.iter().map(|n| match b.error {
+                        Some(_) => "-".to_string(),
+                        None => n.to_string(),
+                    });
+                    std::iter::once(b.board.clone()).chain(cells).collect()
+                })
+                .collect();
+            match ctx.format {
+                OutputFormat::Csv => {
+                    let headers = ["board", "total", "open", "overdue", "blocked", "throughput"];
+                    print_csv(&headers, rows);
+                }
+                OutputFormat::Markdown => print_markdown(&headers, rows),
+                _ => print_table(&headers, rows),
+            }
+            for board in &rollup.boards {
+                if let Some(ref error) = board.error {
+                    eprintln!("{}: {}", board.board, error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, column: Column, due: Option<&str>, blocked_by: &[&str], updated: &str) -> Card {
+        testing::card(id, serde_json::json!({
+            "column": column, "due_date": due, "blocked_by": blocked_by, "updated_at": updated,
+        }))
+    }
+
+    #[test]
+    fn summarizes_one_board() {
+        let now: DateTime<Utc> = "2026-03-10T12:00:00Z".parse().unwrap();
+        let cards = [
+            card("late", Column::Todo, Some("2026-03-01T23:59:59Z"), &[], "2026-03-01T00:00:00Z"),
+            card("waiting", Column::Backlog, None, &["late"], "2026-03-01T00:00:00Z"),
+            card("shipped", Column::Done, None, &[], "2026-03-09T00:00:00Z"),
+            card("old", Column::Done, None, &[], "2026-01-05T00:00:00Z"),
+        ];
+        let mut row = BoardRollup::default();
+        summarize(&mut row, &cards, now - chrono::Duration::days(7), now);
+        assert_eq!((row.total, row.open, row.overdue, row.blocked, row.throughput), (4, 2, 1, 1, 1));
+    }
+
+    #[test]
+    fn wip_slack_blocks() {
+        let now: DateTime<Utc> = "2026-03-10T12:00:00Z".parse().unwrap();
+        let mut cards = vec![
+            card("a1", Column::InProgress, None, &[], "2026-03-10T00:00:00Z"),
+            card("a2", Column::Review, None, &[], "2026-03-10T00:00:00Z"),
+            card("b1", Column::InProgress, None, &[], "2026-03-10T00:00:00Z"),
+            card("c1", Column::Todo, None, &[], "2026-03-10T00:00:00Z"),
+        ];
+        cards[0].assignee = Some("dana".into());
+        cards[0].entered_column_at = Some("2026-03-01T00:00:00Z".parse().unwrap());
+        cards[1].assignee = Some("Dana".into());
+        cards[1].title = "Fix <login> & co".into();
+        let groups = wip_groups(cards, now - chrono::Duration::days(3));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].assignee, None);
+
+        let message = slack_message(&groups, WipPayload::SlackBlocks, 3, now);
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 4);
+        let dana = blocks[1]["text"]["text"].as_str().unwrap();
+        assert!(dana.starts_with("*dana* (2)"));
+        assert!(dana.contains("since 9d ago :hourglass:"));
+        assert!(dana.contains("Fix &lt;login&gt; &amp; co"));
+        let summary = &blocks[3]["elements"][0]["text"];
+        assert_eq!(summary, "3 cards in progress or review, 1 in the same column for over 3d");
+    }
+}
@@ -1,8 +1,11 @@
-//! `blaze rm` - Delete a card.
+//! `blaze rm` - Delete cards.
+//!
+//! Deleted cards go to the trash (see `blaze trash`) unless `--permanent`.
 
-use crate::client::Client;
+use crate::commands::{bulk, trash};
+use crate::context::CommandContext;
 use crate::error::Result;
-use crate::output::print_json;
+use crate::output::{print_json, short_id};
 use serde::Serialize;
 use std::io::{self, Write};
 
@@ -11,17 +14,28 @@ struct DeleteResult {
     deleted: bool,
     id: String,
     title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
 }
 
-pub async fn run(client: &Client, card_id: &str, force: bool) -> Result<()> {
+pub async fn run(ctx: &CommandContext, card_ids: &[String], force: bool, permanent: bool) -> Result<()> {
+    match card_ids {
+        [card_id] => run_one(ctx, card_id, force, permanent).await,
+        _ => run_many(ctx, card_ids, force, permanent).await,
+    }
+}
+
+async fn run_one(ctx: &CommandContext, card_id: &str, force: bool, permanent: bool) -> Result<()> {
     // Fetch card details for confirmation
-    let card = client.get_card(card_id).await?;
+    let card_id = &ctx.resolve_card_id(card_id).await?;
+    let card = ctx.client.get_card(card_id).await?;
 
-    if !force {
+    // Nothing is deleted in a dry run, so there is nothing to confirm
+    if !force && !ctx.dry_run {
         eprint!(
             "Delete card \"{}\" ({})? [y/N] ",
             card.title,
-            &card.id[..8.min(card.id.len())]
+            short_id(&card.id)
         );
         io::stderr().flush().unwrap();
 
@@ -33,18 +47,46 @@ pub async fn run(client: &Client, card_id: &str, force: bool) -> Result<()> {
                 deleted: false,
                 id: card.id,
                 title: card.title,
+                profile: ctx.profile().map(String::from),
             };
             print_json(&result);
             return Ok(());
         }
     }
 
-    client.delete_card(card_id).await?;
+    trash::delete(ctx, &card, permanent).await?;
+    if !permanent {
+        eprintln!("Moved to the trash; `blaze trash restore {}` brings it back", short_id(&card.id));
+    }
     let result = DeleteResult {
         deleted: true,
         id: card.id,
         title: card.title,
+        profile: ctx.profile().map(String::from),
     };
     print_json(&result);
     Ok(())
 }
+
+async fn run_many(ctx: &CommandContext, card_ids: &[String], force: bool, permanent: bool) -> Result<()> {
+    // Large deletions get the typed confirmation instead
+    if !force && !ctx.dry_run && !bulk::needs_phrase(ctx, card_ids.len()) {
+        eprint!("Delete {} cards? [y/N] ", card_ids.len());
+        io::stderr().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    bulk::apply(ctx, "delete", card_ids, |id| async move {
+        let id = ctx.resolve_card_id(&id).await?;
+        let card = ctx.client.get_card(&id).await?;
+        trash::delete(ctx, &card, permanent).await?;
+        Ok(card)
+    })
+    .await
+}
@@ -0,0 +1,55 @@
+//! `blaze script` - Run Rhai scripts against the board.
+
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::parsers::Query;
+use crate::script::{self, Op, Request};
+use std::fs;
+use std::path::Path;
+use tokio::sync::mpsc;
+
+pub async fn run(ctx: &CommandContext, file: &Path, args: Vec<String>) -> Result<()> {
+    let source = fs::read_to_string(file)
+        .map_err(|e| BlazeError::InvalidInput(format!("Can't read {}: {}", file.display(), e)))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
+    let script = tokio::task::spawn_blocking(move || script::run(&source, args, tx));
+
+    // Ends when the script finishes and drops its end of the channel
+    while let Some(req) = rx.recv().await {
+        let result = answer(ctx, req.op).await.map_err(|e| e.to_string());
+        let _ = req.reply.send(result);
+    }
+
+    script.await.map_err(|e| BlazeError::Other(e.to_string()))?
+}
+
+async fn answer(ctx: &CommandContext, op: Op) -> Result<serde_json::Value> {
+    match op {
+        Op::Cards(query) => {
+            let query = query.as_deref().map(Query::parse).transpose()?;
+            let cards: Vec<_> = ctx
+                .client
+                .list_cards(None, false)
+                .await?
+                .into_iter()
+                .filter(|c| query.as_ref().is_none_or(|q| q.matches(c)))
+                .collect();
+            Ok(serde_json::to_value(cards)?)
+        }
+        Op::Card(id) => {
+            let id = ctx.resolve_card_id(&id).await?;
+            Ok(serde_json::to_value(ctx.client.get_card(&id).await?)?)
+        }
+        Op::Update(id, update) => {
+            let id = ctx.resolve_card_id(&id).await?;
+            let card = match ctx.client.update_card(&id, &update).await {
+                Ok(card) => card,
+                // The change was printed (or queued); carry on with the card as it is
+                Err(BlazeError::DryRun | BlazeError::Queued) => ctx.client.get_card(&id).await?,
+                Err(e) => return Err(e),
+            };
+            Ok(serde_json::to_value(card)?)
+        }
+    }
+}
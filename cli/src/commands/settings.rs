@@ -0,0 +1,238 @@
+//! `blaze settings` - Share a CLI setup as one bundle.
+//!
+//! `export` packs saved views (views.toml), criteria sets (criteria.toml),
+//! `[agent_rules]`, `[display]` (row rules, timestamps, ID length) and
+//! `[colors]` (styles, emoji) into a JSON file; `import` merges one into the
+//! local files. Entries with the same name are replaced, everything else is
+//! kept. Server URLs, profiles, credentials and notify.toml (webhooks) stay
+//! out: they're personal or secret.
+//!
+//! Like `blaze config`, these commands don't need a server.
+
+use crate::agent_rules::AgentRules;
+use crate::config::{AgentRule, ColorsConfig, Config, DisplayConfig};
+use crate::criteria::{parse_set_name, CriteriaSet, CriteriaStore};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{Highlighter, Theme};
+use crate::parsers::Query;
+use crate::types::SavedView;
+use crate::views::{parse_view_name, ViewStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Bumped when a bundle changes in a way older versions would misread
+const BUNDLE_VERSION: u32 = 1;
+
+/// Everything `settings export` writes
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Bundle {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    views: Vec<SavedView>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    criteria: Vec<CriteriaSet>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    agent_rules: BTreeMap<String, AgentRule>,
+    #[serde(default, skip_serializing_if = "DisplayConfig::is_empty")]
+    display: DisplayConfig,
+    #[serde(default, skip_serializing_if = "ColorsConfig::is_empty")]
+    colors: ColorsConfig,
+}
+
+/// What an import changed
+#[derive(Debug, Default, PartialEq)]
+struct Imported {
+    views: usize,
+    criteria: usize,
+    agent_rules: usize,
+    display_rules: usize,
+    /// Timestamps, ID length or colors
+    preferences: bool,
+}
+
+/// Write the local settings to `file` (`-` for stdout)
+pub fn export(file: &Path) -> Result<()> {
+    let config = Config::load(None)?;
+    let bundle = Bundle {
+        version: BUNDLE_VERSION,
+        views: ViewStore::load()?.all(),
+        criteria: CriteriaStore::load()?.all(),
+        agent_rules: config.agent_rules,
+        display: config.display,
+        colors: config.colors,
+    };
+    let json = serde_json::to_string_pretty(&bundle)?;
+    if file == Path::new("-") {
+        outln!("{}", json);
+        return Ok(());
+    }
+    fs::write(file, json + "\n")?;
+    eprintln!(
+        "Exported {} view(s), {} criteria set(s), {} agent rule(s) and display settings to {}",
+        bundle.views.len(),
+        bundle.criteria.len(),
+        bundle.agent_rules.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// Merge a bundle from `file` (`-` for stdin) into the local settings
+pub fn import(file: &Path, dry_run: bool) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(file)
+            .map_err(|e| BlazeError::InvalidInput(format!("Can't read {}: {}", file.display(), e)))?
+    };
+    let bundle: Bundle = serde_json::from_str(&content)
+        .map_err(|e| BlazeError::InvalidInput(format!("{} is not a settings bundle: {}", file.display(), e)))?;
+
+    let mut config = Config::load(None)?;
+    let mut views = ViewStore::load()?;
+    let mut criteria = CriteriaStore::load()?;
+    let imported = merge(bundle, &mut config, &mut views, &mut criteria)?;
+
+    let mut summary = format!(
+        "{} view(s), {} criteria set(s), {} agent rule(s), {} display rule(s)",
+        imported.views, imported.criteria, imported.agent_rules, imported.display_rules
+    );
+    if imported.preferences {
+        summary.push_str(" and table preferences");
+    }
+    if dry_run {
+        eprintln!("Would import {} (nothing saved)", summary);
+        return Ok(());
+    }
+    views.save()?;
+    criteria.save()?;
+    config.save()?;
+    eprintln!("Imported {}", summary);
+    Ok(())
+}
+
+/// Apply `bundle` to the settings, checking it all before anything is changed
+fn merge(
+    bundle: Bundle,
+    config: &mut Config,
+    views: &mut ViewStore,
+    criteria: &mut CriteriaStore,
+) -> Result<Imported> {
+    if bundle.version > BUNDLE_VERSION {
+        return Err(BlazeError::InvalidInput(format!(
+            "The bundle is version {}; this blaze reads up to {} (upgrade it first)",
+            bundle.version, BUNDLE_VERSION
+        )));
+    }
+    for view in &bundle.views {
+        parse_view_name(&view.name)?;
+        Query::parse(&view.query)
+            .map_err(|e| BlazeError::InvalidInput(format!("View '{}' has an invalid query: {}", view.name, e)))?;
+    }
+    for set in &bundle.criteria {
+        parse_set_name(&set.name)?;
+        if set.criteria.is_empty() || set.criteria.iter().any(|c| c.trim().is_empty()) {
+            return Err(BlazeError::InvalidInput(format!("Criteria set '{}' has empty criteria", set.name)));
+        }
+    }
+
+    let mut agent_rules = config.agent_rules.clone();
+    agent_rules.extend(bundle.agent_rules.clone());
+    AgentRules::from_config(&agent_rules)?;
+
+    let mut display = config.display.clone();
+    display.rules.extend(bundle.display.rules.clone());
+    display.timestamps = bundle.display.timestamps.or(display.timestamps);
+    display.id_length = bundle.display.id_length.or(display.id_length);
+    Highlighter::from_config(&display, true)?;
+
+    let mut colors = config.colors.clone();
+    colors.emoji = bundle.colors.emoji.or(colors.emoji);
+    colors.priority.extend(bundle.colors.priority.clone());
+    colors.column.extend(bundle.colors.column.clone());
+    Theme::from_config(&colors, true)?;
+
+    let imported = Imported {
+        views: bundle.views.len(),
+        criteria: bundle.criteria.len(),
+        agent_rules: bundle.agent_rules.len(),
+        display_rules: bundle.display.rules.len(),
+        preferences: bundle.display.timestamps.is_some()
+            || bundle.display.id_length.is_some()
+            || !bundle.colors.is_empty(),
+    };
+    for view in bundle.views {
+        views.insert(view);
+    }
+    for set in bundle.criteria {
+        criteria.insert(set);
+    }
+    config.agent_rules = agent_rules;
+    config.display = display;
+    config.colors = colors;
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bundles_merge_by_name() {
+        let mut config = Config::default();
+        config.display.id_length = Some(12);
+        config.agent_rules.insert(
+            "mine".into(),
+            AgentRule {
+                when: "tag:mine".into(),
+                assignable: false,
+            },
+        );
+        let mut views = ViewStore::default();
+        views.insert(SavedView {
+            name: "urgent".into(),
+            query: "priority:high".into(),
+            description: None,
+        });
+        let mut criteria = CriteriaStore::default();
+
+        let bundle: Bundle = serde_json::from_value(json!({
+            "version": 1,
+            "views": [{ "name": "urgent", "query": "priority:urgent" }],
+            "criteria": [{ "name": "review", "criteria": ["Tests pass"] }],
+            "agent_rules": { "ready": { "when": "column:todo" } },
+            "display": { "timestamps": "relative" },
+            "colors": { "emoji": false },
+        }))
+        .unwrap();
+        let imported = merge(bundle, &mut config, &mut views, &mut criteria).unwrap();
+
+        assert_eq!((imported.views, imported.criteria, imported.agent_rules), (1, 1, 1));
+        assert!(imported.preferences);
+        assert_eq!(views.get("urgent").unwrap().query, "priority:urgent");
+        assert_eq!(criteria.get("review").unwrap().criteria, ["Tests pass"]);
+        assert_eq!(config.agent_rules.keys().collect::<Vec<_>>(), ["mine", "ready"]);
+        // Settings the bundle doesn't have are kept
+        assert_eq!(config.display.id_length, Some(12));
+        assert_eq!(config.colors.emoji, Some(false));
+
+        let broken: Bundle = serde_json::from_value(json!({
+            "version": 1,
+            "views": [{ "name": "ok", "query": "priority:" }],
+            "agent_rules": { "later": { "when": "tag:x" } },
+        }))
+        .unwrap();
+        assert!(merge(broken, &mut config, &mut views, &mut criteria).is_err());
+        // Nothing from a broken bundle is applied
+        assert!(!config.agent_rules.contains_key("later"));
+        let newer: Bundle = serde_json::from_value(json!({ "version": 2 })).unwrap();
+        assert!(merge(newer, &mut config, &mut views, &mut criteria).is_err());
+    }
+}
@@ -1,11 +1,31 @@
 //! `blaze show <id>` - Show card details.
 
-use crate::client::Client;
+use crate::context::CommandContext;
+use crate::deps::DependencyIndex;
 use crate::error::Result;
+use crate::history;
 use crate::output::print_card_detail;
 
-pub async fn run(client: &Client, card_id: &str) -> Result<()> {
-    let card = client.get_card(card_id).await?;
-    print_card_detail(&card);
+pub async fn run(ctx: &CommandContext, card_id: &str, comments: bool) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut card = ctx.client.get_card(&card_id).await?;
+    history::fill_entered_column_at(&ctx.client, std::slice::from_mut(&mut card)).await?;
+    let all = ctx.client.list_cards(None, true).await?;
+    card.blocks = DependencyIndex::new(&all).blocks(&card).into_iter().map(|c| c.id.clone()).collect();
+    card.attachments = match ctx.client.list_attachments(&card_id).await {
+        Ok(attachments) => attachments,
+        // Servers without attachments, and the offline store, have none to show
+        Err(e) if e.is_endpoint_missing() => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    card.threads = match ctx.client.list_threads(&card_id).await {
+        Ok(threads) => threads,
+        Err(e) if e.is_endpoint_missing() => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    if comments {
+        card.comments = ctx.client.list_comments(&card_id).await?;
+    }
+    print_card_detail(ctx, &card);
     Ok(())
 }
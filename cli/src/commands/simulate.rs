@@ -0,0 +1,220 @@
+//! `blaze simulate` - What-if projections from the board's history.
+//!
+//! `intake` estimates what accepting new work does to delivery. Open cards
+//! are taken to be worked in priority order, urgent first, at the board's
+//! recent throughput: estimate points (cards without one count as
+//! `--default-estimate`) that reached done per week over the last `--weeks`.
+//! Each priority class is projected to be finished once all work up to and
+//! including it is; the new cards join at `--priority` and push back that
+//! class and every one below it. It's an average, not a promise.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::history;
+use crate::outln;
+use crate::output::{format_date, print_csv, print_json, print_markdown, print_table};
+use crate::types::{Card, Column, Priority};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Priority classes in the order they're worked
+const CLASSES: [Priority; 4] = [Priority::Urgent, Priority::High, Priority::Medium, Priority::Low];
+
+/// The work `simulate intake` is asked about
+#[derive(Debug, Clone)]
+pub struct Intake {
+    pub new_cards: usize,
+    /// Estimate points of all new cards (`new_cards` × the default estimate if none)
+    pub points: Option<f64>,
+    pub priority: Priority,
+    /// Weeks of history to take throughput from
+    pub weeks: u32,
+    pub default_estimate: f64,
+}
+
+/// When one priority class is projected to be finished, without and with the new work
+#[derive(Debug, Serialize)]
+struct ClassProjection {
+    priority: Priority,
+    /// Open cards in the class today
+    cards: usize,
+    points: f64,
+    weeks: f64,
+    weeks_with_intake: f64,
+    shift_weeks: f64,
+    done_by: DateTime<Utc>,
+    done_by_with_intake: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct Projection {
+    /// Points finished per week, on average
+    throughput: f64,
+    window_weeks: u32,
+    new_cards: usize,
+    new_points: f64,
+    priority: Priority,
+    classes: Vec<ClassProjection>,
+}
+
+pub async fn intake(ctx: &CommandContext, intake: Intake) -> Result<()> {
+    if intake.new_cards == 0 {
+        return Err(BlazeError::InvalidInput("--new-cards must be at least 1".into()));
+    }
+    if intake.weeks == 0 {
+        return Err(BlazeError::InvalidInput("--weeks must be at least 1".into()));
+    }
+    if intake.points.is_some_and(|p| p <= 0.0) || intake.default_estimate <= 0.0 {
+        return Err(BlazeError::InvalidInput("Points and estimates must be above zero".into()));
+    }
+
+    let open: Vec<Card> = ctx
+        .client
+        .list_cards(None, false)
+        .await?
+        .into_iter()
+        .filter(|c| c.column != Column::Done)
+        .collect();
+    // Finished cards are often archived soon after
+    let mut done = ctx.client.list_cards(Some(Column::Done), true).await?;
+    history::fill_entered_column_at(&ctx.client, &mut done).await?;
+
+    let now = Utc::now();
+    let since = now - Duration::weeks(intake.weeks.into());
+    // Without a recorded move, the last update is the best guess for when a card was finished
+    let finished: f64 = done
+        .iter()
+        .filter(|c| c.entered_column_at.unwrap_or(c.updated_at) >= since)
+        .map(|c| c.estimate.unwrap_or(intake.default_estimate))
+        .sum();
+    let throughput = finished / f64::from(intake.weeks);
+    if throughput == 0.0 {
+        return Err(BlazeError::InvalidInput(format!(
+            "No cards reached done in the last {} weeks, so there's no throughput to project from",
+            intake.weeks
+        )));
+    }
+
+    let projection = project(&open, &intake, throughput, now);
+    print_projection(ctx, &projection);
+    Ok(())
+}
+
+fn project(open: &[Card], intake: &Intake, throughput: f64, now: DateTime<Utc>) -> Projection {
+    let new_points = intake
+        .points
+        .unwrap_or(intake.new_cards as f64 * intake.default_estimate);
+    let at = |weeks: f64| now + Duration::seconds((weeks * 7.0 * 86_400.0).round() as i64);
+
+    let mut before = 0.0;
+    let mut after = 0.0;
+    let mut classes = Vec::new();
+    for priority in CLASSES {
+        let cards: Vec<&Card> = open.iter().filter(|c| c.priority == priority).collect();
+        // Not sum(): that's -0.0 for no cards
+        let points = cards.iter().fold(0.0, |sum, c| sum + c.estimate.unwrap_or(intake.default_estimate));
+        before += points;
+        after += points;
+        if priority == intake.priority {
+            after += new_points;
+        }
+        let (weeks, weeks_with_intake) = (before / throughput, after / throughput);
+        classes.push(ClassProjection {
+            priority,
+            cards: cards.len(),
+            points,
+            weeks,
+            weeks_with_intake,
+            shift_weeks: weeks_with_intake - weeks,
+            done_by: at(weeks),
+            done_by_with_intake: at(weeks_with_intake),
+        });
+    }
+
+    Projection {
+        throughput,
+        window_weeks: intake.weeks,
+        new_cards: intake.new_cards,
+        new_points,
+        priority: intake.priority,
+        classes,
+    }
+}
+
+fn print_projection(ctx: &CommandContext, projection: &Projection) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(projection),
+        OutputFormat::Quiet => projection
+            .classes
+            .iter()
+            .for_each(|c| outln!("{} {:.1}", c.priority, c.shift_weeks)),
+        OutputFormat::Table | OutputFormat::Csv | OutputFormat::Markdown => {
+            let weeks = |weeks: f64, at: DateTime<Utc>| format!("{:.1}w ({})", weeks, format_date(ctx, at));
+            let rows = projection
+                .classes
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.priority.to_string(),
+                        c.cards.to_string(),
+                        format!("{:.1}", c.points),
+                        weeks(c.weeks, c.done_by),
+                        weeks(c.weeks_with_intake, c.done_by_with_intake),
+                        if c.shift_weeks > 0.0 { format!("+{:.1}w", c.shift_weeks) } else { "-".into() },
+                    ]
+                })
+                .collect();
+            let headers = ["PRIORITY", "CARDS", "POINTS", "DONE IN", "WITH NEW WORK", "SHIFT"];
+            match ctx.format {
+                OutputFormat::Csv => {
+                    let headers = ["priority", "cards", "points", "done_in", "with_new_work", "shift"];
+                    print_csv(&headers, rows);
+                }
+                OutputFormat::Markdown => print_markdown(&headers, rows),
+                _ => {
+                    outln!(
+                        "Throughput {:.1} points/week (last {} weeks); {} new cards, {:.1} points at {}:",
+                        projection.throughput,
+                        projection.window_weeks,
+                        projection.new_cards,
+                        projection.new_points,
+                        projection.priority
+                    );
+                    print_table(&headers, rows);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(priority: &str, estimate: Option<f64>) -> Card {
+        testing::card("c", serde_json::json!({ "priority": priority, "estimate": estimate }))
+    }
+
+    #[test]
+    fn new_work_pushes_back_its_class_and_lower() {
+        let now: DateTime<Utc> = "2026-03-02T00:00:00Z".parse().unwrap();
+        let open = [card("urgent", Some(2.0)), card("high", None), card("medium", Some(3.0)), card("low", None)];
+        let intake = Intake {
+            new_cards: 4,
+            points: Some(8.0),
+            priority: Priority::High,
+            weeks: 4,
+            default_estimate: 1.0,
+        };
+        let projection = project(&open, &intake, 2.0, now);
+        let shifts: Vec<f64> = projection.classes.iter().map(|c| c.shift_weeks).collect();
+        assert_eq!(shifts, [0.0, 4.0, 4.0, 4.0]);
+        let medium = &projection.classes[2];
+        assert_eq!((medium.weeks, medium.weeks_with_intake), (3.0, 7.0));
+        assert_eq!(medium.done_by, now + Duration::weeks(3));
+
+        let by_count = Intake { points: None, ..intake };
+        assert_eq!(project(&open, &by_count, 2.0, now).new_points, 4.0);
+    }
+}
@@ -1,11 +1,71 @@
 //! `blaze stats` - Show detailed board statistics.
+//!
+//! `--from-store` computes them from the local store instead (see
+//! `local_stats`), which also works offline.
 
-use crate::client::Client;
-use crate::error::Result;
-use crate::output::print_stats;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::local_stats::{self, LocalStats};
+use crate::outln;
+use crate::output::{format_time, print_csv, print_json, print_markdown, print_stats, print_table};
+use chrono::Utc;
 
-pub async fn run(client: &Client) -> Result<()> {
-    let stats = client.stats().await?;
-    print_stats(&stats);
+pub async fn run(ctx: &CommandContext, from_store: bool) -> Result<()> {
+    if from_store {
+        return run_local(ctx);
+    }
+    let stats = ctx.client.stats().await?;
+    print_stats(ctx, &stats);
     Ok(())
 }
+
+fn run_local(ctx: &CommandContext) -> Result<()> {
+    let store = ctx
+        .client
+        .store()
+        .ok_or_else(|| BlazeError::Other("The local store isn't available (run with -v for why)".into()))?;
+    let Some(synced_at) = store.synced_at()? else {
+        return Err(BlazeError::InvalidInput(
+            "The local store has no full card list yet; run `blaze list` once while online".into(),
+        ));
+    };
+    let mut stats = local_stats::compute(&store.active_cards()?, Utc::now());
+    stats.synced_at = Some(synced_at);
+    print_local(ctx, &stats);
+    Ok(())
+}
+
+fn print_local(ctx: &CommandContext, stats: &LocalStats) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(stats),
+        OutputFormat::Quiet => outln!("{}", stats.total_cards),
+        OutputFormat::Table | OutputFormat::Csv | OutputFormat::Markdown => {
+            let mut rows = vec![
+                vec!["total".to_string(), stats.total_cards.to_string()],
+                vec!["overdue".to_string(), stats.overdue_count.to_string()],
+                vec!["open_estimate".to_string(), stats.open_estimate.to_string()],
+            ];
+            let breakdowns = [
+                ("column", &stats.by_column),
+                ("priority", &stats.by_priority),
+                ("tag", &stats.by_tag),
+                ("assignee", &stats.by_assignee),
+                ("agent", &stats.by_agent_status),
+            ];
+            for (kind, counts) in breakdowns {
+                rows.extend(counts.iter().map(|(k, v)| vec![format!("{}:{}", kind, k), v.to_string()]));
+            }
+            rows.extend(stats.by_age.iter().map(|b| vec![format!("age:{}", b.age), b.cards.to_string()]));
+            match ctx.format {
+                OutputFormat::Csv => print_csv(&["metric", "value"], rows),
+                OutputFormat::Markdown => print_markdown(&["METRIC", "VALUE"], rows),
+                _ => {
+                    print_table(&["METRIC", "VALUE"], rows);
+                    if let Some(at) = stats.synced_at {
+                        eprintln!("From the local store, last synced {}", format_time(ctx, at));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! `blaze sync` - Replay changes queued with `--offline`.
+//!
+//! Each queued change remembers the card's `updated_at` from when it was
+//! made. If the card changed on the server since (or was deleted), the change
+//! is reported as a conflict and stays queued until it's forced or dropped.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_json, print_table};
+use crate::progress::Progress;
+use crate::store::QueuedRequest;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Options for `blaze sync`
+pub struct SyncOptions {
+    /// Only show the queue
+    pub list: bool,
+    /// Apply changes even when the card changed on the server
+    pub force: bool,
+    /// Queue entries to discard before syncing
+    pub drop: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum SyncStatus {
+    Applied,
+    Conflict,
+    Failed,
+}
+
+/// What happened to one queued change
+#[derive(Debug, Serialize)]
+struct SyncResult {
+    seq: i64,
+    method: String,
+    path: String,
+    result: SyncStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+pub async fn run(ctx: &CommandContext, options: SyncOptions) -> Result<()> {
+    if ctx.offline {
+        return Err(BlazeError::InvalidInput("Can't sync with --offline".into()));
+    }
+    let store = ctx
+        .client
+        .store()
+        .ok_or_else(|| BlazeError::Config("The local store is unavailable".into()))?;
+
+    let mut queued = store.queued()?;
+    for seq in &options.drop {
+        if !queued.iter().any(|req| req.seq == *seq) {
+            return Err(BlazeError::InvalidInput(format!("No queued change #{}", seq)));
+        }
+        if ctx.dry_run {
+            eprintln!("Would drop queued change #{}", seq);
+        } else {
+            store.dequeue(*seq)?;
+            eprintln!("Dropped queued change #{}", seq);
+        }
+    }
+    queued.retain(|req| !options.drop.contains(&req.seq));
+
+    if options.list {
+        print_queue(ctx, &queued);
+        return Ok(());
+    }
+
+    // Fail early and clearly rather than once per queued change
+    ctx.client.health().await?;
+
+    // Cards changed by earlier entries, with the `updated_at` that change produced
+    let mut rebased: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut results = Vec::new();
+    let progress = Progress::start("sync", Some(queued.len()));
+    for req in &queued {
+        let (result, detail) = match check(ctx, req, &rebased, options.force).await {
+            Some(conflict) => (SyncStatus::Conflict, Some(conflict)),
+            None => match ctx.client.send_queued(req).await {
+                Ok(response) => {
+                    store.dequeue(req.seq)?;
+                    let updated_at = response["updated_at"].as_str().and_then(|t| t.parse().ok());
+                    if let (Some(id), Some(at)) = (&req.card_id, updated_at) {
+                        rebased.insert(id.clone(), at);
+                    }
+                    (SyncStatus::Applied, None)
+                }
+                // The request was printed instead of sent
+                Err(BlazeError::DryRun) => {
+                    progress.advance();
+                    continue;
+                }
+                Err(e) => (SyncStatus::Failed, Some(e.to_string())),
+            },
+        };
+        results.push(SyncResult {
+            seq: req.seq,
+            method: req.method.clone(),
+            path: req.path.clone(),
+            result,
+            detail,
+        });
+        progress.advance();
+    }
+
+    if ctx.dry_run {
+        return Err(BlazeError::DryRun);
+    }
+
+    // Pick up whatever else changed while we were away
+    if !queued.is_empty() {
+        ctx.client.list_cards(None, true).await?;
+    }
+
+    print_results(ctx, &results);
+
+    let pending = results.iter().filter(|r| r.result != SyncStatus::Applied).count();
+    if pending > 0 {
+        if results.iter().any(|r| r.result == SyncStatus::Conflict) {
+            eprintln!("Resolve conflicts with `blaze sync --force` or `blaze sync --drop <seq>`");
+        }
+        return Err(BlazeError::Other(format!(
+            "{} of {} queued changes not applied",
+            pending,
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Describe why a queued change conflicts with the server, if it does
+async fn check(
+    ctx: &CommandContext,
+    req: &QueuedRequest,
+    rebased: &HashMap<String, DateTime<Utc>>,
+    force: bool,
+) -> Option<String> {
+    let card_id = req.card_id.as_ref()?;
+    let base = rebased.get(card_id).copied().or(req.base_updated_at)?;
+    if force {
+        return None;
+    }
+
+    match ctx.client.get_card(card_id).await {
+        Ok(card) if card.updated_at != base => Some(format!(
+            "card changed on the server at {} (queued against {})",
+            format_time(ctx, card.updated_at),
+            format_time(ctx, base)
+        )),
+        Ok(_) => None,
+        Err(e) if e.is_endpoint_missing() => Some("card was deleted on the server".into()),
+        // Let the request itself report it
+        Err(_) => None,
+    }
+}
+
+fn print_queue(ctx: &CommandContext, queued: &[QueuedRequest]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(queued),
+        OutputFormat::Quiet => queued.iter().for_each(|q| outln!("{}", q.seq)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = queued
+                .iter()
+                .map(|q| {
+                    vec![
+                        q.seq.to_string(),
+                        format_time(ctx, q.queued_at),
+                        q.method.clone(),
+                        q.path.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["SEQ", "QUEUED", "METHOD", "PATH"], rows);
+        }
+    }
+    if queued.is_empty() && !matches!(ctx.format, OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv) {
+        eprintln!("Nothing queued");
+    }
+}
+
+fn print_results(ctx: &CommandContext, results: &[SyncResult]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(results),
+        OutputFormat::Quiet => results
+            .iter()
+            .filter(|r| r.result == SyncStatus::Applied)
+            .for_each(|r| outln!("{}", r.seq)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = results
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.seq.to_string(),
+                        r.method.clone(),
+                        r.path.clone(),
+                        format!("{:?}", r.result).to_lowercase(),
+                        r.detail.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["SEQ", "METHOD", "PATH", "RESULT", "DETAIL"], rows);
+        }
+    }
+}
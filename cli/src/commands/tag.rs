@@ -0,0 +1,97 @@
+//! `blaze tag apply|strip` - Add or remove a tag across every card matching a filter.
+
+use crate::commands::bulk;
+use crate::context::CommandContext;
+use crate::error::Result;
+use crate::output::short_id;
+use crate::parsers::Query;
+use crate::types::{Card, CardUpdate, ValidationError};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Options shared by `tag apply` and `tag strip`
+pub struct TagOptions {
+    pub tag: String,
+    pub filter: Query,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Maximum number of updates in flight
+    pub concurrency: usize,
+}
+
+#[derive(Clone, Copy)]
+enum TagChange {
+    Apply,
+    Strip,
+}
+
+/// Add the tag to matching cards that don't have it yet
+pub async fn apply(ctx: &CommandContext, options: TagOptions) -> Result<()> {
+    run(ctx, options, TagChange::Apply).await
+}
+
+/// Remove the tag from matching cards that have it
+pub async fn strip(ctx: &CommandContext, options: TagOptions) -> Result<()> {
+    run(ctx, options, TagChange::Strip).await
+}
+
+async fn run(ctx: &CommandContext, options: TagOptions, change: TagChange) -> Result<()> {
+    let tag = options.tag.trim().to_string();
+    if tag.is_empty() {
+        return Err(ValidationError::EmptyTag.into());
+    }
+
+    // Only cards the change would actually touch
+    let cards: HashMap<String, Card> = ctx
+        .client
+        .list_cards(None, false)
+        .await?
+        .into_iter()
+        .filter(|c| options.filter.matches(c))
+        .filter(|c| match change {
+            TagChange::Apply => !c.tags.contains(&tag),
+            TagChange::Strip => c.tags.contains(&tag),
+        })
+        .map(|c| (c.id.clone(), c))
+        .collect();
+
+    let mut ids: Vec<String> = cards.keys().cloned().collect();
+    ids.sort_by_key(|id| (cards[id].position, cards[id].created_at));
+
+    let action = match change {
+        TagChange::Apply => format!("Add tag '{}' to", tag),
+        TagChange::Strip => format!("Remove tag '{}' from", tag),
+    };
+    // Large operations get the typed confirmation instead
+    if !ids.is_empty() && !ctx.dry_run && !options.yes && !bulk::needs_phrase(ctx, ids.len()) {
+        for id in &ids {
+            eprintln!("  {}  {}", short_id(id), cards[id].title);
+        }
+        eprint!("{} {} card(s)? [y/N] ", action, ids.len());
+        io::stderr().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let operation = match change {
+        TagChange::Apply => "tag",
+        TagChange::Strip => "untag",
+    };
+    bulk::apply_concurrent(ctx, operation, &ids, options.concurrency, |id| {
+        let card = &cards[&id];
+        let tags: Vec<String> = match change {
+            TagChange::Apply => card.tags.iter().cloned().chain([tag.clone()]).collect(),
+            TagChange::Strip => card.tags.iter().filter(|t| **t != tag).cloned().collect(),
+        };
+        async move {
+            let update = CardUpdate::builder().tags(Some(tags)).build()?;
+            ctx.client.update_card(&id, &update).await
+        }
+    })
+    .await
+}
@@ -0,0 +1,91 @@
+//! `blaze task` - Checklist items on a card.
+//!
+//! Subtasks are the human's to-do list for a card, separate from the
+//! acceptance criteria an agent checks off. `list` and `show` print how many
+//! are done (3/7); `criteria to-subtasks` / `from-subtasks` convert between them.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_card_detail, print_json, print_table};
+use crate::types::{CardUpdate, Subtask};
+
+/// Add a subtask to the end of a card's checklist
+pub async fn add(ctx: &CommandContext, card_id: &str, title: &str) -> Result<()> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(BlazeError::InvalidInput("Subtasks need a title".into()));
+    }
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut subtasks = ctx.client.get_card(&card_id).await?.subtasks;
+    subtasks.push(Subtask {
+        title: title.to_string(),
+        done: false,
+    });
+    save(ctx, &card_id, subtasks).await
+}
+
+/// Mark a subtask done (or not done)
+pub async fn check(ctx: &CommandContext, card_id: &str, index: usize, done: bool) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut subtasks = ctx.client.get_card(&card_id).await?.subtasks;
+    let count = subtasks.len();
+    let Some(subtask) = subtasks.get_mut(index) else {
+        return Err(no_subtask(&card_id, index, count));
+    };
+    subtask.done = done;
+    save(ctx, &card_id, subtasks).await
+}
+
+/// Remove a subtask
+pub async fn rm(ctx: &CommandContext, card_id: &str, index: usize) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut subtasks = ctx.client.get_card(&card_id).await?.subtasks;
+    if index >= subtasks.len() {
+        return Err(no_subtask(&card_id, index, subtasks.len()));
+    }
+    subtasks.remove(index);
+    save(ctx, &card_id, subtasks).await
+}
+
+/// List a card's subtasks
+pub async fn list(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let subtasks = ctx.client.get_card(&card_id).await?.subtasks;
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&subtasks),
+        OutputFormat::Quiet => subtasks
+            .iter()
+            .filter(|s| !s.done)
+            .for_each(|s| outln!("{}", s.title)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = subtasks
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    vec![
+                        i.to_string(),
+                        if s.done { "x" } else { " " }.to_string(),
+                        s.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["#", "DONE", "TASK"], rows);
+        }
+    }
+    Ok(())
+}
+
+async fn save(ctx: &CommandContext, card_id: &str, subtasks: Vec<Subtask>) -> Result<()> {
+    let update = CardUpdate::builder().subtasks(Some(subtasks)).build()?;
+    let card = ctx.client.update_card(card_id, &update).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+fn no_subtask(card_id: &str, index: usize, count: usize) -> BlazeError {
+    BlazeError::InvalidInput(format!(
+        "Card {} has no subtask {} (it has {}; see `blaze task list`)",
+        card_id, index, count
+    ))
+}
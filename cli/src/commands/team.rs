@@ -0,0 +1,246 @@
+//! `blaze team` - A team's slice of a shared board.
+//!
+//! Teams are `[teams.<name>]` sections in config.toml (see README.md).
+//! `list`, `board` and `stats` only see the team's cards; `board` compares
+//! each column with the team's WIP limit and `add` warns before going over.
+
+use crate::columns;
+use crate::commands::add::{self, AddOptions};
+use crate::commands::list::{self, ListFilters};
+use crate::config::Config;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_csv, print_json, print_markdown, print_stats, print_table};
+use crate::parsers::Query;
+use crate::types::{BoardStats, Card, Column, Priority};
+use crate::wip::WipLimits;
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// A team from config.toml, ready to use
+#[derive(Debug)]
+pub struct Team {
+    pub name: String,
+    query: Query,
+    tags: Vec<String>,
+    wip: WipLimits,
+}
+
+impl Team {
+    /// Look up `[teams.<name>]` and check its filter and limits
+    pub fn load(config: &Config, name: &str) -> Result<Self> {
+        let team = config.teams.get(name).ok_or_else(|| {
+            let known: Vec<&str> = config.teams.keys().map(String::as_str).collect();
+            BlazeError::Config(format!(
+                "Unknown team '{}' (configured: {})",
+                name,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            ))
+        })?;
+        let invalid = |msg: String| BlazeError::Config(format!("[teams.{}]: {}", name, msg));
+
+        let filter = match (&team.filter, team.tags.as_slice()) {
+            (Some(filter), _) => filter.clone(),
+            (None, []) => return Err(invalid("set `filter` or `tags`".into())),
+            (None, tags) => tags.iter().map(|t| format!("tag:{}", t)).collect::<Vec<_>>().join(" AND "),
+        };
+        let query = Query::parse(&filter).map_err(|e| invalid(e.to_string()))?;
+
+        let wip = WipLimits::parse(&team.wip).map_err(invalid)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            query,
+            tags: team.tags.clone(),
+            wip,
+        })
+    }
+
+    /// WIP limit for `column`, if the team has one
+    pub fn limit(&self, column: &Column) -> Option<usize> {
+        self.wip.limit(column)
+    }
+
+    fn matches(&self, card: &Card) -> bool {
+        self.query.matches(card)
+    }
+}
+
+/// Cards per column against the team's limit
+#[derive(Debug, Serialize)]
+struct ColumnLoad {
+    column: Column,
+    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+/// `list` limited to the team's cards (`filters.query` narrows further)
+pub async fn list(ctx: &CommandContext, team: &str, mut filters: ListFilters) -> Result<()> {
+    let team = Team::load(&ctx.config, team)?;
+    filters.query = Some(match filters.query.take() {
+        Some(extra) => Query::And(Box::new(team.query), Box::new(extra)),
+        None => team.query,
+    });
+    list::run(ctx, filters).await
+}
+
+/// Column counts for the team, with WIP limits
+pub async fn board(ctx: &CommandContext, team: &str) -> Result<()> {
+    let team = Team::load(&ctx.config, team)?;
+    let cards = team_cards(ctx, &team).await?;
+    let loads: Vec<ColumnLoad> = columns::in_order(ctx.client.columns().await, &cards)
+        .into_iter()
+        .map(|column| ColumnLoad {
+            count: cards.iter().filter(|c| c.column == column).count(),
+            limit: team.limit(&column),
+            column,
+        })
+        .collect();
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&loads),
+        OutputFormat::Quiet => loads.iter().for_each(|l| outln!("{}", l.count)),
+        OutputFormat::Csv => {
+            let rows = loads
+                .iter()
+                .map(|l| {
+                    let limit = l.limit.map(|n| n.to_string()).unwrap_or_default();
+                    vec![l.column.to_string(), l.count.to_string(), limit]
+                })
+                .collect();
+            print_csv(&["column", "count", "limit"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = loads
+                .iter()
+                .map(|l| {
+                    let count = match l.limit {
+                        Some(limit) if l.count > limit => ctx.theme.alert(&format!("{}/{}", l.count, limit)),
+                        Some(limit) => format!("{}/{}", l.count, limit),
+                        None => l.count.to_string(),
+                    };
+                    vec![l.column.display_name().to_string(), count]
+                })
+                .collect();
+            if ctx.format == OutputFormat::Markdown {
+                print_markdown(&["COLUMN", "COUNT"], rows);
+            } else {
+                print_table(&["COLUMN", "COUNT"], rows);
+            }
+        }
+    }
+    for load in &loads {
+        if let Some(limit) = load.limit.filter(|limit| load.count > *limit) {
+            eprintln!(
+                "Warning: {} has {} card(s) in {} (WIP limit {})",
+                team.name,
+                load.count,
+                load.column.display_name(),
+                limit
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `stats` for the team's cards only
+pub async fn stats(ctx: &CommandContext, team: &str) -> Result<()> {
+    let team = Team::load(&ctx.config, team)?;
+    let cards = team_cards(ctx, &team).await?;
+    print_stats(ctx, &team_stats(&cards));
+    Ok(())
+}
+
+/// `add` with the team's tags, warning when the target column is at its limit
+pub async fn add(ctx: &CommandContext, team: &str, mut options: AddOptions) -> Result<()> {
+    let team = Team::load(&ctx.config, team)?;
+    for tag in &team.tags {
+        if !options.tags.contains(tag) {
+            options.tags.push(tag.clone());
+        }
+    }
+
+    if let Some(limit) = team.limit(&options.column) {
+        let cards = team_cards(ctx, &team).await?;
+        let count = cards.iter().filter(|c| c.column == options.column).count();
+        if count >= limit {
+            eprintln!(
+                "Warning: {} already has {} card(s) in {} (WIP limit {})",
+                team.name,
+                count,
+                options.column.display_name(),
+                limit
+            );
+        }
+    }
+    add::run(ctx, options).await
+}
+
+async fn team_cards(ctx: &CommandContext, team: &Team) -> Result<Vec<Card>> {
+    let cards = ctx.client.list_cards(None, false).await?;
+    Ok(cards.into_iter().filter(|c| team.matches(c)).collect())
+}
+
+/// Board statistics computed from a set of cards, like the server's
+fn team_stats(cards: &[Card]) -> BoardStats {
+    let now = Utc::now();
+    let mut by_column: std::collections::HashMap<String, i32> =
+        Column::DEFAULTS.iter().map(|c| (c.to_string(), 0)).collect();
+    let mut by_priority: std::collections::HashMap<String, i32> =
+        Priority::value_variants().iter().map(|p| (p.to_string(), 0)).collect();
+    let mut overdue_count = 0;
+    for card in cards {
+        *by_column.entry(card.column.to_string()).or_default() += 1;
+        *by_priority.entry(card.priority.to_string()).or_default() += 1;
+        if card.column != Column::Done && card.due_date.is_some_and(|d| d < now) {
+            overdue_count += 1;
+        }
+    }
+    BoardStats {
+        total_cards: cards.len() as i32,
+        by_column,
+        by_priority,
+        overdue_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TeamConfig;
+
+    fn config(team: TeamConfig) -> Config {
+        let mut config = Config::default();
+        config.teams.insert("payments".to_string(), team);
+        config
+    }
+
+    #[test]
+    fn loads_filter_from_tags_and_checks_limits() {
+        let team = Team::load(
+            &config(TeamConfig {
+                tags: vec!["payments".into(), "q4".into()],
+                wip: [("in_progress".to_string(), 3)].into(),
+                ..TeamConfig::default()
+            }),
+            "payments",
+        )
+        .unwrap();
+        assert_eq!(team.query, Query::parse("tag:payments AND tag:q4").unwrap());
+        assert_eq!(team.limit(&Column::InProgress), Some(3));
+        assert_eq!(team.limit(&Column::Todo), None);
+
+        let bad_column = config(TeamConfig {
+            filter: Some("tag:payments".into()),
+            wip: [("on hold!".to_string(), 3)].into(),
+            ..TeamConfig::default()
+        });
+        let error = Team::load(&bad_column, "payments").unwrap_err().to_string();
+        assert!(error.contains("invalid column name 'on hold!'"));
+        assert!(Team::load(&config(TeamConfig::default()), "payments").is_err());
+        assert!(Team::load(&bad_column, "search").unwrap_err().to_string().contains("configured: payments"));
+    }
+}
@@ -0,0 +1,145 @@
+//! `blaze thread` - Review threads on a card.
+//!
+//! Where comments are one flat discussion, a thread is about one topic ("API
+//! shape") and stays open until someone resolves it, like a review comment
+//! on a merge request. `blaze show` prints a card's threads, open ones with
+//! their replies; `blaze list --unresolved-threads` finds cards with open ones.
+//! Authors are named as for comments (`--author`, `author` in config.toml, $USER).
+
+use crate::commands::comment::author_or_default;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_json, print_table, short_id};
+use crate::types::{Card, CommentCreate, Thread, ThreadCreate, ThreadUpdate};
+use futures::{stream, StreamExt, TryStreamExt};
+
+/// Thread fetches in flight at once for `--unresolved-threads`
+const THREAD_FETCHES: usize = 8;
+
+/// Start a thread, optionally with an opening message
+pub async fn start(
+    ctx: &CommandContext,
+    card_id: &str,
+    topic: &str,
+    message: Option<String>,
+    author: Option<String>,
+) -> Result<()> {
+    let topic = topic.trim();
+    if topic.is_empty() {
+        return Err(BlazeError::InvalidInput("Threads need a --topic".into()));
+    }
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let thread = ThreadCreate {
+        topic: topic.to_string(),
+        body: message.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()),
+        author: author_or_default(ctx, author),
+    };
+    let thread = ctx.client.start_thread(&card_id, &thread).await?;
+    print_threads(ctx, std::slice::from_ref(&thread));
+    Ok(())
+}
+
+/// Reply to a thread
+pub async fn reply(
+    ctx: &CommandContext,
+    card_id: &str,
+    thread_id: &str,
+    message: &str,
+    author: Option<String>,
+) -> Result<()> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(BlazeError::InvalidInput("Reply must not be empty".into()));
+    }
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let thread = find(ctx, &card_id, thread_id).await?;
+    let reply = CommentCreate {
+        body: message.to_string(),
+        author: author_or_default(ctx, author),
+    };
+    let thread = ctx.client.reply_to_thread(&card_id, &thread.id, &reply).await?;
+    print_threads(ctx, std::slice::from_ref(&thread));
+    Ok(())
+}
+
+/// Resolve a thread, or reopen it
+pub async fn resolve(ctx: &CommandContext, card_id: &str, thread_id: &str, reopen: bool) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let thread = find(ctx, &card_id, thread_id).await?;
+    if thread.resolved != reopen {
+        let state = if reopen { "open" } else { "resolved" };
+        eprintln!("Thread {} is already {}", short_id(&thread.id), state);
+        print_threads(ctx, std::slice::from_ref(&thread));
+        return Ok(());
+    }
+    let update = ThreadUpdate {
+        resolved: !reopen,
+        resolved_by: if reopen { None } else { author_or_default(ctx, None) },
+    };
+    let thread = ctx.client.update_thread(&card_id, &thread.id, &update).await?;
+    print_threads(ctx, std::slice::from_ref(&thread));
+    Ok(())
+}
+
+/// List a card's threads, oldest first
+pub async fn list(ctx: &CommandContext, card_id: &str, unresolved: bool) -> Result<()> {
+    let card_id = ctx.resolve_card_id(card_id).await?;
+    let mut threads = ctx.client.list_threads(&card_id).await?;
+    if unresolved {
+        threads.retain(|t| !t.resolved);
+    }
+    print_threads(ctx, &threads);
+    Ok(())
+}
+
+/// The cards with at least one open thread, in their original order
+pub async fn with_unresolved(ctx: &CommandContext, cards: Vec<Card>) -> Result<Vec<Card>> {
+    let open: Vec<bool> = stream::iter(&cards)
+        .map(|card| async move {
+            let threads = ctx.client.list_threads(&card.id).await?;
+            Ok::<_, BlazeError>(threads.iter().any(|t| !t.resolved))
+        })
+        .buffered(THREAD_FETCHES)
+        .try_collect()
+        .await?;
+    Ok(cards.into_iter().zip(open).filter(|(_, open)| *open).map(|(card, _)| card).collect())
+}
+
+/// A thread by ID or unique ID prefix
+async fn find(ctx: &CommandContext, card_id: &str, thread_id: &str) -> Result<Thread> {
+    let threads = ctx.client.list_threads(card_id).await?;
+    let mut matches: Vec<Thread> = threads.into_iter().filter(|t| t.id.starts_with(thread_id)).collect();
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(BlazeError::InvalidInput(format!("Card {} has no thread {}", card_id, thread_id))),
+        n => Err(BlazeError::InvalidInput(format!(
+            "Thread ID '{}' matches {} threads; use more characters",
+            thread_id, n
+        ))),
+    }
+}
+
+fn print_threads(ctx: &CommandContext, threads: &[Thread]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(threads),
+        OutputFormat::Quiet => threads.iter().for_each(|t| outln!("{}", t.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = threads
+                .iter()
+                .map(|t| {
+                    let last = t.replies.last().map_or(t.created_at, |r| r.created_at);
+                    vec![
+                        short_id(&t.id).to_string(),
+                        if t.resolved { "resolved" } else { "open" }.to_string(),
+                        t.topic.clone(),
+                        t.author.clone(),
+                        t.replies.len().to_string(),
+                        format_time(ctx, last),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "STATE", "TOPIC", "BY", "REPLIES", "LAST"], rows);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! `blaze timeline` - What happened on a card, or the whole board, in order.
+//!
+//! `events` stitches server events (created, moved, ...), agent progress
+//! entries and comments into one chronological list, oldest first. Tables
+//! show relative times ("3h ago"); JSON keeps the timestamps. Servers
+//! without events or comments just leave those out.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_relative, print_csv, print_json, print_markdown, print_table, short_id};
+use crate::types::{BoardEvent, Card, Column, Comment};
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Comment requests in flight at once for a whole board
+const COMMENT_FETCHES: usize = 8;
+
+/// One thing that happened
+#[derive(Debug, Serialize)]
+struct Entry {
+    at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// Event type (`card.moved`), `progress` or `comment`
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    text: String,
+}
+
+/// The timeline of one card, or of the board without `card`
+pub async fn events(ctx: &CommandContext, card: Option<&str>, since: Option<DateTime<Utc>>) -> Result<()> {
+    let (cards, events) = match card {
+        Some(id) => {
+            let id = ctx.resolve_card_id(id).await?;
+            let card = ctx.client.get_card(&id).await?;
+            let mut events = or_missing(ctx.client.list_events(Some(&id)).await)?;
+            events.retain(|e| e.card_id.as_deref() == Some(id.as_str()));
+            (vec![card], events)
+        }
+        // Archived cards have a history too
+        None => (ctx.client.list_cards(None, true).await?, or_missing(ctx.client.list_events(None).await)?),
+    };
+    let comments: Vec<Vec<Comment>> = stream::iter(&cards)
+        .map(|card| async move {
+            let mut comments = or_missing(ctx.client.list_comments(&card.id).await)?;
+            // Not every server repeats the card ID in its comments
+            comments.iter_mut().for_each(|c| c.card_id.clone_from(&card.id));
+            Ok::<_, BlazeError>(comments)
+        })
+        .buffer_unordered(COMMENT_FETCHES)
+        .try_collect()
+        .await?;
+
+    let mut entries = timeline(&cards, &events, comments.into_iter().flatten());
+    if let Some(since) = since {
+        entries.retain(|e| e.at >= since);
+    }
+    print_entries(ctx, &entries, card.is_none());
+    Ok(())
+}
+
+/// Missing endpoints (no events or comments on this server) as nothing
+fn or_missing<T: Default>(result: Result<T>) -> Result<T> {
+    match result {
+        Err(e) if e.is_endpoint_missing() => Ok(T::default()),
+        result => result,
+    }
+}
+
+/// Everything in time order (events first among equal times)
+fn timeline(cards: &[Card], events: &[BoardEvent], comments: impl IntoIterator<Item = Comment>) -> Vec<Entry> {
+    let titles: HashMap<&str, &str> = cards.iter().map(|c| (c.id.as_str(), c.title.as_str())).collect();
+    let title = |id: &str| titles.get(id).map(|t| t.to_string());
+
+    let mut entries: Vec<Entry> = events
+        .iter()
+        .map(|event| Entry {
+            at: event.timestamp,
+            card_id: event.card_id.clone(),
+            // Deleted cards are only in their events
+            title: event.card_id.as_deref().and_then(title).or_else(|| {
+                event.data.pointer("/card/title").and_then(|t| t.as_str()).map(String::from)
+            }),
+            kind: event.kind.clone(),
+            author: None,
+            text: event_text(event),
+        })
+        .collect();
+    entries.extend(cards.iter().flat_map(|card| {
+        card.agent_progress.iter().map(|entry| Entry {
+            at: entry.timestamp,
+            card_id: Some(card.id.clone()),
+            title: Some(card.title.clone()),
+            kind: "progress".into(),
+            author: Some("agent".into()),
+            text: entry.message.clone(),
+        })
+    }));
+    entries.extend(comments.into_iter().map(|comment| Entry {
+        at: comment.created_at,
+        title: title(&comment.card_id),
+        card_id: Some(comment.card_id),
+        kind: "comment".into(),
+        author: Some(comment.author),
+        text: comment.body,
+    }));
+    entries.sort_by_key(|e| e.at);
+    entries
+}
+
+/// What an event did, e.g. "Todo → In Progress" for a move
+fn event_text(event: &BoardEvent) -> String {
+    let column = |key: &str| {
+        event
+            .data
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|name| Column::from(name.to_string()).display_name().into_owned())
+    };
+    match event.kind.as_str() {
+        "card.moved" => match (column("from"), column("to")) {
+            (Some(from), Some(to)) => format!("{} → {}", from, to),
+            (_, Some(to)) => format!("→ {}", to),
+            _ => String::new(),
+        },
+        "card.created" => column("column").map(|c| format!("in {}", c)).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn print_entries(ctx: &CommandContext, entries: &[Entry], board: bool) {
+    let what = |e: &Entry| e.kind.strip_prefix("card.").unwrap_or(&e.kind).to_string();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(entries),
+        OutputFormat::Quiet => outln!("{}", entries.len()),
+        OutputFormat::Csv => {
+            let rows = entries
+                .iter()
+                .map(|e| {
+                    vec![
+                        e.at.to_rfc3339(),
+                        e.card_id.clone().unwrap_or_default(),
+                        e.kind.clone(),
+                        e.author.clone().unwrap_or_default(),
+                        e.text.clone(),
+                    ]
+                })
+                .collect();
+            print_csv(&["at", "card_id", "kind", "author", "text"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            if entries.is_empty() {
+                eprintln!("Nothing recorded");
+                return;
+            }
+            let now = Utc::now();
+            let rows = entries
+                .iter()
+                .map(|e| {
+                    let mut row = vec![format_relative(e.at, now)];
+                    if board {
+                        row.push(e.card_id.as_deref().map(short_id).unwrap_or("-").to_string());
+                    }
+                    // Tables get the first line; JSON has the whole comment
+                    let text = e.text.lines().next().unwrap_or_default();
+                    let text = match (board, &e.title) {
+                        (true, Some(title)) if !text.is_empty() => format!("{}: {}", title, text),
+                        (true, Some(title)) => title.clone(),
+                        _ => text.to_string(),
+                    };
+                    row.extend([what(e), e.author.clone().unwrap_or_default(), text]);
+                    row
+                })
+                .collect();
+            let headers: &[&str] = if board {
+                &["WHEN", "CARD", "WHAT", "BY", "DETAIL"]
+            } else {
+                &["WHEN", "WHAT", "BY", "DETAIL"]
+            };
+            match ctx.format {
+                OutputFormat::Markdown => print_markdown(headers, rows),
+                _ => print_table(headers, rows),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::json;
+
+    #[test]
+    fn merges_events_progress_and_comments_in_order() {
+        let card = testing::card("c1", json!({
+            "title": "Fix login", "created_at": "2026-03-01T09:00:00Z",
+            "agent_progress": [{ "timestamp": "2026-03-02T12:00:00Z", "message": "Reproduced" }],
+        }));
+        let events: Vec<BoardEvent> = serde_json::from_value(json!([
+            { "type": "card.moved", "card_id": "c1", "timestamp": "2026-03-02T09:00:00Z",
+              "data": { "from": "todo", "to": "in_progress" } },
+            { "type": "card.deleted", "card_id": "gone", "timestamp": "2026-03-01T10:00:00Z",
+              "data": { "card": { "title": "Old idea" } } },
+        ]))
+        .unwrap();
+        let comment = Comment {
+            id: "m1".into(),
+            card_id: "c1".into(),
+            author: "dana".into(),
+            body: "Looks good\nShip it".into(),
+            created_at: "2026-03-03T08:00:00Z".parse().unwrap(),
+        };
+
+        let entries = timeline(&[card], &events, [comment]);
+        let kinds: Vec<&str> = entries.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, ["card.deleted", "card.moved", "progress", "comment"]);
+        assert_eq!(entries[0].title.as_deref(), Some("Old idea"));
+        assert_eq!(entries[1].text, "Todo → In Progress");
+        assert_eq!((entries[3].author.as_deref(), entries[3].title.as_deref()), (Some("dana"), Some("Fix login")));
+    }
+}
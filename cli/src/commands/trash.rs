@@ -0,0 +1,172 @@
+//! `blaze trash` - Cards removed with `blaze rm`.
+//!
+//! `rm` keeps a copy of each card in `trash.json` (per profile or server)
+//! before deleting it; `rm --permanent` doesn't. The server has no undelete,
+//! so `trash restore` creates the card again (with a new ID) and replays its
+//! links, checked criteria, subtasks and agent fields. `trash purge` forgets cards
+//! for good, e.g. `--older-than 30d` from cron.
+
+use crate::commands::export::{card_create, restore_agent_fields};
+use crate::config::Config;
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{format_time, print_card_detail, print_json, print_table, short_id};
+use crate::types::Card;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+
+const FILE: &str = "trash.json";
+
+/// A deleted card and when it went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trashed {
+    pub deleted_at: DateTime<Utc>,
+    pub card: Card,
+}
+
+/// Deleted cards, keyed by profile or server URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Trash {
+    #[serde(default)]
+    servers: BTreeMap<String, Vec<Trashed>>,
+}
+
+impl Trash {
+    fn load() -> Result<Self> {
+        match Config::dir().map(|d| d.join(FILE)) {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.servers.retain(|_, cards| !cards.is_empty());
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn cards(&mut self, ctx: &CommandContext) -> &mut Vec<Trashed> {
+        self.servers.entry(server(ctx)).or_default()
+    }
+}
+
+fn server(ctx: &CommandContext) -> String {
+    ctx.profile().unwrap_or(&ctx.url).to_string()
+}
+
+/// Delete a card, keeping a copy in the trash unless `permanent`.
+/// Confidential descriptions are kept encrypted, as on the server.
+pub async fn delete(ctx: &CommandContext, card: &Card, permanent: bool) -> Result<()> {
+    if permanent || ctx.dry_run {
+        return ctx.client.delete_card(&card.id).await;
+    }
+    // Trashed first, so a card is never gone without a copy
+    let mut trash = Trash::load()?;
+    let cards = trash.cards(ctx);
+    cards.retain(|t| t.card.id != card.id);
+    cards.push(Trashed {
+        deleted_at: Utc::now(),
+        card: ctx.client.seal_card(card.clone())?,
+    });
+    trash.save()?;
+
+    match ctx.client.delete_card(&card.id).await {
+        // Queued deletions still happen, later
+        Err(e) if !matches!(e, BlazeError::Queued) => {
+            let mut trash = Trash::load()?;
+            trash.cards(ctx).retain(|t| t.card.id != card.id);
+            trash.save()?;
+            Err(e)
+        }
+        result => result,
+    }
+}
+
+/// List trashed cards, most recently deleted first
+pub async fn list(ctx: &CommandContext) -> Result<()> {
+    let mut cards = Trash::load()?.cards(ctx).clone();
+    cards.sort_by_key(|t| Reverse(t.deleted_at));
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&cards),
+        OutputFormat::Quiet => cards.iter().for_each(|t| outln!("{}", t.card.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = cards
+                .iter()
+                .map(|t| {
+                    vec![
+                        short_id(&t.card.id).to_string(),
+                        ctx.theme.column(&t.card.column),
+                        format_time(ctx, t.deleted_at),
+                        t.card.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "COLUMN", "DELETED", "TITLE"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Create a trashed card again and take it out of the trash
+pub async fn restore(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let mut trash = Trash::load()?;
+    let matches: Vec<&Trashed> = trash.cards(ctx).iter().filter(|t| t.card.id.starts_with(card_id)).collect();
+    let old = match matches.as_slice() {
+        [one] => one.card.clone(),
+        [] => return Err(BlazeError::InvalidInput(format!("No card {} in the trash", card_id))),
+        _ => {
+            return Err(BlazeError::InvalidInput(format!(
+                "'{}' matches {} cards in the trash; use more of the ID",
+                card_id,
+                matches.len()
+            )))
+        }
+    };
+
+    let mut create = card_create(&old);
+    create.blocked_by = old.blocked_by.clone();
+    let card = ctx.client.create_card(&create).await?;
+    restore_agent_fields(ctx, &old, &card.id).await?;
+    let card = ctx.client.get_card(&card.id).await?;
+
+    trash.cards(ctx).retain(|t| t.card.id != old.id);
+    trash.save()?;
+    eprintln!("Restored {} as {}", short_id(&old.id), short_id(&card.id));
+    ctx.remember_card(&card.id);
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Forget trashed cards for good: those deleted before `older_than`, or all of them
+pub async fn purge(ctx: &CommandContext, older_than: Option<DateTime<Utc>>, force: bool) -> Result<()> {
+    let mut trash = Trash::load()?;
+    let cards = trash.cards(ctx);
+    let purged = cards.iter().filter(|t| older_than.is_none_or(|cutoff| t.deleted_at < cutoff)).count();
+    if purged == 0 {
+        eprintln!("Nothing to purge");
+        return Ok(());
+    }
+
+    if older_than.is_none() && !force {
+        eprint!("Permanently forget all {} trashed card(s)? [y/N] ", purged);
+        io::stderr().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            eprintln!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    cards.retain(|t| older_than.is_some_and(|cutoff| t.deleted_at >= cutoff));
+    trash.save()?;
+    eprintln!("Purged {} card(s) from the trash", purged);
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! `blaze undo` - Revert the last command's changes.
+//!
+//! Changes come from the undo journal (see [`crate::journal`]) and are
+//! reverted newest first. If a card the command touched has changed again
+//! since, nothing is reverted unless `--force` is given. Deleted cards come
+//! back with a new ID, as with `trash restore`. Changes that have no inverse
+//! (agent progress, new threads, plan files, ...) are reported and dropped.
+//! `undo --list` shows recent operations, newest first.
+
+use crate::commands::export::{card_create, restore_agent_fields};
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::journal::{Inverse, Journal, Operation};
+use crate::outln;
+use crate::output::{format_time, print_json, print_table, short_id};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Operations `--list` shows by default
+const LIST_LIMIT: usize = 20;
+
+#[derive(Serialize)]
+struct Undone {
+    command: String,
+    undone: usize,
+    /// Changes that couldn't be reverted, and why
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+}
+
+/// Revert the last operation on this server
+pub async fn run(ctx: &CommandContext, force: bool) -> Result<()> {
+    // Reverting isn't an operation of its own; undoing twice goes further back
+    ctx.client.record_as(None);
+    let server = ctx.profile().unwrap_or(&ctx.url).to_string();
+    let mut journal = Journal::load()?;
+    let Some(operation) = journal.operations(&server).last().cloned() else {
+        return Err(BlazeError::InvalidInput("Nothing to undo".into()));
+    };
+    if !force {
+        check_unchanged(ctx, &operation).await?;
+    }
+
+    let mut result = Undone {
+        command: operation.command.clone(),
+        undone: 0,
+        skipped: Vec::new(),
+    };
+    for change in operation.changes.iter().rev() {
+        let mut reverted = None;
+        match change.inverse() {
+            Ok(inverse) => match revert(ctx, inverse).await {
+                Ok(card) => {
+                    result.undone += 1;
+                    reverted = card;
+                }
+                Err(BlazeError::DryRun) => continue,
+                // Queued inverses still happen, at `blaze sync`
+                Err(BlazeError::Queued) => result.undone += 1,
+                Err(e) => return Err(e),
+            },
+            Err(reason) => {
+                eprintln!("Not undone: {}", reason);
+                result.skipped.push(reason);
+            }
+        }
+        if ctx.dry_run {
+            continue;
+        }
+        // Dropped one by one, so a failure halfway leaves only the rest to undo
+        let operations = journal.operations(&server);
+        if let Some(last) = operations.last_mut() {
+            last.changes.pop();
+            if last.changes.is_empty() {
+                operations.pop();
+            }
+        }
+        if let Some(card) = reverted {
+            journal.reverted_to(&server, &card);
+        }
+        journal.save()?;
+    }
+    if ctx.dry_run {
+        return Ok(());
+    }
+    if result.undone == 0 {
+        return Err(BlazeError::InvalidInput(format!(
+            "`{}` can't be undone (dropped from the journal)",
+            operation.command
+        )));
+    }
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&result),
+        OutputFormat::Quiet => {}
+        OutputFormat::Table | OutputFormat::Markdown => {
+            outln!("Undid `{}` ({} of {} changes)", result.command, result.undone, operation.changes.len())
+        }
+    }
+    Ok(())
+}
+
+/// Fail if a card the operation left behind has been changed since
+async fn check_unchanged(ctx: &CommandContext, operation: &Operation) -> Result<()> {
+    let mut expected: BTreeMap<&str, Option<DateTime<Utc>>> = BTreeMap::new();
+    for change in &operation.changes {
+        let Some(id) = change.card_id() else { continue };
+        match change.card_after() {
+            Some(card) => {
+                let updated_at = card.get("updated_at").cloned().and_then(|t| serde_json::from_value(t).ok());
+                expected.insert(id, updated_at);
+            }
+            None if change.method == "DELETE" && change.path.ends_with(id) => {
+                expected.remove(id);
+            }
+            None => {}
+        }
+    }
+
+    for (id, updated_at) in expected {
+        let Some(updated_at) = updated_at else { continue };
+        let changed = match ctx.client.get_card(id).await {
+            Ok(card) => card.updated_at != updated_at,
+            Err(BlazeError::NotFound(_) | BlazeError::Api { status: 404, .. }) => true,
+            Err(e) => return Err(e),
+        };
+        if changed {
+            return Err(BlazeError::InvalidInput(format!(
+                "Card {} changed after `{}`; use --force to undo anyway",
+                short_id(id),
+                operation.command
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Send the inverse of a change; returns the card as the server sent it back, if it did
+async fn revert(ctx: &CommandContext, inverse: Inverse) -> Result<Option<Value>> {
+    match inverse {
+        Inverse::Request { method, path, body } => Ok(Some(ctx.client.send_raw(method, &path, body).await?)),
+        Inverse::Recreate(old) => {
+            let card = ctx.client.create_card(&card_create(&old)).await?;
+            restore_agent_fields(ctx, &old, &card.id).await?;
+            eprintln!("Card {} is back as {}", short_id(&old.id), short_id(&card.id));
+            ctx.remember_card(&card.id);
+            Ok(None)
+        }
+    }
+}
+
+/// Recent operations, newest first
+pub fn list(ctx: &CommandContext, limit: Option<usize>) -> Result<()> {
+    let server = ctx.profile().unwrap_or(&ctx.url).to_string();
+    let mut journal = Journal::load()?;
+    let operations: Vec<Operation> = journal
+        .operations(&server)
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(LIST_LIMIT))
+        .cloned()
+        .collect();
+
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&operations),
+        OutputFormat::Quiet => operations.iter().for_each(|op| outln!("{}", op.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = operations
+                .iter()
+                .map(|op| {
+                    let reversible = op.changes.iter().filter(|c| c.inverse().is_ok()).count();
+                    let undo = match reversible {
+                        0 => "no",
+                        n if n == op.changes.len() => "yes",
+                        _ => "partly",
+                    };
+                    vec![
+                        op.id.clone(),
+                        format_time(ctx, op.at),
+                        op.command.clone(),
+                        op.changes.len().to_string(),
+                        undo.to_string(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "WHEN", "COMMAND", "CHANGES", "UNDO"], rows);
+        }
+    }
+    Ok(())
+}
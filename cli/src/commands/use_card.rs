@@ -0,0 +1,99 @@
+//! `blaze use` - Pick the card to work on.
+//!
+//! `blaze done` and `blaze agent start|progress|block|done|check` act on the
+//! current card when no ID is given. It's kept per server in
+//! `current-card.json`; set `BLAZE_SESSION` to give a terminal or agent its
+//! own.
+
+use crate::config::Config;
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::output::print_card_detail;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::{env, fs};
+
+const FILE: &str = "current-card.json";
+
+/// Session used when `BLAZE_SESSION` is unset
+const DEFAULT_SESSION: &str = "default";
+
+/// Current card IDs, keyed by session and then by profile or server URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CurrentCards {
+    #[serde(default)]
+    sessions: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl CurrentCards {
+    fn load() -> Result<Self> {
+        match Config::dir().map(|d| d.join(FILE)) {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn servers(&mut self) -> &mut BTreeMap<String, String> {
+        self.sessions.entry(session()).or_default()
+    }
+}
+
+fn session() -> String {
+    env::var("BLAZE_SESSION")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_SESSION.to_string())
+}
+
+fn server(ctx: &CommandContext) -> String {
+    ctx.profile().unwrap_or(&ctx.url).to_string()
+}
+
+/// Make `card_id` the current card
+pub async fn set(ctx: &CommandContext, card_id: &str) -> Result<()> {
+    let card = ctx.client.get_card(&ctx.resolve_card_id(card_id).await?).await?;
+    let mut store = CurrentCards::load()?;
+    store.servers().insert(server(ctx), card.id.clone());
+    store.save()?;
+    ctx.remember_card(&card.id);
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Show the current card
+pub async fn show(ctx: &CommandContext) -> Result<()> {
+    let card = ctx.client.get_card(&or_current(ctx, None)?).await?;
+    print_card_detail(ctx, &card);
+    Ok(())
+}
+
+/// Forget the current card
+pub fn clear(ctx: &CommandContext) -> Result<()> {
+    let mut store = CurrentCards::load()?;
+    if store.servers().remove(&server(ctx)).is_some() {
+        store.sessions.retain(|_, servers| !servers.is_empty());
+        store.save()?;
+    }
+    Ok(())
+}
+
+/// `card_id` if given, else the current card
+pub fn or_current(ctx: &CommandContext, card_id: Option<String>) -> Result<String> {
+    if let Some(card_id) = card_id {
+        return Ok(card_id);
+    }
+    CurrentCards::load()?
+        .servers()
+        .get(&server(ctx))
+        .cloned()
+        .ok_or_else(|| {
+            BlazeError::InvalidInput("No card ID given and no current card; pick one with `blaze use <id>`".into())
+        })
+}
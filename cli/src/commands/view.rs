@@ -0,0 +1,138 @@
+//! `blaze view` - Saved filter views, optionally shared through the server.
+
+use crate::context::{CommandContext, OutputFormat};
+use crate::error::{BlazeError, Result};
+use crate::outln;
+use crate::output::{print_json, print_table};
+use crate::parsers::Query;
+use crate::types::SavedView;
+use crate::views::{parse_view_name, ViewStore};
+
+/// List local views
+pub async fn list(ctx: &CommandContext) -> Result<()> {
+    let views = ViewStore::load()?.all();
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(&views),
+        OutputFormat::Quiet => views.iter().for_each(|v| outln!("{}", v.name)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = views
+                .iter()
+                .map(|v| {
+                    vec![
+                        v.name.clone(),
+                        v.query.clone(),
+                        v.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["NAME", "QUERY", "DESCRIPTION"], rows);
+        }
+    }
+    Ok(())
+}
+
+/// Save a view locally
+pub async fn save(ctx: &CommandContext, name: String, query: String, description: Option<String>) -> Result<()> {
+    Query::parse(&query)?;
+
+    let mut store = ViewStore::load()?;
+    let view = SavedView {
+        name,
+        query,
+        description,
+    };
+    store.insert(view.clone());
+    store.save()?;
+    print_view(ctx, &view);
+    Ok(())
+}
+
+/// Show a local view
+pub async fn show(ctx: &CommandContext, name: &str) -> Result<()> {
+    let view = ViewStore::load()?.get(name)?;
+    print_view(ctx, &view);
+    Ok(())
+}
+
+/// Remove a local view
+pub async fn rm(name: &str) -> Result<()> {
+    let mut store = ViewStore::load()?;
+    if !store.remove(name) {
+        return Err(BlazeError::InvalidInput(format!("Unknown view '{}'", name)));
+    }
+    store.save()?;
+    eprintln!("Removed view {}", name);
+    Ok(())
+}
+
+/// Upload local views to the server (all of them if no name is given)
+pub async fn push(ctx: &CommandContext, name: Option<&str>) -> Result<()> {
+    let store = ViewStore::load()?;
+    let views = match name {
+        Some(name) => vec![store.get(name)?],
+        None => store.all(),
+    };
+
+    for view in &views {
+        match ctx.client.put_view(view).await {
+            Ok(_) => eprintln!("Pushed view {}", view.name),
+            Err(e) if e.is_endpoint_missing() => {
+                eprintln!("Server does not support shared views; keeping views local only");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Download shared views from the server, replacing local views with the same name
+pub async fn pull(ctx: &CommandContext, name: Option<&str>) -> Result<()> {
+    let remote = match ctx.client.list_views().await {
+        Ok(views) => views,
+        Err(e) if e.is_endpoint_missing() => {
+            eprintln!("Server does not support shared views; keeping views local only");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let remote: Vec<SavedView> = match name {
+        Some(name) => {
+            let found: Vec<_> = remote.into_iter().filter(|v| v.name == name).collect();
+            if found.is_empty() {
+                return Err(BlazeError::InvalidInput(format!("No shared view named '{}'", name)));
+            }
+            found
+        }
+        None => remote,
+    };
+
+    let mut store = ViewStore::load()?;
+    let mut updated = 0;
+    for view in remote.iter().cloned() {
+        if parse_view_name(&view.name).is_err() || Query::parse(&view.query).is_err() {
+            eprintln!("Skipping invalid view {}: '{}'", view.name, view.query);
+            continue;
+        }
+        if store.insert(view) {
+            updated += 1;
+        }
+    }
+    store.save()?;
+    eprintln!("Pulled {} view(s), {} updated", remote.len(), updated);
+    Ok(())
+}
+
+fn print_view(ctx: &CommandContext, view: &SavedView) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(view),
+        OutputFormat::Quiet => outln!("{}", view.name),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            outln!("{}: {}", view.name, view.query);
+            if let Some(ref desc) = view.description {
+                outln!("  {}", desc);
+            }
+        }
+    }
+}
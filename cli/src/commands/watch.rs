@@ -0,0 +1,108 @@
+//! `blaze watch` - Poll the board and print what changed.
+//!
+//! For servers without an event stream (see `blaze events`): the board is
+//! refetched every interval and compared with the previous fetch. Changes are
+//! printed like `blaze changes`, one JSON object per line by default.
+
+use crate::changes::{detect, Change, ChangeKind, Cursor};
+use crate::commands::changes::print_changes;
+use crate::context::CommandContext;
+use crate::error::Result;
+use crate::parsers::Query;
+use crate::types::Card;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub async fn run(ctx: &CommandContext, query: Option<Query>, interval: Duration) -> Result<()> {
+    let mut previous = ctx.client.list_cards(None, true).await?;
+    eprintln!(
+        "Watching {} cards every {} s (Ctrl-C to stop)",
+        previous.iter().filter(|c| matches(&query, c)).count(),
+        interval.as_secs()
+    );
+
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick fires immediately
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let cards = match ctx.client.list_cards(None, true).await {
+            Ok(cards) => cards,
+            // Keep watching through server restarts
+            Err(e) if e.is_unreachable() => {
+                eprintln!("Refresh failed ({}); trying again in {} s", e, interval.as_secs());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let changes = diff(&previous, &cards, &query);
+        if !changes.is_empty() {
+            print_changes(ctx, &changes);
+        }
+        previous = cards;
+    }
+}
+
+/// Changes between two fetches of the whole board, limited to cards matching
+/// `query` before or after (so cards moving out of a filtered column show up)
+fn diff(previous: &[Card], cards: &[Card], query: &Option<Query>) -> Vec<Change> {
+    let cursor = Cursor::advance(previous, None);
+    let before: HashMap<&str, &Card> = previous.iter().map(|c| (c.id.as_str(), c)).collect();
+    detect(cards, Some(&cursor), Some(cursor.last))
+        .into_iter()
+        .filter(|change| {
+            change.card.as_ref().is_some_and(|c| matches(query, c))
+                || before.get(change.id.as_str()).is_some_and(|c| matches(query, c))
+        })
+        .map(|mut change| {
+            // With the whole previous board at hand, an unknown card is a new one
+            if change.change == ChangeKind::Updated && !before.contains_key(change.id.as_str()) {
+                change.change = ChangeKind::Created;
+            }
+            change
+        })
+        .collect()
+}
+
+fn matches(query: &Option<Query>, card: &Card) -> bool {
+    query.as_ref().is_none_or(|q| q.matches(card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, column: &str, updated: &str) -> Card {
+        testing::card(id, serde_json::json!({ "column": column, "updated_at": updated }))
+    }
+
+    #[test]
+    fn diff_reports_changes_in_and_out_of_the_filter() {
+        let before = vec![
+            card("a", "in_progress", "2026-01-02T10:00:00Z"),
+            card("b", "todo", "2026-01-02T10:00:00Z"),
+            card("c", "in_progress", "2026-01-02T10:00:00Z"),
+        ];
+        let after = vec![
+            card("a", "review", "2026-01-03T10:00:00Z"),
+            card("b", "todo", "2026-01-03T10:00:00Z"),
+            card("d", "in_progress", "2026-01-03T10:00:00Z"),
+        ];
+        let query = Some(Query::parse("column:in_progress").unwrap());
+        let kinds: Vec<(ChangeKind, String)> =
+            diff(&before, &after, &query).into_iter().map(|c| (c.change, c.id)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (ChangeKind::Moved, "a".to_string()),
+                (ChangeKind::Created, "d".to_string()),
+                (ChangeKind::Deleted, "c".to_string()),
+            ]
+        );
+
+        // Nothing changed, nothing printed
+        assert!(diff(&after, &after, &None).is_empty());
+    }
+}
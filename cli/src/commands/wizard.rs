@@ -0,0 +1,250 @@
+//! `blaze add -i` - Create a card by answering questions.
+//!
+//! Asks for the title, description, column, priority, tags and due date in
+//! turn, starting from whatever flags were given. Column, priority and tags
+//! complete with Tab (tags from the ones already on the board). The card is
+//! previewed before anything is sent; `e` goes round again with the answers
+//! so far. Prompts and the preview go to stderr, the created card to stdout.
+
+use crate::commands::add::{self, AddOptions};
+use crate::context::CommandContext;
+use crate::error::{BlazeError, Result};
+use crate::output::format_due;
+use crate::parsers::parse_due_date;
+use crate::types::{Column, Priority};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal};
+
+pub async fn run(ctx: &CommandContext, mut options: AddOptions) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        return Err(BlazeError::InvalidInput("add -i needs a terminal; pass flags or use --batch".into()));
+    }
+    // Suggestions only; a board that can't be read just means no tag completion
+    let tags: BTreeSet<String> = match ctx.client.list_cards(None, false).await {
+        Ok(cards) => cards.into_iter().flat_map(|c| c.tags).collect(),
+        Err(_) => BTreeSet::new(),
+    };
+    let columns = ctx.client.columns().await.unwrap_or(&Column::DEFAULTS);
+
+    let mut editor: Editor<Choices, DefaultHistory> = Editor::new().map_err(readline_error)?;
+    eprintln!("New card (Tab completes, Ctrl-C cancels)");
+    loop {
+        match ask_all(&mut editor, &mut options, columns, &tags, ctx.tz) {
+            Ok(()) => {}
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => {
+                eprintln!("Aborted.");
+                return Ok(());
+            }
+            Err(e) => return Err(readline_error(e)),
+        }
+
+        preview(ctx, &options);
+        editor.set_helper(Some(Choices::new(["yes", "no", "edit"])));
+        let answer = match editor.readline("Create this card? [Y/n/e] ") {
+            Ok(answer) => answer.trim().to_lowercase(),
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => "n".to_string(),
+            Err(e) => return Err(readline_error(e)),
+        };
+        match answer.as_str() {
+            "" | "y" | "yes" => return add::run(ctx, options).await,
+            "e" | "edit" => continue,
+            _ => {
+                eprintln!("Aborted.");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Ask every question once, with the current values as defaults
+fn ask_all(
+    editor: &mut Editor<Choices, DefaultHistory>,
+    options: &mut AddOptions,
+    columns: &[Column],
+    tags: &BTreeSet<String>,
+    tz: Tz,
+) -> rustyline::Result<()> {
+    editor.set_helper(None);
+    options.title = loop {
+        let title = editor.readline_with_initial("Title: ", (&options.title, ""))?;
+        if !title.trim().is_empty() {
+            break title.trim().to_string();
+        }
+        eprintln!("  A title is required");
+    };
+
+    let description = options.description.clone().unwrap_or_default();
+    let description = editor.readline_with_initial("Description (optional): ", (&description, ""))?;
+    options.description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+
+    let column_names: Vec<String> = columns.iter().map(Column::to_string).collect();
+    editor.set_helper(Some(Choices::new(column_names.iter().cloned())));
+    let current = options.column.clone();
+    options.column = ask_value(editor, "Column", current, &column_names, |answer| {
+        answer.parse().ok().filter(|c| columns.contains(c))
+    })?;
+    let priority_names = names::<Priority>();
+    editor.set_helper(Some(Choices::new(priority_names.iter().cloned())));
+    options.priority = ask_value(editor, "Priority", options.priority, &priority_names, |answer| {
+        Priority::from_str(answer, true).ok()
+    })?;
+
+    let mut choices = Choices::new(tags.iter().cloned());
+    choices.list = true;
+    editor.set_helper(Some(choices));
+    let answer = editor.readline_with_initial("Tags (comma-separated): ", (&options.tags.join(", "), ""))?;
+    options.tags = answer.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect();
+
+    editor.set_helper(None);
+    options.due = loop {
+        let due = options.due.clone().unwrap_or_default();
+        let answer = editor.readline_with_initial("Due (YYYY-MM-DD [HH:MM], optional): ", (&due, ""))?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            break None;
+        }
+        match parse_due_date(answer, tz) {
+            Ok(_) => break Some(answer.to_string()),
+            Err(e) => eprintln!("  {}", e),
+        }
+    };
+    Ok(())
+}
+
+/// Ask for one of `choices` until a valid one is given (empty keeps `current`)
+fn ask_value<T: ToString>(
+    editor: &mut Editor<Choices, DefaultHistory>,
+    label: &str,
+    current: T,
+    choices: &[String],
+    parse: impl Fn(&str) -> Option<T>,
+) -> rustyline::Result<T> {
+    loop {
+        let answer = editor.readline(&format!("{} [{}]: ", label, current.to_string()))?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(current);
+        }
+        match parse(answer) {
+            Some(value) => return Ok(value),
+            None => eprintln!("  Choose one of: {}", choices.join(", ")),
+        }
+    }
+}
+
+fn names<T: ValueEnum>() -> Vec<String> {
+    T::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+fn preview(ctx: &CommandContext, options: &AddOptions) {
+    let mut fields = vec![
+        ("Title", options.title.clone()),
+        ("Column", ctx.theme.column(&options.column)),
+        ("Priority", ctx.theme.priority_label(options.priority)),
+    ];
+    if let Some(ref description) = options.description {
+        fields.insert(1, ("Description", description.clone()));
+    }
+    if !options.tags.is_empty() {
+        fields.push(("Tags", options.tags.join(", ")));
+    }
+    if let Some(due) = options.due.as_deref().and_then(|d| parse_due_date(d, ctx.tz).ok()) {
+        fields.push(("Due", format_due(ctx, due)));
+    }
+    if let Some(ref assignee) = options.assignee {
+        fields.push(("Assignee", assignee.clone()));
+    }
+    eprintln!();
+    for (label, value) in fields {
+        eprintln!("  {:<12} {}", format!("{}:", label), value);
+    }
+    eprintln!();
+}
+
+fn readline_error(e: ReadlineError) -> BlazeError {
+    BlazeError::Other(format!("Line editor: {}", e))
+}
+
+/// Tab completion from a fixed set of words
+struct Choices {
+    words: Vec<String>,
+    /// Complete the last entry of a comma-separated list
+    list: bool,
+}
+
+impl Choices {
+    fn new<S: Into<String>>(words: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            words: words.into_iter().map(Into::into).collect(),
+            list: false,
+        }
+    }
+
+    /// Where the word being typed starts, and the candidates for it
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = if self.list {
+            before.rfind(',').map_or(0, |i| i + 1)
+        } else {
+            0
+        };
+        let start = start + (before[start..].len() - before[start..].trim_start().len());
+        let typed = before[start..].to_lowercase();
+        let taken: Vec<&str> = if self.list { before[..start].split(',').map(str::trim).collect() } else { vec![] };
+        let matches = self
+            .words
+            .iter()
+            .filter(|w| w.to_lowercase().starts_with(&typed) && !taken.contains(&w.as_str()))
+            .cloned()
+            .collect();
+        (start, matches)
+    }
+}
+
+impl Completer for Choices {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &rustyline::Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for Choices {
+    type Hint = String;
+}
+
+impl Highlighter for Choices {}
+
+impl Validator for Choices {}
+
+impl Helper for Choices {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_the_word_being_typed() {
+        let columns = Choices::new(Column::DEFAULTS.iter().map(Column::to_string));
+        assert_eq!(columns.candidates("in", 2), (0, vec!["in_progress".to_string()]));
+
+        let mut tags = Choices::new(["backend", "bug", "docs"]);
+        tags.list = true;
+        assert_eq!(tags.candidates("docs, b", 7), (6, vec!["backend".to_string(), "bug".to_string()]));
+        // Tags already in the list aren't offered again
+        assert_eq!(tags.candidates("bug,", 4).1, ["backend", "docs"]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Dynamic shell completion.
+//!
+//! `blaze completions <shell>` prints a script that calls back into
+//! `COMPLETE=<shell> blaze -- <words>` on every tab press. Card ID arguments
+//! complete from the live card list, which is cached for a few seconds under
+//! ~/.cache/blaze/ so repeated tabs don't each hit the server. Column
+//! arguments complete from the column list the last command cached, or the
+//! default columns.
+
+use crate::cache;
+use crate::client::Client;
+use crate::columns;
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::Column;
+use chrono::{DateTime, Utc};
+use clap_complete::engine::CompletionCandidate;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::time::Duration;
+
+/// How long a fetched card list is reused
+const CACHE_TTL_SECS: i64 = 30;
+
+/// Give up on the server after this long; a slow tab is worse than no candidates
+const FETCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CardName {
+    id: String,
+    title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCards {
+    url: String,
+    fetched_at: DateTime<Utc>,
+    cards: Vec<CardName>,
+}
+
+/// Candidates for a card ID argument: IDs starting with the input, or
+/// failing that, cards whose title contains it. Titles are shown as help.
+pub fn card_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let cards = match cards() {
+        Ok(cards) => cards,
+        // Nothing useful to say in the middle of a command line
+        Err(_) => return Vec::new(),
+    };
+    matching(&cards, &current.to_string_lossy())
+        .into_iter()
+        .map(|c| CompletionCandidate::new(&c.id).help(Some(c.title.clone().into())))
+        .collect()
+}
+
+/// Candidates for a column argument; never asks the server
+pub fn columns(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let known = server_url()
+        .ok()
+        .and_then(|url| columns::cached(url.trim_end_matches('/'), None))
+        .unwrap_or_else(|| Column::DEFAULTS.to_vec());
+    known
+        .iter()
+        .map(Column::to_string)
+        .filter(|name| name.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn server_url() -> Result<String> {
+    let profile = std::env::var("BLAZE_PROFILE").ok();
+    let config = Config::load(profile.as_deref())?;
+    Ok(std::env::var("BLAZE_URL")
+        .ok()
+        .or(config.url().map(String::from))
+        .unwrap_or_else(|| "http://localhost:8080".to_string()))
+}
+
+fn matching<'a>(cards: &'a [CardName], current: &str) -> Vec<&'a CardName> {
+    let by_id: Vec<&CardName> = cards.iter().filter(|c| c.id.starts_with(current)).collect();
+    if !by_id.is_empty() {
+        return by_id;
+    }
+    let needle = current.to_lowercase();
+    cards.iter().filter(|c| c.title.to_lowercase().contains(&needle)).collect()
+}
+
+/// Active cards on the configured server, from the cache when it's fresh
+fn cards() -> Result<Vec<CardName>> {
+    let profile = std::env::var("BLAZE_PROFILE").ok();
+    let config = Config::load(profile.as_deref())?;
+    let url = std::env::var("BLAZE_URL")
+        .ok()
+        .or(config.url().map(String::from))
+        .unwrap_or_else(|| "http://localhost:8080".to_string());
+
+    let path = cache::dir()?.join("completion-cards.json");
+    if let Ok(content) = fs::read_to_string(&path) {
+        if let Ok(cached) = serde_json::from_str::<CachedCards>(&content) {
+            let age = Utc::now() - cached.fetched_at;
+            if cached.url == url && age.num_seconds() < CACHE_TTL_SECS {
+                return Ok(cached.cards);
+            }
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let cards = runtime.block_on(async {
+        let fetch = async {
+            let token = match std::env::var("BLAZE_TOKEN") {
+                Ok(token) => Some(token),
+                Err(_) => config.resolve_token(&config.connection()).await?,
+            };
+            Client::new(&url, token)?.list_cards(None, false).await
+        };
+        tokio::time::timeout(FETCH_TIMEOUT, fetch)
+            .await
+            .map_err(|_| BlazeError::Other("Timed out fetching cards".into()))?
+    })?;
+
+    let cards: Vec<CardName> = cards
+        .into_iter()
+        .map(|c| CardName { id: c.id, title: c.title })
+        .collect();
+    let cached = CachedCards {
+        url,
+        fetched_at: Utc::now(),
+        cards,
+    };
+    // A failed cache write only costs a refetch next time
+    if fs::create_dir_all(cache::dir()?).is_ok() {
+        let _ = fs::write(&path, serde_json::to_string(&cached)?);
+    }
+    Ok(cached.cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_first_then_titles() {
+        let cards = vec![
+            CardName { id: "1a2b3c4d5e6f".into(), title: "Fix login".into() },
+            CardName { id: "1a9f00000000".into(), title: "Rate limiting".into() },
+            CardName { id: "7777aaaabbbb".into(), title: "Login audit".into() },
+        ];
+        let ids = |current| matching(&cards, current).iter().map(|c| c.id.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(ids("1a"), ["1a2b3c4d5e6f", "1a9f00000000"]);
+        assert_eq!(ids("login"), ["1a2b3c4d5e6f", "7777aaaabbbb"]);
+        assert_eq!(ids(""), ["1a2b3c4d5e6f", "1a9f00000000", "7777aaaabbbb"]);
+        assert!(ids("zzz").is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Client-side encryption for confidential cards.
+//!
+//! With `[encryption]` recipients configured (see README.md), the
+//! description and attachments of cards tagged `confidential` are encrypted
+//! with age before they leave the client, so the server only stores
+//! ciphertext. Descriptions are ASCII-armored (they're JSON strings),
+//! attachments binary. Reading them back takes an identity file holding one
+//! of the recipients' keys; without one, the ciphertext is shown as is.
+//!
+//! Only X25519 keys (`age1...`, `AGE-SECRET-KEY-1...`, as made by
+//! `age-keygen`) are supported. The identity's own public key is always a
+//! recipient, so you can read what you wrote.
+
+use crate::config::EncryptionConfig;
+use crate::error::{BlazeError, Result};
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::x25519;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+
+/// Tag marking cards to encrypt, unless `[encryption] tag` says otherwise
+pub const DEFAULT_TAG: &str = "confidential";
+
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const BINARY_MAGIC: &[u8] = b"age-encryption.org/v1\n";
+
+/// Keys for confidential cards on the current server
+#[derive(Clone)]
+pub struct Confidential {
+    tag: String,
+    recipients: Vec<x25519::Recipient>,
+    identities: Vec<x25519::Identity>,
+}
+
+// Keys stay out of `--verbose` and error output
+impl fmt::Debug for Confidential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Confidential")
+            .field("tag", &self.tag)
+            .field("recipients", &self.recipients.len())
+            .field("identities", &self.identities.len())
+            .finish()
+    }
+}
+
+impl Confidential {
+    /// Keys from `[encryption]`; `None` when no recipients are configured
+    pub fn from_config(config: &EncryptionConfig) -> Result<Option<Self>> {
+        let invalid = |msg: String| BlazeError::Config(format!("[encryption]: {}", msg));
+        let mut identities = Vec::new();
+        if let Some(ref path) = config.identity {
+            let text = fs::read_to_string(path)
+                .map_err(|e| invalid(format!("can't read identity {}: {}", path.display(), e)))?;
+            for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+                let identity = line
+                    .parse::<x25519::Identity>()
+                    .map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+                identities.push(identity);
+            }
+        }
+        if config.recipients.is_empty() && identities.is_empty() {
+            return Ok(None);
+        }
+
+        let mut recipients = Vec::new();
+        for recipient in &config.recipients {
+            let parsed = recipient
+                .parse::<x25519::Recipient>()
+                .map_err(|e| invalid(format!("recipient '{}': {}", recipient, e)))?;
+            recipients.push(parsed);
+        }
+        for identity in &identities {
+            let own = identity.to_public();
+            if !recipients.iter().any(|r| r.to_string() == own.to_string()) {
+                recipients.push(own);
+            }
+        }
+
+        Ok(Some(Self {
+            tag: config.tag.clone().unwrap_or_else(|| DEFAULT_TAG.to_string()),
+            recipients,
+            identities,
+        }))
+    }
+
+    /// Whether cards with these tags are encrypted
+    pub fn covers(&self, tags: &[String]) -> bool {
+        tags.iter().any(|t| t.eq_ignore_ascii_case(&self.tag))
+    }
+
+    /// Encrypt a description (already encrypted ones are left alone)
+    pub fn seal_text(&self, text: &str) -> Result<String> {
+        if is_sealed_text(text) {
+            return Ok(text.to_string());
+        }
+        let mut armored = Vec::new();
+        let writer = ArmoredWriter::wrap_output(&mut armored, Format::AsciiArmor)?;
+        let writer = self.encrypt(writer, text.as_bytes())?;
+        writer.finish()?;
+        Ok(String::from_utf8(armored).expect("age armor is ASCII"))
+    }
+
+    /// Decrypt a description; `None` if it isn't encrypted or not for us
+    pub fn open_text(&self, text: &str) -> Option<String> {
+        if !is_sealed_text(text) {
+            return None;
+        }
+        let plain = self.decrypt(ArmoredReader::new(text.trim().as_bytes()))?;
+        String::from_utf8(plain).ok()
+    }
+
+    /// Encrypt an attachment's content
+    pub fn seal_bytes(&self, content: &[u8]) -> Result<Vec<u8>> {
+        if content.starts_with(BINARY_MAGIC) {
+            return Ok(content.to_vec());
+        }
+        self.encrypt(Vec::new(), content)
+    }
+
+    /// Decrypt an attachment; `None` if it isn't encrypted or not for us
+    pub fn open_bytes(&self, content: &[u8]) -> Option<Vec<u8>> {
+        if !content.starts_with(BINARY_MAGIC) {
+            return None;
+        }
+        self.decrypt(content)
+    }
+
+    fn encrypt<W: Write>(&self, output: W, plain: &[u8]) -> Result<W> {
+        let failed = |e: &dyn fmt::Display| BlazeError::Other(format!("Encryption failed: {}", e));
+        let encryptor = age::Encryptor::with_recipients(self.recipients.iter().map(|r| r as &dyn age::Recipient))
+            .map_err(|e| failed(&e))?;
+        let mut writer = encryptor.wrap_output(output)?;
+        writer.write_all(plain)?;
+        Ok(writer.finish()?)
+    }
+
+    fn decrypt<R: std::io::BufRead>(&self, input: R) -> Option<Vec<u8>> {
+        let decryptor = age::Decryptor::new_buffered(input).ok()?;
+        let mut reader = decryptor
+            .decrypt(self.identities.iter().map(|i| i as &dyn age::Identity))
+            .ok()?;
+        let mut plain = Vec::new();
+        reader.read_to_end(&mut plain).ok()?;
+        Some(plain)
+    }
+}
+
+/// Whether a description holds age ciphertext
+pub fn is_sealed_text(text: &str) -> bool {
+    text.trim_start().starts_with(ARMOR_BEGIN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::secrecy::ExposeSecret;
+
+    #[test]
+    fn seals_and_opens_for_the_identity() {
+        let identity = x25519::Identity::generate();
+        let path = std::env::temp_dir().join(format!("blaze-age-{}.txt", std::process::id()));
+        fs::write(&path, format!("# created: now\n{}\n", identity.to_string().expose_secret())).unwrap();
+        let config = EncryptionConfig {
+            recipients: vec![x25519::Identity::generate().to_public().to_string()],
+            identity: Some(path.clone()),
+            tag: None,
+        };
+        let keys = Confidential::from_config(&config).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(keys.covers(&["Confidential".to_string()]));
+        assert!(!keys.covers(&["ops".to_string()]));
+
+        let sealed = keys.seal_text("salary bands for 2027").unwrap();
+        assert!(is_sealed_text(&sealed) && !sealed.contains("salary"));
+        assert_eq!(keys.seal_text(&sealed).unwrap(), sealed);
+        assert_eq!(keys.open_text(&sealed).as_deref(), Some("salary bands for 2027"));
+        assert_eq!(keys.open_text("plain"), None);
+
+        let bytes = keys.seal_bytes(b"\x00\x01 report").unwrap();
+        assert_eq!(keys.open_bytes(&bytes).as_deref(), Some(&b"\x00\x01 report"[..]));
+
+        // Someone else's key can't read it
+        let other = Confidential {
+            identities: vec![x25519::Identity::generate()],
+            ..keys
+        };
+        assert_eq!(other.open_text(&sealed), None);
+
+        assert!(Confidential::from_config(&EncryptionConfig::default()).unwrap().is_none());
+    }
+}
@@ -1,10 +1,19 @@
 //! Configuration file handling for Blaze CLI.
 //!
 //! Config location: ~/.config/blaze/config.toml
-//! Token location: ~/.config/blaze/token (separate file, not in config.toml)
+//! Token location: the OS keyring, or ~/.config/blaze/token with
+//! `[auth] store = "file"`, unless `[auth]` selects another provider (see `auth.rs`).
+//!
+//! The sections and their keys are documented in README.md ("CLI Configuration").
 
+use crate::auth::{self, AuthConfig};
 use crate::error::{BlazeError, Result};
+use crate::github::ConflictPolicy;
+use crate::output::TimestampMode;
+use crate::wip::WipEnforcement;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -12,9 +21,350 @@ use std::path::PathBuf;
 pub struct Config {
     /// API base URL
     pub url: Option<String>,
-    /// API token (loaded separately from token file)
+    /// Time zone for due dates and displayed timestamps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<Tz>,
+    /// Name on your comments (default: $USER)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Token provider selection
+    #[serde(default, skip_serializing_if = "AuthConfig::is_empty")]
+    pub auth: AuthConfig,
+    /// Named servers
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Request retries
+    #[serde(default, skip_serializing_if = "RetryConfig::is_empty")]
+    pub retry: RetryConfig,
+    /// Safeguards for multi-card commands
+    #[serde(default, skip_serializing_if = "BulkConfig::is_empty")]
+    pub bulk: BulkConfig,
+    /// Reasons for priority raises
+    #[serde(default, skip_serializing_if = "EscalationConfig::is_empty")]
+    pub escalation: EscalationConfig,
+    /// Proxy and TLS settings
+    #[serde(default, skip_serializing_if = "ConnectionConfig::is_empty")]
+    pub connection: ConnectionConfig,
+    /// Keys for confidential cards
+    #[serde(default, skip_serializing_if = "EncryptionConfig::is_empty")]
+    pub encryption: EncryptionConfig,
+    /// Table output settings
+    #[serde(default, skip_serializing_if = "DisplayConfig::is_empty")]
+    pub display: DisplayConfig,
+    /// Priority and column colors, emoji
+    #[serde(default, skip_serializing_if = "ColorsConfig::is_empty")]
+    pub colors: ColorsConfig,
+    /// Team slices of the board
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub teams: BTreeMap<String, TeamConfig>,
+    /// GitHub Projects sync
+    #[serde(default, skip_serializing_if = "GithubConfig::is_empty")]
+    pub github: GithubConfig,
+    /// Plan checks
+    #[serde(default, skip_serializing_if = "PlanLintConfig::is_empty")]
+    pub plan_lint: PlanLintConfig,
+    /// Board-wide WIP limits
+    #[serde(default, skip_serializing_if = "WipConfig::is_empty")]
+    pub wip: WipConfig,
+    /// Desktop alerts from `blaze notifyd`
+    #[serde(default, skip_serializing_if = "NotifydConfig::is_empty")]
+    pub notifyd: NotifydConfig,
+    /// Where `blaze metrics` keeps its history
+    #[serde(default, skip_serializing_if = "MetricsConfig::is_empty")]
+    pub metrics: MetricsConfig,
+    /// Rules setting `agent_assignable`, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agent_rules: BTreeMap<String, AgentRule>,
+    /// Profile selected at load time
     #[serde(skip)]
-    pub token: Option<String>,
+    pub profile: Option<String>,
+}
+
+/// `[profiles.<name>]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// API base URL
+    pub url: Option<String>,
+    /// Token provider selection
+    #[serde(default, skip_serializing_if = "AuthConfig::is_empty")]
+    pub auth: AuthConfig,
+    /// Proxy and TLS settings for this server
+    #[serde(default, skip_serializing_if = "ConnectionConfig::is_empty")]
+    pub connection: ConnectionConfig,
+    /// Keys for confidential cards on this server
+    #[serde(default, skip_serializing_if = "EncryptionConfig::is_empty")]
+    pub encryption: EncryptionConfig,
+}
+
+/// `[connection]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    /// Proxy URL for all requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Extra CA certificate (PEM) to trust
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate (PEM) for mTLS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// Private key (PEM) for the client certificate, if not in the same file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+}
+
+impl ConnectionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.proxy.is_none() && self.ca_cert.is_none() && self.client_cert.is_none() && self.client_key.is_none()
+    }
+}
+
+/// `[display]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DisplayConfig {
+    /// Row highlighting by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, DisplayRule>,
+    /// Timestamps when no `--utc`/`--local`/`--relative` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<TimestampMode>,
+    /// ID characters shown in tables (default 8, 0 for whole IDs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_length: Option<usize>,
+}
+
+impl DisplayConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.timestamps.is_none() && self.id_length.is_none()
+    }
+}
+
+/// `[display.rules.<name>]`: highlight cards matching a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayRule {
+    /// Filter query, as for `list --filter`
+    pub when: String,
+    /// e.g. "bold", "red", "white on red"
+    pub style: String,
+}
+
+/// One of `[agent_rules]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentRule {
+    /// Filter query, as for `list --filter`
+    pub when: String,
+    /// Value to set on matching cards
+    #[serde(default = "assignable_default")]
+    pub assignable: bool,
+}
+
+fn assignable_default() -> bool {
+    true
+}
+
+/// `[colors]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ColorsConfig {
+    /// Show emoji for priorities and statuses (default true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+    /// Style per priority name, replacing the default
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub priority: BTreeMap<String, String>,
+    /// Style per column name, replacing the default
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub column: BTreeMap<String, String>,
+}
+
+impl ColorsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.emoji.is_none() && self.priority.is_empty() && self.column.is_empty()
+    }
+}
+
+/// `[teams.<name>]`: a team's part of a shared board
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TeamConfig {
+    /// Filter query for the team's cards, as for `list --filter` (default: all of `tags`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Tags added to cards created with `blaze team <name> add`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Most cards allowed per column, by column name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wip: BTreeMap<String, usize>,
+}
+
+/// `[github]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GithubConfig {
+    /// Token provider (default: `GITHUB_TOKEN` or `GH_TOKEN`)
+    #[serde(default, skip_serializing_if = "AuthConfig::is_empty")]
+    pub auth: AuthConfig,
+    /// GraphQL endpoint, for GitHub Enterprise Server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Single-select project field holding the column (default "Status")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_field: Option<String>,
+    /// Project status for a column, where it differs from the default
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub columns: BTreeMap<String, String>,
+    /// Who wins when both sides changed (default: the newer change)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<ConflictPolicy>,
+}
+
+impl GithubConfig {
+    pub fn is_empty(&self) -> bool {
+        self.auth.is_empty()
+            && self.api_url.is_none()
+            && self.status_field.is_none()
+            && self.columns.is_empty()
+            && self.conflicts.is_none()
+    }
+}
+
+/// `[plan_lint]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PlanLintConfig {
+    /// Headings a plan needs at each status (`ready`, `approved`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sections: BTreeMap<String, Vec<String>>,
+    /// Heading listing the acceptance criteria (default "Acceptance criteria")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criteria_section: Option<String>,
+}
+
+impl PlanLintConfig {
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty() && self.criteria_section.is_none()
+    }
+}
+
+/// `[notifyd]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NotifydConfig {
+    /// Seconds between polls (default 300)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// How many hours ahead of its due date a card counts as due soon, by
+    /// priority name (0 turns it off); unset priorities keep the defaults
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub due_soon_hours: BTreeMap<String, u32>,
+}
+
+impl NotifydConfig {
+    pub fn is_empty(&self) -> bool {
+        self.interval_secs.is_none() && self.due_soon_hours.is_empty()
+    }
+}
+
+/// `[encryption]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// age public keys (`age1...`) that can read confidential cards
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
+    /// age identity file with your secret key, for reading them back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<PathBuf>,
+    /// Tag marking cards to encrypt (default "confidential")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl EncryptionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.recipients.is_empty() && self.identity.is_none() && self.tag.is_none()
+    }
+}
+
+/// `[wip]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WipConfig {
+    /// Most cards allowed per column, by column name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub limits: BTreeMap<String, usize>,
+    /// Refuse moves over a limit, or only warn (default: refuse)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce: Option<WipEnforcement>,
+}
+
+impl WipConfig {
+    pub fn is_empty(&self) -> bool {
+        self.limits.is_empty() && self.enforce.is_none()
+    }
+}
+
+/// `[metrics]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Database for `metrics snapshot`/`query` without `--db`, and `board` trends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db: Option<PathBuf>,
+}
+
+impl MetricsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.db.is_none()
+    }
+}
+
+/// `[retry]` config section (unset fields keep the built-in defaults)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_backoff_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutations: Option<bool>,
+}
+
+impl RetryConfig {
+    pub fn is_empty(&self) -> bool {
+        self.retries.is_none()
+            && self.backoff_ms.is_none()
+            && self.max_backoff_ms.is_none()
+            && self.jitter.is_none()
+            && self.mutations.is_none()
+    }
+}
+
+/// `[bulk]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BulkConfig {
+    /// Operations on more cards than this need a typed confirmation (0 turns it off; default 20)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_above: Option<usize>,
+}
+
+impl BulkConfig {
+    pub fn is_empty(&self) -> bool {
+        self.confirm_above.is_none()
+    }
+}
+
+/// `[escalation]` config section
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EscalationConfig {
+    /// Whether raising a card to urgent needs `--reason` (default true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_reason: Option<bool>,
+}
+
+impl EscalationConfig {
+    pub fn is_empty(&self) -> bool {
+        self.require_reason.is_none()
+    }
 }
 
 impl Config {
@@ -28,20 +378,33 @@ impl Config {
         Self::dir().map(|d| d.join("config.toml"))
     }
 
-    /// Get the token file path
-    pub fn token_path() -> Option<PathBuf> {
-        Self::dir().map(|d| d.join("token"))
+    /// Get the token file path for a profile (or the default server)
+    pub fn token_path(profile: Option<&str>) -> Option<PathBuf> {
+        let name = match profile {
+            Some(profile) => format!("token-{}", profile),
+            None => "token".to_string(),
+        };
+        Self::dir().map(|d| d.join(name))
     }
 
-    /// Load config from disk (or return defaults)
-    pub fn load() -> Result<Self> {
-        let mut config = Self::load_config_file()?;
-        config.token = Self::load_token()?;
+    /// Load config from disk (or return defaults) with `profile` active
+    pub fn load(profile: Option<&str>) -> Result<Self> {
+        let mut config = Self::read()?;
+        if let Some(name) = profile {
+            if !config.profiles.contains_key(name) {
+                let known: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+                return Err(BlazeError::Config(format!(
+                    "Unknown profile '{}' (configured: {})",
+                    name,
+                    if known.is_empty() { "none".to_string() } else { known.join(", ") }
+                )));
+            }
+            config.profile = Some(name.to_string());
+        }
         Ok(config)
     }
 
-    /// Load just the config.toml file
-    fn load_config_file() -> Result<Self> {
+    fn read() -> Result<Self> {
         let path = match Self::path() {
             Some(p) => p,
             None => return Ok(Self::default()),
@@ -56,32 +419,75 @@ impl Config {
         })?;
 
         let config: Config = toml::from_str(&content)?;
+        if let Some(bad) = config.profiles.keys().find(|name| !valid_profile_name(name)) {
+            return Err(BlazeError::Config(format!(
+                "Invalid profile name '{}' (use letters, digits, '-' and '_')",
+                bad
+            )));
+        }
         Ok(config)
     }
 
-    /// Load token from separate file
-    fn load_token() -> Result<Option<String>> {
-        let path = match Self::token_path() {
-            Some(p) => p,
-            None => return Ok(None),
-        };
+    fn active(&self) -> Option<&Profile> {
+        self.profile.as_ref().and_then(|name| self.profiles.get(name))
+    }
 
-        if !path.exists() {
-            return Ok(None);
+    /// API base URL of the active profile
+    pub fn url(&self) -> Option<&str> {
+        match self.active() {
+            Some(profile) => profile.url.as_deref(),
+            None => self.url.as_deref(),
         }
+    }
 
-        let token = fs::read_to_string(&path)
-            .map_err(|e| BlazeError::Config(format!("Failed to read token: {}", e)))?
-            .trim()
-            .to_string();
+    /// Auth settings of the active profile.
+    ///
+    /// Profiles never inherit the top-level provider, so one server's token
+    /// is not sent to another; only the token store choice carries over.
+    pub fn auth(&self) -> AuthConfig {
+        match self.active() {
+            None => self.auth.clone(),
+            Some(profile) if profile.auth.is_empty() => AuthConfig {
+                store: self.auth.store,
+                ..AuthConfig::default()
+            },
+            Some(profile) => profile.auth.clone(),
+        }
+    }
+
+    /// Connection settings of the active profile.
+    ///
+    /// The proxy and CA carry over from the top level; a client certificate
+    /// identifies you, so it is only sent to the server it was configured for.
+    pub fn connection(&self) -> ConnectionConfig {
+        let Some(profile) = self.active() else {
+            return self.connection.clone();
+        };
+        ConnectionConfig {
+            proxy: profile.connection.proxy.clone().or_else(|| self.connection.proxy.clone()),
+            ca_cert: profile.connection.ca_cert.clone().or_else(|| self.connection.ca_cert.clone()),
+            ..profile.connection.clone()
+        }
+    }
 
-        if token.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(token))
+    /// Encryption keys of the active profile (the top-level ones if it has none)
+    pub fn encryption(&self) -> EncryptionConfig {
+        match self.active() {
+            Some(profile) if !profile.encryption.is_empty() => profile.encryption.clone(),
+            _ => self.encryption.clone(),
         }
     }
 
+    /// Resolve the API token through the configured auth provider; token endpoints are
+    /// reached through `connection`'s proxy and CA
+    pub async fn resolve_token(&self, connection: &ConnectionConfig) -> Result<Option<String>> {
+        let provider = auth::provider(&self.auth(), self.profile.as_deref(), connection)?;
+        provider.token().await.map_err(|e| match e {
+            BlazeError::Auth(msg) => BlazeError::Auth(format!("{} provider: {}", provider.name(), msg)),
+            other => other,
+        })
+    }
+
     /// Save config to disk
     pub fn save(&self) -> Result<()> {
         let dir = Self::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
@@ -96,14 +502,44 @@ impl Config {
         Ok(())
     }
 
-    /// Save token to separate file
-    pub fn save_token(token: &str) -> Result<()> {
-        let dir = Self::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
-        fs::create_dir_all(&dir)?;
+    /// Save token to the configured store (OS keyring or token file), for the server at `url`
+    pub async fn save_token(&self, token: &str, url: &str) -> Result<()> {
+        self.auth().store.save(token, self.profile.as_deref()).await?;
+        auth::bind_to_server(token, url, self.profile.as_deref())
+    }
+}
+
+fn valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
 
-        let path = Self::token_path().unwrap();
-        fs::write(&path, token)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(())
+    #[test]
+    fn profiles_do_not_inherit_the_top_level_provider() {
+        let mut config: Config = toml::from_str(
+            r#"
+            url = "http://localhost:8080"
+
+            [auth]
+            command = "pass show blaze/home"
+            store = "file"
+
+            [profiles.work]
+            url = "https://blaze.example.com"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.url(), Some("http://localhost:8080"));
+        assert!(config.auth().has_provider());
+
+        config.profile = Some("work".into());
+        assert_eq!(config.url(), Some("https://blaze.example.com"));
+        let auth = config.auth();
+        assert!(!auth.has_provider());
+        assert_eq!(auth.store, auth::TokenStore::File);
     }
 }
@@ -0,0 +1,352 @@
+//! Shared state handed to every command.
+
+use crate::auth;
+use crate::client::{Client, ClientOptions, RetryPolicy};
+use crate::confidential::Confidential;
+use crate::config::{Config, ConnectionConfig};
+use crate::error::{BlazeError, Result};
+use crate::jq::JsonQuery;
+use crate::journal::Recording;
+use crate::output::{self, ColorMode, Highlighter, Theme, TimestampMode};
+use crate::resolve::IdResolver;
+use chrono_tz::Tz;
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Output format for command results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Pretty-printed JSON (full IDs)
+    #[default]
+    Json,
+    /// Human-readable tables (short IDs)
+    Table,
+    /// Only card/plan IDs, one per line
+    Quiet,
+    /// RFC 4180 CSV with a header row (list, board and stats; other commands print JSON)
+    Csv,
+    /// One compact JSON value per line: list items, events and changes are streamed one per line
+    Ndjson,
+    /// GitHub-flavored Markdown tables (list, board and stats; other commands print tables)
+    Markdown,
+}
+
+/// Options shared by all commands
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Named server profile from config.toml
+    #[arg(long, global = true, env = "BLAZE_PROFILE")]
+    pub profile: Option<String>,
+
+    /// API base URL
+    #[arg(long, global = true, env = "BLAZE_URL")]
+    pub url: Option<String>,
+
+    /// API token
+    #[arg(long, global = true, env = "BLAZE_TOKEN")]
+    pub token: Option<String>,
+
+    /// Output format
+    #[arg(long, global = true, env = "BLAZE_FORMAT", value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Colors in tables: auto (terminal without NO_COLOR), always or never
+    #[arg(long, global = true, env = "BLAZE_COLOR", value_enum, default_value_t, value_name = "WHEN")]
+    pub color: ColorMode,
+
+    /// Same as --color never
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Show timestamps in UTC
+    #[arg(long, global = true, conflicts_with_all = ["local", "relative"])]
+    pub utc: bool,
+
+    /// Show timestamps in your time zone (--tz, `timezone` in config, else the system's)
+    #[arg(long, global = true, conflicts_with = "relative")]
+    pub local: bool,
+
+    /// Show timestamps relative to now (5m ago, in 3d)
+    #[arg(long, global = true)]
+    pub relative: bool,
+
+    /// Show whole card IDs in tables instead of `[display] id_length` characters
+    #[arg(long, global = true)]
+    pub full_ids: bool,
+
+    /// Print long tables straight to the terminal instead of through $PAGER
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Log requests to stderr (repeat for response bodies)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print mutating requests instead of sending them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Read from the local store and queue changes for `blaze sync`
+    #[arg(long, global = true, env = "BLAZE_OFFLINE", conflicts_with = "replay")]
+    pub offline: bool,
+
+    /// Retries for requests that fail to connect or get 429/502/503/504 (default 2, or [retry] in config)
+    #[arg(long, global = true, env = "BLAZE_RETRIES", value_name = "N")]
+    pub retries: Option<u32>,
+
+    /// Also retry mutating requests (they may be applied twice)
+    #[arg(long, global = true)]
+    pub retry_mutations: bool,
+
+    /// Confirmation phrase for large multi-card operations (e.g. "delete 25 cards"), for scripts
+    #[arg(long, global = true, value_name = "PHRASE")]
+    pub confirm: Option<String>,
+
+    /// Proxy URL for all requests (default: HTTPS_PROXY/HTTP_PROXY, or [connection] proxy)
+    #[arg(long, global = true, env = "BLAZE_PROXY", value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Extra CA certificate (PEM) to trust, e.g. for a self-signed internal server
+    #[arg(long, global = true, env = "BLAZE_CA_CERT", value_name = "PEM")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Client certificate (PEM) to present for mTLS
+    #[arg(long, global = true, env = "BLAZE_CLIENT_CERT", value_name = "PEM")]
+    pub client_cert: Option<PathBuf>,
+
+    /// Private key (PEM) for --client-cert, if not in the same file
+    #[arg(long, global = true, env = "BLAZE_CLIENT_KEY", value_name = "PEM")]
+    pub client_key: Option<PathBuf>,
+
+    /// Time zone for due dates and timestamps (IANA name, e.g. Europe/Berlin; default: `timezone`, else the system's)
+    #[arg(long, global = true, env = "BLAZE_TZ", value_parser = parse_tz)]
+    pub tz: Option<Tz>,
+
+    /// Record all requests/responses into a directory (tokens redacted)
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve all requests from a recorded directory instead of the server
+    #[arg(long, global = true, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Report progress of long operations on stderr as JSON lines (phase, current, total)
+    #[arg(long, global = true, env = "BLAZE_PROGRESS_JSON")]
+    pub progress_json: bool,
+
+    /// Filter JSON output with a jq-style expression, e.g. '.[] | {id, title}'
+    #[arg(long, global = true, value_name = "FILTER")]
+    pub query: Option<JsonQuery>,
+}
+
+/// The token for `url`, unless it's a login token saved for another server; then it is
+/// held back (with a warning) and the second value is true
+fn withhold_foreign_token(token: Option<String>, url: &str, profile: Option<&str>) -> (Option<String>, bool) {
+    let Some(token) = token else {
+        return (None, false);
+    };
+    match auth::saved_for_other_server(&token, url, profile) {
+        Some(server) => {
+            eprintln!(
+                "Warning: the saved token is for {}; not sending it to {} \
+                 (run `blaze login` for this server, or pass --token)",
+                server, url
+            );
+            (None, true)
+        }
+        None => (Some(token), false),
+    }
+}
+
+/// Retry settings from `[retry]`, with flags taking precedence
+fn retry_policy(config: &Config, retries: Option<u32>, mutations: bool) -> RetryPolicy {
+    let defaults = RetryPolicy::default();
+    RetryPolicy {
+        retries: retries.or(config.retry.retries).unwrap_or(defaults.retries),
+        backoff: config.retry.backoff_ms.map(Duration::from_millis).unwrap_or(defaults.backoff),
+        max_backoff: config
+            .retry
+            .max_backoff_ms
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.max_backoff),
+        jitter: config.retry.jitter.unwrap_or(defaults.jitter),
+        mutations: mutations || config.retry.mutations.unwrap_or(defaults.mutations),
+    }
+}
+
+/// The system's time zone: `$TZ` if it names one, else the OS setting
+fn system_tz() -> Option<Tz> {
+    let from_env = std::env::var("TZ").ok().and_then(|tz| tz.trim_start_matches(':').parse().ok());
+    from_env.or_else(|| iana_time_zone::get_timezone().ok()?.parse().ok())
+}
+
+fn parse_tz(input: &str) -> Result<Tz> {
+    input
+        .parse()
+        .map_err(|_| BlazeError::InvalidInput(format!("Unknown time zone '{}'", input)))
+}
+
+/// Everything a command needs to run
+pub struct CommandContext {
+    pub client: Client,
+    pub config: Config,
+    pub url: String,
+    pub token: Option<String>,
+    pub format: OutputFormat,
+    pub verbosity: u8,
+    pub dry_run: bool,
+    pub offline: bool,
+    pub confirm: Option<String>,
+    pub tz: Tz,
+    /// `--utc`, `--local`, `--relative` or `[display] timestamps`
+    pub timestamps: TimestampMode,
+    /// `--color` (`never` with `--no-color`)
+    pub color: ColorMode,
+    /// Priority and column styles for table output
+    pub theme: Theme,
+    /// Row highlighting for table output
+    pub highlighter: Highlighter,
+    ids: IdResolver,
+    /// Most recent card created or referenced (`$last_id` in the repl)
+    last_id: Mutex<Option<String>>,
+}
+
+impl CommandContext {
+    /// Build the context from global flags and the config file, with flags taking precedence
+    pub async fn new(args: GlobalArgs) -> Result<Self> {
+        let config = Config::load(args.profile.as_deref())?;
+
+        let url = args
+            .url
+            .or(config.url().map(String::from))
+            .unwrap_or_else(|| "http://localhost:8080".to_string());
+        let connection = config.connection();
+        let proxy = args.proxy.or(connection.proxy);
+        let ca_cert = args.ca_cert.or(connection.ca_cert);
+        let outside = ConnectionConfig {
+            proxy: proxy.clone(),
+            ca_cert: ca_cert.clone(),
+            ..ConnectionConfig::default()
+        };
+        let (token, token_withheld) = match args.token {
+            Some(token) => (Some(token), false),
+            None => withhold_foreign_token(config.resolve_token(&outside).await?, &url, config.profile.as_deref()),
+        };
+
+        let retry = retry_policy(&config, args.retries, args.retry_mutations);
+
+        // A key from one place only makes sense with the certificate from the same place
+        let (client_cert, client_key) = match args.client_cert {
+            Some(cert) => (Some(cert), args.client_key),
+            None => (connection.client_cert, args.client_key.or(connection.client_key)),
+        };
+        let options = ClientOptions {
+            record: args.record,
+            replay: args.replay,
+            verbosity: args.verbose,
+            dry_run: args.dry_run,
+            store: true,
+            offline: args.offline,
+            retry,
+            proxy,
+            ca_cert,
+            client_cert,
+            client_key,
+            confidential: Confidential::from_config(&config.encryption())?,
+            token_withheld,
+        };
+        let client = Client::with_options(&url, token.clone(), options)?;
+        let color_mode = if args.no_color { ColorMode::Never } else { args.color };
+        // Only tables are colored; Markdown and the machine formats stay plain
+        let color = args.format == OutputFormat::Table && color_mode.enabled();
+        let theme = Theme::from_config(&config.colors, color)?;
+        let highlighter = Highlighter::from_config(&config.display, color)?;
+        let tz = args.tz.or(config.timezone).or_else(system_tz).unwrap_or(Tz::UTC);
+        let timestamps = match (args.utc, args.local, args.relative) {
+            (true, _, _) => TimestampMode::Utc,
+            (_, true, _) => TimestampMode::Local,
+            (_, _, true) => TimestampMode::Relative,
+            _ => config.display.timestamps.unwrap_or_default(),
+        };
+        output::set_id_length(match (args.full_ids, config.display.id_length) {
+            (true, _) | (_, Some(0)) => None,
+            (_, len) => Some(len.unwrap_or(output::DEFAULT_ID_LENGTH)),
+        });
+
+        Ok(Self {
+            client,
+            config,
+            url,
+            token,
+            format: args.format,
+            verbosity: args.verbose,
+            dry_run: args.dry_run,
+            offline: args.offline,
+            confirm: args.confirm,
+            tz,
+            timestamps,
+            color: color_mode,
+            theme,
+            highlighter,
+            ids: IdResolver::default(),
+            last_id: Mutex::new(None),
+        })
+    }
+
+    /// A read-only client for another board: a profile from config.toml, or `default` for
+    /// the top-level server. Returns the board's URL with it.
+    pub async fn board_client(&self, board: &str) -> Result<(String, Client)> {
+        let profile = Some(board).filter(|b| *b != "default" || self.config.profiles.contains_key(*b));
+        let config = Config::load(profile)?;
+        let url = config.url().unwrap_or("http://localhost:8080").to_string();
+        let connection = config.connection();
+        let (token, token_withheld) =
+            withhold_foreign_token(config.resolve_token(&connection).await?, &url, config.profile.as_deref());
+        let options = ClientOptions {
+            verbosity: self.verbosity,
+            token_withheld,
+            retry: retry_policy(&config, None, false),
+            proxy: connection.proxy,
+            ca_cert: connection.ca_cert,
+            client_cert: connection.client_cert,
+            client_key: connection.client_key,
+            confidential: Confidential::from_config(&config.encryption())?,
+            ..ClientOptions::default()
+        };
+        let client = Client::with_options(&url, token, options)?;
+        Ok((url, client))
+    }
+
+    /// Name of the active config profile
+    pub fn profile(&self) -> Option<&str> {
+        self.config.profile.as_deref()
+    }
+
+    /// Resolve a card ID or unique prefix to the full ID
+    pub async fn resolve_card_id(&self, input: &str) -> Result<String> {
+        let id = self.ids.resolve(&self.client, input).await?;
+        self.remember_card(&id);
+        Ok(id)
+    }
+
+    /// Record the changes made from here on in the undo journal, as one operation
+    pub fn begin_operation(&self, command: &str) {
+        let server = self.profile().unwrap_or(&self.url);
+        self.client.record_as(Some(Recording::new(server, command)));
+    }
+
+    /// Forget cached card IDs so prefixes of cards created since resolve
+    pub fn refresh_ids(&mut self) {
+        self.ids = IdResolver::default();
+    }
+
+    pub fn remember_card(&self, id: &str) {
+        *self.last_id.lock().unwrap() = Some(id.to_string());
+    }
+
+    pub fn last_card(&self) -> Option<String> {
+        self.last_id.lock().unwrap().clone()
+    }
+}
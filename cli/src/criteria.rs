@@ -0,0 +1,211 @@
+//! Reusable acceptance criteria sets (definition-of-done lists).
+//!
+//! Sets live in ~/.config/blaze/criteria.toml:
+//!
+//! ```toml
+//! [sets.code-review]
+//! criteria = ["Tests pass", "Reviewed by a second person"]
+//! description = "Standard review checklist"
+//! ```
+//!
+//! `blaze criteria export` / `import` move them between machines as JSON.
+//!
+//! `blaze criteria to-subtasks` / `from-subtasks` turn a card's criteria into
+//! checklist items and back; checked criteria become done subtasks.
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Subtask};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// A named list of acceptance criteria
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriteriaSet {
+    pub name: String,
+    pub criteria: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SetDef {
+    criteria: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+/// Local criteria set storage
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CriteriaStore {
+    #[serde(default)]
+    sets: BTreeMap<String, SetDef>,
+}
+
+impl CriteriaStore {
+    /// Get the criteria file path
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|d| d.join("criteria.toml"))
+    }
+
+    /// Load sets from disk (or return an empty store)
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Self::default()),
+        };
+
+        let content = fs::read_to_string(&path).map_err(|e| {
+            BlazeError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Save sets to disk
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| BlazeError::Config(format!("Failed to serialize criteria sets: {}", e)))?;
+        fs::write(Self::path().unwrap(), content)?;
+        Ok(())
+    }
+
+    /// Look up a set by name
+    pub fn get(&self, name: &str) -> Result<CriteriaSet> {
+        self.sets
+            .get(name)
+            .map(|def| to_set(name, def))
+            .ok_or_else(|| BlazeError::InvalidInput(format!("Unknown criteria set '{}'", name)))
+    }
+
+    /// All sets, sorted by name
+    pub fn all(&self) -> Vec<CriteriaSet> {
+        self.sets.iter().map(|(name, def)| to_set(name, def)).collect()
+    }
+
+    /// Add or replace a set; returns true if it changed
+    pub fn insert(&mut self, set: CriteriaSet) -> bool {
+        let def = SetDef {
+            criteria: set.criteria,
+            description: set.description,
+        };
+        self.sets.insert(set.name, def.clone()) != Some(def)
+    }
+
+    /// Remove a set; returns true if it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.sets.remove(name).is_some()
+    }
+}
+
+fn to_set(name: &str, def: &SetDef) -> CriteriaSet {
+    CriteriaSet {
+        name: name.to_string(),
+        criteria: def.criteria.clone(),
+        description: def.description.clone(),
+    }
+}
+
+/// Validate a set name (used as a TOML key)
+pub fn parse_set_name(input: &str) -> Result<String> {
+    let name = input.trim();
+    if name.is_empty() {
+        return Err(BlazeError::InvalidInput("Criteria set name must not be empty".into()));
+    }
+    if let Some(bad) = name.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_')) {
+        return Err(BlazeError::InvalidInput(format!(
+            "Invalid criteria set name '{}': unexpected character '{}'",
+            name, bad
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Criteria from `set` that `existing` doesn't already have, in set order
+pub fn missing_criteria(existing: &[String], set: &CriteriaSet) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for criterion in &set.criteria {
+        if !existing.contains(criterion) && !missing.contains(criterion) {
+            missing.push(criterion.clone());
+        }
+    }
+    missing
+}
+
+/// The card's subtasks followed by its criteria, done where checked.
+/// A criterion matching an existing subtask marks it done instead of repeating it.
+pub fn criteria_to_subtasks(card: &Card) -> Vec<Subtask> {
+    let mut subtasks = card.subtasks.clone();
+    for (i, criterion) in card.acceptance_criteria.iter().enumerate() {
+        let checked = card.acceptance_checked.get(i).copied().unwrap_or(false);
+        match subtasks.iter_mut().find(|s| &s.title == criterion) {
+            Some(subtask) => subtask.done |= checked,
+            None => subtasks.push(Subtask {
+                title: criterion.clone(),
+                done: checked,
+            }),
+        }
+    }
+    subtasks
+}
+
+/// The card's criteria followed by its subtasks, and the positions to check
+pub fn subtasks_to_criteria(card: &Card) -> (Vec<String>, Vec<usize>) {
+    let mut criteria = card.acceptance_criteria.clone();
+    let mut checked: Vec<bool> = (0..criteria.len())
+        .map(|i| card.acceptance_checked.get(i).copied().unwrap_or(false))
+        .collect();
+    for subtask in &card.subtasks {
+        match criteria.iter().position(|c| c == &subtask.title) {
+            Some(i) => checked[i] |= subtask.done,
+            None => {
+                criteria.push(subtask.title.clone());
+                checked.push(subtask.done);
+            }
+        }
+    }
+    let indices = checked.iter().enumerate().filter(|(_, c)| **c).map(|(i, _)| i).collect();
+    (criteria, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn only_missing_criteria_are_added() {
+        let set = CriteriaSet {
+            name: "review".into(),
+            criteria: vec!["Tests pass".into(), "Docs updated".into(), "Tests pass".into()],
+            description: None,
+        };
+        let existing = vec!["Tests pass".to_string()];
+        assert_eq!(missing_criteria(&existing, &set), ["Docs updated"]);
+        assert!(missing_criteria(&set.criteria, &set).is_empty());
+    }
+
+    #[test]
+    fn conversion_keeps_check_state() {
+        let card = testing::card("c1", serde_json::json!({
+            "acceptance_criteria": ["Tests pass", "Docs updated", "Reviewed"],
+            "acceptance_checked": [true, false],
+            "subtasks": [{"title": "Docs updated", "done": true}, {"title": "Deploy"}],
+        }));
+
+        let subtasks = criteria_to_subtasks(&card);
+        let done: Vec<(&str, bool)> = subtasks.iter().map(|s| (s.title.as_str(), s.done)).collect();
+        assert_eq!(
+            done,
+            [("Docs updated", true), ("Deploy", false), ("Tests pass", true), ("Reviewed", false)]
+        );
+
+        let (criteria, checked) = subtasks_to_criteria(&card);
+        assert_eq!(criteria, ["Tests pass", "Docs updated", "Reviewed", "Deploy"]);
+        assert_eq!(checked, [0, 1]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Background daemon holding warm HTTP connections.
+//!
+//! The daemon listens on a Unix socket in a directory only its owner can
+//! enter (~/.config/blaze/daemon) and performs requests on behalf of
+//! short-lived CLI invocations, so each command reuses pooled (already
+//! TLS-negotiated) connections instead of opening new ones.
+//!
+//! Protocol: one JSON request line per connection, answered by one JSON line.
+
+use crate::client::Client;
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that disables delegation to a running daemon
+pub const NO_DAEMON_ENV: &str = "BLAZE_NO_DAEMON";
+
+/// An HTTP request as forwarded to the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// The caller held back a token saved for another server, so the daemon mustn't send its own
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub token_withheld: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+/// An HTTP response as returned by the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawResponse {
+    pub status: u16,
+    pub body: String,
+    /// `rel="next"` target from the Link header, for paginated collections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_link: Option<String>,
+}
+
+/// Messages sent to the daemon
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    Http(RawRequest),
+    Status,
+    Shutdown,
+}
+
+/// Messages returned by the daemon
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum DaemonResponse {
+    Http(RawResponse),
+    Status(DaemonStatus),
+    Error { message: String },
+    ShuttingDown,
+}
+
+/// Daemon runtime information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub url: String,
+    pub socket: PathBuf,
+    pub started_at: DateTime<Utc>,
+    pub requests: u64,
+}
+
+/// Get the daemon socket path (~/.config/blaze/daemon/daemon.sock)
+pub fn socket_path() -> Option<PathBuf> {
+    Config::dir().map(|d| d.join("daemon").join("daemon.sock"))
+}
+
+/// Socket to delegate to, if a daemon appears to be running and delegation is enabled
+pub fn active_socket() -> Option<PathBuf> {
+    if !cfg!(unix) || std::env::var_os(NO_DAEMON_ENV).is_some() {
+        return None;
+    }
+    socket_path().filter(|p| p.exists())
+}
+
+/// Forward a request to the daemon.
+///
+/// Returns `Ok(None)` if the daemon could not be reached, so callers can
+/// fall back to a direct request.
+pub async fn forward(socket: &Path, req: &RawRequest) -> Result<Option<RawResponse>> {
+    match call(socket, &DaemonRequest::Http(req.clone())).await {
+        Ok(DaemonResponse::Http(resp)) => Ok(Some(resp)),
+        Ok(DaemonResponse::Error { message }) => Err(BlazeError::Daemon(message)),
+        Ok(other) => Err(BlazeError::Daemon(format!("Unexpected reply: {:?}", other))),
+        Err(BlazeError::Io(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Send a single message to the daemon and wait for the reply
+#[cfg(unix)]
+pub async fn call(socket: &Path, req: &DaemonRequest) -> Result<DaemonResponse> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    let stream = UnixStream::connect(socket).await?;
+    let (read, mut write) = stream.into_split();
+
+    let mut line = serde_json::to_string(req)?;
+    line.push('\n');
+    write.write_all(line.as_bytes()).await?;
+    write.shutdown().await?;
+
+    let mut reply = String::new();
+    BufReader::new(read).read_line(&mut reply).await?;
+    if reply.is_empty() {
+        return Err(BlazeError::Daemon("Connection closed without a reply".into()));
+    }
+    Ok(serde_json::from_str(&reply)?)
+}
+
+#[cfg(not(unix))]
+pub async fn call(_socket: &Path, _req: &DaemonRequest) -> Result<DaemonResponse> {
+    Err(BlazeError::Daemon("The daemon is only supported on Unix platforms".into()))
+}
+
+/// Run the daemon in the foreground until it receives a shutdown request
+#[cfg(unix)]
+pub async fn serve(socket: &Path, url: &str, token: Option<String>) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+    use tokio::sync::Notify;
+
+    // Requests carry the API token, so only the owner may connect: the
+    // socket's directory is private before the socket exists
+    if let Some(dir) = socket.parent() {
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    // Remove a socket left behind by a daemon that didn't exit cleanly
+    if socket.exists() {
+        std::fs::remove_file(socket)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+
+    let client = Arc::new(Client::direct(url, token)?);
+    let shutdown = Arc::new(Notify::new());
+    let requests = Arc::new(AtomicU64::new(0));
+    let status = DaemonStatus {
+        pid: std::process::id(),
+        url: url.to_string(),
+        socket: socket.to_path_buf(),
+        started_at: Utc::now(),
+        requests: 0,
+    };
+
+    loop {
+        let (stream, _) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = shutdown.notified() => break,
+        };
+
+        let client = Arc::clone(&client);
+        let shutdown = Arc::clone(&shutdown);
+        let requests = Arc::clone(&requests);
+        let mut status = status.clone();
+
+        tokio::spawn(async move {
+            let (read, mut write) = stream.into_split();
+            let mut line = String::new();
+            if BufReader::new(read).read_line(&mut line).await.is_err() {
+                return;
+            }
+
+            let reply = match serde_json::from_str::<DaemonRequest>(&line) {
+                Ok(DaemonRequest::Http(req)) => {
+                    requests.fetch_add(1, Ordering::Relaxed);
+                    match client.execute(req).await {
+                        Ok(resp) => DaemonResponse::Http(resp),
+                        Err(e) => DaemonResponse::Error {
+                            message: e.to_string(),
+                        },
+                    }
+                }
+                Ok(DaemonRequest::Status) => {
+                    status.requests = requests.load(Ordering::Relaxed);
+                    DaemonResponse::Status(status)
+                }
+                Ok(DaemonRequest::Shutdown) => {
+                    shutdown.notify_one();
+                    DaemonResponse::ShuttingDown
+                }
+                Err(e) => DaemonResponse::Error {
+                    message: format!("Malformed request: {}", e),
+                },
+            };
+
+            if let Ok(mut out) = serde_json::to_string(&reply) {
+                out.push('\n');
+                let _ = write.write_all(out.as_bytes()).await;
+            }
+        });
+    }
+
+    let _ = std::fs::remove_file(socket);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn serve(_socket: &Path, _url: &str, _token: Option<String>) -> Result<()> {
+    Err(BlazeError::Daemon("The daemon is only supported on Unix platforms".into()))
+}
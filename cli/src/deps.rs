@@ -0,0 +1,326 @@
+//! Card dependencies (`blocked_by`), readiness, and critical path scheduling.
+//!
+//! A card is ready once every card it is blocked by is done. Blockers that no
+//! longer exist (deleted cards) don't hold anything back.
+
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Column};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Slack below this counts as zero (estimates are floats)
+const EPSILON: f64 = 1e-9;
+
+/// Lookup of cards by ID for resolving dependencies
+pub struct DependencyIndex<'a> {
+    cards: HashMap<&'a str, &'a Card>,
+}
+
+impl<'a> DependencyIndex<'a> {
+    /// Build an index; pass archived cards too so archived-but-done blockers resolve
+    pub fn new(cards: &'a [Card]) -> Self {
+        Self {
+            cards: cards.iter().map(|c| (c.id.as_str(), c)).collect(),
+        }
+    }
+
+    /// Blockers of `card` that are not done yet
+    pub fn open_blockers(&self, card: &Card) -> Vec<&'a Card> {
+        card.blocked_by
+            .iter()
+            .filter_map(|id| self.cards.get(id.as_str()).copied())
+            .filter(|blocker| blocker.column != Column::Done)
+            .collect()
+    }
+
+    /// Whether nothing upstream is holding `card` back
+    pub fn is_unblocked(&self, card: &Card) -> bool {
+        self.open_blockers(card).is_empty()
+    }
+
+    /// Cards directly blocked by `card`, by ID
+    pub fn blocks(&self, card: &Card) -> Vec<&'a Card> {
+        let mut blocked: Vec<&Card> =
+            self.cards.values().copied().filter(|c| c.blocked_by.contains(&card.id)).collect();
+        blocked.sort_by(|a, b| a.id.cmp(&b.id));
+        blocked
+    }
+
+    /// Whether `card_id` waits on `blocker_id`, directly or through other cards
+    pub fn depends_on(&self, card_id: &str, blocker_id: &str) -> bool {
+        let mut pending = vec![card_id];
+        let mut seen = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if id == blocker_id {
+                return true;
+            }
+            if seen.insert(id) {
+                if let Some(card) = self.cards.get(id) {
+                    pending.extend(card.blocked_by.iter().map(String::as_str));
+                }
+            }
+        }
+        false
+    }
+
+    /// Explain why `card` is held back
+    pub fn held_back(&self, card: &Card) -> HeldBack {
+        HeldBack {
+            id: card.id.clone(),
+            title: card.title.clone(),
+            blocked_by: self
+                .open_blockers(card)
+                .into_iter()
+                .map(|b| BlockerRef {
+                    id: b.id.clone(),
+                    title: b.title.clone(),
+                    column: b.column.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A card waiting on unfinished upstream cards
+#[derive(Debug, Serialize)]
+pub struct HeldBack {
+    pub id: String,
+    pub title: String,
+    pub blocked_by: Vec<BlockerRef>,
+}
+
+/// An unfinished upstream card
+#[derive(Debug, Serialize)]
+pub struct BlockerRef {
+    pub id: String,
+    pub title: String,
+    pub column: Column,
+}
+
+/// Timing of one unfinished card in a schedule
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledCard {
+    pub id: String,
+    pub title: String,
+    pub column: Column,
+    pub estimate: f64,
+    /// Earliest start, counted from now
+    pub start: f64,
+    /// Earliest finish, counted from now
+    pub finish: f64,
+    /// How much the card can slip without delaying completion
+    pub slack: f64,
+}
+
+/// Result of critical path analysis over the unfinished cards
+#[derive(Debug, Serialize)]
+pub struct Schedule {
+    /// Length of the longest dependency chain
+    pub total: f64,
+    /// Cards on the longest chain, in the order they must be done
+    pub critical_path: Vec<ScheduledCard>,
+    /// Every unfinished card, least slack first
+    pub cards: Vec<ScheduledCard>,
+}
+
+/// Compute the critical path of the unfinished cards.
+///
+/// Cards without an estimate count as `default_estimate`. Done cards are
+/// finished work and drop out of the graph.
+pub fn critical_path(cards: &[Card], default_estimate: f64) -> Result<Schedule> {
+    let open: Vec<&Card> = cards.iter().filter(|c| c.column != Column::Done).collect();
+    let position: HashMap<&str, usize> = open.iter().enumerate().map(|(i, c)| (c.id.as_str(), i)).collect();
+
+    let preds: Vec<Vec<usize>> = open
+        .iter()
+        .map(|c| c.blocked_by.iter().filter_map(|id| position.get(id.as_str()).copied()).collect())
+        .collect();
+    let mut succs: Vec<Vec<usize>> = vec![Vec::new(); open.len()];
+    for (i, ps) in preds.iter().enumerate() {
+        for &p in ps {
+            succs[p].push(i);
+        }
+    }
+
+    let order = topological_order(&preds, &succs).map_err(|cycle| {
+        let ids: Vec<&str> = cycle.iter().map(|&i| open[i].id.as_str()).collect();
+        BlazeError::InvalidInput(format!("Dependency cycle: {}", ids.join(" -> ")))
+    })?;
+
+    let estimate: Vec<f64> = open.iter().map(|c| c.estimate.unwrap_or(default_estimate)).collect();
+
+    // Forward pass: earliest finish
+    let mut finish = vec![0.0; open.len()];
+    for &i in &order {
+        let start = preds[i].iter().map(|&p| finish[p]).fold(0.0, f64::max);
+        finish[i] = start + estimate[i];
+    }
+    let total = finish.iter().copied().fold(0.0, f64::max);
+
+    // Backward pass: latest finish
+    let mut latest = vec![total; open.len()];
+    for &i in order.iter().rev() {
+        if let Some(min) = succs[i].iter().map(|&s| latest[s] - estimate[s]).reduce(f64::min) {
+            latest[i] = min;
+        }
+    }
+
+    let scheduled = |i: usize| ScheduledCard {
+        id: open[i].id.clone(),
+        title: open[i].title.clone(),
+        column: open[i].column.clone(),
+        estimate: estimate[i],
+        start: finish[i] - estimate[i],
+        finish: finish[i],
+        slack: clamp_zero(latest[i] - finish[i]),
+    };
+
+    // Walk back from the last card to finish along predecessors that end exactly when it starts
+    let mut path = Vec::new();
+    let mut current = order.iter().copied().find(|&i| (finish[i] - total).abs() < EPSILON);
+    while let Some(i) = current {
+        path.push(scheduled(i));
+        let start = finish[i] - estimate[i];
+        current = preds[i].iter().copied().find(|&p| (finish[p] - start).abs() < EPSILON);
+    }
+    path.reverse();
+
+    let mut all: Vec<ScheduledCard> = order.iter().map(|&i| scheduled(i)).collect();
+    all.sort_by(|a, b| a.slack.total_cmp(&b.slack).then(a.start.total_cmp(&b.start)));
+
+    Ok(Schedule {
+        total,
+        critical_path: path,
+        cards: all,
+    })
+}
+
+fn clamp_zero(value: f64) -> f64 {
+    if value.abs() < EPSILON {
+        0.0
+    } else {
+        value
+    }
+}
+
+/// Kahn's algorithm; on failure returns one cycle (first node repeated at the end)
+pub fn topological_order(preds: &[Vec<usize>], succs: &[Vec<usize>]) -> std::result::Result<Vec<usize>, Vec<usize>> {
+    let mut remaining: Vec<usize> = preds.iter().map(Vec::len).collect();
+    let mut queue: Vec<usize> = (0..preds.len()).filter(|&i| remaining[i] == 0).collect();
+    let mut order = Vec::with_capacity(preds.len());
+
+    while let Some(i) = queue.pop() {
+        order.push(i);
+        for &s in &succs[i] {
+            remaining[s] -= 1;
+            if remaining[s] == 0 {
+                queue.push(s);
+            }
+        }
+    }
+
+    if order.len() == preds.len() {
+        return Ok(order);
+    }
+
+    // Every unprocessed node has an unprocessed predecessor, so following them must loop
+    let mut seen: Vec<Option<usize>> = vec![None; preds.len()];
+    let mut walk = Vec::new();
+    let mut node = (0..preds.len()).find(|&i| remaining[i] > 0).unwrap();
+    loop {
+        if let Some(at) = seen[node] {
+            let mut cycle: Vec<usize> = walk[at..].to_vec();
+            cycle.reverse();
+            cycle.push(cycle[0]);
+            return Err(cycle);
+        }
+        seen[node] = Some(walk.len());
+        walk.push(node);
+        node = preds[node].iter().copied().find(|&p| remaining[p] > 0).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, column: Column, blocked_by: &[&str]) -> Card {
+        estimated(id, column, blocked_by, None)
+    }
+
+    fn estimated(id: &str, column: Column, blocked_by: &[&str], estimate: Option<f64>) -> Card {
+        testing::card(id, serde_json::json!({ "column": column, "blocked_by": blocked_by, "estimate": estimate }))
+    }
+
+    #[test]
+    fn only_unfinished_blockers_hold_back() {
+        let cards = vec![
+            card("a", Column::Done, &[]),
+            card("b", Column::InProgress, &[]),
+            card("c", Column::Todo, &["a"]),
+            card("d", Column::Todo, &["a", "b", "deleted"]),
+        ];
+        let index = DependencyIndex::new(&cards);
+
+        assert!(index.is_unblocked(&cards[2]));
+        assert!(!index.is_unblocked(&cards[3]));
+
+        let held = index.held_back(&cards[3]);
+        assert_eq!(held.blocked_by.len(), 1);
+        assert_eq!(held.blocked_by[0].id, "b");
+    }
+
+    #[test]
+    fn dependencies_in_both_directions() {
+        let cards = vec![
+            card("a", Column::Todo, &[]),
+            card("b", Column::Todo, &["a"]),
+            card("c", Column::Todo, &["b", "a"]),
+        ];
+        let index = DependencyIndex::new(&cards);
+
+        let blocked: Vec<&str> = index.blocks(&cards[0]).iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(blocked, ["b", "c"]);
+        // c waits on a through b; making a wait on c would be a cycle
+        assert!(index.depends_on("c", "a"));
+        assert!(!index.depends_on("a", "c"));
+    }
+
+    #[test]
+    fn critical_path_follows_longest_chain() {
+        // a(2) -> b(3) -> d(1); a -> c(1) -> d; e(1) independent; f done
+        let cards = vec![
+            estimated("a", Column::Todo, &[], Some(2.0)),
+            estimated("b", Column::Todo, &["a"], Some(3.0)),
+            estimated("c", Column::Todo, &["a", "f"], Some(1.0)),
+            estimated("d", Column::Todo, &["b", "c"], Some(1.0)),
+            estimated("e", Column::Todo, &[], None),
+            estimated("f", Column::Done, &[], Some(8.0)),
+        ];
+        let schedule = critical_path(&cards, 1.0).unwrap();
+
+        assert_eq!(schedule.total, 6.0);
+        let path: Vec<&str> = schedule.critical_path.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(path, ["a", "b", "d"]);
+
+        let slack: HashMap<&str, f64> = schedule.cards.iter().map(|c| (c.id.as_str(), c.slack)).collect();
+        assert_eq!(slack["a"], 0.0);
+        assert_eq!(slack["c"], 2.0);
+        assert_eq!(slack["e"], 5.0);
+        assert!(!slack.contains_key("f"));
+    }
+
+    #[test]
+    fn critical_path_reports_cycles() {
+        let cards = vec![
+            card("a", Column::Todo, &["c"]),
+            card("b", Column::Todo, &["a"]),
+            card("c", Column::Todo, &["b"]),
+            card("d", Column::Todo, &[]),
+        ];
+        let err = critical_path(&cards, 1.0).unwrap_err().to_string();
+        assert!(err.starts_with("Invalid input: Dependency cycle: "), "{err}");
+        assert_eq!(err.matches(" -> ").count(), 3);
+    }
+}
@@ -0,0 +1,77 @@
+//! Handing files to external programs: the user's `$VISUAL` / `$EDITOR`,
+//! and the OS default application.
+
+use crate::error::{BlazeError, Result};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Open `initial` in the user's editor and return the saved text.
+///
+/// `name` becomes part of the temporary file name so editors pick the right
+/// syntax highlighting (e.g. `overview.md`).
+pub fn edit_text(initial: &str, name: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".into() } else { "vi".into() });
+
+    let safe_name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    let path = std::env::temp_dir().join(format!("blaze-{}-{}", std::process::id(), safe_name));
+    fs::write(&path, initial)?;
+
+    // Run through the shell so EDITOR may carry arguments (e.g. "code --wait")
+    let status = if cfg!(windows) {
+        Command::new("cmd")
+            .args(["/C", &format!("{} \"{}\"", editor, path.display())])
+            .status()
+    } else {
+        Command::new("sh")
+            .args(["-c", &format!("{} \"$1\"", editor), "sh"])
+            .arg(&path)
+            .status()
+    };
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(BlazeError::from),
+        Ok(status) => Err(BlazeError::InvalidInput(format!(
+            "Editor '{}' exited with {}; changes discarded",
+            editor, status
+        ))),
+        Err(e) => Err(BlazeError::InvalidInput(format!("Failed to run editor '{}': {}", editor, e))),
+    };
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// Open a file with the OS default application
+pub fn open_path(path: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let program = format!("{:?}", command.get_program());
+    match command.arg(path).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(BlazeError::InvalidInput(format!(
+            "{} exited with {} while opening {}",
+            program,
+            status,
+            path.display()
+        ))),
+        Err(e) => Err(BlazeError::InvalidInput(format!(
+            "Failed to run {} (file is at {}): {}",
+            program,
+            path.display(),
+            e
+        ))),
+    }
+}
@@ -27,12 +27,64 @@ pub enum BlazeError {
     #[error("TOML parse error: {0}")]
     Toml(#[from] toml::de::Error),
 
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+
+    /// A mutating request was skipped because of `--dry-run`
+    #[error("Dry run: request not sent")]
+    DryRun,
+
+    /// A mutating request was queued for `blaze sync` because of `--offline`
+    #[error("Offline: request queued")]
+    Queued,
+
+    #[error("Local store error: {0}")]
+    Store(#[from] rusqlite::Error),
+
+    #[error("Replay error: {0}")]
+    Replay(String),
+
     #[error("Card not found: {0}")]
     NotFound(String),
 
+    #[error("Ambiguous card ID '{prefix}' matches: {candidates}")]
+    AmbiguousId { prefix: String, candidates: String },
+
+    #[error("Validation failed: {0}")]
+    Validation(#[from] crate::types::ValidationError),
+
+    /// Some cards in a multi-card command failed (details are in the printed summary)
+    #[error("{failed} of {total} cards failed")]
+    PartialFailure { failed: usize, total: usize },
+
+    #[error("Merge conflict: {0}")]
+    Conflict(String),
+
+    /// The server lacks an optional feature (see `blaze ping --capabilities`)
+    #[error("Server doesn't support {feature} (no {endpoint} endpoint)")]
+    Unsupported {
+        feature: &'static str,
+        endpoint: &'static str,
+    },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
     #[error("{0}")]
     Other(String),
 }
+
+impl BlazeError {
+    /// Older servers answer unknown routes with 404/405
+    pub fn is_endpoint_missing(&self) -> bool {
+        matches!(self, BlazeError::Api { status: 404 | 405, .. } | BlazeError::Unsupported { .. })
+    }
+
+    /// The server couldn't be reached at all (as opposed to answering with an error)
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, BlazeError::Http(e) if e.is_connect() || e.is_timeout())
+    }
+}
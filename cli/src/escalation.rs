@@ -0,0 +1,80 @@
+//! Priority raises and their reasons.
+//!
+//! `blaze edit --priority ... --reason ...` records every raise as a comment
+//! on the card ("Priority raised from high to urgent: ..."), so the reason
+//! stays with the card and any client shows it. `blaze report escalations`
+//! finds those comments again. Raising to urgent requires a reason unless
+//! `[escalation] require_reason = false`.
+
+use crate::types::{Card, Comment, Priority};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+const PREFIX: &str = "Priority raised from ";
+
+/// One recorded priority raise
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Escalation {
+    pub card_id: String,
+    pub title: String,
+    pub from: Priority,
+    pub to: Priority,
+    pub reason: String,
+    pub author: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Whether going from `from` to `to` is a raise
+pub fn is_raise(from: Priority, to: Priority) -> bool {
+    to as u8 > from as u8
+}
+
+/// The comment recording a raise
+pub fn comment_body(from: Priority, to: Priority, reason: &str) -> String {
+    format!("{}{} to {}: {}", PREFIX, from, to, reason.trim())
+}
+
+/// The raise a comment records, if it is one
+pub fn parse(card: &Card, comment: &Comment) -> Option<Escalation> {
+    let rest = comment.body.strip_prefix(PREFIX)?;
+    let (from, rest) = rest.split_once(" to ")?;
+    let (to, reason) = rest.split_once(": ")?;
+    Some(Escalation {
+        card_id: card.id.clone(),
+        title: card.title.clone(),
+        from: Priority::from_str(from, true).ok()?,
+        to: Priority::from_str(to, true).ok()?,
+        reason: reason.to_string(),
+        author: comment.author.clone(),
+        at: comment.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn recorded_raises_parse_back() {
+        let card = testing::card("c1", serde_json::json!({ "title": "Checkout fails", "priority": "urgent" }));
+        let comment = |body: String| Comment {
+            id: "m1".into(),
+            card_id: "c1".into(),
+            author: "dana".into(),
+            body,
+            created_at: card.created_at,
+        };
+
+        let body = comment_body(Priority::High, Priority::Urgent, " Customers can't pay: all regions ");
+        let escalation = parse(&card, &comment(body)).unwrap();
+        assert_eq!((escalation.from, escalation.to), (Priority::High, Priority::Urgent));
+        assert_eq!(escalation.reason, "Customers can't pay: all regions");
+        assert_eq!(escalation.author, "dana");
+
+        assert!(parse(&card, &comment("Priority raised from high, sadly".into())).is_none());
+        assert!(is_raise(Priority::Low, Priority::High));
+        assert!(!is_raise(Priority::Urgent, Priority::Urgent));
+    }
+}
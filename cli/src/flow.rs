@@ -0,0 +1,248 @@
+//! Flow analytics: cycle time, lead time, throughput and WIP over time.
+//!
+//! Worked out from card timestamps and `card.moved` events (see
+//! `history.rs`). A card is *started* when it first moves out of backlog or
+//! todo and *finished* when it last moved into done; lead time runs from
+//! creation to finish, cycle time from start to finish. A done card without a
+//! recorded move into done counts as finished at `entered_column_at` (or its
+//! last update) and has no cycle time. WIP at a point in time is the number
+//! of cards started but not yet finished then, so it needs events too.
+
+use crate::metrics::Every;
+use crate::types::{BoardEvent, Card, Column};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One card's way across the board
+#[derive(Debug, Clone, Serialize)]
+pub struct Flow {
+    pub id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl Flow {
+    /// Creation to finish, in days
+    pub fn lead_days(&self) -> Option<f64> {
+        self.finished_at.map(|f| days(f - self.created_at))
+    }
+
+    /// Start to finish, in days
+    pub fn cycle_days(&self) -> Option<f64> {
+        Some(days(self.finished_at? - self.started_at?))
+    }
+
+    fn finished_within(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        self.finished_at.is_some_and(|f| from <= f && f < to)
+    }
+
+    fn in_progress_at(&self, at: DateTime<Utc>) -> bool {
+        self.started_at.is_some_and(|s| s <= at) && self.finished_at.is_none_or(|f| f > at)
+    }
+}
+
+/// Spread of a set of durations, in days
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Durations {
+    pub cards: usize,
+    pub median: f64,
+    pub p85: f64,
+    pub mean: f64,
+}
+
+impl Durations {
+    /// `None` without any durations
+    pub fn of(mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        // Nearest rank: the smallest value at least `p` of them don't exceed
+        let percentile = |p: f64| values[((p * values.len() as f64).ceil() as usize).max(1) - 1];
+        Some(Self {
+            cards: values.len(),
+            median: percentile(0.5),
+            p85: percentile(0.85),
+            mean: round(values.iter().sum::<f64>() / values.len() as f64),
+        })
+    }
+}
+
+/// One calendar week of the window
+#[derive(Debug, Clone, Serialize)]
+pub struct Week {
+    /// ISO week, e.g. 2026-W41
+    pub week: String,
+    /// Cards finished in the week
+    pub throughput: usize,
+    /// Cards in progress at the end of the week (or of the window); `None` without events
+    pub wip: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_lead_days: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_cycle_days: Option<f64>,
+}
+
+/// Flow metrics of the cards finished within a window
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowReport {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub finished: usize,
+    pub lead_time: Option<Durations>,
+    pub cycle_time: Option<Durations>,
+    pub weeks: Vec<Week>,
+}
+
+/// Every card's flow; `events` are the board's, or `None` if the server keeps none
+pub fn flows(cards: &[Card], events: Option<&[BoardEvent]>) -> Vec<Flow> {
+    let mut moves: HashMap<&str, Vec<(DateTime<Utc>, Column)>> = HashMap::new();
+    for event in events.unwrap_or_default().iter().filter(|e| e.kind == "card.moved") {
+        let to = event.data.get("to").and_then(|v| v.as_str());
+        let (Some(card_id), Some(to)) = (event.card_id.as_deref(), to) else {
+            continue;
+        };
+        moves.entry(card_id).or_default().push((event.timestamp, Column::from(to.to_string())));
+    }
+
+    cards
+        .iter()
+        .map(|card| {
+            let own = moves.get(card.id.as_str()).map(Vec::as_slice).unwrap_or_default();
+            let finished_at = (card.column == Column::Done).then(|| {
+                own.iter()
+                    .filter(|(_, to)| *to == Column::Done)
+                    .map(|(at, _)| *at)
+                    .max()
+                    .unwrap_or(card.entered_column_at.unwrap_or(card.updated_at))
+            });
+            let started_at = own
+                .iter()
+                .filter(|(_, to)| !matches!(to, Column::Backlog | Column::Todo))
+                .map(|(at, _)| *at)
+                .min()
+                // Clocks disagreeing can't make a card finish before it started
+                .filter(|start| finished_at.is_none_or(|finish| *start <= finish));
+            Flow {
+                id: card.id.clone(),
+                title: card.title.clone(),
+                created_at: card.created_at,
+                started_at,
+                finished_at,
+            }
+        })
+        .collect()
+}
+
+/// Flow metrics for `[since, until)`, week by week in `tz`; `history` says
+/// whether there were events to tell when cards started
+pub fn report(flows: &[Flow], since: DateTime<Utc>, until: DateTime<Utc>, tz: Tz, history: bool) -> FlowReport {
+    let finished: Vec<&Flow> = flows.iter().filter(|f| f.finished_within(since, until)).collect();
+    let median = |cards: &[&Flow], days: fn(&Flow) -> Option<f64>| {
+        Durations::of(cards.iter().filter_map(|f| days(f)).collect()).map(|d| d.median)
+    };
+
+    let mut weeks = Vec::new();
+    let mut start = week_start(since, tz);
+    while start < until {
+        let end = week_start(start + Duration::days(8), tz);
+        let (from, to) = (start.max(since), end.min(until));
+        let done: Vec<&Flow> = finished.iter().copied().filter(|f| f.finished_within(from, to)).collect();
+        weeks.push(Week {
+            week: Every::Week.period(start, tz),
+            throughput: done.len(),
+            wip: history.then(|| flows.iter().filter(|f| f.in_progress_at(to)).count()),
+            median_lead_days: median(&done, Flow::lead_days),
+            median_cycle_days: median(&done, Flow::cycle_days),
+        });
+        start = end;
+    }
+
+    FlowReport {
+        since,
+        until,
+        finished: finished.len(),
+        lead_time: Durations::of(finished.iter().filter_map(|f| f.lead_days()).collect()),
+        cycle_time: Durations::of(finished.iter().filter_map(|f| f.cycle_days()).collect()),
+        weeks,
+    }
+}
+
+/// Monday 00:00 in `tz` of the week `at` falls in
+fn week_start(at: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+    let date = at.with_timezone(&tz).date_naive();
+    let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+    tz.from_local_datetime(&monday.and_time(NaiveTime::MIN))
+        .earliest()
+        // A midnight skipped by DST: read as UTC, a few hours off at most
+        .unwrap_or_else(|| tz.from_utc_datetime(&monday.and_time(NaiveTime::MIN)))
+        .with_timezone(&Utc)
+}
+
+fn days(duration: Duration) -> f64 {
+    round(duration.num_seconds() as f64 / 86_400.0)
+}
+
+/// Two decimals are plenty for days
+fn round(days: f64) -> f64 {
+    (days * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::json;
+
+    fn card(id: &str, column: &str, created: &str) -> Card {
+        testing::card(id, json!({ "column": column, "created_at": created, "updated_at": "2026-03-20T09:00:00Z" }))
+    }
+
+    fn moved(card_id: &str, at: &str, to: &str) -> BoardEvent {
+        let event = json!({ "type": "card.moved", "card_id": card_id, "timestamp": at, "data": { "to": to } });
+        serde_json::from_value(event).unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn cycle_and_lead_times_from_moves() {
+        let cards = [
+            card("a", "done", "2026-03-02T09:00:00Z"),
+            card("b", "in_progress", "2026-03-02T09:00:00Z"),
+            card("c", "done", "2026-03-01T09:00:00Z"),
+        ];
+        let events = [
+            moved("a", "2026-03-03T09:00:00Z", "in_progress"),
+            moved("a", "2026-03-04T09:00:00Z", "review"),
+            moved("a", "2026-03-05T09:00:00Z", "done"),
+            moved("b", "2026-03-06T09:00:00Z", "in_progress"),
+        ];
+        let flows = flows(&cards, Some(&events));
+        assert_eq!((flows[0].lead_days(), flows[0].cycle_days()), (Some(3.0), Some(2.0)));
+        assert_eq!((flows[1].started_at, flows[1].finished_at), (Some(at("2026-03-06T09:00:00Z")), None));
+        // Never seen moving: finished at its last update, no cycle time
+        assert_eq!((flows[2].lead_days(), flows[2].cycle_days()), (Some(19.0), None));
+
+        let report = report(&flows, at("2026-03-02T00:00:00Z"), at("2026-03-16T00:00:00Z"), Tz::UTC, true);
+        assert_eq!(report.finished, 1);
+        assert_eq!(report.cycle_time.as_ref().map(|d| d.median), Some(2.0));
+        let weeks: Vec<(&str, usize, Option<usize>)> =
+            report.weeks.iter().map(|w| (w.week.as_str(), w.throughput, w.wip)).collect();
+        assert_eq!(weeks, [("2026-W10", 1, Some(1)), ("2026-W11", 0, Some(1))]);
+    }
+
+    #[test]
+    fn percentiles_by_nearest_rank() {
+        let spread = Durations::of((1..=10).map(f64::from).collect()).unwrap();
+        assert_eq!((spread.median, spread.p85, spread.mean), (5.0, 9.0, 5.5));
+        assert_eq!(Durations::of(vec![4.0]).map(|d| d.p85), Some(4.0));
+        assert_eq!(Durations::of(Vec::new()), None);
+    }
+}
@@ -0,0 +1,725 @@
+//! Two-way sync with a GitHub Projects (v2) board.
+//!
+//! Cards and project items are linked one to one. Every run compares both
+//! sides with how they looked after the previous run (kept per server and
+//! project in ~/.config/blaze/github-sync.json): whatever changed on one side
+//! is copied to the other, and when both changed the [`ConflictPolicy`]
+//! decides. Titles and the column (the project's single-select `Status`
+//! field) are synced. New items become cards, new cards become draft issues,
+//! and an unlinked card and item with the same title are linked instead.
+//! Deleting or archiving either side only unlinks the pair; the other side
+//! is left out of later syncs rather than recreated.
+//!
+//! Settings live in `[github]` in config.toml (see README.md).
+
+use crate::auth;
+use crate::client;
+use crate::config::{Config, ConnectionConfig, GithubConfig};
+use crate::error::{BlazeError, Result};
+use crate::parsers::Query;
+use crate::types::{Card, Column};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+
+const API_URL: &str = "https://api.github.com/graphql";
+const STATUS_FIELD: &str = "Status";
+
+/// Project status for each column unless `[github.columns]` says otherwise
+const DEFAULT_STATUSES: [(Column, &str); 5] = [
+    (Column::Backlog, "Backlog"),
+    (Column::Todo, "Todo"),
+    (Column::InProgress, "In Progress"),
+    (Column::Review, "In Review"),
+    (Column::Done, "Done"),
+];
+
+/// Who wins when a card and its item both changed since the last sync
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Whichever side was updated last
+    #[default]
+    Newest,
+    /// The Blaze card
+    Blaze,
+    /// The GitHub item
+    Github,
+    /// Neither; report the conflict and try again next time
+    Skip,
+}
+
+/// Blaze columns and the project statuses they stand for
+#[derive(Debug, Clone)]
+pub struct StatusMap(Vec<(Column, String)>);
+
+impl StatusMap {
+    /// The defaults with `[github.columns]` applied
+    pub fn from_config(columns: &BTreeMap<String, String>) -> Result<Self> {
+        let mut map: Vec<(Column, String)> = DEFAULT_STATUSES.iter().map(|(c, s)| (c.clone(), s.to_string())).collect();
+        for (name, status) in columns {
+            let column: Column = name
+                .parse()
+                .map_err(|e| BlazeError::Config(format!("[github.columns]: {}", e)))?;
+            // Columns beyond the defaults (on servers that have them) get a status too
+            match map.iter_mut().find(|(c, _)| *c == column) {
+                Some(entry) => entry.1 = status.clone(),
+                None => map.push((column, status.clone())),
+            }
+        }
+        Ok(Self(map))
+    }
+
+    /// Project status for a column; columns without one use their display name
+    pub fn status(&self, column: &Column) -> String {
+        match self.0.iter().find(|(c, _)| c == column) {
+            Some((_, status)) => status.clone(),
+            None => column.display_name().into_owned(),
+        }
+    }
+
+    /// Column for a status (case-insensitive); none for statuses no column maps to
+    pub fn column(&self, status: &str) -> Option<Column> {
+        self.0.iter().find(|(_, s)| s.eq_ignore_ascii_case(status)).map(|(c, _)| c.clone())
+    }
+}
+
+/// What a project item holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Draft,
+    Issue,
+    PullRequest,
+}
+
+/// One project item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub id: String,
+    pub kind: ContentKind,
+    /// Node ID of the draft, issue or pull request (for title changes)
+    pub content_id: String,
+    pub title: String,
+    pub status: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A project with its status field and (unarchived) items
+#[derive(Debug)]
+pub struct Project {
+    pub id: String,
+    pub title: String,
+    status_field: String,
+    /// Status option IDs by name
+    options: Vec<(String, String)>,
+    pub items: Vec<Item>,
+}
+
+impl Project {
+    fn option_id(&self, status: &str) -> Result<&str> {
+        self.options
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(status))
+            .map(|(id, _)| id.as_str())
+            .ok_or_else(|| BlazeError::Config(format!("Project '{}' has no status '{}'", self.title, status)))
+    }
+}
+
+/// GitHub GraphQL API client
+pub struct GithubClient {
+    http: reqwest::Client,
+    url: String,
+    token: String,
+    status_field: String,
+}
+
+impl GithubClient {
+    /// Client with the token from `[github.auth]`, else `GITHUB_TOKEN` or `GH_TOKEN`,
+    /// going through `connection`'s proxy and CA
+    pub async fn from_config(config: &GithubConfig, connection: &ConnectionConfig) -> Result<Self> {
+        let mut token = None;
+        if config.auth.has_provider() {
+            token = auth::github_provider(&config.auth, connection)?.token().await?;
+        }
+        let token = token
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .or_else(|| std::env::var("GH_TOKEN").ok())
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| BlazeError::Auth("No GitHub token; set GITHUB_TOKEN or [github.auth] in config.toml".into()))?;
+        let http = client::outside_client(connection)?;
+        Ok(Self {
+            http,
+            url: config.api_url.clone().unwrap_or_else(|| API_URL.to_string()),
+            token,
+            status_field: config.status_field.clone().unwrap_or_else(|| STATUS_FIELD.to_string()),
+        })
+    }
+
+    async fn query(&self, query: &str, variables: Value) -> Result<Value> {
+        let response = self
+            .http
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BlazeError::Auth("GitHub rejected the token".into()));
+        }
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(BlazeError::Api {
+                status: status.as_u16(),
+                message: format!("GitHub: {}", message),
+            });
+        }
+        let mut body: Value = response.json().await?;
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            return Err(BlazeError::Other(format!("GitHub: {}", messages.join("; "))));
+        }
+        Ok(body["data"].take())
+    }
+
+    /// Fetch a project by node ID (`PVT_...`), following item pages
+    pub async fn project(&self, id: &str) -> Result<Project> {
+        const QUERY: &str = "query($id: ID!, $field: String!, $after: String) {
+          node(id: $id) {
+            ... on ProjectV2 {
+              title
+              field(name: $field) { ... on ProjectV2SingleSelectField { id options { id name } } }
+              items(first: 100, after: $after) {
+                pageInfo { hasNextPage endCursor }
+                nodes {
+                  id updatedAt isArchived
+                  fieldValueByName(name: $field) { ... on ProjectV2ItemFieldSingleSelectValue { name } }
+                  content {
+                    __typename
+                    ... on DraftIssue { id title }
+                    ... on Issue { id title }
+                    ... on PullRequest { id title }
+                  }
+                }
+              }
+            }
+          }
+        }";
+
+        let mut project: Option<Project> = None;
+        let mut after: Option<String> = None;
+        loop {
+            let variables = json!({ "id": id, "field": self.status_field, "after": after });
+            let data = self.query(QUERY, variables).await?;
+            let node: ProjectNode = match data["node"] {
+                Value::Null => return Err(BlazeError::Other(format!("No GitHub project {}", id))),
+                ref node => serde_json::from_value(node.clone())
+                    .map_err(|_| BlazeError::InvalidInput(format!("{} is not a GitHub project (v2)", id)))?,
+            };
+            let field = node.field.filter(|f| f.id.is_some()).ok_or_else(|| {
+                BlazeError::Config(format!("Project '{}' has no single-select field '{}'", node.title, self.status_field))
+            })?;
+            let project = project.get_or_insert_with(|| Project {
+                id: id.to_string(),
+                title: node.title.clone(),
+                status_field: field.id.clone().unwrap_or_default(),
+                options: field.options.iter().map(|o| (o.id.clone(), o.name.clone())).collect(),
+                items: Vec::new(),
+            });
+            project.items.extend(node.items.nodes.into_iter().filter_map(ItemNode::into_item));
+            if !node.items.page_info.has_next_page {
+                break;
+            }
+            after = node.items.page_info.end_cursor;
+        }
+        Ok(project.expect("the loop runs at least once"))
+    }
+
+    /// Add a draft issue to the project; returns the new item
+    pub async fn add_draft(&self, project: &Project, title: &str) -> Result<Item> {
+        const MUTATION: &str = "mutation($project: ID!, $title: String!) {
+          addProjectV2DraftIssue(input: {projectId: $project, title: $title}) {
+            projectItem { id updatedAt content { ... on DraftIssue { id } } }
+          }
+        }";
+        let data = self.query(MUTATION, json!({ "project": project.id, "title": title })).await?;
+        let item = &data["addProjectV2DraftIssue"]["projectItem"];
+        let id = item["id"].as_str().ok_or_else(|| BlazeError::Other("GitHub returned no item".into()))?;
+        Ok(Item {
+            id: id.to_string(),
+            kind: ContentKind::Draft,
+            content_id: item["content"]["id"].as_str().unwrap_or_default().to_string(),
+            title: title.to_string(),
+            status: None,
+            updated_at: item["updatedAt"].as_str().and_then(|t| t.parse().ok()).unwrap_or_else(Utc::now),
+        })
+    }
+
+    /// Set an item's status
+    pub async fn set_status(&self, project: &Project, item: &str, status: &str) -> Result<()> {
+        const MUTATION: &str = "mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) {
+          updateProjectV2ItemFieldValue(input: {
+            projectId: $project, itemId: $item, fieldId: $field, value: {singleSelectOptionId: $option}
+          }) { projectV2Item { id } }
+        }";
+        let variables = json!({
+            "project": project.id,
+            "item": item,
+            "field": project.status_field,
+            "option": project.option_id(status)?,
+        });
+        self.query(MUTATION, variables).await?;
+        Ok(())
+    }
+
+    /// Retitle the draft, issue or pull request behind an item
+    pub async fn set_title(&self, item: &Item, title: &str) -> Result<()> {
+        let mutation = match item.kind {
+            ContentKind::Draft => {
+                "mutation($id: ID!, $title: String!) {
+                  updateProjectV2DraftIssue(input: {draftIssueId: $id, title: $title}) { draftIssue { id } }
+                }"
+            }
+            ContentKind::Issue => {
+                "mutation($id: ID!, $title: String!) {
+                  updateIssue(input: {id: $id, title: $title}) { issue { id } }
+                }"
+            }
+            ContentKind::PullRequest => {
+                "mutation($id: ID!, $title: String!) {
+                  updatePullRequest(input: {pullRequestId: $id, title: $title}) { pullRequest { id } }
+                }"
+            }
+        };
+        self.query(mutation, json!({ "id": item.content_id, "title": title })).await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct ProjectNode {
+    title: String,
+    field: Option<FieldNode>,
+    items: ItemPage,
+}
+
+#[derive(Deserialize)]
+struct FieldNode {
+    id: Option<String>,
+    #[serde(default)]
+    options: Vec<OptionNode>,
+}
+
+#[derive(Deserialize)]
+struct OptionNode {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemPage {
+    page_info: PageInfo,
+    nodes: Vec<ItemNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemNode {
+    id: String,
+    updated_at: DateTime<Utc>,
+    is_archived: bool,
+    field_value_by_name: Option<StatusValue>,
+    content: Option<ContentNode>,
+}
+
+#[derive(Deserialize)]
+struct StatusValue {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ContentNode {
+    #[serde(rename = "__typename")]
+    typename: String,
+    id: Option<String>,
+    title: Option<String>,
+}
+
+impl ItemNode {
+    /// The item, unless it's archived or its content can't be seen
+    fn into_item(self) -> Option<Item> {
+        let content = self.content.filter(|_| !self.is_archived)?;
+        let kind = match content.typename.as_str() {
+            "DraftIssue" => ContentKind::Draft,
+            "Issue" => ContentKind::Issue,
+            "PullRequest" => ContentKind::PullRequest,
+            _ => return None,
+        };
+        Some(Item {
+            id: self.id,
+            kind,
+            content_id: content.id?,
+            title: content.title?,
+            status: self.field_value_by_name.and_then(|v| v.name),
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// A linked card and item as they were after the last sync
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub card: String,
+    pub card_title: String,
+    pub card_column: Column,
+    pub item_title: String,
+    pub item_status: Option<String>,
+}
+
+impl Link {
+    pub fn new(card: &Card, item: &Item) -> Self {
+        Self {
+            card: card.id.clone(),
+            card_title: card.title.clone(),
+            card_column: card.column.clone(),
+            item_title: item.title.clone(),
+            item_status: item.status.clone(),
+        }
+    }
+}
+
+/// What one server and project have been through
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectState {
+    /// Links by item ID
+    #[serde(default)]
+    pub links: BTreeMap<String, Link>,
+    /// Cards and items whose counterpart went away; they're not synced again
+    #[serde(default)]
+    pub detached: BTreeSet<String>,
+}
+
+/// Sync state for every server and project
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    #[serde(default)]
+    projects: BTreeMap<String, ProjectState>,
+}
+
+impl SyncState {
+    const FILE: &'static str = "github-sync.json";
+
+    pub fn load() -> Result<Self> {
+        match Config::dir().map(|d| d.join(Self::FILE)) {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(Self::FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// State of `project` on `server` (a profile name or URL)
+    pub fn project(&mut self, server: &str, project: &str) -> &mut ProjectState {
+        self.projects.entry(format!("{} {}", server, project)).or_default()
+    }
+}
+
+/// One thing a sync does
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Link an unlinked card and item with the same title
+    Link { card: String, item: String },
+    /// Make a card for a new item
+    CreateCard { item: String, title: String, column: Column },
+    /// Make a draft issue for a new card
+    CreateItem { card: String, title: String, status: String },
+    /// Copy the card's title and/or column to the item
+    Push {
+        card: String,
+        item: String,
+        title: Option<String>,
+        status: Option<String>,
+    },
+    /// Copy the item's title and/or status to the card
+    Pull {
+        card: String,
+        item: String,
+        title: Option<String>,
+        column: Option<Column>,
+    },
+    /// Both sides changed and the policy is `skip`
+    Conflict { card: String, item: String },
+    /// The card or the item is gone; the other one is left alone
+    Unlink { card: String, item: String, gone: &'static str },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Blaze,
+    Github,
+}
+
+/// What to do about one field of a linked pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resolution {
+    Keep,
+    Take(Side),
+    Conflict,
+}
+
+/// Resolve a field from whether each side `changed` since the last sync.
+/// `both` is the policy's pick when both did (none for `skip`).
+fn resolve(changed: (bool, bool), same: bool, both: Option<Side>) -> Resolution {
+    if same {
+        return Resolution::Keep;
+    }
+    match changed {
+        (true, false) => Resolution::Take(Side::Blaze),
+        (false, true) => Resolution::Take(Side::Github),
+        (true, true) => both.map_or(Resolution::Conflict, Resolution::Take),
+        // Neither moved since last time; don't fight over an old difference
+        (false, false) => Resolution::Keep,
+    }
+}
+
+/// Work out what a sync has to do. `new_cards` limits which unlinked cards get items.
+pub fn plan(
+    cards: &[Card],
+    items: &[Item],
+    state: &ProjectState,
+    map: &StatusMap,
+    policy: ConflictPolicy,
+    new_cards: Option<&Query>,
+) -> Vec<Step> {
+    let links = &state.links;
+    let mut steps = Vec::new();
+
+    for (item_id, link) in links {
+        let card = cards.iter().find(|c| c.id == link.card);
+        let item = items.iter().find(|i| i.id == *item_id);
+        let (card, item) = match (card, item) {
+            (Some(card), Some(item)) => (card, item),
+            (card, _) => {
+                let gone = if card.is_none() { "card" } else { "item" };
+                steps.push(Step::Unlink {
+                    card: link.card.clone(),
+                    item: item_id.clone(),
+                    gone,
+                });
+                continue;
+            }
+        };
+
+        let both = match policy {
+            ConflictPolicy::Newest if card.updated_at >= item.updated_at => Some(Side::Blaze),
+            ConflictPolicy::Newest | ConflictPolicy::Github => Some(Side::Github),
+            ConflictPolicy::Blaze => Some(Side::Blaze),
+            ConflictPolicy::Skip => None,
+        };
+        let item_column = item.status.as_deref().and_then(|s| map.column(s));
+
+        let title = resolve(
+            (card.title != link.card_title, item.title != link.item_title),
+            card.title == item.title,
+            both,
+        );
+        let mut column = resolve(
+            (card.column != link.card_column, item.status != link.item_status),
+            item_column.as_ref() == Some(&card.column),
+            both,
+        );
+        match item.status {
+            // An item without a status takes the card's column
+            None => column = Resolution::Take(Side::Blaze),
+            // An item in a status without a column has nothing to give
+            Some(_) if column == Resolution::Take(Side::Github) && item_column.is_none() => column = Resolution::Keep,
+            Some(_) => {}
+        }
+        if title == Resolution::Conflict || column == Resolution::Conflict {
+            steps.push(Step::Conflict {
+                card: card.id.clone(),
+                item: item.id.clone(),
+            });
+            continue;
+        }
+
+        let blaze = Resolution::Take(Side::Blaze);
+        let github = Resolution::Take(Side::Github);
+        let push_title = (title == blaze).then(|| card.title.clone());
+        let push_status = (column == blaze).then(|| map.status(&card.column));
+        if push_title.is_some() || push_status.is_some() {
+            steps.push(Step::Push {
+                card: card.id.clone(),
+                item: item.id.clone(),
+                title: push_title,
+                status: push_status,
+            });
+        }
+        let pull_title = (title == github).then(|| item.title.clone());
+        let pull_column = item_column.filter(|_| column == github);
+        if pull_title.is_some() || pull_column.is_some() {
+            steps.push(Step::Pull {
+                card: card.id.clone(),
+                item: item.id.clone(),
+                title: pull_title,
+                column: pull_column,
+            });
+        }
+    }
+
+    // Everything else is new on one side; pair up equal titles before creating anything
+    let linked_cards: HashSet<&str> = links.values().map(|l| l.card.as_str()).collect();
+    let mut new: Vec<&Card> = cards
+        .iter()
+        .filter(|c| !linked_cards.contains(c.id.as_str()) && !state.detached.contains(&c.id))
+        .filter(|c| new_cards.is_none_or(|q| q.matches(c)))
+        .collect();
+    for item in items.iter().filter(|i| !links.contains_key(&i.id) && !state.detached.contains(&i.id)) {
+        match new.iter().position(|c| c.title.eq_ignore_ascii_case(&item.title)) {
+            Some(pos) => {
+                let card = new.remove(pos);
+                steps.push(Step::Link {
+                    card: card.id.clone(),
+                    item: item.id.clone(),
+                });
+            }
+            None => steps.push(Step::CreateCard {
+                item: item.id.clone(),
+                title: item.title.clone(),
+                column: item.status.as_deref().and_then(|s| map.column(s)).unwrap_or(Column::Backlog),
+            }),
+        }
+    }
+    for card in new {
+        steps.push(Step::CreateItem {
+            card: card.id.clone(),
+            title: card.title.clone(),
+            status: map.status(&card.column),
+        });
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, title: &str, column: Column, minute: u32) -> Card {
+        testing::card(id, json!({
+            "title": title, "column": column, "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": format!("2026-01-01T00:{:02}:00Z", minute),
+        }))
+    }
+
+    fn item(id: &str, title: &str, status: &str, minute: u32) -> Item {
+        Item {
+            id: id.to_string(),
+            kind: ContentKind::Draft,
+            content_id: format!("DI_{}", id),
+            title: title.to_string(),
+            status: Some(status.to_string()),
+            updated_at: format!("2026-01-01T00:{:02}:00Z", minute).parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn copies_changes_and_resolves_conflicts() {
+        let map = StatusMap::from_config(&[("review".to_string(), "Reviewing".to_string())].into()).unwrap();
+        assert_eq!(map.status(&Column::Review), "Reviewing");
+        assert_eq!(map.column("in progress"), Some(Column::InProgress));
+
+        let was = Link::new(&card("c1", "Fix login", Column::Todo, 0), &item("i1", "Fix login", "Todo", 0));
+        let state = ProjectState {
+            links: BTreeMap::from([("i1".to_string(), was)]),
+            ..ProjectState::default()
+        };
+
+        // Card moved, item retitled: each side gets the other's change
+        let cards = [card("c1", "Fix login", Column::Review, 5)];
+        let items = [item("i1", "Fix login page", "Todo", 3)];
+        let steps = plan(&cards, &items, &state, &map, ConflictPolicy::Newest, None);
+        assert_eq!(
+            steps,
+            [
+                Step::Push {
+                    card: "c1".into(),
+                    item: "i1".into(),
+                    title: None,
+                    status: Some("Reviewing".into()),
+                },
+                Step::Pull {
+                    card: "c1".into(),
+                    item: "i1".into(),
+                    title: Some("Fix login page".into()),
+                    column: None,
+                },
+            ]
+        );
+
+        // Both moved: the newer side wins, or nobody with `skip`
+        let items = [item("i1", "Fix login", "Done", 9)];
+        let steps = plan(&cards, &items, &state, &map, ConflictPolicy::Newest, None);
+        assert!(matches!(&steps[..], [Step::Pull { column: Some(Column::Done), .. }]));
+        let steps = plan(&cards, &items, &state, &map, ConflictPolicy::Skip, None);
+        assert!(matches!(&steps[..], [Step::Conflict { .. }]));
+    }
+
+    #[test]
+    fn links_creates_and_unlinks() {
+        let map = StatusMap::from_config(&BTreeMap::new()).unwrap();
+        let gone = Link::new(&card("c0", "Old", Column::Todo, 0), &item("i0", "Old", "Todo", 0));
+        let state = ProjectState {
+            links: BTreeMap::from([("i0".to_string(), gone)]),
+            detached: BTreeSet::from(["c9".to_string()]),
+        };
+        let cards = [
+            card("c1", "Write docs", Column::Todo, 0),
+            card("c2", "Ship it", Column::InProgress, 0),
+            card("c9", "Was on the project", Column::Todo, 0),
+        ];
+        let items = [item("i1", "write docs", "Todo", 0), item("i2", "Triage", "Nope", 0)];
+        let steps = plan(&cards, &items, &state, &map, ConflictPolicy::Newest, None);
+        assert_eq!(
+            steps,
+            [
+                Step::Unlink {
+                    card: "c0".into(),
+                    item: "i0".into(),
+                    gone: "card",
+                },
+                Step::Link {
+                    card: "c1".into(),
+                    item: "i1".into(),
+                },
+                Step::CreateCard {
+                    item: "i2".into(),
+                    title: "Triage".into(),
+                    column: Column::Backlog,
+                },
+                Step::CreateItem {
+                    card: "c2".into(),
+                    title: "Ship it".into(),
+                    status: "In Progress".into(),
+                },
+            ]
+        );
+    }
+}
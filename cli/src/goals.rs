@@ -0,0 +1,224 @@
+//! Goals (objectives) that cards roll up to.
+//!
+//! Goals live in ~/.config/blaze/goals.toml:
+//!
+//! ```toml
+//! [goals.faster-onboarding]
+//! title = "Cut onboarding time in half"
+//! due = "2026-12-31T23:59:59Z"
+//! ```
+//!
+//! A card belongs to a goal through a `goal:<id>` tag, so the link is stored
+//! on the server and visible to everyone (`list --filter tag:goal:<id>`).
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Column};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Tag prefix linking a card to a goal
+pub const GOAL_TAG_PREFIX: &str = "goal:";
+
+/// A goal with its ID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct GoalDef {
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<DateTime<Utc>>,
+}
+
+/// Local goal storage
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GoalStore {
+    #[serde(default)]
+    goals: BTreeMap<String, GoalDef>,
+}
+
+impl GoalStore {
+    /// Get the goals file path
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|d| d.join("goals.toml"))
+    }
+
+    /// Load goals from disk (or return an empty store)
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Self::default()),
+        };
+
+        let content = fs::read_to_string(&path).map_err(|e| {
+            BlazeError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Save goals to disk
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| BlazeError::Config(format!("Failed to serialize goals: {}", e)))?;
+        fs::write(dir.join("goals.toml"), content)?;
+        Ok(())
+    }
+
+    /// Look up a goal by ID
+    pub fn get(&self, id: &str) -> Result<Goal> {
+        self.goals
+            .get(id)
+            .map(|def| to_goal(id, def))
+            .ok_or_else(|| BlazeError::InvalidInput(format!("Unknown goal '{}' (see `blaze goal list`)", id)))
+    }
+
+    /// All goals, sorted by ID
+    pub fn all(&self) -> Vec<Goal> {
+        self.goals.iter().map(|(id, def)| to_goal(id, def)).collect()
+    }
+
+    /// Add or replace a goal
+    pub fn insert(&mut self, goal: Goal) {
+        let def = GoalDef {
+            title: goal.title,
+            description: goal.description,
+            due: goal.due,
+        };
+        self.goals.insert(goal.id, def);
+    }
+
+    /// Remove a goal; returns true if it existed
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.goals.remove(id).is_some()
+    }
+}
+
+fn to_goal(id: &str, def: &GoalDef) -> Goal {
+    Goal {
+        id: id.to_string(),
+        title: def.title.clone(),
+        description: def.description.clone(),
+        due: def.due,
+    }
+}
+
+/// Validate a goal ID (used as a TOML key and in tags)
+pub fn parse_goal_id(input: &str) -> Result<String> {
+    let id = input.trim();
+    if id.is_empty() {
+        return Err(BlazeError::InvalidInput("Goal ID must not be empty".into()));
+    }
+    if let Some(bad) = id.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_')) {
+        return Err(BlazeError::InvalidInput(format!(
+            "Invalid goal ID '{}': unexpected character '{}'",
+            id, bad
+        )));
+    }
+    Ok(id.to_string())
+}
+
+/// The tag linking a card to `goal_id`
+pub fn goal_tag(goal_id: &str) -> String {
+    format!("{}{}", GOAL_TAG_PREFIX, goal_id)
+}
+
+/// The goal a card belongs to, if any
+pub fn goal_of(card: &Card) -> Option<&str> {
+    card.tags.iter().find_map(|t| t.strip_prefix(GOAL_TAG_PREFIX))
+}
+
+/// Card completion for one goal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoalProgress {
+    pub goal: String,
+    pub title: String,
+    pub cards: usize,
+    pub done: usize,
+    pub in_progress: usize,
+    /// Share of done cards, or of done estimate when every card has one (0-100)
+    pub percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Utc>>,
+}
+
+/// Progress of each goal from the cards linked to it
+pub fn progress(goals: &[Goal], cards: &[Card]) -> Vec<GoalProgress> {
+    goals
+        .iter()
+        .map(|goal| {
+            let linked: Vec<&Card> = cards.iter().filter(|c| goal_of(c) == Some(goal.id.as_str())).collect();
+            let done: Vec<&&Card> = linked.iter().filter(|c| c.column == Column::Done).collect();
+
+            // Weigh by size only when it's known for every card
+            let estimates: Option<Vec<f64>> = linked.iter().map(|c| c.estimate).collect();
+            let percent = match estimates {
+                Some(all) if all.iter().sum::<f64>() > 0.0 => {
+                    let done_size: f64 = done.iter().filter_map(|c| c.estimate).sum();
+                    100.0 * done_size / all.iter().sum::<f64>()
+                }
+                _ if linked.is_empty() => 0.0,
+                _ => 100.0 * done.len() as f64 / linked.len() as f64,
+            };
+
+            GoalProgress {
+                goal: goal.id.clone(),
+                title: goal.title.clone(),
+                cards: linked.len(),
+                done: done.len(),
+                in_progress: linked.iter().filter(|c| c.column == Column::InProgress).count(),
+                percent: (percent * 10.0).round() / 10.0,
+                due: goal.due,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(column: &str, tags: &[&str], estimate: Option<f64>) -> Card {
+        testing::card("a1", serde_json::json!({ "column": column, "tags": tags, "estimate": estimate }))
+    }
+
+    #[test]
+    fn progress_counts_linked_cards() {
+        let goals = vec![
+            Goal { id: "ship".into(), title: "Ship".into(), description: None, due: None },
+            Goal { id: "empty".into(), title: "Nothing yet".into(), description: None, due: None },
+        ];
+        let cards = vec![
+            card("done", &["goal:ship"], None),
+            card("in_progress", &["x", "goal:ship"], None),
+            card("todo", &["goal:ship"], None),
+            card("done", &["goal:other"], None),
+        ];
+        let progress = progress(&goals, &cards);
+        assert_eq!((progress[0].cards, progress[0].done, progress[0].in_progress), (3, 1, 1));
+        assert_eq!(progress[0].percent, 33.3);
+        assert_eq!((progress[1].cards, progress[1].percent), (0, 0.0));
+
+        // Estimates weigh in once every card has one
+        let sized = vec![card("done", &["goal:ship"], Some(3.0)), card("todo", &["goal:ship"], Some(1.0))];
+        assert_eq!(super::progress(&goals[..1], &sized)[0].percent, 75.0);
+
+        assert!(parse_goal_id("q4 growth").is_err());
+    }
+}
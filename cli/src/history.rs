@@ -0,0 +1,98 @@
+//! Card history derived from server events.
+//!
+//! Servers that don't report `entered_column_at` on cards may still record
+//! events; the time a card entered its column is then the latest move into
+//! that column, or its creation if it was never moved.
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::types::{BoardEvent, Card};
+use chrono::{DateTime, Utc};
+
+/// Fill in `entered_column_at` from events wherever the server left it out
+pub async fn fill_entered_column_at(client: &Client, cards: &mut [Card]) -> Result<()> {
+    let missing: Vec<&str> = cards
+        .iter()
+        .filter(|c| c.entered_column_at.is_none())
+        .map(|c| c.id.as_str())
+        .collect();
+    let card_id = match missing.as_slice() {
+        [] => return Ok(()),
+        [id] => Some(*id),
+        _ => None,
+    };
+
+    let events = match client.list_events(card_id).await {
+        Ok(events) => events,
+        // Without an events endpoint there is nothing to derive from
+        Err(e) if e.is_endpoint_missing() => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    for card in cards.iter_mut().filter(|c| c.entered_column_at.is_none()) {
+        card.entered_column_at = entered_column_at(card, &events);
+    }
+    Ok(())
+}
+
+/// When `card` entered its current column, according to `events`
+pub fn entered_column_at(card: &Card, events: &[BoardEvent]) -> Option<DateTime<Utc>> {
+    let own: Vec<&BoardEvent> = events
+        .iter()
+        .filter(|e| e.card_id.as_deref() == Some(card.id.as_str()))
+        .collect();
+
+    let moves: Vec<&&BoardEvent> = own.iter().filter(|e| e.kind == "card.moved").collect();
+    if moves.is_empty() {
+        return own.iter().find(|e| e.kind == "card.created").map(|e| e.timestamp);
+    }
+
+    let column = card.column.to_string();
+    moves
+        .iter()
+        .filter(|e| e.data.get("to").and_then(|v| v.as_str()) == Some(column.as_str()))
+        .map(|e| e.timestamp)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::json;
+
+    fn card(id: &str, column: &str) -> Card {
+        testing::card(id, json!({
+            "column": column, "created_at": "2026-02-02T09:00:00Z", "updated_at": "2026-02-04T09:00:00Z",
+        }))
+    }
+
+    fn event(kind: &str, card_id: &str, timestamp: &str, data: serde_json::Value) -> BoardEvent {
+        serde_json::from_value(json!({
+            "type": kind,
+            "card_id": card_id,
+            "timestamp": timestamp,
+            "data": data,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn latest_move_into_current_column_wins() {
+        let events = vec![
+            event("card.created", "a", "2026-02-02T09:00:00Z", json!({})),
+            event("card.moved", "a", "2026-02-03T09:00:00Z", json!({"from": "todo", "to": "in_progress"})),
+            event("card.moved", "a", "2026-02-03T12:00:00Z", json!({"from": "in_progress", "to": "review"})),
+            event("card.moved", "a", "2026-02-04T09:00:00Z", json!({"from": "review", "to": "in_progress"})),
+            event("card.moved", "b", "2026-02-05T09:00:00Z", json!({"from": "todo", "to": "in_progress"})),
+            event("card.created", "c", "2026-02-01T09:00:00Z", json!({})),
+        ];
+
+        let at = |id, column| entered_column_at(&card(id, column), &events).map(|t| t.to_rfc3339());
+        assert_eq!(at("a", "in_progress").as_deref(), Some("2026-02-04T09:00:00+00:00"));
+        assert_eq!(at("c", "todo").as_deref(), Some("2026-02-01T09:00:00+00:00"));
+        // Moved, but never into the column it is in now: history is incomplete
+        assert_eq!(at("a", "done"), None);
+        assert_eq!(at("d", "todo"), None);
+    }
+}
@@ -0,0 +1,319 @@
+//! The undo journal: what each command changed on the server.
+//!
+//! While a command runs, the client records every mutation it sends in
+//! `journal.json` (per profile or server URL), together with the card as it
+//! was just before. All requests of one command line (or REPL line, or `blaze
+//! bot` command) form one operation; `blaze undo` reverts the last one by
+//! sending the inverse requests, newest first. Only the last [`KEEP`]
+//! operations are kept. Dry runs, `--offline` queueing and replays send
+//! nothing and so record nothing; attachment uploads aren't recorded either.
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::Card;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+
+const FILE: &str = "journal.json";
+
+/// Operations kept per server
+pub const KEEP: usize = 50;
+
+/// One command's changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub id: String,
+    pub command: String,
+    pub at: DateTime<Utc>,
+    pub changes: Vec<Change>,
+}
+
+/// One mutating request that succeeded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// The card the request went to, fetched just before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    /// The response body
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub after: Value,
+}
+
+/// What it takes to revert a change
+#[derive(Debug, Clone)]
+pub enum Inverse {
+    /// Send this request
+    Request {
+        method: &'static str,
+        path: String,
+        body: Option<Value>,
+    },
+    /// Create the deleted card again (the server has no undelete)
+    Recreate(Box<Card>),
+}
+
+impl Change {
+    fn segments(&self) -> Vec<&str> {
+        let path = self.path.split('?').next().unwrap_or(&self.path);
+        path.trim_start_matches("/api/").split('/').collect()
+    }
+
+    /// The card the change was made to, created by it included
+    pub fn card_id(&self) -> Option<&str> {
+        match (self.method.as_str(), self.segments().as_slice()) {
+            ("POST", ["cards"]) => self.after.get("id").and_then(Value::as_str),
+            (_, ["cards", id, ..]) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// The card as the change left it, if the server sent it back
+    pub fn card_after(&self) -> Option<&Value> {
+        let id = self.card_id()?;
+        (self.after.get("id").and_then(Value::as_str) == Some(id)).then_some(&self.after)
+    }
+
+    /// The request that reverts this change, or why there is none
+    pub fn inverse(&self) -> std::result::Result<Inverse, String> {
+        let before = |field: &str| self.before.as_ref().and_then(|card| card.get(field)).cloned();
+        let request = |method, path: String, body| Ok(Inverse::Request { method, path, body });
+        let irreversible = || Err(format!("{} {} can't be undone", self.method, self.path));
+
+        match (self.method.as_str(), self.segments().as_slice()) {
+            ("POST", ["cards"]) => match self.card_id() {
+                Some(id) => request("DELETE", format!("/api/cards/{}", id), None),
+                None => Err("the created card's ID wasn't recorded".into()),
+            },
+            ("PUT", ["cards", id]) => {
+                let (Some(Value::Object(update)), Some(Value::Object(card))) = (&self.body, &self.before) else {
+                    return Err(format!("card {} wasn't recorded before the edit", id));
+                };
+                let restored = update
+                    .keys()
+                    .map(|field| (field.clone(), card.get(field).cloned().unwrap_or(Value::Null)))
+                    .collect();
+                request("PUT", format!("/api/cards/{}", id), Some(Value::Object(restored)))
+            }
+            ("PATCH", ["cards", id, "move"]) => match before("column") {
+                Some(column) => request("PATCH", format!("/api/cards/{}/move", id), Some(json!({ "column": column }))),
+                None => Err(format!("card {}'s column wasn't recorded before the move", id)),
+            },
+            ("PATCH", [kind @ ("cards" | "plans"), id, action @ ("archive" | "unarchive")]) => {
+                let inverse = if *action == "archive" { "unarchive" } else { "archive" };
+                request("PATCH", format!("/api/{}/{}/{}", kind, id, inverse), Some(json!({})))
+            }
+            ("PATCH", ["cards", id, "agent-status"]) => match before("agent_status") {
+                Some(status) if !status.is_null() => {
+                    let body = json!({ "status": status, "blocked_reason": before("blocked_reason") });
+                    request("PATCH", format!("/api/cards/{}/agent-status", id), Some(body))
+                }
+                _ => Err(format!("card {} had no agent status to go back to", id)),
+            },
+            ("POST", ["cards", id, "criteria", index, "check"]) => {
+                let checked = self.body.as_ref().and_then(|b| b.get("checked")).and_then(Value::as_bool);
+                match checked {
+                    Some(checked) => request(
+                        "POST",
+                        format!("/api/cards/{}/criteria/{}/check", id, index),
+                        Some(json!({ "checked": !checked })),
+                    ),
+                    None => irreversible(),
+                }
+            }
+            ("DELETE", ["cards", id]) => match self.before.clone().map(serde_json::from_value::<Card>) {
+                Some(Ok(card)) => Ok(Inverse::Recreate(Box::new(card))),
+                _ => Err(format!("card {} wasn't recorded before it was deleted", id)),
+            },
+            ("POST", ["cards", id, "comments"]) => match self.after.get("id").and_then(Value::as_str) {
+                Some(comment) => request("DELETE", format!("/api/cards/{}/comments/{}", id, comment), None),
+                None => irreversible(),
+            },
+            ("PATCH", ["cards", id, "threads", thread]) => {
+                let resolved = self.body.as_ref().and_then(|b| b.get("resolved")).and_then(Value::as_bool);
+                match resolved {
+                    Some(resolved) => request(
+                        "PATCH",
+                        format!("/api/cards/{}/threads/{}", id, thread),
+                        Some(json!({ "resolved": !resolved, "resolved_by": null })),
+                    ),
+                    None => irreversible(),
+                }
+            }
+            _ => irreversible(),
+        }
+    }
+}
+
+/// Where the current command's changes go
+#[derive(Debug, Clone)]
+pub struct Recording {
+    /// Profile or server URL
+    pub server: String,
+    pub operation: String,
+    pub command: String,
+}
+
+impl Recording {
+    pub fn new(server: &str, command: &str) -> Self {
+        Self {
+            server: server.to_string(),
+            operation: format!("{:x}", Utc::now().timestamp_micros()),
+            command: command.to_string(),
+        }
+    }
+}
+
+/// Operations, oldest first, keyed by profile or server URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default)]
+    servers: BTreeMap<String, Vec<Operation>>,
+}
+
+impl Journal {
+    pub fn load() -> Result<Self> {
+        match Config::dir().map(|d| d.join(FILE)) {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        self.servers.retain(|_, operations| !operations.is_empty());
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn operations(&mut self, server: &str) -> &mut Vec<Operation> {
+        self.servers.entry(server.to_string()).or_default()
+    }
+
+    /// Note a card's state after undo reverted a change to it, so the earlier
+    /// operations that left it like this don't look overtaken
+    pub fn reverted_to(&mut self, server: &str, card: &Value) {
+        let (Some(id), Some(updated_at)) = (card.get("id").and_then(Value::as_str), card.get("updated_at")) else {
+            return;
+        };
+        let changes = self.operations(server).iter_mut().rev().flat_map(|op| op.changes.iter_mut().rev());
+        for change in changes {
+            if change.card_after().is_some() && change.card_id() == Some(id) {
+                change.after["updated_at"] = updated_at.clone();
+                return;
+            }
+        }
+    }
+
+    /// Add a change to the recording's operation, starting it if needed
+    pub fn append(recording: &Recording, change: Change) -> Result<()> {
+        let mut journal = Self::load()?;
+        let operations = journal.operations(&recording.server);
+        match operations.last_mut() {
+            Some(last) if last.id == recording.operation => last.changes.push(change),
+            _ => operations.push(Operation {
+                id: recording.operation.clone(),
+                command: recording.command.clone(),
+                at: Utc::now(),
+                changes: vec![change],
+            }),
+        }
+        let excess = operations.len().saturating_sub(KEEP);
+        operations.drain(..excess);
+        journal.save()
+    }
+}
+
+/// A command line for the journal, with the token left out
+pub fn command_line<S: AsRef<str>>(words: &[S]) -> String {
+    let mut shown = Vec::new();
+    let mut words = words.iter().map(AsRef::as_ref);
+    while let Some(word) = words.next() {
+        if word == "--token" {
+            words.next();
+        } else if !word.starts_with("--token=") {
+            shown.push(if word.contains(' ') { format!("'{}'", word) } else { word.to_string() });
+        }
+    }
+    shown.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(method: &str, path: &str, body: Option<Value>, before: Option<Value>, after: Value) -> Change {
+        Change {
+            method: method.into(),
+            path: path.into(),
+            body,
+            before,
+            after,
+        }
+    }
+
+    /// The request that undoes `change`, if it's undone by one
+    fn sent(change: &Change) -> Option<(&'static str, String, Option<Value>)> {
+        match change.inverse() {
+            Ok(Inverse::Request { method, path, body }) => Some((method, path, body)),
+            _ => None,
+        }
+    }
+
+    fn request(method: &'static str, path: &str, body: Option<Value>) -> Option<(&'static str, String, Option<Value>)> {
+        Some((method, path.to_string(), body))
+    }
+
+    #[test]
+    fn changes_invert() {
+        let card = json!({ "id": "c1", "title": "Old", "column": "todo", "due_date": null, "agent_status": null });
+
+        let created = change("POST", "/api/cards", Some(json!({ "title": "New" })), None, json!({ "id": "c2" }));
+        assert_eq!(created.card_id(), Some("c2"));
+        assert_eq!(sent(&created), request("DELETE", "/api/cards/c2", None));
+
+        let body = json!({ "title": "New", "due_date": "2026-01-01T00:00:00Z" });
+        let edited = change("PUT", "/api/cards/c1", Some(body), Some(card.clone()), json!({ "id": "c1" }));
+        let restored = json!({ "title": "Old", "due_date": null });
+        assert_eq!(sent(&edited), request("PUT", "/api/cards/c1", Some(restored)));
+
+        let moved = change("PATCH", "/api/cards/c1/move", None, Some(card.clone()), Value::Null);
+        let back = json!({ "column": "todo" });
+        assert_eq!(sent(&moved), request("PATCH", "/api/cards/c1/move", Some(back)));
+
+        let archived = change("PATCH", "/api/cards/c1/archive", None, None, Value::Null);
+        assert_eq!(sent(&archived), request("PATCH", "/api/cards/c1/unarchive", Some(json!({}))));
+
+        let check = Some(json!({ "checked": true }));
+        let checked = change("POST", "/api/cards/c1/criteria/2/check", check, None, Value::Null);
+        let unchecked = Some(json!({ "checked": false }));
+        assert_eq!(sent(&checked), request("POST", "/api/cards/c1/criteria/2/check", unchecked));
+
+        let commented = change("POST", "/api/cards/c1/comments", None, None, json!({ "id": "m1" }));
+        assert_eq!(sent(&commented), request("DELETE", "/api/cards/c1/comments/m1", None));
+    }
+
+    #[test]
+    fn some_changes_stay() {
+        let card = json!({ "id": "c1", "agent_status": null });
+        let status = change("PATCH", "/api/cards/c1/agent-status", None, Some(card), Value::Null);
+        assert!(status.inverse().is_err());
+        let progress = change("POST", "/api/cards/c1/agent-progress", None, None, Value::Null);
+        assert!(progress.inverse().is_err());
+        let edited = change("PUT", "/api/cards/c1", Some(json!({ "title": "New" })), None, Value::Null);
+        assert!(edited.inverse().is_err());
+    }
+
+    #[test]
+    fn command_lines_hide_the_token() {
+        let words = ["edit", "c1", "--token", "secret", "--title", "Two words", "--token=secret"];
+        assert_eq!(command_line(&words), "edit c1 --title 'Two words'");
+    }
+}
@@ -0,0 +1,638 @@
+//! A small jq subset for `--query`.
+//!
+//! Supported: `.`, `.field`, `."field"`, `.[n]` (negative from the end),
+//! `.[m:n]`, `.[]`, pipes `|`, commas `,`, array `[...]` and object
+//! `{id, name: .title}` construction, literals, `==` `!=` `<` `<=` `>` `>=`,
+//! `and`/`or`, parentheses, and the functions `select(f)`, `map(f)`,
+//! `length`, `keys`, `not` and `has(key)`.
+//!
+//! ```text
+//! blaze list --query '.[] | select(.priority == "urgent") | {id, title}'
+//! ```
+
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonQuery {
+    source: String,
+    expr: Expr,
+}
+
+impl JsonQuery {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.pipe()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {}", token));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Every output of the filter for `input`
+    pub fn apply(&self, input: &Value) -> Result<Vec<Value>, String> {
+        eval(&self.expr, input)
+    }
+}
+
+impl FromStr for JsonQuery {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, String> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for JsonQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(char),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Dot => write!(f, "'.'"),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Punct(c) => write!(f, "'{}'", c),
+            Token::Op(op) => write!(f, "'{}'", op),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Token::Dot);
+            }
+            '|' | ',' | ':' | '(' | ')' | '[' | ']' | '{' | '}' => {
+                chars.next();
+                tokens.push(Token::Punct(c));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let eq = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Op(match (c, eq) {
+                    ('=', true) => "==",
+                    ('!', true) => "!=",
+                    ('<', false) => "<",
+                    ('<', true) => "<=",
+                    ('>', false) => ">",
+                    ('>', true) => ">=",
+                    _ => return Err(format!("unexpected '{}'", c)),
+                }));
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(escaped) => s.push(escaped),
+                            None => return Err("unterminated string".into()),
+                        },
+                        Some(ch) => s.push(ch),
+                        None => return Err("unterminated string".into()),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '-' | '0'..='9' => {
+                let mut s = String::new();
+                s.push(c);
+                chars.next();
+                while let Some(ch) = chars.next_if(|ch| ch.is_ascii_digit() || *ch == '.') {
+                    s.push(ch);
+                }
+                let n = s.parse().map_err(|_| format!("invalid number '{}'", s))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = String::new();
+                while let Some(ch) = chars.next_if(|ch| ch.is_alphanumeric() || *ch == '_') {
+                    s.push(ch);
+                }
+                tokens.push(Token::Ident(s));
+            }
+            _ => return Err(format!("unexpected '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Identity,
+    Literal(Value),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Slice(Box<Expr>, Option<i64>, Option<i64>),
+    Iterate(Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(String, Expr)>),
+    Compare(&'static str, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matched = self.peek() == Some(token);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Punct(p)) if p == c => Ok(()),
+            Some(token) => Err(format!("expected '{}', found {}", c, token)),
+            None => Err(format!("expected '{}' at the end", c)),
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Expr, String> {
+        let mut expr = self.comma()?;
+        while self.eat(&Token::Punct('|')) {
+            expr = Expr::Pipe(Box::new(expr), Box::new(self.comma()?));
+        }
+        Ok(expr)
+    }
+
+    fn comma(&mut self) -> Result<Expr, String> {
+        let mut expr = self.or()?;
+        while self.eat(&Token::Punct(',')) {
+            expr = Expr::Comma(Box::new(expr), Box::new(self.or()?));
+        }
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Ident("or".into())) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.compare()?;
+        while self.eat(&Token::Ident("and".into())) {
+            expr = Expr::And(Box::new(expr), Box::new(self.compare()?));
+        }
+        Ok(expr)
+    }
+
+    fn compare(&mut self) -> Result<Expr, String> {
+        let left = self.postfix()?;
+        if let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            return Ok(Expr::Compare(op, Box::new(left), Box::new(self.postfix()?)));
+        }
+        Ok(left)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.peek() == Some(&Token::Dot) {
+                match self.tokens.get(self.pos + 1) {
+                    Some(Token::Ident(name) | Token::Str(name)) => {
+                        expr = Expr::Field(Box::new(expr), name.clone());
+                        self.pos += 2;
+                    }
+                    Some(Token::Punct('[')) => self.pos += 1,
+                    _ => return Err("expected a field name after '.'".into()),
+                }
+            } else if self.eat(&Token::Punct('[')) {
+                expr = self.bracket(expr)?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// After `[`: iteration, an index or a slice of `base`
+    fn bracket(&mut self, base: Expr) -> Result<Expr, String> {
+        if self.eat(&Token::Punct(']')) {
+            return Ok(Expr::Iterate(Box::new(base)));
+        }
+        let start = self.slice_bound()?;
+        if self.eat(&Token::Punct(':')) {
+            let end = self.slice_bound()?;
+            self.expect(']')?;
+            return Ok(Expr::Slice(Box::new(base), start, end));
+        }
+        let index = match start {
+            Some(n) => Expr::Literal(Value::from(n)),
+            None => self.pipe()?,
+        };
+        self.expect(']')?;
+        Ok(Expr::Index(Box::new(base), Box::new(index)))
+    }
+
+    fn slice_bound(&mut self) -> Result<Option<i64>, String> {
+        match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            (Some(Token::Num(n)), Some(Token::Punct(':' | ']'))) => {
+                let n = *n;
+                self.pos += 1;
+                Ok(Some(n as i64))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Dot) => match self.peek().cloned() {
+                Some(Token::Ident(name) | Token::Str(name)) => {
+                    self.pos += 1;
+                    Ok(Expr::Field(Box::new(Expr::Identity), name))
+                }
+                _ => Ok(Expr::Identity),
+            },
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Num(n)) => Ok(Expr::Literal(number(n))),
+            Some(Token::Punct('(')) => {
+                let expr = self.pipe()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Punct('[')) => {
+                if self.eat(&Token::Punct(']')) {
+                    return Ok(Expr::Array(None));
+                }
+                let expr = self.pipe()?;
+                self.expect(']')?;
+                Ok(Expr::Array(Some(Box::new(expr))))
+            }
+            Some(Token::Punct('{')) => self.object(),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => {
+                    let mut args = Vec::new();
+                    if self.eat(&Token::Punct('(')) {
+                        args.push(self.pipe()?);
+                        self.expect(')')?;
+                    }
+                    check_call(&name, args.len())?;
+                    Ok(Expr::Call(name, args))
+                }
+            },
+            Some(token) => Err(format!("unexpected {}", token)),
+            None => Err("unexpected end of query".into()),
+        }
+    }
+
+    /// After `{`: `key`, `key: value` or `"key": value` entries
+    fn object(&mut self) -> Result<Expr, String> {
+        let mut entries = Vec::new();
+        if self.eat(&Token::Punct('}')) {
+            return Ok(Expr::Object(entries));
+        }
+        loop {
+            let key = match self.next() {
+                Some(Token::Ident(key) | Token::Str(key)) => key,
+                Some(token) => return Err(format!("expected an object key, found {}", token)),
+                None => return Err("unterminated object".into()),
+            };
+            let value = if self.eat(&Token::Punct(':')) {
+                self.or()?
+            } else {
+                Expr::Field(Box::new(Expr::Identity), key.clone())
+            };
+            entries.push((key, value));
+            if !self.eat(&Token::Punct(',')) {
+                self.expect('}')?;
+                return Ok(Expr::Object(entries));
+            }
+        }
+    }
+}
+
+fn check_call(name: &str, args: usize) -> Result<(), String> {
+    let expected = match name {
+        "length" | "keys" | "not" => 0,
+        "select" | "map" | "has" => 1,
+        _ => return Err(format!("unknown function '{}'", name)),
+    };
+    if args != expected {
+        return Err(format!("{} takes {} argument(s)", name, expected));
+    }
+    Ok(())
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects
+fn compare(a: &Value, b: &Value) -> Ordering {
+    let rank = |v: &Value| match v {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            x.as_f64().unwrap_or_default().total_cmp(&y.as_f64().unwrap_or_default())
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => {
+            x.iter().zip(y).map(|(x, y)| compare(x, y)).find(|o| o.is_ne()).unwrap_or(x.len().cmp(&y.len()))
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Resolve a possibly negative position against a length
+fn position(n: i64, len: usize) -> usize {
+    if n < 0 {
+        len.saturating_sub(n.unsigned_abs() as usize)
+    } else {
+        (n as usize).min(len)
+    }
+}
+
+/// Evaluate `a` and `b` against `input` and combine every pair of outputs
+fn pairs(a: &Expr, b: &Expr, input: &Value, f: impl Fn(&Value, &Value) -> Value) -> Result<Vec<Value>, String> {
+    let rights = eval(b, input)?;
+    let mut out = Vec::new();
+    for left in eval(a, input)? {
+        out.extend(rights.iter().map(|right| f(&left, right)));
+    }
+    Ok(out)
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, String> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Field(base, name) => eval(base, input)?
+            .into_iter()
+            .map(|value| match value {
+                Value::Object(mut map) => Ok(map.remove(name).unwrap_or(Value::Null)),
+                Value::Null => Ok(Value::Null),
+                other => Err(format!("cannot index {} with \"{}\"", kind(&other), name)),
+            })
+            .collect(),
+        Expr::Index(base, index) => {
+            let indices = eval(index, input)?;
+            let mut out = Vec::new();
+            for value in eval(base, input)? {
+                for index in &indices {
+                    out.push(match (&value, index) {
+                        (Value::Array(items), Value::Number(n)) => {
+                            let n = n.as_f64().unwrap_or_default() as i64;
+                            let i = if n < 0 { items.len() as i64 + n } else { n };
+                            usize::try_from(i).ok().and_then(|i| items.get(i)).cloned().unwrap_or(Value::Null)
+                        }
+                        (Value::Object(map), Value::String(key)) => map.get(key).cloned().unwrap_or(Value::Null),
+                        (Value::Null, _) => Value::Null,
+                        _ => return Err(format!("cannot index {} with {}", kind(&value), kind(index))),
+                    });
+                }
+            }
+            Ok(out)
+        }
+        Expr::Slice(base, start, end) => eval(base, input)?
+            .into_iter()
+            .map(|value| {
+                let len = match &value {
+                    Value::Array(items) => items.len(),
+                    Value::String(s) => s.chars().count(),
+                    Value::Null => return Ok(Value::Null),
+                    other => return Err(format!("cannot slice {}", kind(other))),
+                };
+                let from = start.map_or(0, |n| position(n, len));
+                let to = end.map_or(len, |n| position(n, len)).max(from);
+                Ok(match value {
+                    Value::Array(items) => Value::Array(items[from..to].to_vec()),
+                    Value::String(s) => Value::String(s.chars().skip(from).take(to - from).collect()),
+                    _ => unreachable!(),
+                })
+            })
+            .collect(),
+        Expr::Iterate(base) => {
+            let mut out = Vec::new();
+            for value in eval(base, input)? {
+                match value {
+                    Value::Array(items) => out.extend(items),
+                    Value::Object(map) => out.extend(map.into_iter().map(|(_, v)| v)),
+                    other => return Err(format!("cannot iterate over {}", kind(&other))),
+                }
+            }
+            Ok(out)
+        }
+        Expr::Pipe(a, b) => {
+            let mut out = Vec::new();
+            for value in eval(a, input)? {
+                out.extend(eval(b, &value)?);
+            }
+            Ok(out)
+        }
+        Expr::Comma(a, b) => {
+            let mut out = eval(a, input)?;
+            out.extend(eval(b, input)?);
+            Ok(out)
+        }
+        Expr::Array(None) => Ok(vec![Value::Array(Vec::new())]),
+        Expr::Array(Some(inner)) => Ok(vec![Value::Array(eval(inner, input)?)]),
+        Expr::Object(entries) => {
+            // Every combination of the entries' outputs, like jq
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let values = eval(value, input)?;
+                objects = objects
+                    .into_iter()
+                    .flat_map(|object| {
+                        values.iter().map(move |v| {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), v.clone());
+                            object
+                        })
+                    })
+                    .collect();
+            }
+            Ok(objects.into_iter().map(Value::Object).collect())
+        }
+        Expr::Compare(op, a, b) => pairs(a, b, input, |x, y| {
+            let order = compare(x, y);
+            Value::Bool(match *op {
+                "==" => order.is_eq(),
+                "!=" => order.is_ne(),
+                "<" => order.is_lt(),
+                "<=" => order.is_le(),
+                ">" => order.is_gt(),
+                _ => order.is_ge(),
+            })
+        }),
+        Expr::And(a, b) => pairs(a, b, input, |x, y| Value::Bool(truthy(x) && truthy(y))),
+        Expr::Or(a, b) => pairs(a, b, input, |x, y| Value::Bool(truthy(x) || truthy(y))),
+        Expr::Call(name, args) => call(name, args, input),
+    }
+}
+
+fn call(name: &str, args: &[Expr], input: &Value) -> Result<Vec<Value>, String> {
+    match name {
+        "length" => Ok(vec![match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err("boolean has no length".into()),
+            Value::Number(n) => Value::from(n.as_f64().unwrap_or_default().abs()),
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }]),
+        "keys" => match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                Ok(vec![Value::from(keys.into_iter().cloned().collect::<Vec<_>>())])
+            }
+            Value::Array(items) => Ok(vec![Value::from((0..items.len()).collect::<Vec<_>>())]),
+            other => Err(format!("{} has no keys", kind(other))),
+        },
+        "not" => Ok(vec![Value::Bool(!truthy(input))]),
+        "has" => eval(&args[0], input)?
+            .into_iter()
+            .map(|key| match (input, &key) {
+                (Value::Object(map), Value::String(key)) => Ok(Value::Bool(map.contains_key(key))),
+                (Value::Array(items), Value::Number(n)) => {
+                    Ok(Value::Bool(n.as_f64().is_some_and(|n| n >= 0.0 && (n as usize) < items.len())))
+                }
+                _ => Err(format!("cannot check whether {} has a {} key", kind(input), kind(&key))),
+            })
+            .collect(),
+        "select" => {
+            let keep = eval(&args[0], input)?.iter().filter(|v| truthy(v)).count();
+            Ok(vec![input.clone(); keep])
+        }
+        "map" => {
+            let items: Vec<Value> = match input {
+                Value::Array(items) => items.clone(),
+                Value::Object(map) => map.values().cloned().collect(),
+                other => return Err(format!("cannot iterate over {}", kind(other))),
+            };
+            let mut out = Vec::new();
+            for item in &items {
+                out.extend(eval(&args[0], item)?);
+            }
+            Ok(vec![Value::Array(out)])
+        }
+        _ => Err(format!("unknown function '{}'", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(query: &str, input: Value) -> Vec<Value> {
+        JsonQuery::parse(query).unwrap().apply(&input).unwrap()
+    }
+
+    #[test]
+    fn filters_project_and_index() {
+        let cards = json!([
+            {"id": "a1", "title": "Fix login", "priority": "urgent", "tags": ["bug"], "estimate": 3},
+            {"id": "b2", "title": "Docs", "priority": "low", "tags": [], "estimate": null},
+            {"id": "c3", "title": "Release", "priority": "urgent", "tags": ["ops", "release"], "estimate": 8}
+        ]);
+        assert_eq!(
+            run(r#".[] | select(.priority == "urgent") | {id, name: .title}"#, cards.clone()),
+            vec![json!({"id": "a1", "name": "Fix login"}), json!({"id": "c3", "name": "Release"})]
+        );
+        assert_eq!(run("map(.id)", cards.clone()), vec![json!(["a1", "b2", "c3"])]);
+        assert_eq!(run(".[-1].tags[0], length", cards.clone()), vec![json!("ops"), json!(3)]);
+        assert_eq!(run("[.[1:] | .[] | .id]", cards.clone()), vec![json!(["b2", "c3"])]);
+        assert_eq!(
+            run("[.[] | select(.estimate >= 3 and (.tags | length) > 1) | .id]", cards.clone()),
+            vec![json!(["c3"])]
+        );
+        assert_eq!(run(r#".[0] | has("id"), (.missing | not)"#, cards), vec![json!(true), json!(true)]);
+    }
+
+    #[test]
+    fn reports_parse_and_type_errors() {
+        assert_eq!(JsonQuery::parse(".[] |").unwrap_err(), "unexpected end of query");
+        assert_eq!(JsonQuery::parse("sort_by(.x)").unwrap_err(), "unknown function 'sort_by'");
+        assert_eq!(JsonQuery::parse("{id").unwrap_err(), "expected '}' at the end");
+        let query = JsonQuery::parse(".title").unwrap();
+        assert_eq!(query.apply(&json!([1])).unwrap_err(), "cannot index array with \"title\"");
+    }
+}
@@ -0,0 +1,185 @@
+//! Board statistics from the local store, for `blaze stats --from-store`.
+//!
+//! The server's `/api/board/stats` only counts columns and priorities. With
+//! every card at hand, open cards are also broken down by tag, assignee,
+//! agent status and age. Cards are tallied in parallel, so this stays instant on boards
+//! with many thousands of cards and works without a connection.
+
+use crate::types::{Card, Column, Priority};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Age buckets for open cards: label and upper bound in days
+const AGE_BUCKETS: &[(&str, i64)] = &[
+    ("<1d", 1),
+    ("1-7d", 7),
+    ("7-30d", 30),
+    ("30-90d", 90),
+    (">90d", i64::MAX),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalStats {
+    pub total_cards: usize,
+    pub overdue_count: usize,
+    pub by_column: BTreeMap<String, usize>,
+    pub by_priority: BTreeMap<String, usize>,
+    /// Open (not done) cards per tag
+    pub by_tag: BTreeMap<String, usize>,
+    /// Open cards per assignee, "none" for unassigned ones
+    pub by_assignee: BTreeMap<String, usize>,
+    /// Open cards per agent status, "none" for cards no agent works on
+    pub by_agent_status: BTreeMap<String, usize>,
+    /// Open cards by time since they were created, youngest first
+    pub by_age: Vec<AgeBucket>,
+    /// Sum of open cards' estimates
+    pub open_estimate: f64,
+    /// When the store last saw the whole card list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgeBucket {
+    pub age: String,
+    pub cards: usize,
+}
+
+/// Counts for part of the board, merged pairwise
+#[derive(Default)]
+struct Tally {
+    total: usize,
+    overdue: usize,
+    by_column: BTreeMap<String, usize>,
+    by_priority: BTreeMap<String, usize>,
+    by_tag: BTreeMap<String, usize>,
+    by_assignee: BTreeMap<String, usize>,
+    by_agent_status: BTreeMap<String, usize>,
+    by_age: Vec<usize>,
+    open_estimate: f64,
+}
+
+impl Tally {
+    fn add(mut self, card: &Card, now: DateTime<Utc>) -> Self {
+        self.total += 1;
+        *self.by_column.entry(card.column.to_string()).or_default() += 1;
+        *self.by_priority.entry(card.priority.to_string()).or_default() += 1;
+        if card.column == Column::Done {
+            return self;
+        }
+
+        if card.due_date.is_some_and(|d| d < now) {
+            self.overdue += 1;
+        }
+        for tag in &card.tags {
+            *self.by_tag.entry(tag.clone()).or_default() += 1;
+        }
+        let assignee = card.assignee.clone().unwrap_or_else(|| "none".to_string());
+        *self.by_assignee.entry(assignee).or_default() += 1;
+        let agent = card.agent_status.map_or_else(|| "none".to_string(), |s| s.to_string());
+        *self.by_agent_status.entry(agent).or_default() += 1;
+        let days = (now - card.created_at).num_days();
+        let bucket = AGE_BUCKETS.iter().position(|(_, max)| days < *max).unwrap_or(AGE_BUCKETS.len() - 1);
+        self.by_age.resize(AGE_BUCKETS.len(), 0);
+        self.by_age[bucket] += 1;
+        self.open_estimate += card.estimate.unwrap_or(0.0);
+        self
+    }
+
+    fn merge(mut self, other: Tally) -> Self {
+        self.total += other.total;
+        self.overdue += other.overdue;
+        for (mine, theirs) in [
+            (&mut self.by_column, other.by_column),
+            (&mut self.by_priority, other.by_priority),
+            (&mut self.by_tag, other.by_tag),
+            (&mut self.by_assignee, other.by_assignee),
+            (&mut self.by_agent_status, other.by_agent_status),
+        ] {
+            for (key, n) in theirs {
+                *mine.entry(key).or_default() += n;
+            }
+        }
+        self.by_age.resize(AGE_BUCKETS.len(), 0);
+        for (i, n) in other.by_age.into_iter().enumerate() {
+            self.by_age[i] += n;
+        }
+        self.open_estimate += other.open_estimate;
+        self
+    }
+}
+
+/// Statistics for the given (unarchived) cards
+pub fn compute(cards: &[Card], now: DateTime<Utc>) -> LocalStats {
+    let tally = cards
+        .par_iter()
+        .fold(Tally::default, |tally, card| tally.add(card, now))
+        .reduce(Tally::default, Tally::merge);
+
+    // Every column and priority is listed, like the server does
+    let mut by_column: BTreeMap<String, usize> = Column::DEFAULTS.iter().map(|c| (c.to_string(), 0)).collect();
+    by_column.extend(tally.by_column);
+    let mut by_priority: BTreeMap<String, usize> =
+        Priority::value_variants().iter().map(|p| (p.to_string(), 0)).collect();
+    by_priority.extend(tally.by_priority);
+
+    LocalStats {
+        total_cards: tally.total,
+        overdue_count: tally.overdue,
+        by_column,
+        by_priority,
+        by_tag: tally.by_tag,
+        by_assignee: tally.by_assignee,
+        by_agent_status: tally.by_agent_status,
+        by_age: AGE_BUCKETS
+            .iter()
+            .enumerate()
+            .map(|(i, (age, _))| AgeBucket {
+                age: age.to_string(),
+                cards: tally.by_age.get(i).copied().unwrap_or(0),
+            })
+            .collect(),
+        open_estimate: tally.open_estimate,
+        synced_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::Duration;
+
+    fn card(column: &str, tags: &[&str], age_days: i64, now: DateTime<Utc>) -> Card {
+        testing::card(&format!("{}-{}", column, age_days), serde_json::json!({
+            "title": "Card", "priority": "high", "column": column, "due_date": now - Duration::hours(1),
+            "tags": tags, "created_at": now - Duration::days(age_days), "updated_at": now, "estimate": 2.0,
+        }))
+    }
+
+    #[test]
+    fn breakdowns_count_open_cards_only() {
+        let now = Utc::now();
+        let cards: Vec<Card> = (0..200)
+            .map(|i| card("todo", &["api"], [0, 10, 50][i % 3], now))
+            .chain([card("done", &["api", "ui"], 400, now)])
+            .collect();
+        let stats = compute(&cards, now);
+
+        assert_eq!(stats.total_cards, 201);
+        assert_eq!(stats.by_column["todo"], 200);
+        assert_eq!(stats.by_column["done"], 1);
+        assert_eq!(stats.by_column["in_progress"], 0);
+        assert_eq!(stats.overdue_count, 200);
+        assert_eq!(stats.by_tag.get("api"), Some(&200));
+        assert_eq!(stats.by_tag.get("ui"), None);
+        assert_eq!(stats.by_assignee["none"], 200);
+        assert_eq!(stats.by_agent_status["none"], 200);
+        let ages: Vec<usize> = stats.by_age.iter().map(|b| b.cards).collect();
+        assert_eq!(ages, [67, 0, 67, 66, 0]);
+        assert_eq!(stats.open_estimate, 400.0);
+    }
+}
@@ -2,29 +2,66 @@
 //! 
 //! All output is JSON for machine parsing and full ID visibility.
 
+mod agent_rules;
+mod auth;
+mod breakdown;
+mod cache;
+mod capabilities;
+mod cassette;
+mod changes;
 mod client;
+mod columns;
 mod commands;
+mod complete;
+mod confidential;
 mod config;
+mod context;
+mod criteria;
+mod daemon;
+mod deps;
+mod editor;
 mod error;
+mod escalation;
+mod flow;
+mod github;
+mod goals;
+mod history;
+mod jq;
+mod journal;
+mod local_stats;
+mod merge;
+mod metrics;
+mod notify;
 mod output;
+mod pager;
+mod parsers;
+mod plan_lint;
+mod plan_sign;
+mod progress;
+mod publish;
+mod resolve;
+mod script;
+mod store;
+#[cfg(test)]
+mod testing;
 mod types;
+mod views;
+mod wip;
 
-use clap::{Parser, Subcommand};
-use commands::{add, agent, board, edit, list, move_card, ping, plan, rm, show, stats};
-use types::{Column, PlanStatus, Priority};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::{ArgValueCompleter, CompleteEnv};
+use commands::{add, agent, analytics, attachment, backup, bench, board, bootstrap, bot, cache as cache_cmd, changes as changes_cmd, comment, completions, config as config_cmd, criteria as criteria_cmd, daemon as daemon_cmd, dep, edit, events, export, focus, github_project, goal, link, list, login, maintenance, metrics as metrics_cmd, move_card, notify as notify_cmd, notifyd, ping, plan, plugin, poker, publish as publish_cmd, quick, repl, replace, report, rm, script as script_cmd, settings, show, simulate, stats, sync, tag, task, team, thread, timeline, trash, undo, use_card, view, watch, wizard};
+use context::{CommandContext, GlobalArgs, OutputFormat};
+use std::path::PathBuf;
+use types::{Column, LinkKind, PlanStatus, Priority};
 
 #[derive(Parser)]
 #[command(name = "blaze")]
 #[command(about = "CLI for Blaze task board (JSON output)", long_about = None)]
 #[command(version)]
 struct Cli {
-    /// API base URL
-    #[arg(long, global = true, env = "BLAZE_URL")]
-    url: Option<String>,
-
-    /// API token
-    #[arg(long, global = true, env = "BLAZE_TOKEN")]
-    token: Option<String>,
+    #[command(flatten)]
+    global: GlobalArgs,
 
     #[command(subcommand)]
     command: Commands,
@@ -33,12 +70,26 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Check API connectivity
-    Ping,
+    Ping {
+        /// Also check which optional features (comments, events, ...) the server has
+        #[arg(long)]
+        capabilities: bool,
+    },
+
+    /// Verify an API token and save it to the OS keyring (or token file)
+    Login {
+        /// Read the token from stdin instead of prompting
+        #[arg(long)]
+        with_token: bool,
+    },
+
+    /// Show the identity and scopes of the current token
+    Whoami,
 
     /// List cards (with optional filters)
     List {
         /// Filter by column
-        #[arg(short, long)]
+        #[arg(short, long, value_parser = parsers::parse_column, add = ArgValueCompleter::new(complete::columns))]
         column: Option<Column>,
 
         /// Filter by priority (comma-separated: high,urgent)
@@ -53,34 +104,145 @@ enum Commands {
         #[arg(long)]
         overdue: bool,
 
+        /// Only cards changed since a date, timestamp or age (e.g. 2026-01-05, 7d)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time)]
+        updated_since: Option<chrono::DateTime<chrono::Utc>>,
+
         /// Include archived cards (excluded by default)
         #[arg(long)]
         include_archived: bool,
+
+        /// Filter query (e.g. "column:todo AND priority:high,urgent")
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: Option<parsers::Query>,
+
+        /// Apply a saved view (combined with --filter)
+        #[arg(long, value_name = "NAME")]
+        view: Option<String>,
+
+        /// Only cards assigned to NAME
+        #[arg(long, value_name = "NAME")]
+        assignee: Option<String>,
+
+        /// Only cards assigned to you (the identity `blaze whoami` shows, else `author` or $USER)
+        #[arg(long, conflicts_with = "assignee")]
+        mine: bool,
+
+        /// Only cards with open review threads (one request per card)
+        #[arg(long)]
+        unresolved_threads: bool,
+
+        /// Only cards that aren't done and whose dependencies are all done
+        #[arg(long)]
+        ready: bool,
+
+        /// Only cards that aren't done and wait on unfinished dependencies
+        #[arg(long, conflicts_with_all = ["ready", "unblocked", "show_blocked_by"])]
+        blocked: bool,
+
+        /// Only cards without unfinished dependencies (unlike --ready, done cards too)
+        #[arg(long, conflicts_with_all = ["ready", "show_blocked_by"])]
+        unblocked: bool,
+
+        /// Show cards held back by unfinished dependencies, and what blocks them
+        #[arg(long)]
+        show_blocked_by: bool,
+
+        /// Fields to show in any format (comma-separated, e.g. id,title,due,tags)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fields: Vec<output::CardField>,
+
+        /// Order by due, priority, created, updated or title (ties: priority, then due date)
+        #[arg(long, value_enum, value_name = "KEY")]
+        sort: Option<list::SortKey>,
+
+        /// Reverse the --sort order
+        #[arg(long, requires = "sort")]
+        desc: bool,
+
+        /// Show at most N cards (fetches only as many pages as needed)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Fetch only this page of the server's list (page size: --limit)
+        #[arg(long, value_name = "N")]
+        page: Option<u64>,
     },
 
     /// Show card details
     Show {
         /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
         card_id: String,
+
+        /// Also print the card's comments, oldest first
+        #[arg(long)]
+        comments: bool,
     },
 
     /// Show board overview (column counts)
-    Board,
+    Board {
+        /// Break the counts down into swimlanes
+        #[arg(long, value_enum, value_name = "LANE")]
+        by: Option<board::Swimlane>,
+    },
+
+    /// Show what's in progress right now: blockers, due dates, criteria and latest progress
+    Focus {
+        /// Latest progress entries per card
+        #[arg(long, default_value_t = 3, value_name = "N")]
+        entries: usize,
+    },
 
     /// Show detailed board statistics
-    Stats,
+    Stats {
+        /// Compute from the local store instead of asking the server, with tag, agent and age breakdowns
+        #[arg(long)]
+        from_store: bool,
+    },
+
+    /// Cycle time, lead time, weekly throughput and WIP over time
+    Analytics {
+        /// Cards finished since a date, timestamp or age (default: 12 weeks before --until)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Cards finished before a date, timestamp or age (default: now)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// List the finished cards with their lead and cycle times instead
+        #[arg(long)]
+        cards: bool,
+    },
+
+    /// A team's slice of the board (`[teams.<name>]` in config.toml)
+    Team {
+        /// Team name
+        name: String,
+
+        #[command(subcommand)]
+        action: TeamCommands,
+    },
 
     /// Create a new card
     Add {
         /// Card title
-        title: String,
+        #[arg(required_unless_present_any = ["batch", "interactive"])]
+        title: Option<String>,
 
         /// Card description
         #[arg(short, long)]
         desc: Option<String>,
 
         /// Column to place the card in
-        #[arg(short, long, default_value = "todo")]
+        #[arg(
+            short,
+            long,
+            default_value = "todo",
+            value_parser = parsers::parse_column,
+            add = ArgValueCompleter::new(complete::columns)
+        )]
         column: Column,
 
         /// Priority level
@@ -91,15 +253,68 @@ enum Commands {
         #[arg(short, long, value_delimiter = ',')]
         tag: Vec<String>,
 
-        /// Due date (YYYY-MM-DD)
+        /// Due date (YYYY-MM-DD, or "YYYY-MM-DD HH:MM"; see --tz)
         #[arg(long)]
         due: Option<String>,
+
+        /// Make the card available for agent work
+        #[arg(long)]
+        agent: bool,
+
+        /// Acceptance criterion (repeatable)
+        #[arg(long = "criterion", value_name = "TEXT")]
+        criteria: Vec<String>,
+
+        /// Card that must be done before this one (repeatable)
+        #[arg(long, value_name = "ID", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocked_by: Vec<String>,
+
+        /// Estimated size (points, days, ...)
+        #[arg(long)]
+        estimate: Option<f64>,
+
+        /// Expire the card at a date, timestamp or time from now (12h, 3d, 2w); see `blaze maintenance expire`
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_expiry)]
+        expires: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Who works on the card
+        #[arg(long, value_name = "NAME")]
+        assignee: Option<String>,
+
+        /// Create cards from NDJSON or a JSON array on stdin (one result line per card)
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "title", "desc", "tag", "due", "agent", "criteria", "blocked_by", "estimate", "expires", "assignee"
+            ]
+        )]
+        batch: bool,
+
+        /// Ask for each field in turn, with a preview before creating (flags given become the defaults)
+        #[arg(short, long, conflicts_with = "batch")]
+        interactive: bool,
+    },
+
+    /// Create a card from one line: 'Fix login bug !high #backend @friday ^review'
+    Quick {
+        /// Title words with inline !priority, #tag, @due (date, today, tomorrow, weekday, 3d, 2w) and ^column
+        #[arg(required_unless_present = "stdin")]
+        text: Vec<String>,
+
+        /// Create a card for each line on stdin (one result line per card)
+        #[arg(long, conflicts_with = "text")]
+        stdin: bool,
+
+        /// Maximum number of cards created at once with --stdin
+        #[arg(long, default_value_t = 4, requires = "stdin")]
+        concurrency: usize,
     },
 
-    /// Update an existing card
+    /// Update existing cards
     Edit {
-        /// Card ID
-        card_id: String,
+        /// Card IDs
+        #[arg(required = true, value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_ids: Vec<String>,
 
         /// New title
         #[arg(long)]
@@ -110,7 +325,7 @@ enum Commands {
         desc: Option<String>,
 
         /// Move to column
-        #[arg(short, long)]
+        #[arg(short, long, value_parser = parsers::parse_column, add = ArgValueCompleter::new(complete::columns))]
         column: Option<Column>,
 
         /// Set priority
@@ -125,38 +340,235 @@ enum Commands {
         #[arg(long = "untag", value_name = "TAG")]
         tags_remove: Vec<String>,
 
-        /// Set due date (YYYY-MM-DD)
+        /// Set due date (YYYY-MM-DD, or "YYYY-MM-DD HH:MM"; see --tz)
         #[arg(long)]
         due: Option<String>,
 
         /// Clear due date
-        #[arg(long)]
+        #[arg(long, conflicts_with = "due")]
         clear_due: bool,
+
+        /// Make the card available (true) or unavailable (false) for agent work
+        #[arg(long, value_name = "BOOL")]
+        agent: Option<bool>,
+
+        /// Append an acceptance criterion (repeatable)
+        #[arg(long = "criterion", value_name = "TEXT")]
+        criteria_add: Vec<String>,
+
+        /// Add a card that must be done first (repeatable)
+        #[arg(long = "blocked-by", value_name = "ID", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocked_by_add: Vec<String>,
+
+        /// Set the estimated size
+        #[arg(long)]
+        estimate: Option<f64>,
+
+        /// Expire the card at a date, timestamp or time from now (12h, 3d, 2w)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_expiry)]
+        expires: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Link to a goal (replaces any previous goal)
+        #[arg(long, value_name = "GOAL", value_parser = goals::parse_goal_id)]
+        goal: Option<String>,
+
+        /// Why the priority goes up, kept as a comment (required for urgent; see `report escalations`)
+        #[arg(long, requires = "priority", value_name = "TEXT")]
+        reason: Option<String>,
+
+        /// Assign the card to someone
+        #[arg(long, value_name = "NAME")]
+        assignee: Option<String>,
+
+        /// Remove the assignee
+        #[arg(long, conflicts_with = "assignee")]
+        unassign: bool,
     },
 
-    /// Move a card to a different column
+    /// Move cards to a different column
     Move {
-        /// Card ID
-        card_id: String,
+        /// Card IDs
+        #[arg(required = true, num_args = 1.., value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_ids: Vec<String>,
 
         /// Target column
+        #[arg(value_parser = parsers::parse_column, add = ArgValueCompleter::new(complete::columns))]
         column: Column,
+
+        /// Move even if it takes the column over its WIP limit
+        #[arg(short, long)]
+        force: bool,
     },
 
-    /// Mark a card as done (shortcut for move to done)
+    /// Mark cards as done (shortcut for move to done)
     Done {
+        /// Card IDs (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_ids: Vec<String>,
+
+        /// Finish cards even if their dependencies aren't done (or done is at its WIP limit)
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Set the card `done` and `agent` commands use when given no ID (shows it when run without one)
+    Use {
         /// Card ID
-        card_id: String,
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
+
+        /// Forget the current card
+        #[arg(long, conflicts_with = "card_id")]
+        clear: bool,
     },
 
-    /// Delete a card
+    /// Delete cards
     Rm {
-        /// Card ID
-        card_id: String,
+        /// Card IDs
+        #[arg(required = true, value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_ids: Vec<String>,
 
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
+
+        /// Delete for good instead of moving to the trash
+        #[arg(long)]
+        permanent: bool,
+    },
+
+    /// Cards removed with `rm`: list, restore, purge
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+
+    /// Revert the last command's changes on the server
+    Undo {
+        /// Show recent operations instead of undoing one
+        #[arg(long)]
+        list: bool,
+
+        /// Operations to show with --list (default 20)
+        #[arg(short = 'n', long, requires = "list")]
+        limit: Option<usize>,
+
+        /// Undo even if the cards were changed again since
+        #[arg(long, conflicts_with = "list")]
+        force: bool,
+    },
+
+    /// Dependencies between cards
+    Dep {
+        #[command(subcommand)]
+        action: DepCommands,
+    },
+
+    /// External links on a card (CI runs, docs, designs)
+    Link {
+        #[command(subcommand)]
+        action: LinkCommands,
+    },
+
+    /// Upload files to a card
+    Attach {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Files to upload
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Files attached to a card
+    Attachment {
+        #[command(subcommand)]
+        action: AttachmentCommands,
+    },
+
+    /// Discussion on a card
+    Comment {
+        #[command(subcommand)]
+        action: CommentCommands,
+    },
+
+    /// Review threads on a card: a topic with replies, open until resolved
+    Thread {
+        #[command(subcommand)]
+        action: ThreadCommands,
+    },
+
+    /// Checklist items on a card, separate from acceptance criteria
+    Task {
+        #[command(subcommand)]
+        action: TaskCommands,
+    },
+
+    /// Add or remove a tag across cards matching a filter
+    Tag {
+        #[command(subcommand)]
+        action: TagCommands,
+    },
+
+    /// Reusable acceptance criteria sets
+    Criteria {
+        #[command(subcommand)]
+        action: CriteriaCommands,
+    },
+
+    /// Estimate a card together: blind votes, reveal, record the result
+    Poker {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Participant, in voting order (repeatable; asked for when omitted)
+        #[arg(short, long = "participant", value_name = "NAME")]
+        participants: Vec<String>,
+
+        /// Estimate to record if the votes differ (default: ask, or the median when piped)
+        #[arg(long)]
+        estimate: Option<f64>,
+    },
+
+    /// Find and replace text in titles, descriptions and tags across cards
+    Replace {
+        /// Text to look for (case-sensitive)
+        #[arg(long)]
+        find: String,
+
+        /// Text to put in its place (may be empty)
+        #[arg(long = "replace", value_name = "TEXT")]
+        replacement: String,
+
+        /// Fields to change (comma-separated; default: all)
+        #[arg(long = "in", value_enum, value_delimiter = ',', value_name = "FIELDS")]
+        fields: Vec<replace::ReplaceField>,
+
+        /// Only cards matching this query (same syntax as `list --filter`)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: Option<parsers::Query>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Maximum number of cards updated at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+
+    /// Goals that cards roll up to
+    Goal {
+        #[command(subcommand)]
+        action: GoalCommands,
+    },
+
+    /// Saved filter views
+    View {
+        #[command(subcommand)]
+        action: ViewCommands,
     },
 
     /// Plan management
@@ -170,6 +582,276 @@ enum Commands {
         #[command(subcommand)]
         action: AgentCommands,
     },
+
+    /// Cards created, updated, moved or deleted since a point in time (JSON lines)
+    Changes {
+        /// last, YYYY-MM-DD, an RFC 3339 timestamp, or an age like 30m, 2h, 7d
+        #[arg(long, value_parser = parsers::parse_since)]
+        since: parsers::Since,
+    },
+
+    /// Poll the board and print cards created, moved, edited or deleted (JSON lines)
+    Watch {
+        /// Only cards matching a filter query (before or after the change)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: Option<parsers::Query>,
+
+        /// How often to refetch the board, e.g. 10s, 1m
+        #[arg(long, value_parser = parsers::parse_interval, default_value = "10s")]
+        interval: std::time::Duration,
+    },
+
+    /// Follow board changes live, one JSON object per line (SSE or WebSocket)
+    Events {
+        /// Only events matching KEY=VALUE (repeatable; column, priority, tag, agent, title, type, card), e.g. column=in_progress
+        #[arg(long, value_name = "KEY=VALUE")]
+        filter: Vec<String>,
+
+        /// How to connect to the server
+        #[arg(long, value_enum, default_value_t)]
+        transport: client::EventTransport,
+    },
+
+    /// A card's or the board's history: events, progress entries and comments in order
+    Timeline {
+        #[command(subcommand)]
+        action: TimelineCommands,
+    },
+
+    /// Serve JSON commands (list, add, move, show) from another process, one per line
+    Bot {
+        /// How commands arrive and replies leave
+        #[arg(long, value_enum, default_value_t)]
+        transport: bot::BotTransport,
+    },
+
+    /// Send changes queued with --offline to the server, or sync with GitHub
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        target: Option<SyncCommands>,
+
+        /// Only list queued changes
+        #[arg(long)]
+        list: bool,
+
+        /// Apply changes even if the card changed on the server since
+        #[arg(long)]
+        force: bool,
+
+        /// Discard a queued change (repeatable)
+        #[arg(long, value_name = "SEQ")]
+        drop: Vec<i64>,
+    },
+
+    /// Measure list/get/create latency against the server
+    Bench {
+        /// Requests per operation
+        #[arg(long, default_value_t = 100)]
+        requests: usize,
+
+        /// Requests in flight at once
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+
+        /// Operations to measure (comma-separated; default: all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        ops: Vec<bench::BenchOp>,
+    },
+
+    /// Fill a fresh board with starter cards, saved views and optionally a plan
+    Bootstrap {
+        /// Kind of board
+        #[arg(long, value_enum)]
+        template: bootstrap::Template,
+
+        /// Also create a plan with the template's standard files
+        #[arg(long)]
+        plan: bool,
+
+        /// Add the starter cards even if the board already has cards
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Dump all cards (and optionally plans) to one JSON or YAML document
+    Export {
+        /// Include plans and their files
+        #[arg(long)]
+        plans: bool,
+
+        /// Include archived cards (they are imported as active cards)
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Write YAML instead of JSON (implied by a .yaml/.yml output file)
+        #[arg(long)]
+        yaml: bool,
+
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Recreate cards and plans from `blaze export` on this server
+    Import {
+        /// Export file (JSON or YAML; reads stdin if omitted or '-')
+        file: Option<PathBuf>,
+
+        /// Ignore plans in the export
+        #[arg(long)]
+        skip_plans: bool,
+    },
+
+    /// Archive the whole board; `--incremental` saves only what changed since the last backup
+    #[command(args_conflicts_with_subcommands = true)]
+    Backup {
+        #[command(subcommand)]
+        action: Option<BackupCommands>,
+
+        /// Only save cards and plans changed since the last backup (chained to it)
+        #[arg(long)]
+        incremental: bool,
+
+        /// Backup directory (default ~/.config/blaze/backups/<profile>)
+        #[arg(long, global = true)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Send recent changes to Slack, the desktop or email (rules in notify.toml)
+    Notify {
+        #[command(subcommand)]
+        action: NotifyCommands,
+    },
+
+    /// Desktop alerts when cards become due soon, overdue or blocked ([notifyd] in config.toml)
+    Notifyd {
+        /// Time between polls, e.g. 30s, 5m (default: `interval_secs`, else 5m)
+        #[arg(long, value_parser = parsers::parse_interval)]
+        interval: Option<std::time::Duration>,
+
+        /// Poll once and exit
+        #[arg(long, conflicts_with_all = ["detach", "stop"])]
+        once: bool,
+
+        /// Run in the background
+        #[arg(long, conflicts_with = "stop")]
+        detach: bool,
+
+        /// Stop the one started with --detach
+        #[arg(long)]
+        stop: bool,
+    },
+
+    /// Serve a read-only, auto-refreshing board page for status screens
+    Publish {
+        /// Address to listen on (the token is never exposed)
+        #[arg(long, default_value = "127.0.0.1:8088")]
+        listen: String,
+
+        /// How often to refetch the board, e.g. 30s, 5m
+        #[arg(long, value_parser = parsers::parse_interval, default_value = "60s")]
+        refresh: std::time::Duration,
+    },
+
+    /// Custom reports and automations in Rhai
+    Script {
+        #[command(subcommand)]
+        action: ScriptCommands,
+    },
+
+    /// What-if projections from the board's history
+    Simulate {
+        #[command(subcommand)]
+        action: SimulateCommands,
+    },
+
+    /// Board reports
+    Report {
+        #[command(subcommand)]
+        action: ReportCommands,
+    },
+
+    /// Board stats history in a local SQLite database
+    Metrics {
+        #[command(subcommand)]
+        action: MetricsCommands,
+
+        /// Metrics database (default: `[metrics] db`, else ~/.config/blaze/metrics.sqlite3)
+        #[arg(long, global = true)]
+        db: Option<PathBuf>,
+    },
+
+    /// Housekeeping tasks (e.g. closing expired cards from cron)
+    Maintenance {
+        #[command(subcommand)]
+        action: MaintenanceCommands,
+    },
+
+    /// Manage downloaded files
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommands,
+    },
+
+    /// Show or edit ~/.config/blaze/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+
+    /// Share views, criteria sets, agent rules and display preferences as one bundle
+    Settings {
+        #[command(subcommand)]
+        action: SettingsCommands,
+    },
+
+    /// Interactive shell reusing one connection, with history, ID completion and $last_id
+    Repl,
+
+    /// Print a shell completion script (card IDs complete from the server)
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
+
+    /// Background daemon holding warm connections
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonCommands,
+    },
+
+    /// External `blaze-<name>` commands on PATH
+    Plugin {
+        #[command(subcommand)]
+        action: PluginCommands,
+    },
+
+    /// Any other command runs the `blaze-<name>` plugin on PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+enum SyncCommands {
+    /// Two-way sync with a GitHub Projects board: titles and column <-> status (settings in [github])
+    GithubProject {
+        /// Project node ID (PVT_...)
+        #[arg(long)]
+        project: String,
+
+        /// Who wins when a card and its item both changed (default: [github] conflicts, else newest)
+        #[arg(long, value_enum)]
+        conflicts: Option<github::ConflictPolicy>,
+
+        /// Only cards matching this query get project items (others are still synced once linked)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: Option<parsers::Query>,
+
+        /// Keep syncing at this interval (e.g. 5m) instead of once
+        #[arg(long, value_name = "INTERVAL", value_parser = parsers::parse_interval)]
+        watch: Option<std::time::Duration>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -179,6 +861,14 @@ enum PlanCommands {
         /// Filter by status
         #[arg(short, long)]
         status: Option<PlanStatus>,
+
+        /// Show at most N plans
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Fetch only this page of the server's list (page size: --limit)
+        #[arg(long, value_name = "N")]
+        page: Option<u64>,
     },
 
     /// Show plan details
@@ -197,188 +887,1199 @@ enum PlanCommands {
         file: Option<String>,
     },
 
-    /// Update a plan
-    Edit {
-        /// Plan ID
-        plan_id: String,
+    /// Update a plan
+    Edit {
+        /// Plan ID
+        plan_id: String,
+
+        /// New title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Set status (draft, ready, approved)
+        #[arg(short, long)]
+        status: Option<PlanStatus>,
+
+        /// Approve even if `plan lint` finds errors
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Approve a plan; with --sign, also store a gpg signature over its files
+    Approve {
+        /// Plan ID
+        plan_id: String,
+
+        /// Clearsign the files' checksums and store it as an approval-*.asc plan file
+        #[arg(long)]
+        sign: bool,
+
+        /// gpg key to sign with (default: gpg's default key)
+        #[arg(long, value_name = "KEYID", requires = "sign")]
+        key: Option<String>,
+
+        /// Approve even if `plan lint` finds errors
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check a plan's signed approvals against its current files
+    Verify {
+        /// Plan ID
+        plan_id: String,
+    },
+
+    /// Check a plan for missing sections, broken links, TODOs and missing acceptance criteria
+    Lint {
+        /// Plan ID
+        plan_id: String,
+
+        /// Judge the plan as if it had this status (default: its current one)
+        #[arg(short, long)]
+        status: Option<PlanStatus>,
+    },
+
+    /// Delete a plan
+    Rm {
+        /// Plan ID
+        plan_id: String,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// File operations within a plan
+    File {
+        #[command(subcommand)]
+        action: PlanFileCommands,
+    },
+
+    /// Create cards from the plan's task lists; `(after: other task)` becomes a dependency
+    Breakdown {
+        /// Plan ID
+        plan_id: String,
+
+        /// Only this file's tasks
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Column for the new cards
+        #[arg(
+            short,
+            long,
+            default_value = "todo",
+            value_parser = parsers::parse_column,
+            add = ArgValueCompleter::new(complete::columns)
+        )]
+        column: Column,
+
+        /// Tags for the new cards (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        tag: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlanFileCommands {
+    /// Add a file to a plan
+    Add {
+        /// Plan ID
+        plan_id: String,
+
+        /// Filename (e.g., "overview.md")
+        filename: String,
+    },
+
+    /// Show a file's content
+    Show {
+        /// Plan ID
+        plan_id: String,
+
+        /// Filename
+        filename: String,
+    },
+
+    /// Update a file
+    Edit {
+        /// Plan ID
+        plan_id: String,
+
+        /// Filename
+        filename: String,
+
+        /// Rename the file
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Set content (reads from stdin if not provided)
+        #[arg(long)]
+        content: Option<String>,
+
+        /// Merge with changes saved on the server while editing (opens $EDITOR if stdin is a terminal)
+        #[arg(long)]
+        watch_conflicts: bool,
+
+        /// File holding the version the edit started from (with --watch-conflicts; default: the copy
+        /// `plan file open` downloaded, or the server's current one)
+        #[arg(long, value_name = "PATH", requires = "watch_conflicts")]
+        base: Option<PathBuf>,
+    },
+
+    /// Download a file and open it with the default application
+    Open {
+        /// Plan ID
+        plan_id: String,
+
+        /// Filename
+        filename: String,
+    },
+
+    /// Remove a file from a plan
+    Rm {
+        /// Plan ID
+        plan_id: String,
+
+        /// Filename
+        filename: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PluginCommands {
+    /// List blaze-* executables on PATH
+    List,
+}
+
+#[derive(Subcommand)]
+enum ScriptCommands {
+    /// Run a Rhai script with bindings to list, get and update cards
+    Run {
+        /// Script file
+        file: PathBuf,
+
+        /// Arguments for the script (available as ARGS)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum NotifyCommands {
+    /// Route changes since the last run through the notify.toml rules
+    Run {
+        /// last, YYYY-MM-DD, an RFC 3339 timestamp, or an age like 30m, 2h, 7d
+        #[arg(long, value_parser = parsers::parse_since, default_value = "last")]
+        since: parsers::Since,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagCommands {
+    /// Add a tag to every matching card
+    Apply {
+        /// Tag name
+        tag: String,
+
+        /// Cards to change (same syntax as `list --filter`)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: parsers::Query,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Maximum number of cards updated at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+
+    /// Remove a tag from every matching card
+    Strip {
+        /// Tag name
+        tag: String,
+
+        /// Cards to change (same syntax as `list --filter`)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: parsers::Query,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Maximum number of cards updated at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum GoalCommands {
+    /// Add a goal (or replace one with the same ID)
+    Add {
+        /// Short ID, e.g. q4-onboarding
+        #[arg(value_parser = goals::parse_goal_id)]
+        id: String,
+
+        /// What the goal is
+        title: String,
+
+        /// Longer description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Target date (YYYY-MM-DD)
+        #[arg(long)]
+        due: Option<String>,
+    },
+
+    /// List goals
+    List,
+
+    /// Remove a goal (linked cards keep their tag)
+    Rm {
+        /// Goal ID
+        id: String,
+    },
+
+    /// Card completion per goal
+    Progress {
+        /// Only this goal
+        id: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CriteriaCommands {
+    /// List saved criteria sets
+    List,
+
+    /// Show a criteria set
+    Show {
+        /// Set name
+        name: String,
+    },
+
+    /// Save (or replace) a named criteria set
+    SaveSet {
+        /// Set name
+        #[arg(value_parser = criteria::parse_set_name)]
+        name: String,
+
+        /// Criterion (repeatable)
+        #[arg(short, long = "criterion")]
+        criteria: Vec<String>,
+
+        /// Copy the criteria of an existing card
+        #[arg(long, value_name = "CARD_ID", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        from_card: Option<String>,
+
+        /// Description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Append a set's criteria to a card (existing ones are skipped)
+    ApplySet {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Set name
+        name: String,
+    },
+
+    /// Turn a card's acceptance criteria into subtasks (checked ones are done)
+    ToSubtasks {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Turn a card's subtasks into acceptance criteria (done ones are checked)
+    FromSubtasks {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Remove a criteria set
+    RmSet {
+        /// Set name
+        name: String,
+    },
+
+    /// Print sets as JSON (all if no names are given)
+    Export {
+        /// Set names
+        names: Vec<String>,
+    },
+
+    /// Load sets printed by `criteria export` (from stdin if no file is given)
+    Import {
+        /// JSON file, or - for stdin
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DepCommands {
+    /// Make a card wait for others, or others wait for it
+    Add {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Card that can't be done before this one (repeatable)
+        #[arg(long, value_name = "ID", required_unless_present = "blocked_by", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocks: Vec<String>,
+
+        /// Card that must be done first (repeatable)
+        #[arg(long, value_name = "ID", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocked_by: Vec<String>,
+    },
+
+    /// Remove dependencies added with `dep add`
+    Rm {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Card that no longer waits for this one (repeatable)
+        #[arg(long, value_name = "ID", required_unless_present = "blocked_by", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocks: Vec<String>,
+
+        /// Card this one no longer waits for (repeatable)
+        #[arg(long, value_name = "ID", value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        blocked_by: Vec<String>,
+    },
+
+    /// Show what a card waits for and what waits for it
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LinkCommands {
+    /// Link a card to a URL (an existing link to it is updated)
+    Add {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Full URL
+        #[arg(value_name = "URL", value_parser = parsers::parse_link_url)]
+        link_url: String,
+
+        /// Text shown for the link
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// What it points at
+        #[arg(short, long, value_enum, default_value = "other")]
+        kind: LinkKind,
+    },
+
+    /// List a card's links
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Remove a link
+    Rm {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Index (from `link list`) or URL
+        link: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AttachmentCommands {
+    /// List a card's attachments
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Download an attachment
+    Get {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Attachment name (from `attachment list`)
+        name: String,
+
+        /// Directory or file to save to (default: the current directory)
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// Overwrite an existing file
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Download an attachment and open it with the default application
+    Open {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Attachment name (from `attachment list`)
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CommentCommands {
+    /// Comment on a card
+    Add {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Comment text
+        message: String,
+
+        /// Name to sign with (default: `author` in config.toml, else $USER)
+        #[arg(long)]
+        author: Option<String>,
+    },
+
+    /// List a card's comments, oldest first
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Delete a comment
+    Rm {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Comment ID (a unique prefix is enough)
+        comment_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ThreadCommands {
+    /// Start a thread
+    Start {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// What the thread is about
+        #[arg(long)]
+        topic: String,
+
+        /// Opening message
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Name to sign with (default: `author` in config.toml, else $USER)
+        #[arg(long)]
+        author: Option<String>,
+    },
+
+    /// Reply to a thread
+    Reply {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Thread ID (a unique prefix is enough)
+        thread_id: String,
+
+        /// Reply text
+        message: String,
+
+        /// Name to sign with (default: `author` in config.toml, else $USER)
+        #[arg(long)]
+        author: Option<String>,
+    },
+
+    /// Mark a thread resolved
+    Resolve {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Thread ID (a unique prefix is enough)
+        thread_id: String,
+
+        /// Open a resolved thread again
+        #[arg(long)]
+        reopen: bool,
+    },
+
+    /// List a card's threads, oldest first
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Only open threads
+        #[arg(long)]
+        unresolved: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TaskCommands {
+    /// Add a subtask
+    Add {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// What to do
+        title: String,
+    },
+
+    /// Mark a subtask done
+    Check {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Subtask index (0-based, from `task list`)
+        index: usize,
+
+        /// Mark it not done again
+        #[arg(long)]
+        undo: bool,
+    },
+
+    /// List a card's subtasks
+    List {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+    },
+
+    /// Remove a subtask
+    Rm {
+        /// Card ID
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: String,
+
+        /// Subtask index (0-based, from `task list`)
+        index: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommands {
+    /// List trashed cards, most recently deleted first
+    List,
+
+    /// Create a trashed card again (it gets a new ID)
+    Restore {
+        /// ID (or prefix) of the deleted card
+        #[arg(value_parser = parsers::parse_id_prefix)]
+        card_id: String,
+    },
+
+    /// Forget trashed cards for good
+    Purge {
+        /// Only cards deleted before this (a date, or an age like 30d)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time)]
+        older_than: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Skip the confirmation prompt (only asked when purging everything)
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ViewCommands {
+    /// List saved views
+    List,
+
+    /// Save a named filter query
+    Save {
+        /// View name
+        #[arg(value_parser = views::parse_view_name)]
+        name: String,
+
+        /// Filter query (same syntax as `list --filter`)
+        query: String,
+
+        /// Description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Show a saved view
+    Show {
+        /// View name
+        name: String,
+    },
+
+    /// Remove a saved view
+    Rm {
+        /// View name
+        name: String,
+    },
+
+    /// Share local views on the server (all if no name is given)
+    Push {
+        /// View name
+        name: Option<String>,
+    },
+
+    /// Fetch shared views from the server (all if no name is given)
+    Pull {
+        /// View name
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Longest dependency chain to completion, with slack for other cards
+    CriticalPath {
+        /// Estimate used for cards without one
+        #[arg(long, default_value_t = 1.0)]
+        default_estimate: f64,
+    },
+
+    /// Totals, overdue, blocked and throughput across boards, side by side
+    Rollup {
+        /// Profiles to compare (`default` is the top-level server; all configured if omitted)
+        #[arg(long, value_delimiter = ',', value_name = "PROFILE")]
+        boards: Vec<String>,
+
+        /// Count cards finished within this many days as throughput
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+
+    /// Priority raises and their reasons (from `edit --reason`), newest first
+    Escalations {
+        /// How far back to look
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
 
-        /// New title
-        #[arg(long)]
-        title: Option<String>,
+    /// Cards in progress or review per assignee, for standups
+    Wip {
+        /// Mark cards that have been in their column longer than this many days
+        #[arg(long, default_value_t = 3)]
+        stale_days: u32,
 
-        /// Set status (draft, ready, approved)
-        #[arg(short, long)]
-        status: Option<PlanStatus>,
+        /// Print a chat message to POST to a webhook instead (slack-blocks or slack-text)
+        #[arg(long, value_enum, value_name = "KIND")]
+        payload: Option<report::WipPayload>,
     },
+}
 
-    /// Delete a plan
-    Rm {
-        /// Plan ID
-        plan_id: String,
+#[derive(Subcommand)]
+enum MetricsCommands {
+    /// Append the board's current stats (run from cron to build up a history)
+    Snapshot,
 
-        /// Skip confirmation prompt
-        #[arg(short, long)]
-        force: bool,
-    },
+    /// Recorded stats over time for this board, or read-only SQL
+    Query {
+        /// Metrics to show: total, overdue, column:<name>, priority:<name> (total, overdue and columns if omitted)
+        #[arg(conflicts_with = "sql")]
+        metrics: Vec<String>,
 
-    /// File operations within a plan
-    File {
-        #[command(subcommand)]
-        action: PlanFileCommands,
+        /// Only snapshots since a date, timestamp or age (e.g. 2026-01-05, 30d)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time, conflicts_with = "sql")]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// One row per period: the last snapshot in it
+        #[arg(long, value_enum, value_name = "PERIOD", conflicts_with = "sql")]
+        every: Option<metrics::Every>,
+
+        /// Run this SQL against the database instead (tables: snapshots, counts)
+        #[arg(long)]
+        sql: Option<String>,
     },
 }
 
 #[derive(Subcommand)]
-enum PlanFileCommands {
-    /// Add a file to a plan
-    Add {
-        /// Plan ID
-        plan_id: String,
+enum SimulateCommands {
+    /// How accepting new work would shift when each priority class gets done
+    Intake {
+        /// Number of new cards
+        #[arg(long)]
+        new_cards: usize,
 
-        /// Filename (e.g., "overview.md")
-        filename: String,
+        /// Their estimate points in total (default: one default estimate per card)
+        #[arg(long)]
+        points: Option<f64>,
+
+        /// Priority the new cards would get
+        #[arg(long, default_value = "medium")]
+        priority: Priority,
+
+        /// Weeks of history to measure throughput over
+        #[arg(long, default_value_t = 8)]
+        weeks: u32,
+
+        /// Estimate used for cards without one
+        #[arg(long, default_value_t = 1.0)]
+        default_estimate: f64,
     },
+}
 
-    /// Show a file's content
-    Show {
-        /// Plan ID
-        plan_id: String,
+#[derive(Subcommand)]
+enum TeamCommands {
+    /// List the team's cards
+    List {
+        /// Narrow down further (same syntax as `list --filter`)
+        #[arg(short, long, value_parser = parsers::Query::parse)]
+        filter: Option<parsers::Query>,
 
-        /// Filename
-        filename: String,
+        /// Fields to show in any format (comma-separated, e.g. id,title,due,tags)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fields: Vec<output::CardField>,
+
+        /// Order by due, priority, created, updated or title
+        #[arg(long, value_enum, value_name = "KEY")]
+        sort: Option<list::SortKey>,
+
+        /// Reverse the --sort order
+        #[arg(long, requires = "sort")]
+        desc: bool,
+
+        /// Show at most N cards
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
     },
 
-    /// Update a file
-    Edit {
-        /// Plan ID
-        plan_id: String,
+    /// Card count per column, against the team's WIP limits
+    Board,
 
-        /// Filename
-        filename: String,
+    /// Statistics for the team's cards
+    Stats,
 
-        /// Rename the file
-        #[arg(long)]
-        name: Option<String>,
+    /// Create a card with the team's tags
+    Add {
+        /// Card title
+        title: String,
 
-        /// Set content (reads from stdin if not provided)
+        /// Card description
+        #[arg(short, long)]
+        desc: Option<String>,
+
+        /// Column to place the card in
+        #[arg(
+            short,
+            long,
+            default_value = "todo",
+            value_parser = parsers::parse_column,
+            add = ArgValueCompleter::new(complete::columns)
+        )]
+        column: Column,
+
+        /// Priority level
+        #[arg(short, long, default_value = "medium")]
+        priority: Priority,
+
+        /// More tags (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        tag: Vec<String>,
+
+        /// Due date (YYYY-MM-DD, or "YYYY-MM-DD HH:MM"; see --tz)
         #[arg(long)]
-        content: Option<String>,
+        due: Option<String>,
     },
+}
 
-    /// Remove a file from a plan
-    Rm {
-        /// Plan ID
-        plan_id: String,
-
-        /// Filename
-        filename: String,
+#[derive(Subcommand)]
+enum MaintenanceCommands {
+    /// Close or archive cards whose expiry (`--expires`) has passed
+    Expire {
+        /// What to do with expired cards
+        #[arg(long, value_enum, default_value_t)]
+        action: maintenance::ExpireAction,
     },
+
+    /// Set agent_assignable on every card as `[agent_rules]` say
+    AgentRules,
 }
 
 #[derive(Subcommand)]
-enum AgentCommands {
-    /// List cards ready for agent work
+enum BackupCommands {
+    /// Check checksums and parent links, and that the chain restores every card and plan
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// List cached downloads
     List,
 
+    /// Remove all cached downloads
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum AgentCommands {
+    /// List cards ready for agent work (excluding cards with unfinished dependencies)
+    List {
+        /// Show held-back cards and the cards blocking them instead
+        #[arg(long)]
+        show_blocked_by: bool,
+    },
+
     /// Start working on a card (sets status to in_progress)
     Start {
-        /// Card ID
-        card_id: String,
+        /// Card ID (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
     },
 
     /// Add a progress entry to a card
+    #[command(allow_missing_positional = true)]
     Progress {
-        /// Card ID
-        card_id: String,
+        /// Card ID (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
 
         /// Progress message
         message: String,
     },
 
     /// Mark card as blocked
+    #[command(allow_missing_positional = true)]
     Block {
-        /// Card ID
-        card_id: String,
+        /// Card ID (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
 
         /// Reason for blocking
         reason: String,
     },
 
     /// Complete work on a card (sets status to needs_review)
+    #[command(visible_alias = "review")]
     Done {
-        /// Card ID
-        card_id: String,
+        /// Card ID (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
     },
 
     /// Check/uncheck an acceptance criterion
+    #[command(allow_missing_positional = true)]
     Check {
-        /// Card ID
-        card_id: String,
+        /// Card ID (default: the current card, see `blaze use`)
+        #[arg(value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card_id: Option<String>,
 
         /// Criterion index (0-based)
         index: usize,
 
         /// Check (true) or uncheck (false)
-        #[arg(long, default_value = "true")]
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set, value_name = "BOOL")]
         checked: bool,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Interactive setup of server URL and token (into a profile with --profile)
+    Init,
+
+    /// Print the config, or one value (dotted key, e.g. profiles.work.url)
+    Get {
+        /// Key
+        key: Option<String>,
+    },
+
+    /// Set a value (dotted key, e.g. url or auth.store)
+    Set {
+        /// Key
+        key: String,
+
+        /// Value
+        value: String,
+    },
+
+    /// Remove a value
+    Unset {
+        /// Key
+        key: String,
+    },
+
+    /// Print the config file path
+    Path,
+}
+
+#[derive(Subcommand)]
+enum TimelineCommands {
+    /// Events, progress entries and comments, oldest first
+    Events {
+        /// Only this card (the whole board if omitted)
+        #[arg(long, value_parser = parsers::parse_id_prefix, add = ArgValueCompleter::new(complete::card_ids))]
+        card: Option<String>,
+
+        /// Only what happened since a date, timestamp or age (e.g. 2026-01-05, 7d)
+        #[arg(long, value_name = "WHEN", value_parser = parsers::parse_time)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SettingsCommands {
+    /// Write the local settings to a bundle file
+    Export {
+        /// JSON file, or - for stdout
+        file: PathBuf,
+    },
+
+    /// Merge a bundle into the local settings, replacing entries with the same name
+    Import {
+        /// JSON file, or - for stdin
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonCommands {
+    /// Start the daemon in the background
+    Start,
+
+    /// Stop the running daemon
+    Stop,
+
+    /// Show daemon status
+    Status,
+
+    /// Run the daemon in the foreground
+    #[command(hide = true)]
+    Run,
+}
+
+fn main() {
+    // `COMPLETE=<shell> blaze -- ...` is the completion script asking for candidates
+    CompleteEnv::with_factory(Cli::command).complete();
+    start();
+}
+
 #[tokio::main]
-async fn main() {
-    if let Err(e) = run().await {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+async fn start() {
+    match run().await {
+        // The request was printed (or queued) instead of sent
+        Ok(()) | Err(error::BlazeError::DryRun | error::BlazeError::Queued) => {}
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
     }
 }
 
 async fn run() -> error::Result<()> {
     let cli = Cli::parse();
+    output::configure_json(cli.global.format, cli.global.query.clone());
+    if cli.global.progress_json {
+        progress::enable();
+    }
+    if cli.global.format == OutputFormat::Table && !cli.global.no_pager {
+        pager::enable();
+    }
+
+    // Config commands must work even when the config can't produce a working client
+    if let Commands::Config { action } = cli.command {
+        return run_config(action, cli.global.profile.as_deref(), cli.global.format).await;
+    }
+    if let Commands::Settings { action } = cli.command {
+        return run_settings(action, cli.global.dry_run);
+    }
+    if let Commands::Completions { shell } = cli.command {
+        return completions::run(shell);
+    }
+    if let Commands::Plugin { action } = cli.command {
+        return match action {
+            PluginCommands::List => plugin::list(cli.global.format),
+        };
+    }
+    if let Commands::External(args) = cli.command {
+        let code = plugin::run(&args, &plugin::PluginEnv::from_args(&cli.global)?)?;
+        if code != 0 {
+            std::process::exit(code);
+        }
+        return Ok(());
+    }
+
+    let mut ctx = CommandContext::new(cli.global).await?;
+    if let Commands::Repl = cli.command {
+        return repl::run(&mut ctx).await;
+    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    ctx.begin_operation(&journal::command_line(&args));
+    dispatch(&ctx, cli.command).await
+}
+
+async fn run_config(action: ConfigCommands, profile: Option<&str>, format: OutputFormat) -> error::Result<()> {
+    match action {
+        ConfigCommands::Init => config_cmd::init(profile).await,
+        ConfigCommands::Get { key } => config_cmd::get(format, key.as_deref()),
+        ConfigCommands::Set { key, value } => config_cmd::set(&key, &value),
+        ConfigCommands::Unset { key } => config_cmd::unset(&key),
+        ConfigCommands::Path => config_cmd::path(),
+    }
+}
 
-    // Load config, with CLI args taking precedence
-    let cfg = config::Config::load()?;
-    let url = cli
-        .url
-        .or(cfg.url)
-        .unwrap_or_else(|| "http://localhost:8080".to_string());
-    let token = cli.token.or(cfg.token);
+fn run_settings(action: SettingsCommands, dry_run: bool) -> error::Result<()> {
+    match action {
+        SettingsCommands::Export { file } => settings::export(&file),
+        SettingsCommands::Import { file } => settings::import(&file, dry_run),
+    }
+}
 
-    match cli.command {
-        Commands::Ping => ping::run(&url).await,
+/// Run one command with an existing context (`blaze repl` calls this once per line)
+async fn dispatch(ctx: &CommandContext, command: Commands) -> error::Result<()> {
+    match command {
+        Commands::Config { .. }
+        | Commands::Settings { .. }
+        | Commands::Completions { .. }
+        | Commands::Plugin { .. }
+        | Commands::External(_)
+        | Commands::Repl => {
+            unreachable!("handled before dispatch")
+        }
+        Commands::Ping { capabilities } => ping::run(ctx, capabilities).await,
+        Commands::Login { with_token } => login::login(ctx, with_token).await,
+        Commands::Whoami => login::whoami(ctx).await,
 
         Commands::List {
             column,
             priority,
             tag,
             overdue,
+            updated_since,
             include_archived,
+            filter,
+            view,
+            assignee,
+            mine,
+            unresolved_threads,
+            ready,
+            blocked,
+            unblocked,
+            show_blocked_by,
+            fields,
+            sort,
+            desc,
+            limit,
+            page,
         } => {
-            let client = client::Client::new(&url, token)?;
+            let query = match view {
+                Some(name) => {
+                    let saved = views::ViewStore::load()?.query(&name)?;
+                    Some(match filter {
+                        Some(f) => parsers::Query::And(Box::new(saved), Box::new(f)),
+                        None => saved,
+                    })
+                }
+                None => filter,
+            };
             let filters = list::ListFilters {
                 column,
                 priorities: priority,
                 tags: tag,
                 overdue,
+                updated_since,
                 include_archived,
+                query,
+                assignee,
+                mine,
+                unresolved_threads,
+                ready,
+                blocked,
+                unblocked,
+                show_blocked_by,
+                fields,
+                sort,
+                desc,
+                limit,
+                page,
             };
-            list::run(&client, filters).await
+            list::run(ctx, filters).await
         }
 
-        Commands::Show { card_id } => {
-            let client = client::Client::new(&url, token)?;
-            show::run(&client, &card_id).await
+        Commands::Show { card_id, comments } => {
+            show::run(ctx, &card_id, comments).await
         }
 
-        Commands::Board => {
-            let client = client::Client::new(&url, token)?;
-            board::run(&client).await
+        Commands::Board { by } => {
+            board::run(ctx, by).await
+        }
+        Commands::Focus { entries } => {
+            focus::run(ctx, entries).await
         }
 
-        Commands::Stats => {
-            let client = client::Client::new(&url, token)?;
-            stats::run(&client).await
+        Commands::Stats { from_store } => {
+            stats::run(ctx, from_store).await
+        }
+        Commands::Analytics { since, until, cards } => {
+            analytics::run(ctx, since, until, cards).await
         }
 
+        Commands::Team { name, action } => match action {
+            TeamCommands::List {
+                filter,
+                fields,
+                sort,
+                desc,
+                limit,
+            } => {
+                let filters = list::ListFilters {
+                    query: filter,
+                    fields,
+                    sort,
+                    desc,
+                    limit,
+                    ..list::ListFilters::default()
+                };
+                team::list(ctx, &name, filters).await
+            }
+            TeamCommands::Board => team::board(ctx, &name).await,
+            TeamCommands::Stats => team::stats(ctx, &name).await,
+            TeamCommands::Add {
+                title,
+                desc,
+                column,
+                priority,
+                tag,
+                due,
+            } => {
+                let options = add::AddOptions {
+                    title,
+                    description: desc,
+                    column,
+                    priority,
+                    tags: tag,
+                    due,
+                    agent: false,
+                    criteria: Vec::new(),
+                    blocked_by: Vec::new(),
+                    estimate: None,
+                    expires: None,
+                    assignee: None,
+                };
+                team::add(ctx, &name, options).await
+            }
+        },
+
         Commands::Add {
             title,
             desc,
@@ -386,21 +2087,52 @@ async fn run() -> error::Result<()> {
             priority,
             tag,
             due,
+            agent,
+            criteria,
+            blocked_by,
+            estimate,
+            expires,
+            assignee,
+            batch,
+            interactive,
         } => {
-            let client = client::Client::new(&url, token)?;
+            if batch {
+                return add::run_batch(ctx).await;
+            }
             let options = add::AddOptions {
-                title,
+                // Required by clap unless --batch or --interactive
+                title: title.unwrap_or_default(),
                 description: desc,
                 column,
                 priority,
                 tags: tag,
                 due,
+                agent,
+                criteria,
+                blocked_by,
+                estimate,
+                expires,
+                assignee,
             };
-            add::run(&client, options).await
+            if interactive {
+                return wizard::run(ctx, options).await;
+            }
+            add::run(ctx, options).await
+        }
+
+        Commands::Quick {
+            text,
+            stdin,
+            concurrency,
+        } => {
+            if stdin {
+                return quick::run_stdin(ctx, concurrency).await;
+            }
+            quick::run(ctx, &text.join(" ")).await
         }
 
         Commands::Edit {
-            card_id,
+            card_ids,
             title,
             desc,
             column,
@@ -409,10 +2141,18 @@ async fn run() -> error::Result<()> {
             tags_remove,
             due,
             clear_due,
+            agent,
+            criteria_add,
+            blocked_by_add,
+            estimate,
+            expires,
+            goal,
+            reason,
+            assignee,
+            unassign,
         } => {
-            let client = client::Client::new(&url, token)?;
             let options = edit::EditOptions {
-                card_id,
+                card_ids,
                 title,
                 description: desc,
                 column,
@@ -421,73 +2161,488 @@ async fn run() -> error::Result<()> {
                 tags_remove,
                 due,
                 clear_due,
+                agent,
+                criteria_add,
+                blocked_by_add,
+                estimate,
+                expires,
+                goal,
+                reason,
+                assignee,
+                unassign,
             };
-            edit::run(&client, options).await
+            edit::run(ctx, options).await
         }
 
-        Commands::Move { card_id, column } => {
-            let client = client::Client::new(&url, token)?;
-            move_card::run(&client, &card_id, column).await
+        Commands::Move { card_ids, column, force } => {
+            move_card::run(ctx, &card_ids, column, force).await
         }
 
-        Commands::Done { card_id } => {
-            let client = client::Client::new(&url, token)?;
-            move_card::run_done(&client, &card_id).await
+        Commands::Done { card_ids, force } => {
+            let card_ids = if card_ids.is_empty() { vec![use_card::or_current(ctx, None)?] } else { card_ids };
+            move_card::run_done(ctx, &card_ids, force).await
         }
 
-        Commands::Rm { card_id, force } => {
-            let client = client::Client::new(&url, token)?;
-            rm::run(&client, &card_id, force).await
+        Commands::Use { card_id, clear } => match card_id {
+            Some(card_id) => use_card::set(ctx, &card_id).await,
+            None if clear => use_card::clear(ctx),
+            None => use_card::show(ctx).await,
+        },
+
+        Commands::Rm {
+            card_ids,
+            force,
+            permanent,
+        } => rm::run(ctx, &card_ids, force, permanent).await,
+
+        Commands::Dep { action } => match action {
+            DepCommands::Add {
+                card_id,
+                blocks,
+                blocked_by,
+            } => dep::add(ctx, &card_id, &blocks, &blocked_by).await,
+            DepCommands::Rm {
+                card_id,
+                blocks,
+                blocked_by,
+            } => dep::rm(ctx, &card_id, &blocks, &blocked_by).await,
+            DepCommands::List { card_id } => dep::list(ctx, &card_id).await,
+        },
+
+        Commands::Link { action } => match action {
+            LinkCommands::Add {
+                card_id,
+                link_url,
+                title,
+                kind,
+            } => link::add(ctx, &card_id, link_url, title, kind).await,
+            LinkCommands::List { card_id } => link::list(ctx, &card_id).await,
+            LinkCommands::Rm { card_id, link } => link::rm(ctx, &card_id, &link).await,
+        },
+
+        Commands::Attach { card_id, files } => attachment::attach(ctx, &card_id, &files).await,
+
+        Commands::Attachment { action } => match action {
+            AttachmentCommands::List { card_id } => attachment::list(ctx, &card_id).await,
+            AttachmentCommands::Get {
+                card_id,
+                name,
+                output,
+                force,
+            } => attachment::get(ctx, &card_id, &name, output, force).await,
+            AttachmentCommands::Open { card_id, name } => attachment::open(ctx, &card_id, &name).await,
+        },
+
+        Commands::Comment { action } => match action {
+            CommentCommands::Add {
+                card_id,
+                message,
+                author,
+            } => comment::add(ctx, &card_id, &message, author).await,
+            CommentCommands::List { card_id } => comment::list(ctx, &card_id).await,
+            CommentCommands::Rm { card_id, comment_id } => comment::rm(ctx, &card_id, &comment_id).await,
+        },
+
+        Commands::Thread { action } => match action {
+            ThreadCommands::Start {
+                card_id,
+                topic,
+                message,
+                author,
+            } => thread::start(ctx, &card_id, &topic, message, author).await,
+            ThreadCommands::Reply {
+                card_id,
+                thread_id,
+                message,
+                author,
+            } => thread::reply(ctx, &card_id, &thread_id, &message, author).await,
+            ThreadCommands::Resolve {
+                card_id,
+                thread_id,
+                reopen,
+            } => thread::resolve(ctx, &card_id, &thread_id, reopen).await,
+            ThreadCommands::List { card_id, unresolved } => thread::list(ctx, &card_id, unresolved).await,
+        },
+
+        Commands::Task { action } => match action {
+            TaskCommands::Add { card_id, title } => task::add(ctx, &card_id, &title).await,
+            TaskCommands::Check { card_id, index, undo } => task::check(ctx, &card_id, index, !undo).await,
+            TaskCommands::List { card_id } => task::list(ctx, &card_id).await,
+            TaskCommands::Rm { card_id, index } => task::rm(ctx, &card_id, index).await,
+        },
+
+        Commands::Trash { action } => match action {
+            TrashCommands::List => trash::list(ctx).await,
+            TrashCommands::Restore { card_id } => trash::restore(ctx, &card_id).await,
+            TrashCommands::Purge { older_than, force } => trash::purge(ctx, older_than, force).await,
+        },
+
+        Commands::Undo { list, limit, force } => {
+            if list {
+                undo::list(ctx, limit)
+            } else {
+                undo::run(ctx, force).await
+            }
         }
 
         Commands::Plan { action } => {
-            let client = client::Client::new(&url, token)?;
             match action {
-                PlanCommands::List { status } => plan::list(&client, status).await,
-                PlanCommands::Show { plan_id } => plan::show(&client, &plan_id).await,
-                PlanCommands::Add { title, file } => plan::add(&client, title, file).await,
+                PlanCommands::List { status, limit, page } => {
+                    plan::list(ctx, status, client::PageRequest { limit, page }).await
+                }
+                PlanCommands::Show { plan_id } => plan::show(ctx, &plan_id).await,
+                PlanCommands::Add { title, file } => plan::add(ctx, title, file).await,
                 PlanCommands::Edit {
                     plan_id,
                     title,
                     status,
-                } => plan::edit(&client, &plan_id, title, status).await,
-                PlanCommands::Rm { plan_id, force } => plan::rm(&client, &plan_id, force).await,
+                    force,
+                } => plan::edit(ctx, &plan_id, title, status, force).await,
+                PlanCommands::Approve {
+                    plan_id,
+                    sign,
+                    key,
+                    force,
+                } => plan::approve(ctx, &plan_id, sign, key.as_deref(), force).await,
+                PlanCommands::Verify { plan_id } => plan::verify(ctx, &plan_id).await,
+                PlanCommands::Lint { plan_id, status } => plan::lint(ctx, &plan_id, status).await,
+                PlanCommands::Rm { plan_id, force } => plan::rm(ctx, &plan_id, force).await,
+                PlanCommands::Breakdown {
+                    plan_id,
+                    file,
+                    column,
+                    tag,
+                } => {
+                    let options = plan::BreakdownOptions {
+                        plan_id,
+                        file,
+                        column,
+                        tags: tag,
+                    };
+                    plan::breakdown(ctx, options).await
+                }
                 PlanCommands::File { action } => match action {
                     PlanFileCommands::Add { plan_id, filename } => {
-                        plan::file_add(&client, &plan_id, &filename).await
+                        plan::file_add(ctx, &plan_id, &filename).await
                     }
                     PlanFileCommands::Show { plan_id, filename } => {
-                        plan::file_show(&client, &plan_id, &filename).await
+                        plan::file_show(ctx, &plan_id, &filename).await
                     }
                     PlanFileCommands::Edit {
                         plan_id,
                         filename,
                         name,
                         content,
-                    } => plan::file_edit(&client, &plan_id, &filename, name, content).await,
+                        watch_conflicts,
+                        base,
+                    } => {
+                        let options = plan::FileEditOptions {
+                            new_name: name,
+                            content,
+                            base,
+                            watch_conflicts,
+                        };
+                        plan::file_edit(ctx, &plan_id, &filename, options).await
+                    }
+                    PlanFileCommands::Open { plan_id, filename } => {
+                        plan::file_open(ctx, &plan_id, &filename).await
+                    }
                     PlanFileCommands::Rm { plan_id, filename } => {
-                        plan::file_rm(&client, &plan_id, &filename).await
+                        plan::file_rm(ctx, &plan_id, &filename).await
                     }
                 },
             }
         }
 
+        Commands::Tag { action } => match action {
+            TagCommands::Apply {
+                tag,
+                filter,
+                yes,
+                concurrency,
+            } => {
+                let options = tag::TagOptions {
+                    tag,
+                    filter,
+                    yes,
+                    concurrency,
+                };
+                tag::apply(ctx, options).await
+            }
+            TagCommands::Strip {
+                tag,
+                filter,
+                yes,
+                concurrency,
+            } => {
+                let options = tag::TagOptions {
+                    tag,
+                    filter,
+                    yes,
+                    concurrency,
+                };
+                tag::strip(ctx, options).await
+            }
+        },
+
+        Commands::Poker {
+            card_id,
+            participants,
+            estimate,
+        } => {
+            let options = poker::PokerOptions {
+                card_id,
+                participants,
+                estimate,
+            };
+            poker::run(ctx, options).await
+        }
+
+        Commands::Replace {
+            find,
+            replacement,
+            fields,
+            filter,
+            yes,
+            concurrency,
+        } => {
+            let options = replace::ReplaceOptions {
+                find,
+                replace: replacement,
+                fields,
+                filter,
+                yes,
+                concurrency,
+            };
+            replace::run(ctx, options).await
+        }
+
+        Commands::Goal { action } => match action {
+            GoalCommands::Add {
+                id,
+                title,
+                description,
+                due,
+            } => goal::add(ctx, id, title, description, due.as_deref()).await,
+            GoalCommands::List => goal::list(ctx).await,
+            GoalCommands::Rm { id } => goal::rm(&id).await,
+            GoalCommands::Progress { id } => goal::progress(ctx, id.as_deref()).await,
+        },
+
+        Commands::Criteria { action } => match action {
+            CriteriaCommands::List => criteria_cmd::list(ctx).await,
+            CriteriaCommands::Show { name } => criteria_cmd::show(ctx, &name).await,
+            CriteriaCommands::SaveSet {
+                name,
+                criteria,
+                from_card,
+                description,
+            } => criteria_cmd::save_set(ctx, name, criteria, from_card.as_deref(), description).await,
+            CriteriaCommands::ApplySet { card_id, name } => criteria_cmd::apply_set(ctx, &card_id, &name).await,
+            CriteriaCommands::ToSubtasks { card_id } => criteria_cmd::to_subtasks(ctx, &card_id).await,
+            CriteriaCommands::FromSubtasks { card_id } => criteria_cmd::from_subtasks(ctx, &card_id).await,
+            CriteriaCommands::RmSet { name } => criteria_cmd::rm_set(&name).await,
+            CriteriaCommands::Export { names } => criteria_cmd::export(&names).await,
+            CriteriaCommands::Import { file } => criteria_cmd::import(file.as_deref()).await,
+        },
+
+        Commands::View { action } => match action {
+            ViewCommands::List => view::list(ctx).await,
+            ViewCommands::Save {
+                name,
+                query,
+                description,
+            } => view::save(ctx, name, query, description).await,
+            ViewCommands::Show { name } => view::show(ctx, &name).await,
+            ViewCommands::Rm { name } => view::rm(&name).await,
+            ViewCommands::Push { name } => view::push(ctx, name.as_deref()).await,
+            ViewCommands::Pull { name } => view::pull(ctx, name.as_deref()).await,
+        },
+
         Commands::Agent { action } => {
-            let client = client::Client::new(&url, token)?;
             match action {
-                AgentCommands::List => agent::list(&client).await,
-                AgentCommands::Start { card_id } => agent::start(&client, &card_id).await,
+                AgentCommands::List { show_blocked_by } => agent::list(ctx, show_blocked_by).await,
+                AgentCommands::Start { card_id } => agent::start(ctx, &use_card::or_current(ctx, card_id)?).await,
                 AgentCommands::Progress { card_id, message } => {
-                    agent::progress(&client, &card_id, &message).await
+                    agent::progress(ctx, &use_card::or_current(ctx, card_id)?, &message).await
                 }
                 AgentCommands::Block { card_id, reason } => {
-                    agent::block(&client, &card_id, &reason).await
+                    agent::block(ctx, &use_card::or_current(ctx, card_id)?, &reason).await
                 }
-                AgentCommands::Done { card_id } => agent::done(&client, &card_id).await,
+                AgentCommands::Done { card_id } => agent::done(ctx, &use_card::or_current(ctx, card_id)?).await,
                 AgentCommands::Check { card_id, index, checked } => {
-                    agent::check(&client, &card_id, index, checked).await
+                    agent::check(ctx, &use_card::or_current(ctx, card_id)?, index, checked).await
                 }
             }
         }
+
+        Commands::Changes { since } => changes_cmd::run(ctx, since).await,
+
+        Commands::Watch { filter, interval } => watch::run(ctx, filter, interval).await,
+
+        Commands::Events { filter, transport } => {
+            events::run(ctx, events::EventsOptions { filters: filter, transport }).await
+        }
+
+        Commands::Timeline { action } => match action {
+            TimelineCommands::Events { card, since } => timeline::events(ctx, card.as_deref(), since).await,
+        },
+
+        Commands::Bot { transport } => bot::run(ctx, transport).await,
+
+        Commands::Sync {
+            target: Some(SyncCommands::GithubProject {
+                project,
+                conflicts,
+                filter,
+                watch,
+            }),
+            ..
+        } => {
+            let options = github_project::ProjectSyncOptions {
+                project,
+                conflicts,
+                filter,
+                watch,
+            };
+            github_project::run(ctx, options).await
+        }
+        Commands::Sync {
+            target: None,
+            list,
+            force,
+            drop,
+        } => sync::run(ctx, sync::SyncOptions { list, force, drop }).await,
+
+        Commands::Bench {
+            requests,
+            concurrency,
+            ops,
+        } => {
+            let options = bench::BenchOptions {
+                requests,
+                concurrency,
+                ops,
+            };
+            bench::run(ctx, options).await
+        }
+
+        Commands::Bootstrap { template, plan, force } => {
+            bootstrap::run(ctx, bootstrap::BootstrapOptions { template, plan, force }).await
+        }
+
+        Commands::Export {
+            plans,
+            include_archived,
+            yaml,
+            output,
+        } => {
+            let options = export::ExportOptions {
+                plans,
+                include_archived,
+                yaml,
+                output,
+            };
+            export::export(ctx, options).await
+        }
+
+        Commands::Import { file, skip_plans } => export::import(ctx, file.as_deref(), skip_plans).await,
+
+        Commands::Backup {
+            action,
+            incremental,
+            dir,
+        } => match action {
+            Some(BackupCommands::Verify) => backup::verify(ctx, dir).await,
+            None => backup::run(ctx, backup::BackupOptions { incremental, dir }).await,
+        },
+
+        Commands::Notify { action } => match action {
+            NotifyCommands::Run { since } => notify_cmd::run(ctx, since).await,
+        },
+
+        Commands::Notifyd {
+            interval,
+            once,
+            detach,
+            stop,
+        } => {
+            if stop {
+                notifyd::stop()
+            } else if detach {
+                notifyd::detach(ctx, interval)
+            } else {
+                notifyd::run(ctx, interval, once).await
+            }
+        }
+
+        Commands::Publish { listen, refresh } => publish_cmd::run(ctx, &listen, refresh).await,
+
+        Commands::Script { action } => match action {
+            ScriptCommands::Run { file, args } => script_cmd::run(ctx, &file, args).await,
+        },
+
+        Commands::Maintenance { action } => match action {
+            MaintenanceCommands::Expire { action } => maintenance::expire(ctx, action).await,
+            MaintenanceCommands::AgentRules => maintenance::agent_rules(ctx).await,
+        },
+
+        Commands::Simulate { action } => match action {
+            SimulateCommands::Intake {
+                new_cards,
+                points,
+                priority,
+                weeks,
+                default_estimate,
+            } => {
+                let intake = simulate::Intake {
+                    new_cards,
+                    points,
+                    priority,
+                    weeks,
+                    default_estimate,
+                };
+                simulate::intake(ctx, intake).await
+            }
+        },
+
+        Commands::Metrics { action, db } => match action {
+            MetricsCommands::Snapshot => metrics_cmd::snapshot(ctx, db).await,
+            MetricsCommands::Query {
+                metrics,
+                since,
+                every,
+                sql,
+            } => {
+                let query = metrics_cmd::MetricsQuery {
+                    metrics,
+                    since,
+                    every,
+                    sql,
+                };
+                metrics_cmd::query(ctx, db, query)
+            }
+        },
+
+        Commands::Report { action } => match action {
+            ReportCommands::CriticalPath { default_estimate } => {
+                report::critical_path(ctx, default_estimate).await
+            }
+            ReportCommands::Rollup { boards, days } => report::rollup(ctx, &boards, days).await,
+            ReportCommands::Escalations { days } => report::escalations(ctx, days).await,
+            ReportCommands::Wip { stale_days, payload } => report::wip(ctx, stale_days, payload).await,
+        },
+
+        Commands::Cache { action } => match action {
+            CacheCommands::List => cache_cmd::list(ctx).await,
+            CacheCommands::Clear => cache_cmd::clear(ctx).await,
+        },
+
+        Commands::Daemon { action } => match action {
+            DaemonCommands::Start => daemon_cmd::start(ctx).await,
+            DaemonCommands::Stop => daemon_cmd::stop().await,
+            DaemonCommands::Status => daemon_cmd::status().await,
+            DaemonCommands::Run => daemon_cmd::run(ctx).await,
+        },
     }
 }
@@ -0,0 +1,223 @@
+//! Line-based three-way merge (diff3) for plan file edits.
+//!
+//! Given the version an edit started from (`base`), the edited text (`local`)
+//! and the current server text (`remote`), changes made on only one side are
+//! applied automatically. Regions changed differently on both sides are
+//! reported as conflicts for the caller to resolve.
+
+/// A region of merged output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chunk {
+    /// Lines that merged cleanly
+    Resolved(Vec<String>),
+    /// Lines changed differently on both sides
+    Conflict {
+        base: Vec<String>,
+        local: Vec<String>,
+        remote: Vec<String>,
+    },
+}
+
+/// Merge `local` and `remote`, both derived from `base`
+pub fn merge(base: &str, local: &str, remote: &str) -> Vec<Chunk> {
+    let base = lines(base);
+    let local = lines(local);
+    let remote = lines(remote);
+
+    let to_local = match_lines(&base, &local);
+    let to_remote = match_lines(&base, &remote);
+
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let (mut o, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // Lines unchanged on both sides
+        let mut run = 0;
+        while o + run < base.len()
+            && to_local[o + run] == Some(a + run)
+            && to_remote[o + run] == Some(b + run)
+        {
+            run += 1;
+        }
+        if run > 0 {
+            push_resolved(&mut chunks, &base[o..o + run]);
+            o += run;
+            a += run;
+            b += run;
+            continue;
+        }
+
+        // Next base line kept by both sides, or the end of all three
+        let next = (o..base.len()).find(|&i| to_local[i].is_some() && to_remote[i].is_some());
+        let (o_end, a_end, b_end) = match next {
+            Some(i) => (i, to_local[i].unwrap(), to_remote[i].unwrap()),
+            None => (base.len(), local.len(), remote.len()),
+        };
+
+        let (base_part, local_part, remote_part) = (&base[o..o_end], &local[a..a_end], &remote[b..b_end]);
+        if local_part == base_part || local_part == remote_part {
+            push_resolved(&mut chunks, remote_part);
+        } else if remote_part == base_part {
+            push_resolved(&mut chunks, local_part);
+        } else {
+            chunks.push(Chunk::Conflict {
+                base: base_part.to_vec(),
+                local: local_part.to_vec(),
+                remote: remote_part.to_vec(),
+            });
+        }
+
+        if next.is_none() {
+            break;
+        }
+        o = o_end;
+        a = a_end;
+        b = b_end;
+    }
+
+    chunks
+}
+
+/// Number of conflicts in a merge result
+pub fn conflict_count(chunks: &[Chunk]) -> usize {
+    chunks.iter().filter(|c| matches!(c, Chunk::Conflict { .. })).count()
+}
+
+/// Join resolved chunks back into text, or `None` if any conflict remains
+pub fn merged_text(chunks: &[Chunk]) -> Option<String> {
+    let mut text = String::new();
+    for chunk in chunks {
+        match chunk {
+            Chunk::Resolved(lines) => lines.iter().for_each(|l| text.push_str(l)),
+            Chunk::Conflict { .. } => return None,
+        }
+    }
+    Some(text)
+}
+
+/// Render a conflict with git-style markers
+pub fn conflict_markers(base: &[String], local: &[String], remote: &[String]) -> String {
+    let mut out = String::new();
+    let mut section = |marker: &str, lines: &[String]| {
+        out.push_str(marker);
+        out.push('\n');
+        for line in lines {
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+    };
+    section("<<<<<<< local", local);
+    section("||||||| base", base);
+    section("=======", remote);
+    out.push_str(">>>>>>> remote\n");
+    out
+}
+
+fn push_resolved(chunks: &mut Vec<Chunk>, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    }
+    match chunks.last_mut() {
+        Some(Chunk::Resolved(prev)) => prev.extend_from_slice(lines),
+        _ => chunks.push(Chunk::Resolved(lines.to_vec())),
+    }
+}
+
+/// Split into lines, keeping line endings so the text round-trips exactly
+fn lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(String::from).collect()
+}
+
+/// For each line of `a`, the index of the matching line in `b` (longest common subsequence)
+fn match_lines(a: &[String], b: &[String]) -> Vec<Option<usize>> {
+    let (n, m) = (a.len(), b.len());
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(base: &str, local: &str, remote: &str) -> String {
+        merged_text(&merge(base, local, remote)).expect("expected a clean merge")
+    }
+
+    #[test]
+    fn one_sided_changes_apply() {
+        let base = "a\nb\nc\n";
+        assert_eq!(clean(base, base, base), base);
+        assert_eq!(clean(base, "a\nB\nc\n", base), "a\nB\nc\n");
+        assert_eq!(clean(base, base, "a\nb\nc\nd\n"), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn non_overlapping_changes_combine() {
+        let base = "# Plan\n\nintro\n\n## Steps\n1. one\n2. two\n";
+        let local = "# Plan\n\nintro, revised\n\n## Steps\n1. one\n2. two\n";
+        let remote = "# Plan\n\nintro\n\n## Steps\n1. one\n2. two\n3. three\n";
+        assert_eq!(
+            clean(base, local, remote),
+            "# Plan\n\nintro, revised\n\n## Steps\n1. one\n2. two\n3. three\n"
+        );
+    }
+
+    #[test]
+    fn identical_changes_are_not_conflicts() {
+        assert_eq!(clean("a\nb\n", "a\nx\n", "a\nx\n"), "a\nx\n");
+    }
+
+    #[test]
+    fn missing_final_newline_round_trips() {
+        assert_eq!(clean("a\nb", "z\na\nb", "a\nb"), "z\na\nb");
+    }
+
+    #[test]
+    fn overlapping_changes_conflict() {
+        let chunks = merge("a\nb\nc\n", "a\nlocal\nc\n", "a\nremote\nc\n");
+        assert_eq!(conflict_count(&chunks), 1);
+        assert_eq!(merged_text(&chunks), None);
+        assert_eq!(
+            chunks[1],
+            Chunk::Conflict {
+                base: vec!["b\n".into()],
+                local: vec!["local\n".into()],
+                remote: vec!["remote\n".into()],
+            }
+        );
+        assert_eq!(chunks[0], Chunk::Resolved(vec!["a\n".into()]));
+        assert_eq!(chunks[2], Chunk::Resolved(vec!["c\n".into()]));
+    }
+
+    #[test]
+    fn markers_terminate_lines() {
+        let marked = conflict_markers(&["b".into()], &["l\n".into()], &[]);
+        assert_eq!(marked, "<<<<<<< local\nl\n||||||| base\nb\n=======\n>>>>>>> remote\n");
+    }
+}
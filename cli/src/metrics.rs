@@ -0,0 +1,278 @@
+//! Board metrics history in SQLite.
+//!
+//! `blaze metrics snapshot` appends the board's stats to a database of your
+//! choosing (`[metrics] db`, by default ~/.config/blaze/metrics.sqlite3), so trends build up
+//! locally from cron even when the server keeps no history. Each snapshot is
+//! one row of `snapshots`; its per-column and per-priority counts are rows
+//! of `counts`, so boards with their own columns fit without schema changes:
+//!
+//! ```sql
+//! SELECT s.taken_at, c.count FROM snapshots s JOIN counts c ON c.snapshot_id = s.id
+//! WHERE c.kind = 'column' AND c.name = 'in_progress';
+//! ```
+//!
+//! Snapshots are tagged with the board (profile name, else server URL), so
+//! one database can track several boards.
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::types::BoardStats;
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        board TEXT NOT NULL,
+        taken_at TEXT NOT NULL,
+        total INTEGER NOT NULL,
+        overdue INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS snapshots_board ON snapshots (board, taken_at);
+    CREATE TABLE IF NOT EXISTS counts (
+        snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+        kind TEXT NOT NULL,
+        name TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        PRIMARY KEY (snapshot_id, kind, name)
+    );
+";
+
+/// Default database location
+pub fn default_path() -> Option<PathBuf> {
+    Config::dir().map(|d| d.join("metrics.sqlite3"))
+}
+
+/// One snapshot with its metrics by name (`total`, `overdue`, `column:done`, ...)
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub metrics: BTreeMap<String, i64>,
+}
+
+/// How `history` thins out snapshots: the last one in each period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Every {
+    Day,
+    Week,
+    Month,
+}
+
+impl Every {
+    /// Label of the period `at` falls in, in `tz`
+    pub fn period(self, at: DateTime<Utc>, tz: Tz) -> String {
+        let local = at.with_timezone(&tz);
+        match self {
+            Every::Day => local.format("%Y-%m-%d").to_string(),
+            Every::Week => {
+                let week = local.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Every::Month => local.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// A metrics database
+pub struct MetricsDb {
+    conn: Connection,
+}
+
+impl MetricsDb {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path).map_err(|e| db_error(path, e))?;
+        conn.execute_batch(SCHEMA).map_err(|e| db_error(path, e))?;
+        conn.execute_batch("PRAGMA foreign_keys = ON").map_err(|e| db_error(path, e))?;
+        Ok(Self { conn })
+    }
+
+    /// Open an existing database without write access, for ad-hoc SQL
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(BlazeError::Other(format!(
+                "No metrics database at {}; run `blaze metrics snapshot` first",
+                path.display()
+            )));
+        }
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| db_error(path, e))?;
+        Ok(Self { conn })
+    }
+
+    /// Append a snapshot of `stats`; returns its row ID
+    pub fn record(&mut self, board: &str, at: DateTime<Utc>, stats: &BoardStats) -> Result<i64> {
+        let tx = self.conn.transaction().map_err(sql_error)?;
+        tx.execute(
+            "INSERT INTO snapshots (board, taken_at, total, overdue) VALUES (?1, ?2, ?3, ?4)",
+            params![board, at.to_rfc3339(), stats.total_cards, stats.overdue_count],
+        )
+        .map_err(sql_error)?;
+        let id = tx.last_insert_rowid();
+        for (kind, counts) in [("column", &stats.by_column), ("priority", &stats.by_priority)] {
+            for (name, count) in counts {
+                tx.execute(
+                    "INSERT INTO counts (snapshot_id, kind, name, count) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, kind, name, count],
+                )
+                .map_err(sql_error)?;
+            }
+        }
+        tx.commit().map_err(sql_error)?;
+        Ok(id)
+    }
+
+    /// A board's snapshots since `since`, oldest first
+    pub fn history(&self, board: &str, since: Option<DateTime<Utc>>) -> Result<Vec<Snapshot>> {
+        let since = since.map(|s| s.to_rfc3339()).unwrap_or_default();
+        self.load("s.board = ?1 AND s.taken_at >= ?2", params![board, since])
+    }
+
+    /// A board's most recent snapshot
+    pub fn latest(&self, board: &str) -> Result<Option<Snapshot>> {
+        let latest = "s.id = (SELECT id FROM snapshots WHERE board = ?1 ORDER BY taken_at DESC, id DESC LIMIT 1)";
+        Ok(self.load(latest, params![board])?.pop())
+    }
+
+    fn load(&self, condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Snapshot>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT s.id, s.taken_at, s.total, s.overdue, c.kind, c.name, c.count
+                 FROM snapshots s LEFT JOIN counts c ON c.snapshot_id = s.id
+                 WHERE {}
+                 ORDER BY s.taken_at, s.id",
+                condition
+            ))
+            .map_err(sql_error)?;
+        let mut rows = stmt.query(params).map_err(sql_error)?;
+
+        let mut snapshots: Vec<(i64, Snapshot)> = Vec::new();
+        while let Some(row) = rows.next().map_err(sql_error)? {
+            let id: i64 = row.get(0).map_err(sql_error)?;
+            if snapshots.last().is_none_or(|(last, _)| *last != id) {
+                let taken_at: String = row.get(1).map_err(sql_error)?;
+                let taken_at = DateTime::parse_from_rfc3339(&taken_at)
+                    .map_err(|e| BlazeError::Other(format!("Bad snapshot time '{}': {}", taken_at, e)))?;
+                let metrics = BTreeMap::from([
+                    ("total".to_string(), row.get(2).map_err(sql_error)?),
+                    ("overdue".to_string(), row.get(3).map_err(sql_error)?),
+                ]);
+                snapshots.push((
+                    id,
+                    Snapshot {
+                        taken_at: taken_at.with_timezone(&Utc),
+                        metrics,
+                    },
+                ));
+            }
+            let kind: Option<String> = row.get(4).map_err(sql_error)?;
+            if let (Some(kind), Some((_, snapshot))) = (kind, snapshots.last_mut()) {
+                let name: String = row.get(5).map_err(sql_error)?;
+                snapshot.metrics.insert(format!("{}:{}", kind, name), row.get(6).map_err(sql_error)?);
+            }
+        }
+        Ok(snapshots.into_iter().map(|(_, s)| s).collect())
+    }
+
+    /// Run a read-only statement; returns the column names and rows as text
+    pub fn query(&self, sql: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+        let mut stmt = self.conn.prepare(sql).map_err(sql_error)?;
+        if !stmt.readonly() {
+            return Err(BlazeError::InvalidInput("Only queries that read are allowed".into()));
+        }
+        let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = stmt.query([]).map_err(sql_error)?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next().map_err(sql_error)? {
+            let mut values = Vec::with_capacity(names.len());
+            for i in 0..names.len() {
+                let value = match row.get_ref(i).map_err(sql_error)? {
+                    rusqlite::types::ValueRef::Null => String::new(),
+                    rusqlite::types::ValueRef::Integer(n) => n.to_string(),
+                    rusqlite::types::ValueRef::Real(x) => x.to_string(),
+                    rusqlite::types::ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned(),
+                    rusqlite::types::ValueRef::Blob(b) => format!("<{} bytes>", b.len()),
+                };
+                values.push(value);
+            }
+            out.push(values);
+        }
+        Ok((names, out))
+    }
+}
+
+/// The last snapshot of each period, labelled with the period
+pub fn thin_out(snapshots: Vec<Snapshot>, every: Every, tz: Tz) -> Vec<(String, Snapshot)> {
+    let mut periods: Vec<(String, Snapshot)> = Vec::new();
+    for snapshot in snapshots {
+        let period = every.period(snapshot.taken_at, tz);
+        match periods.last_mut() {
+            Some((last, kept)) if *last == period => *kept = snapshot,
+            _ => periods.push((period, snapshot)),
+        }
+    }
+    periods
+}
+
+fn db_error(path: &Path, e: rusqlite::Error) -> BlazeError {
+    BlazeError::Other(format!("Metrics database {}: {}", path.display(), e))
+}
+
+fn sql_error(e: rusqlite::Error) -> BlazeError {
+    BlazeError::Other(format!("Metrics database: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(total: i32, in_progress: i32) -> BoardStats {
+        BoardStats {
+            total_cards: total,
+            by_column: [("in_progress".to_string(), in_progress), ("qa".to_string(), 1)].into(),
+            by_priority: [("high".to_string(), 2)].into(),
+            overdue_count: 1,
+        }
+    }
+
+    #[test]
+    fn records_and_reads_back_per_board() {
+        let path = std::env::temp_dir().join(format!("blaze-metrics-{}.sqlite3", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut db = MetricsDb::open(&path).unwrap();
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        db.record("work", at("2026-03-02T09:00:00Z"), &stats(10, 3)).unwrap();
+        db.record("work", at("2026-03-02T17:00:00Z"), &stats(12, 4)).unwrap();
+        db.record("home", at("2026-03-03T09:00:00Z"), &stats(2, 0)).unwrap();
+        db.record("work", at("2026-03-04T09:00:00Z"), &stats(11, 2)).unwrap();
+
+        let work = db.history("work", None).unwrap();
+        assert_eq!(work.len(), 3);
+        assert_eq!(work[0].metrics["total"], 10);
+        assert_eq!(work[0].metrics["column:qa"], 1);
+        assert_eq!(work[2].metrics["column:in_progress"], 2);
+        assert_eq!(db.history("work", Some(at("2026-03-03T00:00:00Z"))).unwrap().len(), 1);
+        assert_eq!(db.latest("work").unwrap().unwrap().taken_at, at("2026-03-04T09:00:00Z"));
+        assert!(db.latest("elsewhere").unwrap().is_none());
+
+        let daily = thin_out(work, Every::Day, Tz::UTC);
+        let days: Vec<(&str, i64)> = daily.iter().map(|(d, s)| (d.as_str(), s.metrics["total"])).collect();
+        assert_eq!(days, [("2026-03-02", 12), ("2026-03-04", 11)]);
+        assert_eq!(Every::Week.period(at("2026-03-04T09:00:00Z"), Tz::UTC), "2026-W10");
+
+        let (names, rows) = db
+            .query("SELECT board, COUNT(*) AS n FROM snapshots GROUP BY board ORDER BY board")
+            .unwrap();
+        assert_eq!(names, ["board", "n"]);
+        assert_eq!(rows, [["home", "1"], ["work", "3"]]);
+        assert!(db.query("DELETE FROM snapshots").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+//! Change notifications routed to Slack, the desktop or an email file.
+//!
+//! Channels and routing rules live in ~/.config/blaze/notify.toml:
+//!
+//! ```toml
+//! [channels.infra]
+//! type = "slack"
+//! webhook = "https://hooks.slack.com/services/..."
+//! channel = "#infra"
+//!
+//! [channels.desktop]
+//! type = "desktop"
+//!
+//! [channels.oncall]
+//! type = "email"
+//! to = "oncall@example.com"
+//! path = "~/mail/blaze.mbox"
+//!
+//! [[rules]]
+//! filter = "tag:infra"
+//! notify = ["infra"]
+//!
+//! [[rules]]
+//! filter = "priority:urgent"
+//! changes = ["created", "moved"]
+//! notify = ["desktop", "oncall"]
+//! ```
+//!
+//! Filters use the `list --filter` syntax. A rule without a filter matches
+//! every change, including deletions; rules without `changes` match every
+//! kind of change.
+
+use crate::changes::{Change, ChangeKind};
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::output::short_id;
+use crate::parsers::Query;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where a notification goes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Channel {
+    /// Slack incoming webhook
+    Slack {
+        webhook: String,
+        /// Overrides the webhook's default channel
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel: Option<String>,
+    },
+    /// notify-send on Linux, osascript on macOS
+    Desktop,
+    /// Messages appended to an mbox file for a local mailer to pick up
+    Email { to: String, path: PathBuf },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RuleDef {
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    changes: Vec<ChangeKind>,
+    notify: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NotifyFile {
+    #[serde(default)]
+    channels: BTreeMap<String, Channel>,
+    #[serde(default)]
+    rules: Vec<RuleDef>,
+}
+
+/// A routing rule with its filter parsed
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub filter: Option<Query>,
+    pub changes: Vec<ChangeKind>,
+    pub notify: Vec<String>,
+}
+
+impl Rule {
+    /// Check whether a change should be sent through this rule's channels
+    pub fn matches(&self, change: &Change) -> bool {
+        if !self.changes.is_empty() && !self.changes.contains(&change.change) {
+            return false;
+        }
+        match (&self.filter, &change.card) {
+            (None, _) => true,
+            (Some(query), Some(card)) => query.matches(card),
+            // Deleted cards can't be checked against a filter
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Channels and rules from notify.toml
+#[derive(Debug, Default)]
+pub struct NotifyConfig {
+    pub channels: BTreeMap<String, Channel>,
+    pub rules: Vec<Rule>,
+}
+
+impl NotifyConfig {
+    /// Get the notify config path
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|d| d.join("notify.toml"))
+    }
+
+    /// Load and validate notify.toml (or return an empty config)
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Self::default()),
+        };
+
+        let content = fs::read_to_string(&path).map_err(|e| {
+            BlazeError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let file: NotifyFile = toml::from_str(content)?;
+
+        let mut rules = Vec::new();
+        for (i, def) in file.rules.into_iter().enumerate() {
+            let filter = match def.filter {
+                Some(ref filter) => Some(Query::parse(filter).map_err(|e| {
+                    BlazeError::Config(format!("notify.toml rule {} has an invalid filter: {}", i + 1, e))
+                })?),
+                None => None,
+            };
+            if let Some(unknown) = def.notify.iter().find(|n| !file.channels.contains_key(*n)) {
+                return Err(BlazeError::Config(format!(
+                    "notify.toml rule {} uses unknown channel '{}'",
+                    i + 1,
+                    unknown
+                )));
+            }
+            rules.push(Rule {
+                filter,
+                changes: def.changes,
+                notify: def.notify,
+            });
+        }
+
+        Ok(NotifyConfig {
+            channels: file.channels,
+            rules,
+        })
+    }
+
+    /// Channels a change should go to, each once, in rule order
+    pub fn route(&self, change: &Change) -> Vec<&str> {
+        let mut channels: Vec<&str> = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.matches(change)) {
+            for name in &rule.notify {
+                if !channels.contains(&name.as_str()) {
+                    channels.push(name);
+                }
+            }
+        }
+        channels
+    }
+}
+
+/// One-line summary of a change, e.g. `moved: Fix login (1a2b3c4d) Todo → In Progress`
+pub fn describe(change: &Change) -> String {
+    let kind = format!("{:?}", change.change).to_lowercase();
+    let title = change.title.as_deref().unwrap_or("(deleted card)");
+    let mut text = format!("{}: {} ({})", kind, title, short_id(&change.id));
+    match (&change.from_column, &change.column) {
+        (Some(from), Some(to)) => {
+            text.push_str(&format!(" {} → {}", from.display_name(), to.display_name()))
+        }
+        (None, Some(col)) | (Some(col), None) => text.push_str(&format!(" in {}", col.display_name())),
+        (None, None) => {}
+    }
+    text
+}
+
+/// Send one message through a channel
+pub async fn send(channel: &Channel, message: &str) -> Result<()> {
+    match channel {
+        Channel::Slack { webhook, channel } => {
+            let mut body = serde_json::json!({ "text": message });
+            if let Some(channel) = channel {
+                body["channel"] = channel.clone().into();
+            }
+            let response = reqwest::Client::new().post(webhook).json(&body).send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let message = response.text().await.unwrap_or_default();
+                return Err(BlazeError::Api {
+                    status: status.as_u16(),
+                    message,
+                });
+            }
+            Ok(())
+        }
+        Channel::Desktop => desktop(message),
+        Channel::Email { to, path } => append_mail(&expand_home(path), to, message),
+    }
+}
+
+fn desktop(message: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        let script = format!("display notification {:?} with title \"blaze\"", message);
+        Command::new("osascript").args(["-e", &script]).status()
+    } else {
+        Command::new("notify-send").args(["blaze", message]).status()
+    };
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(BlazeError::Other(format!("desktop notifier exited with {}", s))),
+        Err(e) => Err(BlazeError::Other(format!("desktop notifier unavailable: {}", e))),
+    }
+}
+
+/// Append an mbox message
+fn append_mail(path: &Path, to: &str, message: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let now = Utc::now();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    write!(
+        file,
+        "From blaze {}\nFrom: blaze\nTo: {}\nSubject: [blaze] {}\nDate: {}\n\n{}\n\n",
+        now.format("%a %b %e %H:%M:%S %Y"),
+        to,
+        message,
+        now.to_rfc2822(),
+        message
+    )?;
+    Ok(())
+}
+
+/// Resolve `~/` and paths relative to the config directory
+fn expand_home(path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    match Config::dir() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::types::{Column, Priority};
+
+    fn change(kind: ChangeKind, tags: &[&str], priority: Priority) -> Change {
+        let fields = serde_json::json!({ "title": "Rotate certs", "priority": priority, "tags": tags });
+        let card = testing::card("c1", fields);
+        Change {
+            change: kind,
+            id: card.id.clone(),
+            title: Some(card.title.clone()),
+            column: Some(Column::Todo),
+            from_column: None,
+            updated_at: Some(card.updated_at),
+            card: Some(card),
+        }
+    }
+
+    #[test]
+    fn routes_by_tag_priority_and_kind() {
+        let config = NotifyConfig::parse(
+            r##"
+            [channels.infra]
+            type = "slack"
+            webhook = "https://hooks.example/x"
+            channel = "#infra"
+
+            [channels.desktop]
+            type = "desktop"
+
+            [channels.mail]
+            type = "email"
+            to = "oncall@example.com"
+            path = "blaze.mbox"
+
+            [[rules]]
+            filter = "tag:infra"
+            notify = ["infra"]
+
+            [[rules]]
+            filter = "priority:urgent"
+            changes = ["created", "moved"]
+            notify = ["desktop", "mail", "infra"]
+            "##,
+        )
+        .unwrap();
+
+        let urgent_infra = change(ChangeKind::Created, &["infra"], Priority::Urgent);
+        assert_eq!(config.route(&urgent_infra), ["infra", "desktop", "mail"]);
+
+        let urgent_edit = change(ChangeKind::Updated, &[], Priority::Urgent);
+        assert!(config.route(&urgent_edit).is_empty());
+
+        let mut deleted = change(ChangeKind::Deleted, &["infra"], Priority::Urgent);
+        deleted.card = None;
+        assert!(config.route(&deleted).is_empty());
+    }
+
+    #[test]
+    fn unknown_channel_is_rejected() {
+        let err = NotifyConfig::parse("[[rules]]\nnotify = [\"nowhere\"]").unwrap_err();
+        assert!(err.to_string().contains("unknown channel 'nowhere'"));
+    }
+}
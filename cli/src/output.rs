@@ -1,40 +1,755 @@
-//! Output formatting for CLI commands - JSON only.
+//! Output formatting for CLI commands.
+//!
+//! JSON is the default and always carries full IDs; `--format table` renders
+//! human-readable tables with short IDs, and `--format quiet` prints only IDs.
+//! `--format csv` renders card lists and stats for spreadsheets, with full IDs
+//! and unformatted values. Card lists can be narrowed to chosen fields in
+//! every format (`--fields`).
+//!
+//! JSON output goes through the global `--query` filter (see [`crate::jq`])
+//! when one is given. `--format ndjson` prints the same values compactly,
+//! one per line, with lists split into their items.
+//!
+//! Whether tables get colors is decided here, once: `--color always|never`
+//! wins, otherwise stdout must be a terminal and `NO_COLOR` unset. Priorities
+//! and columns are then styled from `[colors]` ([`Theme`]) and card rows
+//! highlighted by `[display.rules]` ([`Highlighter`]).
+//!
+//! Timestamps in tables and card details are shown in the user's time zone,
+//! in UTC, or relative to now ([`TimestampMode`]); JSON and CSV keep RFC 3339.
 
-use crate::types::{BoardStats, Card, Column};
-use serde::Serialize;
+use crate::config::{ColorsConfig, DisplayConfig};
+use crate::context::{CommandContext, OutputFormat};
+use crate::deps::HeldBack;
+use crate::error::{BlazeError, Result};
+use crate::jq::JsonQuery;
+use crate::metrics::Snapshot;
+use crate::outln;
+use crate::pager;
+use crate::parsers::Query;
+use crate::types::{BoardStats, Card, Column, Plan, Priority};
+use crate::wip::WipLimits;
+use chrono::{DateTime, SecondsFormat, Timelike, Utc};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthStr;
 
-/// Print a list of cards as JSON
-pub fn print_cards(cards: &[Card]) {
-    print_json(cards);
+/// ID characters shown in tables unless `[display] id_length` or `--full-ids` says otherwise
+pub const DEFAULT_ID_LENGTH: usize = 8;
+
+static ID_LENGTH: AtomicUsize = AtomicUsize::new(DEFAULT_ID_LENGTH);
+
+/// How everything printed as JSON is shaped, fixed by the global flags
+#[derive(Debug, Default)]
+struct JsonOutput {
+    /// Filter from `--query`
+    query: Option<JsonQuery>,
+    /// `--format ndjson`: one compact value per line, lists split into items
+    lines: bool,
+}
+
+static JSON_OUTPUT: OnceLock<JsonOutput> = OnceLock::new();
+
+/// Apply `--format ndjson` and `--query` to all JSON output from now on
+pub fn configure_json(format: OutputFormat, query: Option<JsonQuery>) {
+    let _ = JSON_OUTPUT.set(JsonOutput {
+        query,
+        lines: format == OutputFormat::Ndjson,
+    });
+}
+
+/// A card field selectable as an output column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+pub enum CardField {
+    Id,
+    Title,
+    Description,
+    Column,
+    Priority,
+    Due,
+    Expires,
+    Tags,
+    Estimate,
+    Tasks,
+    Assignee,
+    BlockedBy,
+    AgentAssignable,
+    AgentStatus,
+    BlockedReason,
+    Created,
+    Updated,
+    EnteredColumn,
+}
+
+/// Table columns when none are selected
+const DEFAULT_TABLE_FIELDS: &[CardField] = &[
+    CardField::Id,
+    CardField::Priority,
+    CardField::Column,
+    CardField::Due,
+    CardField::Title,
+    CardField::Tags,
+];
+
+/// CSV columns when none are selected
+const DEFAULT_CSV_FIELDS: &[CardField] = &[
+    CardField::Id,
+    CardField::Title,
+    CardField::Column,
+    CardField::Priority,
+    CardField::Due,
+    CardField::Tags,
+];
+
+impl CardField {
+    fn name(&self) -> String {
+        self.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default()
+    }
+
+    /// Table header
+    fn header(&self) -> String {
+        match self {
+            CardField::Priority => "P".to_string(),
+            CardField::EnteredColumn => "IN COLUMN SINCE".to_string(),
+            _ => self.name().replace('_', " ").to_uppercase(),
+        }
+    }
+
+    /// Key of this field in a card's JSON
+    fn json_key(&self) -> &'static str {
+        match self {
+            CardField::Id => "id",
+            CardField::Title => "title",
+            CardField::Description => "description",
+            CardField::Column => "column",
+            CardField::Priority => "priority",
+            CardField::Due => "due_date",
+            CardField::Expires => "expires_at",
+            CardField::Tags => "tags",
+            CardField::Estimate => "estimate",
+            CardField::Tasks => "subtasks",
+            CardField::Assignee => "assignee",
+            CardField::BlockedBy => "blocked_by",
+            CardField::AgentAssignable => "agent_assignable",
+            CardField::AgentStatus => "agent_status",
+            CardField::BlockedReason => "blocked_reason",
+            CardField::Created => "created_at",
+            CardField::Updated => "updated_at",
+            CardField::EnteredColumn => "entered_column_at",
+        }
+    }
+
+    /// The value as shown in a table (short IDs, local times)
+    fn display(&self, ctx: &CommandContext, card: &Card) -> String {
+        match self {
+            CardField::Id => short_id(&card.id).to_string(),
+            CardField::Priority => ctx.theme.priority(card.priority),
+            CardField::Column => ctx.theme.column(&card.column),
+            CardField::Due => card.due_date.map(|d| format_due(ctx, d)).unwrap_or_default(),
+            CardField::Expires => card.expires_at.map(|t| format_time(ctx, t)).unwrap_or_default(),
+            CardField::Created => format_time(ctx, card.created_at),
+            CardField::Updated => format_time(ctx, card.updated_at),
+            CardField::EnteredColumn => card.entered_column_at.map(|t| format_time(ctx, t)).unwrap_or_default(),
+            _ => self.value(card),
+        }
+    }
+
+    /// The raw value for CSV (lists are comma-separated, times RFC 3339)
+    fn value(&self, card: &Card) -> String {
+        let time = |t: DateTime<Utc>| t.to_rfc3339_opts(SecondsFormat::Secs, true);
+        match self {
+            CardField::Id => card.id.clone(),
+            CardField::Title => card.title.clone(),
+            CardField::Description => card.description.clone().unwrap_or_default(),
+            CardField::Column => card.column.to_string(),
+            CardField::Priority => card.priority.to_string(),
+            CardField::Due => card.due_date.map(time).unwrap_or_default(),
+            CardField::Expires => card.expires_at.map(time).unwrap_or_default(),
+            CardField::Tags => card.tags.join(","),
+            CardField::Estimate => card.estimate.map(|e| e.to_string()).unwrap_or_default(),
+            CardField::Tasks => task_progress(card).unwrap_or_default(),
+            CardField::Assignee => card.assignee.clone().unwrap_or_default(),
+            CardField::BlockedBy => card.blocked_by.join(","),
+            CardField::AgentAssignable => card.agent_assignable.to_string(),
+            CardField::AgentStatus => card.agent_status.map(|s| s.to_string()).unwrap_or_default(),
+            CardField::BlockedReason => card.blocked_reason.clone().unwrap_or_default(),
+            CardField::Created => time(card.created_at),
+            CardField::Updated => time(card.updated_at),
+            CardField::EnteredColumn => card.entered_column_at.map(time).unwrap_or_default(),
+        }
+    }
+}
+
+/// `--color`: when tables use ANSI colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    /// When stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Whether to write colors to stdout
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        }
+    }
+}
+
+/// Built-in priority styles, replaced by `[colors] priority`
+const PRIORITY_STYLES: &[(Priority, &str)] = &[
+    (Priority::Urgent, "bold red"),
+    (Priority::High, "red"),
+    (Priority::Medium, "yellow"),
+    (Priority::Low, "green"),
+];
+
+/// Built-in column styles, replaced by `[colors] column`
+const COLUMN_STYLES: &[(Column, &str)] = &[
+    (Column::Backlog, "dim"),
+    (Column::InProgress, "yellow"),
+    (Column::Review, "cyan"),
+    (Column::Done, "green"),
+];
+
+/// How priorities, columns and statuses look in tables
+#[derive(Debug)]
+pub struct Theme {
+    color: bool,
+    emoji: bool,
+    /// SGR parameters
+    priority: Vec<(Priority, String)>,
+    column: Vec<(Column, String)>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            color: false,
+            emoji: true,
+            priority: Vec::new(),
+            column: Vec::new(),
+        }
+    }
+}
+
+impl Theme {
+    /// Merge `[colors]` over the built-in styles; `color` is the `--color` decision
+    pub fn from_config(colors: &ColorsConfig, color: bool) -> Result<Self> {
+        let priority = styles(PRIORITY_STYLES, &colors.priority, "priority", |name| {
+            Priority::from_str(name, true).ok()
+        })?;
+        let column = styles(COLUMN_STYLES, &colors.column, "column", |name| name.parse().ok())?;
+        Ok(Self {
+            color,
+            emoji: colors.emoji.unwrap_or(true),
+            priority,
+            column,
+        })
+    }
+
+    /// A priority in a table cell: its emoji, or its name
+    pub fn priority(&self, priority: Priority) -> String {
+        let text = if self.emoji { priority.emoji().to_string() } else { priority.to_string() };
+        self.paint(&text, lookup(&self.priority, &priority))
+    }
+
+    /// A priority with its name, e.g. "🟠 high"
+    pub fn priority_label(&self, priority: Priority) -> String {
+        let text = self.with_emoji(priority.emoji(), priority);
+        self.paint(&text, lookup(&self.priority, &priority))
+    }
+
+    /// A column's display name
+    pub fn column(&self, column: &Column) -> String {
+        self.paint(&column.display_name(), lookup(&self.column, column))
+    }
+
+    /// `text` after its emoji, unless emoji are turned off
+    pub fn with_emoji(&self, emoji: &str, text: impl std::fmt::Display) -> String {
+        if self.emoji {
+            format!("{} {}", emoji, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// `text` in red, for something over a limit
+    pub fn alert(&self, text: &str) -> String {
+        self.paint(text, Some("31"))
+    }
+
+    /// `text` as a terminal hyperlink (OSC 8) to `url` when colors are on
+    pub fn hyperlink(&self, text: &str, url: &str) -> String {
+        if self.color {
+            format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn paint(&self, text: &str, sgr: Option<&str>) -> String {
+        paint(text, sgr.filter(|_| self.color))
+    }
+}
+
+fn lookup<'a, K: PartialEq>(styles: &'a [(K, String)], key: &K) -> Option<&'a str> {
+    styles.iter().find(|(k, _)| k == key).map(|(_, sgr)| sgr.as_str())
+}
+
+/// Built-in styles with configured ones (by name) replacing them; "none" removes one
+fn styles<K: Clone + PartialEq>(
+    defaults: &[(K, &str)],
+    configured: &std::collections::BTreeMap<String, String>,
+    section: &str,
+    key: impl Fn(&str) -> Option<K>,
+) -> Result<Vec<(K, String)>> {
+    let invalid = |name: &str, msg: String| BlazeError::Config(format!("[colors] {}.{}: {}", section, name, msg));
+    // The built-in styles are known to parse
+    let mut chosen: Vec<(K, String)> =
+        defaults.iter().map(|(k, style)| (k.clone(), parse_style(style).unwrap())).collect();
+    for (name, style) in configured {
+        let k = key(name).ok_or_else(|| invalid(name, format!("unknown {}", section)))?;
+        chosen.retain(|(existing, _)| *existing != k);
+        if !style.trim().eq_ignore_ascii_case("none") {
+            chosen.push((k, parse_style(style).map_err(|e| invalid(name, e))?));
+        }
+    }
+    Ok(chosen)
+}
+
+/// Card highlighting from `[display.rules]`
+#[derive(Debug, Default)]
+pub struct Highlighter {
+    /// Query and SGR parameters, in rule name order
+    rules: Vec<(Query, String)>,
+}
+
+impl Highlighter {
+    /// Compile the rules; they are dropped when `color` is off
+    pub fn from_config(display: &DisplayConfig, color: bool) -> Result<Self> {
+        let rules = display
+            .rules
+            .iter()
+            .map(|(name, rule)| {
+                let query = Query::parse(&rule.when)
+                    .map_err(|e| BlazeError::Config(format!("[display.rules.{}] when: {}", name, e)))?;
+                let sgr = parse_style(&rule.style)
+                    .map_err(|e| BlazeError::Config(format!("[display.rules.{}] style: {}", name, e)))?;
+                Ok((query, sgr))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            rules: if color { rules } else { Vec::new() },
+        })
+    }
+
+    /// SGR parameters for a card, if any rule matches
+    fn style(&self, card: &Card) -> Option<String> {
+        let codes: Vec<&str> = self
+            .rules
+            .iter()
+            .filter(|(query, _)| query.matches(card))
+            .map(|(_, sgr)| sgr.as_str())
+            .collect();
+        (!codes.is_empty()).then(|| codes.join(";"))
+    }
+}
+
+/// Turn a style like "bold white on red" into SGR parameters ("1;37;41")
+fn parse_style(spec: &str) -> std::result::Result<String, String> {
+    const COLORS: &[&str] = &["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    let color = |name: &str, background: bool| {
+        let (bright, base) = match name.strip_prefix("bright-") {
+            Some(base) => (true, base),
+            None => (false, name),
+        };
+        let index = COLORS.iter().position(|c| *c == base)? as u8;
+        Some(match (bright, background) {
+            (false, false) => 30 + index,
+            (false, true) => 40 + index,
+            (true, false) => 90 + index,
+            (true, true) => 100 + index,
+        })
+    };
+
+    let mut codes = Vec::new();
+    let mut words = spec.split_whitespace().map(str::to_ascii_lowercase);
+    while let Some(word) = words.next() {
+        let code = match word.as_str() {
+            "bold" => 1,
+            "dim" => 2,
+            "italic" => 3,
+            "underline" => 4,
+            "reverse" => 7,
+            "on" => {
+                let name = words.next().ok_or("expected a color after 'on'")?;
+                color(&name, true).ok_or_else(|| format!("unknown color '{}'", name))?
+            }
+            name => color(name, false).ok_or_else(|| format!("unknown style '{}'", name))?,
+        };
+        codes.push(code.to_string());
+    }
+    if codes.is_empty() {
+        return Err("style is empty".to_string());
+    }
+    Ok(codes.join(";"))
+}
+
+/// SGR sequence ending a style
+const RESET: &str = "\x1b[0m";
+
+/// Wrap text in an SGR sequence
+fn paint(text: &str, sgr: Option<&str>) -> String {
+    match sgr {
+        Some(sgr) => format!("\x1b[{}m{}{}", sgr, text, RESET),
+        None => text.to_string(),
+    }
+}
+
+/// Display width of text that may contain SGR sequences
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = match rest[start..].find('m') {
+            Some(end) => &rest[start + end + 1..],
+            None => "",
+        };
+    }
+    width + rest.width()
+}
+
+/// Print a list of cards
+pub fn print_cards(ctx: &CommandContext, cards: &[Card]) {
+    print_cards_as(ctx, cards, &[]);
 }
 
-/// Print a single card as JSON
-pub fn print_card_detail(card: &Card) {
-    print_json(card);
+/// Print a list of cards with only the given fields (every format's defaults if empty)
+pub fn print_cards_as(ctx: &CommandContext, cards: &[Card], fields: &[CardField]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson if fields.is_empty() => print_json(cards),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let projected: Vec<serde_json::Value> = cards.iter().map(|c| project(c, fields)).collect();
+            print_json(&projected);
+        }
+        OutputFormat::Csv => {
+            let fields = if fields.is_empty() { DEFAULT_CSV_FIELDS } else { fields };
+            let headers: Vec<String> = fields.iter().map(CardField::name).collect();
+            let rows = cards
+                .iter()
+                .map(|c| fields.iter().map(|f| f.value(c)).collect())
+                .collect();
+            print_csv(&headers, rows);
+        }
+        OutputFormat::Quiet => print_lines(cards.iter().map(|c| &c.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let fields = if !fields.is_empty() {
+                fields.to_vec()
+            } else {
+                // Progress and assignee go right after the title, only when some card has them
+                let mut fields = DEFAULT_TABLE_FIELDS.to_vec();
+                let title = fields.iter().position(|f| *f == CardField::Title).map_or(fields.len(), |i| i + 1);
+                if cards.iter().any(|c| c.assignee.is_some()) {
+                    fields.insert(title, CardField::Assignee);
+                }
+                if cards.iter().any(|c| !c.subtasks.is_empty()) {
+                    fields.insert(title, CardField::Tasks);
+                }
+                fields
+            };
+            let headers: Vec<String> = fields.iter().map(CardField::header).collect();
+            let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+            let rows = cards
+                .iter()
+                .map(|c| fields.iter().map(|f| f.display(ctx, c)).collect())
+                .collect();
+            if ctx.format == OutputFormat::Markdown {
+                print_markdown(&headers, rows);
+            } else {
+                let styles = cards.iter().map(|c| ctx.highlighter.style(c)).collect();
+                print_styled_table(&headers, rows, styles);
+            }
+        }
+    }
+}
+
+/// A card's JSON with only the given fields (missing ones as null)
+fn project(card: &Card, fields: &[CardField]) -> serde_json::Value {
+    let mut full = match serde_json::to_value(card) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => return serde_json::Value::Null,
+    };
+    let projected = fields
+        .iter()
+        .map(|f| {
+            let key = f.json_key();
+            (key.to_string(), full.remove(key).unwrap_or(serde_json::Value::Null))
+        })
+        .collect();
+    serde_json::Value::Object(projected)
+}
+
+/// Print a single card
+pub fn print_card_detail(ctx: &CommandContext, card: &Card) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(card),
+        OutputFormat::Quiet => outln!("{}", card.id),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let mut fields = vec![
+                ("ID", card.id.clone()),
+                ("Title", paint(&card.title, ctx.highlighter.style(card).as_deref())),
+                ("Column", ctx.theme.column(&card.column)),
+                ("Priority", ctx.theme.priority_label(card.priority)),
+            ];
+            if let Some(entered) = card.entered_column_at {
+                fields.push(("In column since", format_time(ctx, entered)));
+            }
+            if let Some(due) = card.due_date {
+                fields.push(("Due", format_due(ctx, due)));
+            }
+            if let Some(expires) = card.expires_at {
+                fields.push(("Expires", format_time(ctx, expires)));
+            }
+            if !card.tags.is_empty() {
+                fields.push(("Tags", card.tags.join(", ")));
+            }
+            if let Some(estimate) = card.estimate {
+                fields.push(("Estimate", estimate.to_string()));
+            }
+            if let Some(ref assignee) = card.assignee {
+                fields.push(("Assignee", assignee.clone()));
+            }
+            if !card.blocked_by.is_empty() {
+                let ids: Vec<&str> = card.blocked_by.iter().map(|id| short_id(id)).collect();
+                fields.push(("Depends on", ids.join(", ")));
+            }
+            if !card.blocks.is_empty() {
+                let ids: Vec<&str> = card.blocks.iter().map(|id| short_id(id)).collect();
+                fields.push(("Blocks", ids.join(", ")));
+            }
+            fields.push(("Created", format_time(ctx, card.created_at)));
+            fields.push(("Updated", format_time(ctx, card.updated_at)));
+            if let Some(status) = card.agent_status {
+                fields.push(("Agent", ctx.theme.with_emoji(status.emoji(), status)));
+            }
+            if let Some(ref reason) = card.blocked_reason {
+                fields.push(("Blocked", reason.clone()));
+            }
+            let mut out = fields_text(&fields);
+
+            if let Some(ref desc) = card.description {
+                out.push_str("\nDescription:\n");
+                for line in desc.lines() {
+                    let _ = writeln!(out, "  {}", line);
+                }
+            }
+
+            if !card.acceptance_criteria.is_empty() {
+                out.push_str("\nAcceptance criteria:\n");
+                for (i, criterion) in card.acceptance_criteria.iter().enumerate() {
+                    let checked = card.acceptance_checked.get(i).copied().unwrap_or(false);
+                    let _ = writeln!(out, "  [{}] {}. {}", if checked { "x" } else { " " }, i, criterion);
+                }
+            }
+
+            if let Some(progress) = task_progress(card) {
+                let _ = writeln!(out, "\nSubtasks ({}):", progress);
+                for (i, subtask) in card.subtasks.iter().enumerate() {
+                    let _ = writeln!(out, "  [{}] {}. {}", if subtask.done { "x" } else { " " }, i, subtask.title);
+                }
+            }
+
+            if !card.links.is_empty() {
+                out.push_str("\nLinks:\n");
+                for (i, link) in card.links.iter().enumerate() {
+                    let text = match link.title {
+                        Some(ref title) => format!("{} <{}>", ctx.theme.hyperlink(title, &link.url), link.url),
+                        None => ctx.theme.hyperlink(&link.url, &link.url),
+                    };
+                    let _ = writeln!(out, "  {}. {:<7} {}", i, link.kind.to_string(), text);
+                }
+            }
+
+            if !card.attachments.is_empty() {
+                out.push_str("\nAttachments:\n");
+                for attachment in &card.attachments {
+                    let _ = writeln!(out, "  {} ({} bytes)", attachment.name, attachment.size);
+                }
+            }
+
+            if !card.agent_progress.is_empty() {
+                out.push_str("\nProgress:\n");
+                for entry in &card.agent_progress {
+                    let _ = writeln!(out, "  {}  {}", format_time(ctx, entry.timestamp), entry.message);
+                }
+            }
+
+            if !card.threads.is_empty() {
+                let open = card.threads.iter().filter(|t| !t.resolved).count();
+                let _ = writeln!(out, "\nThreads ({} open):", open);
+                for thread in &card.threads {
+                    let id = short_id(&thread.id);
+                    // Resolved discussions are done with; only open ones are printed in full
+                    if thread.resolved {
+                        let by = thread.resolved_by.as_deref().map(|b| format!(", resolved by {}", b));
+                        let by = by.unwrap_or_default();
+                        let _ = writeln!(out, "  [resolved] {}  {} ({}{})", id, thread.topic, thread.author, by);
+                        continue;
+                    }
+                    let _ = writeln!(out, "  [open] {}  {} ({})", id, thread.topic, thread.author);
+                    for reply in &thread.replies {
+                        let _ = writeln!(out, "    {}  {}", format_time(ctx, reply.created_at), reply.author);
+                        for line in reply.body.lines() {
+                            let _ = writeln!(out, "      {}", line);
+                        }
+                    }
+                }
+            }
+
+            if !card.comments.is_empty() {
+                out.push_str("\nComments:\n");
+                for comment in &card.comments {
+                    let _ = writeln!(out, "  {}  {}", format_time(ctx, comment.created_at), comment.author);
+                    for line in comment.body.lines() {
+                        let _ = writeln!(out, "    {}", line);
+                    }
+                }
+            }
+            pager::page(&out);
+        }
+    }
 }
 
-/// Print board summary as JSON
-pub fn print_board_summary(cards: &[Card]) {
-    let summary = build_board_summary(cards);
-    print_json(&summary);
+/// Print cards held back by unfinished dependencies
+pub fn print_held_back(ctx: &CommandContext, held: &[HeldBack]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(held),
+        OutputFormat::Quiet => print_lines(held.iter().map(|h| &h.id)),
+        OutputFormat::Csv => {
+            let rows = held
+                .iter()
+                .map(|h| {
+                    let blockers: Vec<&str> = h.blocked_by.iter().map(|b| b.id.as_str()).collect();
+                    vec![h.id.clone(), h.title.clone(), blockers.join(",")]
+                })
+                .collect();
+            print_csv(&["id", "title", "blocked_by"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = held
+                .iter()
+                .map(|h| {
+                    let blockers: Vec<String> = h
+                        .blocked_by
+                        .iter()
+                        .map(|b| format!("{} {} ({})", short_id(&b.id), b.title, b.column.display_name()))
+                        .collect();
+                    vec![short_id(&h.id).to_string(), h.title.clone(), blockers.join("; ")]
+                })
+                .collect();
+            if ctx.format == OutputFormat::Markdown {
+                print_markdown(&["ID", "TITLE", "BLOCKED BY"], rows);
+            } else {
+                print_table(&["ID", "TITLE", "BLOCKED BY"], rows);
+            }
+        }
+    }
 }
 
-fn build_board_summary(cards: &[Card]) -> Vec<ColumnSummary> {
-    let columns = [
-        Column::Backlog,
-        Column::Todo,
-        Column::InProgress,
-        Column::Review,
-        Column::Done,
-    ];
+/// Width of the longest urgency bar in `board` tables
+const URGENCY_BAR_WIDTH: usize = 16;
 
+/// Print board summary: card count per column, the trend since `previous`
+/// (a `metrics` snapshot) and a bar of priority-weighted urgency
+pub fn print_board_summary(
+    ctx: &CommandContext,
+    cards: &[Card],
+    columns: &[Column],
+    limits: &WipLimits,
+    previous: Option<&Snapshot>,
+) {
+    let summary = build_board_summary(cards, columns, limits, previous);
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(&summary),
+        OutputFormat::Quiet => summary.iter().for_each(|s| outln!("{}", s.count)),
+        OutputFormat::Csv => {
+            let rows = summary
+                .iter()
+                .map(|s| {
+                    let limit = s.limit.map(|n| n.to_string()).unwrap_or_default();
+                    let previous = s.previous.map(|n| n.to_string()).unwrap_or_default();
+                    vec![s.column.clone(), s.count.to_string(), limit, previous, s.urgency.to_string()]
+                })
+                .collect();
+            print_csv(&["column", "count", "limit", "previous", "urgency"], rows);
+        }
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let heaviest = summary.iter().map(|s| s.urgency).max().unwrap_or(0);
+            let rows = summary
+                .iter()
+                .map(|s| {
+                    let count = match s.limit {
+                        Some(limit) if s.count > limit => ctx.theme.alert(&format!("{}/{}", s.count, limit)),
+                        Some(limit) => format!("{}/{}", s.count, limit),
+                        None => s.count.to_string(),
+                    };
+                    let mut row = vec![s.column.clone(), count];
+                    if previous.is_some() {
+                        row.push(trend(s.count, s.previous));
+                    }
+                    row.push(urgency_bar(s.urgency, heaviest));
+                    row
+                })
+                .collect();
+            let headers: &[&str] = if previous.is_some() {
+                &["COLUMN", "COUNT", "TREND", "URGENCY"]
+            } else {
+                &["COLUMN", "COUNT", "URGENCY"]
+            };
+            match ctx.format {
+                OutputFormat::Markdown => print_markdown(headers, rows),
+                _ => print_table(headers, rows),
+            }
+            if let Some(previous) = previous {
+                eprintln!("Trend since the metrics snapshot of {}", format_time(ctx, previous.taken_at));
+            }
+        }
+    }
+}
+
+fn build_board_summary(
+    cards: &[Card],
+    columns: &[Column],
+    limits: &WipLimits,
+    previous: Option<&Snapshot>,
+) -> Vec<ColumnSummary> {
     columns
         .iter()
         .map(|col| {
-            let count = cards.iter().filter(|c| c.column == *col).count();
+            let in_column = cards.iter().filter(|c| c.column == *col);
             ColumnSummary {
                 column: col.display_name().to_string(),
-                count,
+                count: in_column.clone().count(),
+                limit: limits.limit(col),
+                // A column the snapshot doesn't know was empty then
+                previous: previous.map(|s| s.metrics.get(&format!("column:{}", col)).copied().unwrap_or(0) as usize),
+                urgency: in_column.map(|c| c.priority.weight()).sum(),
             }
         })
         .collect()
@@ -44,17 +759,439 @@ fn build_board_summary(cards: &[Card]) -> Vec<ColumnSummary> {
 struct ColumnSummary {
     column: String,
     count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// Count in the latest metrics snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous: Option<usize>,
+    /// Sum of the cards' priority weights
+    urgency: u32,
+}
+
+/// "↑2", "↓1" or "→" for a count against an earlier one
+fn trend(count: usize, previous: Option<usize>) -> String {
+    match previous {
+        Some(before) if count > before => format!("↑{}", count - before),
+        Some(before) if count < before => format!("↓{}", before - count),
+        Some(_) => "→".to_string(),
+        None => String::new(),
+    }
+}
+
+/// A bar for `urgency`, as long as the table's widest for `heaviest`
+fn urgency_bar(urgency: u32, heaviest: u32) -> String {
+    if urgency == 0 || heaviest == 0 {
+        return String::new();
+    }
+    let width = (urgency as usize * URGENCY_BAR_WIDTH).div_ceil(heaviest as usize);
+    format!("{} {}", "█".repeat(width), urgency)
+}
+
+/// Print board statistics
+pub fn print_stats(ctx: &CommandContext, stats: &BoardStats) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson => print_json(stats),
+        OutputFormat::Quiet => outln!("{}", stats.total_cards),
+        OutputFormat::Table | OutputFormat::Csv | OutputFormat::Markdown => {
+            let mut rows = vec![
+                vec!["total".to_string(), stats.total_cards.to_string()],
+                vec!["overdue".to_string(), stats.overdue_count.to_string()],
+            ];
+            let mut by_column: Vec<_> = stats.by_column.iter().collect();
+            by_column.sort();
+            rows.extend(by_column.iter().map(|(k, v)| vec![format!("column:{}", k), v.to_string()]));
+            let mut by_priority: Vec<_> = stats.by_priority.iter().collect();
+            by_priority.sort();
+            rows.extend(by_priority.iter().map(|(k, v)| vec![format!("priority:{}", k), v.to_string()]));
+            match ctx.format {
+                OutputFormat::Csv => print_csv(&["metric", "value"], rows),
+                OutputFormat::Markdown => print_markdown(&["METRIC", "VALUE"], rows),
+                _ => print_table(&["METRIC", "VALUE"], rows),
+            }
+        }
+    }
+}
+
+/// Print a list of plans
+pub fn print_plans(ctx: &CommandContext, plans: &[Plan]) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(plans),
+        OutputFormat::Quiet => print_lines(plans.iter().map(|p| &p.id)),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let rows = plans
+                .iter()
+                .map(|p| {
+                    vec![
+                        short_id(&p.id).to_string(),
+                        ctx.theme.with_emoji(p.status.emoji(), p.status),
+                        p.files.len().to_string(),
+                        format_date(ctx, p.updated_at),
+                        p.title.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "STATUS", "FILES", "UPDATED", "TITLE"], rows);
+        }
+    }
 }
 
-/// Print board statistics as JSON
-pub fn print_stats(stats: &BoardStats) {
-    print_json(stats);
+/// Print a single plan
+pub fn print_plan_detail(ctx: &CommandContext, plan: &Plan) {
+    match ctx.format {
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Csv => print_json(plan),
+        OutputFormat::Quiet => outln!("{}", plan.id),
+        OutputFormat::Table | OutputFormat::Markdown => {
+            let mut out = fields_text(&[
+                ("ID", plan.id.clone()),
+                ("Title", plan.title.clone()),
+                ("Status", ctx.theme.with_emoji(plan.status.emoji(), plan.status)),
+                ("Created", format_time(ctx, plan.created_at)),
+                ("Updated", format_time(ctx, plan.updated_at)),
+            ]);
+            if !plan.files.is_empty() {
+                out.push_str("\nFiles:\n");
+                for file in &plan.files {
+                    let _ = writeln!(out, "  {} ({} bytes)", file.name, file.content.len());
+                }
+            }
+            pager::page(&out);
+        }
+    }
 }
 
 /// Print any serializable value as JSON
 pub fn print_json<T: Serialize + ?Sized>(value: &T) {
-    match serde_json::to_string_pretty(value) {
-        Ok(json) => println!("{}", json),
-        Err(e) => eprintln!("Error serializing to JSON: {}", e),
+    emit_json(value, serde_json::to_string_pretty);
+}
+
+/// Print a value as one line of compact JSON (for streams of objects)
+pub fn print_json_line<T: Serialize + ?Sized>(value: &T) {
+    emit_json(value, serde_json::to_string);
+}
+
+/// Print `value`, or each output of `--query` for it
+fn emit_json<T: Serialize + ?Sized>(value: &T, render: fn(&serde_json::Value) -> serde_json::Result<String>) {
+    let value = match serde_json::to_value(value) {
+        Ok(value) => value,
+        Err(e) => return eprintln!("Error serializing to JSON: {}", e),
+    };
+    let settings = JSON_OUTPUT.get_or_init(JsonOutput::default);
+    let outputs = match settings.query {
+        None => vec![value],
+        Some(ref query) => match query.apply(&value) {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                eprintln!("Error: --query '{}': {}", query, e);
+                std::process::exit(1);
+            }
+        },
+    };
+    let outputs: Vec<serde_json::Value> = if settings.lines {
+        let split = |output| match output {
+            serde_json::Value::Array(items) => items,
+            other => vec![other],
+        };
+        outputs.into_iter().flat_map(split).collect()
+    } else {
+        outputs
+    };
+    for output in outputs {
+        let json = if settings.lines { serde_json::to_string(&output) } else { render(&output) };
+        match json {
+            Ok(json) => pager::print(&(json + "\n")),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        }
+    }
+}
+
+/// How many of a card's subtasks are done, e.g. "3/7"; `None` without subtasks
+fn task_progress(card: &Card) -> Option<String> {
+    if card.subtasks.is_empty() {
+        return None;
+    }
+    let done = card.subtasks.iter().filter(|s| s.done).count();
+    Some(format!("{}/{}", done, card.subtasks.len()))
+}
+
+/// Show `len` ID characters in tables from now on; `None` shows whole IDs
+pub fn set_id_length(len: Option<usize>) {
+    ID_LENGTH.store(len.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// Shorten an ID for table display. Quiet, CSV and JSON output always use the whole ID.
+pub fn short_id(id: &str) -> &str {
+    &id[..ID_LENGTH.load(Ordering::Relaxed).min(id.len())]
+}
+
+/// `--utc`, `--local` or `--relative`: how timestamps are shown (default from `[display] timestamps`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampMode {
+    /// In the time zone from `--tz`, `timezone` in config, or the system
+    #[default]
+    Local,
+    Utc,
+    /// `5m ago`, `in 3d`
+    Relative,
+}
+
+/// Format a timestamp for display
+pub fn format_time(ctx: &CommandContext, dt: DateTime<Utc>) -> String {
+    format_in_mode(ctx, dt, "%Y-%m-%d %H:%M")
+}
+
+/// Format the date part of a timestamp for display
+pub fn format_date(ctx: &CommandContext, dt: DateTime<Utc>) -> String {
+    format_in_mode(ctx, dt, "%Y-%m-%d")
+}
+
+/// Format a due date for display, with the time unless it's the end of the day
+pub fn format_due(ctx: &CommandContext, dt: DateTime<Utc>) -> String {
+    let local = dt.with_timezone(&display_tz(ctx));
+    if (local.hour(), local.minute(), local.second()) == (23, 59, 59) {
+        format_date(ctx, dt)
+    } else {
+        format_time(ctx, dt)
+    }
+}
+
+fn display_tz(ctx: &CommandContext) -> Tz {
+    match ctx.timestamps {
+        TimestampMode::Utc => Tz::UTC,
+        TimestampMode::Local | TimestampMode::Relative => ctx.tz,
+    }
+}
+
+fn format_in_mode(ctx: &CommandContext, dt: DateTime<Utc>, format: &str) -> String {
+    match ctx.timestamps {
+        TimestampMode::Relative => format_relative(dt, Utc::now()),
+        TimestampMode::Local | TimestampMode::Utc => dt.with_timezone(&display_tz(ctx)).format(format).to_string(),
+    }
+}
+
+/// `just now`, `5m ago`, `in 3d`, in the largest unit that fits
+pub fn format_relative(dt: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    let seconds = (dt - now).num_seconds();
+    let abs = seconds.unsigned_abs();
+    let span = match abs {
+        0..MINUTE => return "just now".to_string(),
+        MINUTE..HOUR => format!("{}m", abs / MINUTE),
+        HOUR..DAY => format!("{}h", abs / HOUR),
+        // Weeks and months read worse than days for anything under two months
+        _ if abs < 60 * DAY => format!("{}d", abs / DAY),
+        _ if abs < 365 * DAY => format!("{}mo", abs / (30 * DAY)),
+        _ => format!("{}y", abs / (365 * DAY)),
+    };
+    if seconds < 0 {
+        format!("{} ago", span)
+    } else {
+        format!("in {}", span)
+    }
+}
+
+/// Print rows as a left-aligned table with a header
+pub fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    print_styled_table(headers, rows, Vec::new());
+}
+
+/// Like [`print_table`], with SGR parameters for each row (padding included, so backgrounds span the row)
+fn print_styled_table(headers: &[&str], rows: Vec<Vec<String>>, styles: Vec<Option<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.width()).collect();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(visible_width(cell));
+        }
+    }
+
+    let mut out = String::new();
+    let mut render = |cells: Vec<&str>, sgr: Option<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| format!("{}{}", cell, " ".repeat(widths[i] - visible_width(cell))))
+            .collect();
+        let line = line.join("  ");
+        let line = line.trim_end();
+        match sgr {
+            // A colored cell ends with a reset; carry on with the row's style after it
+            Some(sgr) => out.push_str(&paint(&line.replace(RESET, &format!("{}\x1b[{}m", RESET, sgr)), Some(sgr))),
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    };
+
+    render(headers.to_vec(), None);
+    for (i, row) in rows.iter().enumerate() {
+        let sgr = styles.get(i).and_then(Option::as_deref);
+        render(row.iter().map(String::as_str).collect(), sgr);
+    }
+    pager::page(&out);
+}
+
+/// Print rows as RFC 4180 CSV: CRLF line ends, fields quoted when needed
+pub fn print_csv<H: AsRef<str>>(headers: &[H], rows: Vec<Vec<String>>) {
+    let mut out = csv_record(headers);
+    for row in &rows {
+        out.push_str(&csv_record(row));
+    }
+    pager::print(&out);
+}
+
+fn csv_record<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            let f = f.as_ref();
+            if f.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect();
+    fields.join(",") + "\r\n"
+}
+
+/// Print rows as a GitHub-flavored Markdown table
+pub fn print_markdown<H: AsRef<str>>(headers: &[H], rows: Vec<Vec<String>>) {
+    let mut out = markdown_row(headers);
+    out.push_str(&markdown_row(&vec!["---"; headers.len()]));
+    for row in &rows {
+        out.push_str(&markdown_row(row));
+    }
+    pager::page(&out);
+}
+
+/// One table row; pipes are escaped and line breaks become `<br>` so a cell stays on its line
+fn markdown_row<S: AsRef<str>>(cells: &[S]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|c| c.as_ref().replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>"))
+        .collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Aligned `Label: value` lines
+fn fields_text(fields: &[(&str, String)]) -> String {
+    let width = fields.iter().map(|(k, _)| k.len()).max().unwrap_or(0) + 1;
+    let mut out = String::new();
+    for (label, value) in fields {
+        let _ = writeln!(out, "{:<width$} {}", format!("{}:", label), value, width = width);
+    }
+    out
+}
+
+/// Print one value per line (IDs for `--format quiet`)
+fn print_lines<S: AsRef<str>>(lines: impl Iterator<Item = S>) {
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(line.as_ref());
+        out.push('\n');
+    }
+    pager::print(&out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(csv_record(&["id", "title"]), "id,title\r\n");
+        assert_eq!(
+            csv_record(&["a1", "Fix \"login\", again", "line\nbreak", ""]),
+            "a1,\"Fix \"\"login\"\", again\",\"line\nbreak\",\r\n"
+        );
+    }
+
+    #[test]
+    fn theme_merges_configured_colors() {
+        let colors = ColorsConfig {
+            emoji: Some(false),
+            priority: [
+                ("urgent".to_string(), "white on red".to_string()),
+                ("low".to_string(), "none".to_string()),
+            ]
+            .into(),
+            column: Default::default(),
+        };
+        let theme = Theme::from_config(&colors, true).unwrap();
+        assert_eq!(theme.priority(Priority::Urgent), "\x1b[37;41murgent\x1b[0m");
+        assert_eq!(theme.priority(Priority::High), "\x1b[31mhigh\x1b[0m");
+        assert_eq!(theme.priority(Priority::Low), "low");
+        assert_eq!(theme.with_emoji("🟢", "ready"), "ready");
+
+        let plain = Theme::from_config(&ColorsConfig::default(), false).unwrap();
+        assert_eq!(plain.priority_label(Priority::High), "🟠 high");
+        assert_eq!(plain.column(&Column::Done), "Done");
+
+        let bad = ColorsConfig {
+            column: [("on hold!".to_string(), "red".to_string())].into(),
+            ..ColorsConfig::default()
+        };
+        assert!(Theme::from_config(&bad, true).unwrap_err().to_string().contains("column.on hold!: unknown column"));
+    }
+
+    #[test]
+    fn relative_times() {
+        let now = Utc::now();
+        let at = |seconds: i64| format_relative(now + chrono::Duration::seconds(seconds), now);
+        assert_eq!(at(-30), "just now");
+        assert_eq!(at(-5 * 60), "5m ago");
+        assert_eq!(at(3 * 3600 + 59), "in 3h");
+        assert_eq!(at(-45 * 86_400), "45d ago");
+        assert_eq!(at(90 * 86_400), "in 3mo");
+        assert_eq!(at(-800 * 86_400), "2y ago");
+    }
+
+    #[test]
+    fn visible_width_skips_escapes() {
+        assert_eq!(visible_width("\x1b[1;31mIn Progress\x1b[0m"), 11);
+        assert_eq!(visible_width("🟠"), 2);
+        assert_eq!(visible_width("plain"), 5);
+    }
+
+    #[test]
+    fn trends_and_urgency_bars() {
+        assert_eq!(trend(5, Some(3)), "↑2");
+        assert_eq!(trend(1, Some(4)), "↓3");
+        assert_eq!(trend(2, Some(2)), "→");
+        assert_eq!(trend(2, None), "");
+        assert_eq!(urgency_bar(16, 16), format!("{} 16", "█".repeat(URGENCY_BAR_WIDTH)));
+        // Any urgency at all gets at least one block
+        assert_eq!(urgency_bar(1, 100), "█ 1");
+        assert_eq!(urgency_bar(0, 8), "");
+    }
+
+    #[test]
+    fn markdown_rows_stay_on_one_line() {
+        assert_eq!(markdown_row(&["ID", "TITLE"]), "| ID | TITLE |\n");
+        assert_eq!(markdown_row(&["a1", "x | y", "two\nlines", ""]), "| a1 | x \\| y | two<br>lines |  |\n");
+    }
+
+    #[test]
+    fn json_projection_keeps_only_chosen_fields() {
+        let card = testing::card("a1", serde_json::json!({ "title": "Ship it", "due_date": "2026-03-01T00:00:00Z" }));
+        let projected = project(&card, &[CardField::Title, CardField::Id, CardField::Due, CardField::EnteredColumn]);
+        assert_eq!(
+            projected,
+            serde_json::json!({
+                "title": "Ship it", "id": "a1", "due_date": "2026-03-01T00:00:00Z", "entered_column_at": null
+            })
+        );
+    }
+
+    #[test]
+    fn styles_parse_to_sgr() {
+        assert_eq!(parse_style("bold").unwrap(), "1");
+        assert_eq!(parse_style("white on red").unwrap(), "37;41");
+        assert_eq!(parse_style("Underline bright-yellow on bright-black").unwrap(), "4;93;100");
+        assert_eq!(parse_style("on").unwrap_err(), "expected a color after 'on'");
+        assert_eq!(parse_style("purple").unwrap_err(), "unknown style 'purple'");
+        assert!(parse_style(" ").is_err());
     }
 }
@@ -0,0 +1,92 @@
+//! Paging long tables, like git.
+//!
+//! With `--format table`, output taller than the terminal goes through
+//! `$PAGER` (`less -R` if unset) when stdout is a terminal; `--no-pager` turns
+//! this off. The pager gets `LESS=FRX` unless `LESS` is set, so colors survive
+//! and the table stays on screen after quitting. Other formats are never paged.
+//!
+//! All output to stdout goes through here ([`outln!`](crate::outln) and
+//! [`out!`](crate::out) in place of `println!` and `print!`), so a closed pipe
+//! (`blaze events | head`) ends the process quietly instead of panicking.
+
+use std::env;
+use std::io::{self, ErrorKind, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use terminal_size::{terminal_size, Height};
+
+/// Pager used when `$PAGER` is unset or empty
+const DEFAULT_PAGER: &str = "less -R";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Page long output for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Print `text`, through the pager if it's on and `text` doesn't fit on the screen
+pub fn page(text: &str) {
+    if ENABLED.load(Ordering::Relaxed) && io::stdout().is_terminal() {
+        // Leave a line for the shell prompt, as the pager would
+        let too_tall = terminal_size().is_some_and(|(_, Height(rows))| text.lines().count() >= rows as usize);
+        if too_tall && run_pager(text).is_ok() {
+            return;
+        }
+    }
+    print(text);
+}
+
+/// `println!` that ends the process quietly when stdout is a closed pipe
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::pager::print("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::pager::print(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// `print!` that ends the process quietly when stdout is a closed pipe
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::pager::print(&format!($($arg)*))
+    };
+}
+
+/// Print `text` to stdout as is
+pub fn print(text: &str) {
+    let mut stdout = io::stdout().lock();
+    if let Err(e) = stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush()) {
+        // The reader is gone (`| head`); nothing more to say to it
+        if e.kind() == ErrorKind::BrokenPipe {
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Hand `text` to the pager and wait for it (an error means nothing was shown)
+fn run_pager(text: &str) -> io::Result<()> {
+    let command = env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let words = shlex::split(&command)
+        .filter(|w| !w.is_empty())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, format!("can't parse PAGER '{}'", command)))?;
+
+    let mut pager = Command::new(&words[0]);
+    pager.args(&words[1..]).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+    let mut child = pager.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager before the end closes the pipe; that's fine
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,924 @@
+//! Parsers for user-facing input: dates, card ID prefixes, link URLs, filter
+//! queries, and quick-add lines.
+//!
+//! Query syntax (used by `--filter`):
+//!
+//! ```text
+//! column:todo AND (priority:high,urgent OR tag:backend) NOT tag:wontfix
+//! ```
+//!
+//! - `field:value` terms; a comma-separated value matches any of its items
+//! - `AND` (also implied between adjacent terms), `OR`, `NOT`, and parentheses
+//! - bare words match card titles (case-insensitive substring)
+//! - double quotes allow spaces: `title:"login page"`
+
+use crate::error::{BlazeError, Result};
+use crate::types::{AgentStatus, Card, CardCreate, Column, Priority};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use std::fmt;
+
+// --- Dates ---
+
+/// Parse a due date in `tz`: `YYYY-MM-DD` (the end of that day), `YYYY-MM-DD HH:MM`,
+/// or an RFC 3339 timestamp
+pub fn parse_due_date(input: &str, tz: Tz) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return end_of_day(date, tz);
+    }
+    let local = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M"))
+        .map_err(|_| {
+            BlazeError::InvalidInput(format!("Invalid date format '{}'. Use YYYY-MM-DD or YYYY-MM-DD HH:MM", input))
+        })?;
+    in_zone(local, tz)
+}
+
+fn end_of_day(date: NaiveDate, tz: Tz) -> Result<DateTime<Utc>> {
+    in_zone(date.and_hms_opt(23, 59, 59).unwrap(), tz)
+}
+
+/// The instant a wall-clock time in `tz` names (the first, if clocks go back)
+fn in_zone(local: NaiveDateTime, tz: Tz) -> Result<DateTime<Utc>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| {
+            BlazeError::InvalidInput(format!(
+                "{} doesn't exist in {} (the clocks skip it)",
+                local.format("%Y-%m-%d %H:%M"),
+                tz
+            ))
+        })
+}
+
+/// Starting point for `changes --since`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    /// Where the previous `--since last` run left off
+    Last,
+    At(DateTime<Utc>),
+}
+
+/// Parse an interval like `30s`, `5m` or `1h` (plain numbers are seconds)
+pub fn parse_interval(input: &str) -> Result<std::time::Duration> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+    let (amount, unit) = input.split_at(split);
+    let seconds = match (amount.parse::<u64>(), unit) {
+        (Ok(n), "" | "s") => Some(n),
+        (Ok(n), "m") => n.checked_mul(60),
+        (Ok(n), "h") => n.checked_mul(3600),
+        _ => {
+            return Err(BlazeError::InvalidInput(format!(
+                "Invalid interval '{}'. Use e.g. 30s, 5m or 1h",
+                input
+            )))
+        }
+    };
+    let seconds = seconds.ok_or_else(|| BlazeError::InvalidInput(format!("Interval '{}' is too long", input)))?;
+    if seconds == 0 {
+        return Err(BlazeError::InvalidInput("Interval must be longer than zero".into()));
+    }
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+/// Parse `last`, an RFC 3339 timestamp, a date (midnight UTC), or an age like `30m`, `2h`, `7d`
+pub fn parse_since(input: &str) -> Result<Since> {
+    let input = input.trim();
+    if input == "last" {
+        return Ok(Since::Last);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(Since::At(dt.with_timezone(&Utc)));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(Since::At(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())));
+    }
+
+    let unit_at = input.len().saturating_sub(1);
+    let amount = input.get(..unit_at).and_then(|n| n.parse::<u32>().ok());
+    let age = match (amount, input.get(unit_at..)) {
+        (Some(n), Some("m")) => chrono::Duration::minutes(n.into()),
+        (Some(n), Some("h")) => chrono::Duration::hours(n.into()),
+        (Some(n), Some("d")) => chrono::Duration::days(n.into()),
+        _ => {
+            return Err(BlazeError::InvalidInput(format!(
+                "Invalid --since '{}'. Use last, YYYY-MM-DD, an RFC 3339 timestamp, or an age like 30m, 2h, 7d",
+                input
+            )))
+        }
+    };
+    Utc::now()
+        .checked_sub_signed(age)
+        .map(Since::At)
+        .ok_or_else(|| BlazeError::InvalidInput(format!("--since '{}' is too long ago", input)))
+}
+
+/// Parse a point in time like `--since` does, except `last`
+pub fn parse_time(input: &str) -> Result<DateTime<Utc>> {
+    match parse_since(input)? {
+        Since::At(at) => Ok(at),
+        Since::Last => Err(BlazeError::InvalidInput(
+            "'last' only works with `blaze changes`; use a date or an age like 7d".into(),
+        )),
+    }
+}
+
+/// Parse when a card expires: an RFC 3339 timestamp, a date (end of day UTC), or a
+/// time from now like `12h`, `3d` or `2w`
+pub fn parse_expiry(input: &str) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok() {
+        return parse_due_date(input, Tz::UTC);
+    }
+
+    let unit_at = input.len().saturating_sub(1);
+    let amount = input.get(..unit_at).and_then(|n| n.parse::<u32>().ok()).filter(|n| *n > 0);
+    let lifetime = match (amount, input.get(unit_at..)) {
+        (Some(n), Some("h")) => chrono::Duration::hours(n.into()),
+        (Some(n), Some("d")) => chrono::Duration::days(n.into()),
+        (Some(n), Some("w")) => chrono::Duration::weeks(n.into()),
+        _ => {
+            return Err(BlazeError::InvalidInput(format!(
+                "Invalid expiry '{}'. Use YYYY-MM-DD, an RFC 3339 timestamp, or a time from now like 12h, 3d, 2w",
+                input
+            )))
+        }
+    };
+    Utc::now()
+        .checked_add_signed(lifetime)
+        .ok_or_else(|| BlazeError::InvalidInput(format!("Expiry '{}' is too far away", input)))
+}
+
+/// Deserialize an optional due date given as YYYY-MM-DD (UTC) or an RFC 3339 timestamp
+pub fn deserialize_due_date<'de, D>(deserializer: D) -> std::result::Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    let Some(input) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    parse_due_date(&input, Tz::UTC)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid due date '{}' (use YYYY-MM-DD)", input)))
+}
+
+// --- Columns ---
+
+/// Parse a column name as typed by the user (`in-progress`, `QA signoff`).
+/// Whether the board has it is checked against the server later.
+pub fn parse_column(input: &str) -> Result<Column> {
+    input.parse().map_err(BlazeError::InvalidInput)
+}
+
+// --- Card IDs ---
+
+/// Validate a card ID or ID prefix as typed by the user
+pub fn parse_id_prefix(input: &str) -> Result<String> {
+    let id = input.trim();
+
+    if id.is_empty() {
+        return Err(BlazeError::InvalidInput("Card ID must not be empty".into()));
+    }
+
+    if let Some(bad) = id.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_')) {
+        return Err(BlazeError::InvalidInput(format!(
+            "Invalid card ID '{}': unexpected character '{}'",
+            id, bad
+        )));
+    }
+
+    Ok(id.to_string())
+}
+
+// --- Links ---
+
+/// Validate an external link: an absolute URL with a host, like `https://ci.example.com/run/42`
+pub fn parse_link_url(input: &str) -> Result<String> {
+    let input = input.trim();
+    match reqwest::Url::parse(input) {
+        Ok(url) if url.has_host() => Ok(input.to_string()),
+        _ => Err(BlazeError::InvalidInput(format!(
+            "Invalid link '{}'. Use a full URL like https://example.com/page",
+            input
+        ))),
+    }
+}
+
+// --- Filter queries ---
+
+/// A parsed filter query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Term(Term),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+/// A single `field:value` condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Column(Vec<Column>),
+    Priority(Vec<Priority>),
+    Tag(Vec<String>),
+    Agent(Vec<AgentStatus>),
+    Title(String),
+    Id(String),
+    Due(DueFilter),
+}
+
+/// Due date conditions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DueFilter {
+    Overdue,
+    None,
+    Any,
+    /// Due on or before the given date
+    Before(DateTime<Utc>),
+}
+
+const FIELDS: &str = "column, priority, tag, agent, title, id, due";
+
+impl Query {
+    /// Parse a query string
+    pub fn parse(input: &str) -> Result<Query> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(query_error(0, "query is empty"));
+        }
+
+        let mut parser = QueryParser { tokens, pos: 0 };
+        let query = parser.parse_or()?;
+
+        if let Some(tok) = parser.tokens.get(parser.pos) {
+            return Err(query_error(tok.offset, &format!("unexpected {}", tok.kind)));
+        }
+        Ok(query)
+    }
+
+    /// Check whether a card satisfies the query
+    pub fn matches(&self, card: &Card) -> bool {
+        match self {
+            Query::Term(term) => term.matches(card),
+            Query::Not(q) => !q.matches(card),
+            Query::And(a, b) => a.matches(card) && b.matches(card),
+            Query::Or(a, b) => a.matches(card) || b.matches(card),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, card: &Card) -> bool {
+        match self {
+            Term::Column(cols) => cols.contains(&card.column),
+            Term::Priority(prios) => prios.contains(&card.priority),
+            Term::Tag(tags) => tags.iter().any(|t| card.tags.iter().any(|ct| ct.eq_ignore_ascii_case(t))),
+            Term::Agent(statuses) => card.agent_status.is_some_and(|s| statuses.contains(&s)),
+            Term::Title(text) => card.title.to_lowercase().contains(&text.to_lowercase()),
+            Term::Id(prefix) => card.id.starts_with(prefix.as_str()),
+            Term::Due(DueFilter::Overdue) => card.due_date.is_some_and(|d| d < Utc::now()),
+            Term::Due(DueFilter::None) => card.due_date.is_none(),
+            Term::Due(DueFilter::Any) => card.due_date.is_some(),
+            Term::Due(DueFilter::Before(limit)) => card.due_date.is_some_and(|d| d <= *limit),
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Query::Term(term) => write!(f, "{}", term),
+            Query::Not(q) => write!(f, "NOT {}", q),
+            Query::And(a, b) => write!(f, "({} AND {})", a, b),
+            Query::Or(a, b) => write!(f, "({} OR {})", a, b),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: fmt::Display>(items: &[T]) -> String {
+            items.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(",")
+        }
+
+        match self {
+            Term::Column(cols) => write!(f, "column:{}", join(cols)),
+            Term::Priority(prios) => write!(f, "priority:{}", join(prios)),
+            Term::Tag(tags) => write!(f, "tag:{}", quote(&tags.join(","))),
+            Term::Agent(statuses) => write!(f, "agent:{}", join(statuses)),
+            Term::Title(text) => write!(f, "title:{}", quote(text)),
+            Term::Id(prefix) => write!(f, "id:{}", prefix),
+            Term::Due(DueFilter::Overdue) => write!(f, "due:overdue"),
+            Term::Due(DueFilter::None) => write!(f, "due:none"),
+            Term::Due(DueFilter::Any) => write!(f, "due:any"),
+            Term::Due(DueFilter::Before(d)) => write!(f, "due:{}", d.format("%Y-%m-%d")),
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s)
+}
+
+fn query_error(offset: usize, message: &str) -> BlazeError {
+    BlazeError::InvalidInput(format!("Invalid query at position {}: {}", offset + 1, message))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Word { text: String, quoted: bool },
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::LParen => write!(f, "'('"),
+            TokenKind::RParen => write!(f, "')'"),
+            TokenKind::Word { text, .. } => write!(f, "'{}'", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    offset: usize,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token { kind: TokenKind::LParen, offset });
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token { kind: TokenKind::RParen, offset });
+                i += 1;
+            }
+            _ => {
+                let mut text = String::new();
+                let mut quoted = false;
+                while i < chars.len() {
+                    let (pos, c) = chars[i];
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    if c == '"' {
+                        quoted = true;
+                        i += 1;
+                        loop {
+                            match chars.get(i) {
+                                Some((_, '"')) => break,
+                                Some((_, c)) => text.push(*c),
+                                None => return Err(query_error(pos, "unterminated quote")),
+                            }
+                            i += 1;
+                        }
+                    } else {
+                        text.push(c);
+                    }
+                    i += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Word { text, quoted },
+                    offset,
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct QueryParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.tokens.get(self.pos),
+            Some(Token { kind: TokenKind::Word { text, quoted: false }, .. }) if text == keyword
+        )
+    }
+
+    fn end_offset(&self) -> usize {
+        self.tokens
+            .last()
+            .map(|t| match t.kind {
+                TokenKind::Word { ref text, .. } => t.offset + text.len(),
+                _ => t.offset + 1,
+            })
+            .unwrap_or(0)
+    }
+
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("OR") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Query::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut left = self.parse_not()?;
+        loop {
+            if self.peek_keyword("AND") {
+                self.pos += 1;
+            } else if self.pos >= self.tokens.len()
+                || self.peek_keyword("OR")
+                || self.tokens[self.pos].kind == TokenKind::RParen
+            {
+                break;
+            }
+            let right = self.parse_not()?;
+            left = Query::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Query> {
+        if self.peek_keyword("NOT") {
+            self.pos += 1;
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Query> {
+        let tok = match self.tokens.get(self.pos) {
+            Some(tok) => tok.clone(),
+            None => return Err(query_error(self.end_offset(), "expected a term")),
+        };
+        self.pos += 1;
+
+        match tok.kind {
+            TokenKind::LParen => {
+                let inner = self.parse_or()?;
+                match self.tokens.get(self.pos) {
+                    Some(Token { kind: TokenKind::RParen, .. }) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(query_error(tok.offset, "unclosed '('")),
+                }
+            }
+            TokenKind::RParen => Err(query_error(tok.offset, "unexpected ')'")),
+            TokenKind::Word { ref text, quoted } => {
+                if !quoted && matches!(text.as_str(), "AND" | "OR") {
+                    return Err(query_error(tok.offset, &format!("expected a term before '{}'", text)));
+                }
+                parse_term(text, tok.offset).map(Query::Term)
+            }
+        }
+    }
+}
+
+fn parse_term(text: &str, offset: usize) -> Result<Term> {
+    let (field, value) = match text.split_once(':') {
+        Some((field, value)) if !field.is_empty() && field.chars().all(|c| c.is_ascii_alphabetic()) => {
+            (field.to_ascii_lowercase(), value)
+        }
+        _ => return Ok(Term::Title(text.to_string())),
+    };
+
+    if value.is_empty() {
+        return Err(query_error(offset, &format!("expected a value after '{}:'", field)));
+    }
+
+    match field.as_str() {
+        "column" | "col" => parse_columns(value, offset).map(Term::Column),
+        "priority" | "prio" => parse_list(value, offset, &field).map(Term::Priority),
+        "agent" => parse_list(value, offset, &field).map(Term::Agent),
+        "tag" => Ok(Term::Tag(value.split(',').filter(|t| !t.is_empty()).map(String::from).collect())),
+        "title" => Ok(Term::Title(value.to_string())),
+        "id" => parse_id_prefix(value)
+            .map(Term::Id)
+            .map_err(|_| query_error(offset, &format!("invalid card ID '{}'", value))),
+        "due" => match value {
+            "overdue" => Ok(Term::Due(DueFilter::Overdue)),
+            "none" => Ok(Term::Due(DueFilter::None)),
+            "any" => Ok(Term::Due(DueFilter::Any)),
+            date => parse_due_date(date, Tz::UTC).map(|d| Term::Due(DueFilter::Before(d))).map_err(|_| {
+                query_error(
+                    offset,
+                    &format!("invalid due value '{}' (use overdue, none, any, or YYYY-MM-DD)", date),
+                )
+            }),
+        },
+        other => Err(query_error(
+            offset,
+            &format!("unknown field '{}' (expected one of: {})", other, FIELDS),
+        )),
+    }
+}
+
+fn parse_list<T: ValueEnum + fmt::Display>(value: &str, offset: usize, field: &str) -> Result<Vec<T>> {
+    value
+        .split(',')
+        .map(|item| {
+            find_variant(item).ok_or_else(|| {
+                query_error(
+                    offset,
+                    &format!("invalid {} '{}' (expected one of: {})", field, item, variant_names::<T>()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Any well-formed name goes; boards can have columns of their own
+fn parse_columns(value: &str, offset: usize) -> Result<Vec<Column>> {
+    value.split(',').map(|item| item.parse().map_err(|e: String| query_error(offset, &e))).collect()
+}
+
+fn find_variant<T: ValueEnum + fmt::Display>(value: &str) -> Option<T> {
+    // Accept both the API spelling (in_progress) and the flag spelling (in-progress)
+    let normalized = value.replace('-', "_");
+    T::value_variants().iter().find(|v| v.to_string().eq_ignore_ascii_case(&normalized)).cloned()
+}
+
+fn variant_names<T: ValueEnum + fmt::Display>() -> String {
+    T::value_variants().iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// --- Quick add ---
+
+/// Parse a quick-add line: a title with `!priority`, `#tag`, `@due` and
+/// `^column` words anywhere in it, e.g. `Fix login bug !high #backend @friday`.
+///
+/// `@` takes a date (YYYY-MM-DD, or YYYY-MM-DDTHH:MM), `today`, `tomorrow`, a
+/// weekday (the next one, today included) or a time from today like `3d` or
+/// `2w`; days end at midnight in `tz`. A leading `\` keeps a word as title
+/// text (`\#123`).
+pub fn parse_quick(line: &str, today: NaiveDate, tz: Tz) -> Result<CardCreate> {
+    let mut title = Vec::new();
+    let mut tags = Vec::new();
+    let (mut priority, mut due, mut column) = (None, None, None);
+    for word in line.split_whitespace() {
+        let mut chars = word.chars();
+        let marker = chars.next().unwrap_or_default();
+        let rest = chars.as_str();
+        if rest.is_empty() {
+            title.push(word);
+            continue;
+        }
+        match marker {
+            '\\' => title.push(rest),
+            '!' => set_once(&mut priority, quick_choice(rest, "priority")?, word)?,
+            '#' => tags.push(rest.to_string()),
+            '@' => set_once(&mut due, quick_due(rest, today, tz)?, word)?,
+            '^' => set_once(&mut column, rest.parse().map_err(BlazeError::InvalidInput)?, word)?,
+            _ => title.push(word),
+        }
+    }
+
+    let mut builder = CardCreate::builder().tags(tags).maybe_due_date(due);
+    if !title.is_empty() {
+        builder = builder.title(title.join(" "));
+    }
+    if let Some(priority) = priority {
+        builder = builder.priority(priority);
+    }
+    if let Some(column) = column {
+        builder = builder.column(column);
+    }
+    Ok(builder.build()?)
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, word: &str) -> Result<()> {
+    if slot.is_some() {
+        return Err(BlazeError::InvalidInput(format!("'{}' repeats an earlier marker of the same kind", word)));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn quick_choice<T: ValueEnum + fmt::Display>(value: &str, field: &str) -> Result<T> {
+    find_variant(value).ok_or_else(|| {
+        BlazeError::InvalidInput(format!(
+            "Invalid {} '{}' (expected one of: {})",
+            field,
+            value,
+            variant_names::<T>()
+        ))
+    })
+}
+
+fn quick_due(value: &str, today: NaiveDate, tz: Tz) -> Result<DateTime<Utc>> {
+    let lower = value.to_lowercase();
+    let unit_at = lower.len().saturating_sub(1);
+    let amount = lower.get(..unit_at).and_then(|n| n.parse::<u32>().ok());
+    let date = match (lower.as_str(), amount, lower.get(unit_at..)) {
+        ("today", ..) => Some(today),
+        ("tomorrow", ..) => today.succ_opt(),
+        (_, Some(n), Some("d")) => today.checked_add_days(chrono::Days::new(n.into())),
+        (_, Some(n), Some("w")) => today.checked_add_days(chrono::Days::new(u64::from(n) * 7)),
+        _ => match lower.parse::<Weekday>() {
+            Ok(day) => {
+                let ahead = (day.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+                today.checked_add_days(chrono::Days::new(ahead.into()))
+            }
+            Err(_) => None,
+        },
+    };
+    match date {
+        Some(date) => end_of_day(date, tz),
+        None => parse_due_date(value, tz).map_err(|_| {
+            BlazeError::InvalidInput(format!(
+                "Invalid due date '@{}'. Use YYYY-MM-DD, today, tomorrow, a weekday, or 3d/2w from today",
+                value
+            ))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use proptest::prelude::*;
+
+    fn err(result: Result<impl fmt::Debug>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    fn card(column: Column, priority: Priority, tags: &[&str], title: &str) -> Card {
+        let fields = serde_json::json!({ "title": title, "priority": priority, "column": column, "tags": tags });
+        testing::card("84eb0f2a11b7", fields)
+    }
+
+    #[test]
+    fn due_date_is_end_of_day_utc() {
+        let d = parse_due_date("2026-02-10", Tz::UTC).unwrap();
+        assert_eq!(d.to_rfc3339(), "2026-02-10T23:59:59+00:00");
+    }
+
+    #[test]
+    fn due_date_in_time_zone() {
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        let due = |input: &str| parse_due_date(input, berlin).unwrap().to_rfc3339();
+        assert_eq!(due("2026-02-10"), "2026-02-10T22:59:59+00:00");
+        assert_eq!(due("2025-07-01 17:00"), "2025-07-01T15:00:00+00:00");
+        assert_eq!(due("2025-07-01T17:00"), "2025-07-01T15:00:00+00:00");
+        assert_eq!(due("2025-07-01T17:00:00Z"), "2025-07-01T17:00:00+00:00");
+        // 02:30 on the day clocks spring forward never happens in Berlin
+        assert!(err(parse_due_date("2026-03-29 02:30", berlin)).contains("doesn't exist in Europe/Berlin"));
+    }
+
+    #[test]
+    fn due_date_errors() {
+        assert_eq!(
+            err(parse_due_date("10/02/2026", Tz::UTC)),
+            "Invalid input: Invalid date format '10/02/2026'. Use YYYY-MM-DD or YYYY-MM-DD HH:MM"
+        );
+        assert_eq!(
+            err(parse_due_date("2026-02-30", Tz::UTC)),
+            "Invalid input: Invalid date format '2026-02-30'. Use YYYY-MM-DD or YYYY-MM-DD HH:MM"
+        );
+    }
+
+    #[test]
+    fn out_of_range_times_are_errors() {
+        assert_eq!(err(parse_since("100000000d")), "Invalid input: --since '100000000d' is too long ago");
+        assert_eq!(
+            err(parse_interval("9999999999999999h")),
+            "Invalid input: Interval '9999999999999999h' is too long"
+        );
+        assert_eq!(err(parse_expiry("99999999w")), "Invalid input: Expiry '99999999w' is too far away");
+    }
+
+    #[test]
+    fn quick_add_markers() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 10).unwrap(); // a Tuesday
+        let card = parse_quick("Fix login bug !high #backend @friday ^in-progress \\#12", today, Tz::UTC).unwrap();
+        assert_eq!(card.title, "Fix login bug #12");
+        assert_eq!((card.priority, card.column), (Priority::High, Column::InProgress));
+        assert_eq!(card.tags, ["backend"]);
+        assert_eq!(card.due_date.unwrap().to_rfc3339(), "2026-02-13T23:59:59+00:00");
+
+        let due = |word: &str| {
+            let card = parse_quick(&format!("T {}", word), today, Tz::UTC).unwrap();
+            card.due_date.unwrap().date_naive()
+        };
+        assert_eq!(due("@tue"), today);
+        assert_eq!(due("@Tomorrow").to_string(), "2026-02-11");
+        assert_eq!(due("@2w").to_string(), "2026-02-24");
+        assert_eq!(due("@2026-03-01").to_string(), "2026-03-01");
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let card = parse_quick("T @today", today, tokyo).unwrap();
+        assert_eq!(card.due_date.unwrap().to_rfc3339(), "2026-02-10T14:59:59+00:00");
+
+        let plain = parse_quick("Ship it ! now", today, Tz::UTC).unwrap();
+        assert_eq!(plain.title, "Ship it ! now");
+        assert_eq!((plain.priority, plain.column), (Priority::Medium, Column::Todo));
+        assert!(err(parse_quick("T !hi", today, Tz::UTC)).contains("Invalid priority 'hi'"));
+        assert!(err(parse_quick("T @someday", today, Tz::UTC)).contains("Invalid due date '@someday'"));
+        assert!(err(parse_quick("T !low !high", today, Tz::UTC)).contains("'!high' repeats"));
+        assert_eq!(err(parse_quick("#only #tags", today, Tz::UTC)), "Validation failed: title is required");
+    }
+
+    #[test]
+    fn expiry_forms() {
+        assert_eq!(parse_expiry("2026-02-10").unwrap().to_rfc3339(), "2026-02-10T23:59:59+00:00");
+        assert_eq!(parse_expiry("2026-02-10T08:30:00Z").unwrap().to_rfc3339(), "2026-02-10T08:30:00+00:00");
+        let in_three_days = parse_expiry("3d").unwrap() - Utc::now();
+        assert!(in_three_days > chrono::Duration::hours(71) && in_three_days <= chrono::Duration::days(3));
+        assert!(parse_expiry("2w").unwrap() > Utc::now() + chrono::Duration::days(13));
+        for bad in ["0d", "3m", "soon", ""] {
+            assert!(err(parse_expiry(bad)).starts_with("Invalid input: Invalid expiry"), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn since_forms() {
+        assert_eq!(parse_since("last").unwrap(), Since::Last);
+        assert_eq!(
+            parse_since("2026-02-10").unwrap(),
+            Since::At("2026-02-10T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parse_since("2026-02-10T08:30:00+01:00").unwrap(),
+            Since::At("2026-02-10T07:30:00Z".parse().unwrap())
+        );
+        let Since::At(at) = parse_since("2h").unwrap() else { panic!("expected a time") };
+        assert!((Utc::now() - at - chrono::Duration::hours(2)).num_seconds().abs() < 5);
+        for bad in ["", "h", "2w", "-1d", "yesterday"] {
+            assert!(parse_since(bad).is_err(), "input: {bad}");
+        }
+    }
+
+    #[test]
+    fn interval_forms() {
+        assert_eq!(parse_interval("60s").unwrap().as_secs(), 60);
+        assert_eq!(parse_interval("90").unwrap().as_secs(), 90);
+        assert_eq!(parse_interval("5m").unwrap().as_secs(), 300);
+        assert_eq!(parse_interval("1h").unwrap().as_secs(), 3600);
+        for bad in ["", "0s", "s", "1d", "-5s", "1.5m"] {
+            assert!(parse_interval(bad).is_err(), "input: {bad}");
+        }
+    }
+
+    #[test]
+    fn id_prefix_errors() {
+        assert_eq!(err(parse_id_prefix("  ")), "Invalid input: Card ID must not be empty");
+        assert_eq!(
+            err(parse_id_prefix("84eb/../x")),
+            "Invalid input: Invalid card ID '84eb/../x': unexpected character '/'"
+        );
+    }
+
+    #[test]
+    fn link_urls_need_a_host() {
+        assert_eq!(parse_link_url(" https://ci.example.com/runs/42 ").unwrap(), "https://ci.example.com/runs/42");
+        assert!(parse_link_url("ci.example.com/runs/42").is_err());
+        assert!(parse_link_url("mailto:team@example.com").is_err());
+    }
+
+    #[test]
+    fn query_precedence() {
+        let q = Query::parse("column:todo tag:a OR NOT priority:low").unwrap();
+        assert_eq!(q.to_string(), "((column:todo AND tag:\"a\") OR NOT priority:low)");
+    }
+
+    #[test]
+    fn query_matches_cards() {
+        let q = Query::parse("column:todo AND (priority:high,urgent OR tag:Backend) NOT login").unwrap();
+        assert!(q.matches(&card(Column::Todo, Priority::High, &[], "Fix headers")));
+        assert!(q.matches(&card(Column::Todo, Priority::Low, &["backend"], "Fix headers")));
+        assert!(!q.matches(&card(Column::Todo, Priority::Low, &[], "Fix headers")));
+        assert!(!q.matches(&card(Column::Todo, Priority::High, &[], "Fix login page")));
+        assert!(!q.matches(&card(Column::Done, Priority::High, &[], "Fix headers")));
+    }
+
+    #[test]
+    fn query_quoted_values() {
+        let q = Query::parse("title:\"login page\" \"OR\"").unwrap();
+        assert_eq!(
+            q,
+            Query::And(
+                Box::new(Query::Term(Term::Title("login page".into()))),
+                Box::new(Query::Term(Term::Title("OR".into())))
+            )
+        );
+    }
+
+    #[test]
+    fn query_errors() {
+        let cases = [
+            ("", "Invalid query at position 1: query is empty"),
+            ("column:", "Invalid query at position 1: expected a value after 'column:'"),
+            ("column:on+hold", "Invalid query at position 1: invalid column name 'on+hold'"),
+            ("priority:hi", "Invalid query at position 1: invalid priority 'hi' (expected one of: low, medium, high, urgent)"),
+            ("agent:idle", "Invalid query at position 1: invalid agent 'idle' (expected one of: ready, in_progress, blocked, needs_review)"),
+            ("owner:me", "Invalid query at position 1: unknown field 'owner' (expected one of: column, priority, tag, agent, title, id, due)"),
+            ("due:soon", "Invalid query at position 1: invalid due value 'soon' (use overdue, none, any, or YYYY-MM-DD)"),
+            ("id:a/b", "Invalid query at position 1: invalid card ID 'a/b'"),
+            ("tag:a AND", "Invalid query at position 10: expected a term"),
+            ("OR tag:a", "Invalid query at position 1: expected a term before 'OR'"),
+            ("(tag:a", "Invalid query at position 1: unclosed '('"),
+            ("tag:a)", "Invalid query at position 6: unexpected ')'"),
+            ("title:\"open", "Invalid query at position 7: unterminated quote"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(err(Query::parse(input)), format!("Invalid input: {}", expected), "input: {input}");
+        }
+    }
+
+    fn term_strategy() -> impl Strategy<Value = Query> {
+        prop_oneof![
+            prop::sample::subsequence(Column::DEFAULTS.to_vec(), 1..3).prop_map(|c| Query::Term(Term::Column(c))),
+            prop::sample::subsequence(Priority::value_variants().to_vec(), 1..3).prop_map(|p| Query::Term(Term::Priority(p))),
+            "[a-z][a-z0-9-]{0,8}".prop_map(|t| Query::Term(Term::Tag(vec![t]))),
+            "[a-z][a-z ]{0,10}[a-z]".prop_map(|t| Query::Term(Term::Title(t))),
+            "[0-9a-f]{1,12}".prop_map(|i| Query::Term(Term::Id(i))),
+            Just(Query::Term(Term::Due(DueFilter::Overdue))),
+        ]
+    }
+
+    fn query_strategy() -> impl Strategy<Value = Query> {
+        term_strategy().prop_recursive(4, 16, 2, |inner| {
+            prop_oneof![
+                inner.clone().prop_map(|q| Query::Not(Box::new(q))),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| Query::And(Box::new(a), Box::new(b))),
+                (inner.clone(), inner).prop_map(|(a, b)| Query::Or(Box::new(a), Box::new(b))),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn query_parse_never_panics(input in "\\PC{0,64}") {
+            let _ = Query::parse(&input);
+        }
+
+        #[test]
+        fn since_parse_never_panics(input in "\\PC{0,32}|[0-9]{1,12}[mhd]") {
+            let _ = parse_since(&input);
+        }
+
+        #[test]
+        fn interval_parse_never_panics(input in "\\PC{0,32}|[0-9]{1,20}[smh]?") {
+            let _ = parse_interval(&input);
+        }
+
+        #[test]
+        fn expiry_parse_never_panics(input in "\\PC{0,32}|[0-9]{1,12}[hdw]") {
+            let _ = parse_expiry(&input);
+        }
+
+        #[test]
+        fn due_date_parse_never_panics(input in "\\PC{0,32}|[0-9]{4,6}-[0-9]{2}-[0-9]{2}( [0-9]{2}:[0-9]{2})?") {
+            let _ = parse_due_date(&input, Tz::UTC);
+            let _ = parse_due_date(&input, Tz::Pacific__Kiritimati);
+        }
+
+        #[test]
+        fn query_display_round_trips(query in query_strategy()) {
+            prop_assert_eq!(Query::parse(&query.to_string()).unwrap(), query);
+        }
+
+        #[test]
+        fn due_date_round_trips(days in 0i64..200_000) {
+            let date = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap() + chrono::Duration::days(days);
+            let parsed = parse_due_date(&date.format("%Y-%m-%d").to_string(), Tz::UTC).unwrap();
+            prop_assert_eq!(parsed.date_naive(), date);
+        }
+
+        #[test]
+        fn id_prefix_accepts_hex(id in "[0-9a-f]{1,12}") {
+            prop_assert_eq!(parse_id_prefix(&id).unwrap(), id);
+        }
+
+        #[test]
+        fn id_prefix_rejects_path_characters(prefix in "[0-9a-f]{0,6}", sep in "[/?#%.]", suffix in "[0-9a-f]{1,6}") {
+            let id = format!("{}{}{}", prefix, sep, suffix);
+            prop_assert!(parse_id_prefix(&id).is_err());
+        }
+    }
+}
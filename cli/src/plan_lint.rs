@@ -0,0 +1,297 @@
+//! Checks on plan files, for `blaze plan lint` and before a plan is approved.
+//!
+//! - every heading `[plan_lint] sections` requires for the status is present
+//!   in some file
+//! - links between the plan's files (`[api](design.md#endpoints)`,
+//!   `[goal](#goal)`) point at a file and heading that exist
+//! - no TODO, FIXME, TBD or XXX markers
+//! - an "Acceptance criteria" heading (`criteria_section`) listing at least
+//!   one item
+//!
+//! Markers and missing criteria are only errors for approved plans; before
+//! that they're warnings. Fenced code blocks and inline code are skipped.
+
+use crate::config::PlanLintConfig;
+use crate::types::{PlanFile, PlanStatus};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Heading that lists the acceptance criteria unless configured otherwise
+const DEFAULT_CRITERIA_SECTION: &str = "Acceptance criteria";
+
+const MARKERS: &[&str] = &["TODO", "FIXME", "TBD", "XXX"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem in a plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Where, if the problem is in one place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Finding {
+    /// `file:line` or `-` for plan-wide problems
+    pub fn location(&self) -> String {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => format!("{}:{}", file, line),
+            (Some(file), None) => file.clone(),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+struct Heading {
+    level: usize,
+    text: String,
+    line: usize,
+}
+
+/// The parts of a Markdown file the checks look at
+struct Document<'a> {
+    name: &'a str,
+    headings: Vec<Heading>,
+    /// Line number and text of every line outside code blocks, inline code removed
+    lines: Vec<(usize, String)>,
+}
+
+impl<'a> Document<'a> {
+    fn parse(file: &'a PlanFile) -> Self {
+        let mut headings = Vec::new();
+        let mut lines = Vec::new();
+        let mut fence: Option<&str> = None;
+        for (i, line) in file.content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let opener = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f));
+            match (fence, opener) {
+                (None, Some(f)) => fence = Some(f),
+                (Some(open), Some(f)) if open == f => fence = None,
+                (Some(_), _) => {}
+                (None, None) => {
+                    let text = strip_inline_code(line);
+                    if let Some(heading) = parse_heading(&text, i + 1) {
+                        headings.push(heading);
+                    }
+                    lines.push((i + 1, text));
+                }
+            }
+        }
+        Self {
+            name: &file.name,
+            headings,
+            lines,
+        }
+    }
+
+    fn anchors(&self) -> HashSet<String> {
+        self.headings.iter().map(|h| slug(&h.text)).collect()
+    }
+}
+
+/// Check a plan's files as they'd be judged at `status`
+pub fn lint(files: &[PlanFile], status: PlanStatus, config: &PlanLintConfig) -> Vec<Finding> {
+    let docs: Vec<Document> = files.iter().map(Document::parse).collect();
+    let strict = if status == PlanStatus::Approved { Severity::Error } else { Severity::Warning };
+    let mut findings = Vec::new();
+
+    let has_heading = |name: &str| docs.iter().flat_map(|d| &d.headings).any(|h| same_heading(&h.text, name));
+    for section in config.sections.get(&status.to_string()).into_iter().flatten() {
+        if !has_heading(section) {
+            findings.push(Finding {
+                severity: Severity::Error,
+                file: None,
+                line: None,
+                message: format!("no '{}' section (required for {} plans)", section, status),
+            });
+        }
+    }
+
+    let anchors: HashMap<&str, HashSet<String>> = docs.iter().map(|d| (d.name, d.anchors())).collect();
+    for doc in &docs {
+        for (line, text) in &doc.lines {
+            let at = |severity, message| Finding {
+                severity,
+                file: Some(doc.name.to_string()),
+                line: Some(*line),
+                message,
+            };
+            for target in link_targets(text) {
+                if let Some(problem) = check_link(doc.name, target, &anchors) {
+                    findings.push(at(Severity::Error, problem));
+                }
+            }
+            if let Some(marker) = text.split(|c: char| !c.is_ascii_alphanumeric()).find(|w| MARKERS.contains(w)) {
+                findings.push(at(strict, format!("{} marker", marker)));
+            }
+        }
+    }
+
+    let criteria = config.criteria_section.as_deref().unwrap_or(DEFAULT_CRITERIA_SECTION);
+    let problem = match docs.iter().find_map(|d| criteria_items(d, criteria)) {
+        Some(0) => Some(format!("'{}' lists nothing", criteria)),
+        Some(_) => None,
+        None => Some(format!("no '{}' section", criteria)),
+    };
+    if let Some(message) = problem {
+        findings.push(Finding {
+            severity: strict,
+            file: None,
+            line: None,
+            message,
+        });
+    }
+    findings
+}
+
+/// Number of list items under the criteria heading, if a file has one
+fn criteria_items(doc: &Document, name: &str) -> Option<usize> {
+    let heading = doc.headings.iter().find(|h| same_heading(&h.text, name))?;
+    let end = doc
+        .headings
+        .iter()
+        .find(|h| h.line > heading.line && h.level <= heading.level)
+        .map_or(usize::MAX, |h| h.line);
+    let items = doc
+        .lines
+        .iter()
+        .filter(|(line, _)| *line > heading.line && *line < end)
+        .filter(|(_, text)| is_list_item(text.trim_start()))
+        .count();
+    Some(items)
+}
+
+fn is_list_item(text: &str) -> bool {
+    if let Some(rest) = text.strip_prefix(['-', '*', '+']) {
+        return rest.starts_with(' ');
+    }
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    digits > 0 && text[digits..].starts_with(". ")
+}
+
+/// A problem with a link, unless it points outside the plan or resolves
+fn check_link(from: &str, target: &str, anchors: &HashMap<&str, HashSet<String>>) -> Option<String> {
+    if target.contains("://") || target.starts_with("mailto:") || target.starts_with('/') {
+        return None;
+    }
+    let (file, anchor) = match target.split_once('#') {
+        Some((file, anchor)) => (file, Some(anchor)),
+        None => (target, None),
+    };
+    let file = if file.is_empty() { from } else { file.trim_start_matches("./") };
+    let Some(headings) = anchors.get(file) else {
+        return Some(format!("broken link '{}': no file {} in the plan", target, file));
+    };
+    match anchor {
+        Some(anchor) if !headings.contains(&anchor.to_lowercase()) => {
+            Some(format!("broken link '{}': no heading '{}' in {}", target, anchor, file))
+        }
+        _ => None,
+    }
+}
+
+/// Targets of the `[text](target)` links on a line
+fn link_targets(text: &str) -> Vec<&str> {
+    text.match_indices("](")
+        .filter_map(|(i, _)| {
+            let rest = &text[i + 2..];
+            let target = &rest[..rest.find(')')?];
+            // `[x](file.md "Title")`
+            let target = target.split_whitespace().next()?;
+            Some(target.trim_start_matches('<').trim_end_matches('>'))
+        })
+        .collect()
+}
+
+fn parse_heading(line: &str, number: usize) -> Option<Heading> {
+    let trimmed = line.trim_start();
+    let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let text = trimmed[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| Heading {
+        level,
+        text: text.trim().trim_end_matches('#').trim().to_string(),
+        line: number,
+    })
+}
+
+fn strip_inline_code(line: &str) -> String {
+    line.split('`').step_by(2).collect::<Vec<_>>().join("")
+}
+
+fn same_heading(text: &str, name: &str) -> bool {
+    text.trim_end_matches(':').trim().eq_ignore_ascii_case(name.trim())
+}
+
+/// GitHub-style anchor for a heading: lowercase, punctuation dropped, spaces as dashes
+fn slug(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> PlanFile {
+        PlanFile {
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<String> {
+        findings.iter().map(|f| format!("{:?} {} {}", f.severity, f.location(), f.message)).collect()
+    }
+
+    #[test]
+    fn finds_problems_in_plan_files() {
+        let files = [
+            file(
+                "overview.md",
+                "# Goal\nShip it. See [API](design.md#endpoints), [risks](#risk-list) and [docs](https://x.io).\n\
+                 TODO: pick a date\n```\nTODO in code is fine [x](missing.md)\n```\n## Acceptance criteria:\n",
+            ),
+            file("design.md", "# Design\n## Endpoints\nUse `TODO` freely in code spans.\n"),
+        ];
+        let config = PlanLintConfig {
+            sections: [("approved".to_string(), vec!["Goal".to_string(), "Risks".to_string()])].into(),
+            criteria_section: None,
+        };
+
+        assert_eq!(
+            messages(&lint(&files, PlanStatus::Approved, &config)),
+            [
+                "Error - no 'Risks' section (required for approved plans)",
+                "Error overview.md:2 broken link '#risk-list': no heading 'risk-list' in overview.md",
+                "Error overview.md:3 TODO marker",
+                "Error - 'Acceptance criteria' lists nothing",
+            ]
+        );
+        // Before approval only the link and the missing section for that status count
+        let draft = lint(&files, PlanStatus::Draft, &config);
+        assert_eq!(draft.iter().filter(|f| f.severity == Severity::Error).count(), 1);
+    }
+
+    #[test]
+    fn criteria_need_a_list_item() {
+        let config = PlanLintConfig::default();
+        let listed = [file("a.md", "## Acceptance Criteria\n\n1. Tests pass\n# Next\n")];
+        assert!(lint(&listed, PlanStatus::Approved, &config).is_empty());
+        let missing = [file("a.md", "# Goal\n- not criteria\n")];
+        assert_eq!(messages(&lint(&missing, PlanStatus::Ready, &config)), ["Warning - no 'Acceptance criteria' section"]);
+        assert_eq!(slug("Rollout & Risks: v2"), "rollout--risks-v2");
+    }
+}
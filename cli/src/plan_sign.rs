@@ -0,0 +1,283 @@
+//! Signed plan approvals.
+//!
+//! `plan approve --sign` writes a manifest of the plan's file checksums and
+//! has gpg clearsign it; the result is stored as the plan file
+//! `approval-<time>.asc`, so it travels with the plan. `plan verify` checks
+//! each such file twice: gpg must accept the signature, and the checksums it
+//! signed must still match the plan's files. Approval files themselves are
+//! left out of the manifest, so several people can sign the same plan.
+
+use crate::cache::sha256_hex;
+use crate::error::{BlazeError, Result};
+use crate::types::{Plan, PlanFile};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const HEADER: &str = "blaze plan approval v1";
+const BEGIN_MESSAGE: &str = "-----BEGIN PGP SIGNED MESSAGE-----";
+
+/// Whether a plan file holds a signed approval
+pub fn is_approval(name: &str) -> bool {
+    name.starts_with("approval-") && name.ends_with(".asc")
+}
+
+/// Name for a new approval file, numbered if one from the same second exists
+pub fn approval_name(plan: &Plan, at: DateTime<Utc>) -> String {
+    let stem = format!("approval-{}", at.format("%Y%m%dT%H%M%SZ"));
+    let taken = |name: &str| plan.files.iter().any(|f| f.name == name);
+    let mut name = format!("{}.asc", stem);
+    for n in 2.. {
+        if !taken(&name) {
+            break;
+        }
+        name = format!("{}-{}.asc", stem, n);
+    }
+    name
+}
+
+/// The text that gets signed: plan ID, time, and a checksum per file
+pub fn manifest(plan: &Plan, at: DateTime<Utc>) -> String {
+    let mut text = format!("{}\nplan: {}\napproved: {}\n", HEADER, plan.id, at.to_rfc3339());
+    for (name, sha256) in checksums(&plan.files) {
+        text.push_str(&format!("{}  {}\n", sha256, name));
+    }
+    text
+}
+
+fn checksums(files: &[PlanFile]) -> BTreeMap<&str, String> {
+    files
+        .iter()
+        .filter(|f| !is_approval(&f.name))
+        .map(|f| (f.name.as_str(), sha256_hex(f.content.as_bytes())))
+        .collect()
+}
+
+/// Clearsign `text` with gpg (its default key unless `key` is given)
+pub fn sign(text: &str, key: Option<&str>) -> Result<String> {
+    let mut gpg = Command::new("gpg");
+    gpg.args(["--armor", "--clearsign"]);
+    if let Some(key) = key {
+        gpg.args(["--local-user", key]);
+    }
+    let output = run_gpg(gpg, text)?;
+    if !output.status.success() {
+        return Err(BlazeError::Other(format!(
+            "gpg couldn't sign: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn run_gpg(mut gpg: Command, input: &str) -> Result<std::process::Output> {
+    let mut child = gpg
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| BlazeError::Other(format!("Can't run gpg: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+/// What gpg made of a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Good,
+    Bad,
+    UnknownKey,
+    ExpiredKey,
+    RevokedKey,
+    /// Not a clearsigned approval at all
+    Malformed,
+}
+
+impl SignatureStatus {
+    pub fn describe(self) -> &'static str {
+        match self {
+            SignatureStatus::Good => "good",
+            SignatureStatus::Bad => "BAD",
+            SignatureStatus::UnknownKey => "unknown key",
+            SignatureStatus::ExpiredKey => "expired key",
+            SignatureStatus::RevokedKey => "revoked key",
+            SignatureStatus::Malformed => "malformed",
+        }
+    }
+}
+
+/// One approval file checked against the plan
+#[derive(Debug, Serialize)]
+pub struct Verification {
+    pub file: String,
+    pub signature: SignatureStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<String>,
+    /// How the plan differs from what was signed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<String>,
+}
+
+impl Verification {
+    /// A good signature over the plan as it is now
+    pub fn holds(&self) -> bool {
+        self.signature == SignatureStatus::Good && self.differences.is_empty()
+    }
+}
+
+/// Check every approval file of a plan
+pub fn verify(plan: &Plan) -> Result<Vec<Verification>> {
+    plan.files.iter().filter(|f| is_approval(&f.name)).map(|f| verify_file(plan, f)).collect()
+}
+
+fn verify_file(plan: &Plan, file: &PlanFile) -> Result<Verification> {
+    let mut verification = Verification {
+        file: file.name.clone(),
+        signature: SignatureStatus::Malformed,
+        signer: None,
+        approved_at: None,
+        differences: Vec::new(),
+    };
+    // Another signed block could lend its good signature to unsigned checksums
+    if file.content.matches(BEGIN_MESSAGE).count() != 1 || !file.content.trim_start().starts_with(BEGIN_MESSAGE) {
+        return Ok(verification);
+    }
+
+    // The checksums are read from what gpg verified, not from the file
+    let mut gpg = Command::new("gpg");
+    gpg.args(["--batch", "--status-fd", "2", "--decrypt"]);
+    let output = run_gpg(gpg, &file.content)?;
+    let (signature, signer) = signature_status(&String::from_utf8_lossy(&output.stderr));
+    verification.signature = signature;
+    verification.signer = signer;
+
+    let signed = String::from_utf8_lossy(&output.stdout);
+    let mut lines = signed.lines();
+    if lines.next() != Some(HEADER) {
+        verification.signature = SignatureStatus::Malformed;
+        return Ok(verification);
+    }
+    let mut signed_sums = BTreeMap::new();
+    for line in lines {
+        if let Some(id) = line.strip_prefix("plan: ") {
+            if id != plan.id {
+                verification.differences.push(format!("signed for plan {}", id));
+            }
+        } else if let Some(at) = line.strip_prefix("approved: ") {
+            verification.approved_at = Some(at.to_string());
+        } else if let Some((sha256, name)) = line.split_once("  ") {
+            signed_sums.insert(name.to_string(), sha256.to_string());
+        }
+    }
+    verification.differences.extend(differences(&signed_sums, &checksums(&plan.files)));
+    Ok(verification)
+}
+
+/// The verdict and signer from gpg's `--status-fd` lines; anything but exactly one
+/// signature is malformed
+fn signature_status(status: &str) -> (SignatureStatus, Option<String>) {
+    let mut verdicts = Vec::new();
+    for line in status.lines() {
+        let mut words = line.strip_prefix("[GNUPG:] ").unwrap_or("").splitn(3, ' ');
+        let (keyword, _key, who) = (words.next(), words.next(), words.next().map(String::from));
+        let verdict = match keyword {
+            Some("GOODSIG") => (SignatureStatus::Good, who),
+            Some("BADSIG") => (SignatureStatus::Bad, who),
+            Some("EXPKEYSIG") => (SignatureStatus::ExpiredKey, who),
+            Some("REVKEYSIG") => (SignatureStatus::RevokedKey, who),
+            Some("ERRSIG") => (SignatureStatus::UnknownKey, None),
+            _ => continue,
+        };
+        verdicts.push(verdict);
+    }
+    match verdicts.len() {
+        1 => verdicts.remove(0),
+        _ => (SignatureStatus::Malformed, None),
+    }
+}
+
+fn differences(signed: &BTreeMap<String, String>, current: &BTreeMap<&str, String>) -> Vec<String> {
+    let mut differences = Vec::new();
+    for (name, sha256) in signed {
+        match current.get(name.as_str()) {
+            Some(now) if now == sha256 => {}
+            Some(_) => differences.push(format!("{} changed", name)),
+            None => differences.push(format!("{} removed", name)),
+        }
+    }
+    for name in current.keys().filter(|name| !signed.contains_key(**name)) {
+        differences.push(format!("{} added", name));
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PlanStatus;
+
+    fn plan(files: &[(&str, &str)]) -> Plan {
+        Plan {
+            id: "p1".into(),
+            title: "Plan".into(),
+            status: PlanStatus::Ready,
+            files: files
+                .iter()
+                .map(|(name, content)| PlanFile {
+                    name: name.to_string(),
+                    content: content.to_string(),
+                })
+                .collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn signed_manifest_tracks_file_changes() {
+        let at: DateTime<Utc> = "2026-03-10T12:00:00Z".parse().unwrap();
+        let before = plan(&[("overview.md", "# Goal\n"), ("tasks.md", "- [ ] one\n")]);
+        let manifest = manifest(&before, at);
+        assert!(manifest.starts_with("blaze plan approval v1\nplan: p1\napproved: 2026-03-10T12:00:00+00:00\n"));
+
+        let clearsigned = format!("{}\nHash: SHA512\n\n{}...\n", BEGIN_MESSAGE, manifest);
+        let sums = |plan: &Plan| -> BTreeMap<String, String> {
+            checksums(&plan.files).into_iter().map(|(n, s)| (n.to_string(), s)).collect()
+        };
+        let after = plan(&[
+            ("overview.md", "# Goal\nmore\n"),
+            ("notes.md", ""),
+            ("approval-20260310T120000Z.asc", clearsigned.as_str()),
+        ]);
+        let found = differences(&sums(&before), &checksums(&after.files));
+        assert_eq!(found, ["overview.md changed", "tasks.md removed", "notes.md added"]);
+    }
+
+    #[test]
+    fn gpg_status_lines() {
+        let good = "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 0123ABCD Dana <dana@example.com>\n[GNUPG:] VALIDSIG ...";
+        assert_eq!(signature_status(good), (SignatureStatus::Good, Some("Dana <dana@example.com>".into())));
+        let missing = "[GNUPG:] ERRSIG 0123ABCD 1 10 01 1700000000 9 -\n[GNUPG:] NO_PUBKEY 0123ABCD";
+        assert_eq!(signature_status(missing).0, SignatureStatus::UnknownKey);
+        assert_eq!(signature_status("").0, SignatureStatus::Malformed);
+        let twice = format!("{}\n[GNUPG:] NEWSIG\n[GNUPG:] BADSIG 4567CDEF Eve <eve@example.com>", good);
+        assert_eq!(signature_status(&twice).0, SignatureStatus::Malformed);
+    }
+
+    #[test]
+    fn approvals_with_more_than_one_signed_block_are_malformed() {
+        let content = format!("{0}\nHash: SHA512\n\n{1}\n...\n{0}\nHash: SHA512\n\n{1}\n...\n", BEGIN_MESSAGE, HEADER);
+        let plan = plan(&[("approval-20260310T120000Z.asc", content.as_str())]);
+        let found = verify(&plan).unwrap();
+        assert_eq!(found[0].signature, SignatureStatus::Malformed);
+        assert!(!found[0].holds());
+    }
+}
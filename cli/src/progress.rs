@@ -0,0 +1,70 @@
+//! Machine-readable progress on stderr (`--progress-json`).
+//!
+//! Long operations report one JSON object per step, e.g.
+//! `{"event":"progress","phase":"import","current":3,"total":40}`, starting
+//! at `current` 0. `total` is null when it isn't known up front (such as
+//! while following pages). Without the flag nothing is printed.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn progress events on for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[derive(Serialize)]
+struct Event<'a> {
+    event: &'static str,
+    phase: &'a str,
+    current: usize,
+    total: Option<usize>,
+}
+
+/// Progress through one phase of an operation
+pub struct Progress {
+    phase: String,
+    total: Option<usize>,
+    current: AtomicUsize,
+}
+
+impl Progress {
+    /// Begin a phase (reported as step 0)
+    pub fn start(phase: impl Into<String>, total: Option<usize>) -> Self {
+        let progress = Self {
+            phase: phase.into(),
+            total,
+            current: AtomicUsize::new(0),
+        };
+        progress.emit(0);
+        progress
+    }
+
+    /// One more step done
+    pub fn advance(&self) {
+        self.advance_by(1);
+    }
+
+    /// `n` more steps done (e.g. the items on a page)
+    pub fn advance_by(&self, n: usize) {
+        let current = self.current.fetch_add(n, Ordering::Relaxed) + n;
+        self.emit(current);
+    }
+
+    fn emit(&self, current: usize) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let event = Event {
+            event: "progress",
+            phase: &self.phase,
+            current,
+            total: self.total,
+        };
+        if let Ok(line) = serde_json::to_string(&event) {
+            eprintln!("{}", line);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Read-only board snapshots served over plain HTTP.
+//!
+//! `blaze publish` renders the board to HTML (auto-refreshing, for status
+//! pages on team TVs) and JSON, and serves the latest rendering to anyone who
+//! asks. Only GET is answered and nothing is forwarded to the API, so the
+//! token never leaves the machine running blaze.
+//!
+//! Routes: `/` (HTML), `/board.json`, `/health`.
+
+use crate::error::Result;
+use crate::types::{AgentStatus, Card, Column, Priority};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// What a snapshot shows of a card (no descriptions, progress or criteria)
+#[derive(Debug, Serialize)]
+pub struct PublicCard {
+    pub id: String,
+    pub title: String,
+    pub priority: Priority,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<DateTime<Utc>>,
+    pub overdue: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_status: Option<AgentStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnSnapshot {
+    pub column: Column,
+    pub name: String,
+    pub cards: Vec<PublicCard>,
+}
+
+/// The board at one point in time
+#[derive(Debug, Serialize)]
+pub struct Snapshot {
+    pub generated_at: DateTime<Utc>,
+    pub total: usize,
+    pub columns: Vec<ColumnSnapshot>,
+    /// The latest refresh failed, so the cards are from an earlier one. Why stays on
+    /// stderr: error text can name internal URLs and carry server responses.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+impl Snapshot {
+    pub fn new(cards: &[Card], columns: &[Column]) -> Self {
+        let now = Utc::now();
+        let columns = columns
+            .iter()
+            .map(|column| {
+                let mut in_column: Vec<&Card> = cards.iter().filter(|c| c.column == *column).collect();
+                in_column.sort_by_key(|c| (c.position, c.created_at));
+                ColumnSnapshot {
+                    column: column.clone(),
+                    name: column.display_name().into_owned(),
+                    cards: in_column
+                        .into_iter()
+                        .map(|c| PublicCard {
+                            id: c.id.clone(),
+                            title: c.title.clone(),
+                            priority: c.priority,
+                            tags: c.tags.clone(),
+                            due_date: c.due_date,
+                            overdue: *column != Column::Done && c.due_date.is_some_and(|d| d < now),
+                            agent_status: c.agent_status,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Snapshot {
+            generated_at: now,
+            total: cards.len(),
+            columns,
+            stale: false,
+        }
+    }
+
+    /// A self-refreshing HTML page
+    pub fn to_html(&self, refresh_secs: u64) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<meta http-equiv=\"refresh\" content=\"{}\">\n", refresh_secs));
+        html.push_str("<title>Blaze board</title>\n<style>");
+        html.push_str(STYLE);
+        html.push_str("</style></head><body>\n");
+        html.push_str(&format!(
+            "<header>Blaze board · {} cards · updated {}</header>\n",
+            self.total,
+            self.generated_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+        if self.stale {
+            html.push_str("<p class=\"stale\">Refresh failed; these cards may be out of date</p>\n");
+        }
+        // One grid track per column, however many the board has
+        html.push_str(&format!(
+            "<main style=\"grid-template-columns: repeat({}, 1fr)\">\n",
+            self.columns.len().max(1)
+        ));
+        for column in &self.columns {
+            html.push_str(&format!(
+                "<section><h2>{} <span>{}</span></h2>\n",
+                escape(&column.name),
+                column.cards.len()
+            ));
+            for card in &column.cards {
+                html.push_str(&format!("<article class=\"{}\">", card.priority));
+                html.push_str(&format!("<h3>{} {}</h3>", card.priority.emoji(), escape(&card.title)));
+                if let Some(status) = card.agent_status {
+                    html.push_str(&format!("<p>{} agent: {}</p>", status.emoji(), status));
+                }
+                if let Some(due) = card.due_date {
+                    let class = if card.overdue { " class=\"overdue\"" } else { "" };
+                    html.push_str(&format!("<p{}>Due {}</p>", class, due.format("%Y-%m-%d")));
+                }
+                if !card.tags.is_empty() {
+                    let tags: Vec<String> = card.tags.iter().map(|t| format!("<i>{}</i>", escape(t))).collect();
+                    html.push_str(&format!("<p>{}</p>", tags.join(" ")));
+                }
+                html.push_str("</article>\n");
+            }
+            html.push_str("</section>\n");
+        }
+        html.push_str("</main></body></html>\n");
+        html
+    }
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; background: #111; color: #eee; margin: 1rem; }
+header { font-size: 1.2rem; margin-bottom: 1rem; }
+main { display: grid; gap: 1rem; }
+h2 span { color: #888; font-weight: normal; }
+article { background: #222; border-left: 4px solid #666; border-radius: 4px; padding: .5rem; margin-bottom: .5rem; }
+article.urgent { border-color: #e33; } article.high { border-color: #f90; }
+article.medium { border-color: #ec3; } article.low { border-color: #3c6; }
+h3 { font-size: 1rem; margin: 0; } p { margin: .3rem 0 0; font-size: .85rem; color: #aaa; }
+i { background: #333; border-radius: 3px; padding: 0 .3rem; font-style: normal; }
+.overdue { color: #f66; } .stale { color: #f90; }
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Pre-rendered responses, swapped in on every refresh
+#[derive(Debug, Default, Clone)]
+pub struct Rendered {
+    pub html: String,
+    pub json: String,
+}
+
+pub type SharedRendered = Arc<RwLock<Rendered>>;
+
+/// Answer one connection
+pub async fn handle(mut stream: TcpStream, rendered: SharedRendered) -> Result<()> {
+    // Only the request line matters; headers and bodies are ignored
+    let mut buf = vec![0u8; 4096];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default().split('?').next().unwrap_or_default();
+
+    let (status, content_type, body) = {
+        let rendered = rendered.read().unwrap();
+        match (method, path) {
+            ("GET" | "HEAD", "/" | "/index.html") => ("200 OK", "text/html; charset=utf-8", rendered.html.clone()),
+            ("GET" | "HEAD", "/board.json") => ("200 OK", "application/json", rendered.json.clone()),
+            ("GET" | "HEAD", "/health") => ("200 OK", "application/json", "{\"status\":\"ok\"}".to_string()),
+            ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+            _ => ("405 Method Not Allowed", "text/plain", "Read-only\n".to_string()),
+        }
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn html_escapes_card_text() {
+        let card = testing::card("a", serde_json::json!({
+            "title": "<script>alert(1)</script>", "priority": "urgent", "column": "in_progress", "tags": ["a&b"],
+        }));
+        let snapshot = Snapshot::new(&[card], &Column::DEFAULTS);
+        assert_eq!(snapshot.columns[2].cards.len(), 1);
+
+        let html = snapshot.to_html(60);
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("<i>a&amp;b</i>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn html_escapes_server_column_names_and_fits_the_columns() {
+        let columns = [Column::Todo, Column::Other("<b>qa</b>".into()), Column::Done];
+        let html = Snapshot::new(&[], &columns).to_html(60);
+        assert!(html.contains("<h2>&lt;b&gt;qa&lt;/b&gt; <span>0</span></h2>"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("grid-template-columns: repeat(3, 1fr)"));
+    }
+}
@@ -0,0 +1,88 @@
+//! Resolving card ID prefixes to full IDs.
+//!
+//! Tables show 8-character IDs, so commands accept any unique prefix. Short
+//! input is matched against the card list (fetched once per command,
+//! archived cards included); full-length IDs are used as-is.
+
+use crate::client::Client;
+use crate::error::{BlazeError, Result};
+use crate::output::short_id;
+use crate::types::Card;
+use tokio::sync::OnceCell;
+
+/// Length of server-generated card IDs
+pub const FULL_ID_LEN: usize = 12;
+
+/// How many candidates to list in an ambiguity error
+const MAX_CANDIDATES: usize = 5;
+
+/// Resolves prefixes, caching the card list for the rest of the command
+#[derive(Default)]
+pub struct IdResolver {
+    cards: OnceCell<Vec<Card>>,
+}
+
+impl IdResolver {
+    /// Resolve `input` to exactly one card ID
+    pub async fn resolve(&self, client: &Client, input: &str) -> Result<String> {
+        if input.len() >= FULL_ID_LEN {
+            return Ok(input.to_string());
+        }
+
+        let cards = self
+            .cards
+            .get_or_try_init(|| client.list_cards(None, true))
+            .await?;
+        match_prefix(cards, input)
+    }
+}
+
+/// Pick the single card whose ID starts with `prefix`
+fn match_prefix(cards: &[Card], prefix: &str) -> Result<String> {
+    let matches: Vec<&Card> = cards.iter().filter(|c| c.id.starts_with(prefix)).collect();
+    match matches.as_slice() {
+        [] => Err(BlazeError::NotFound(prefix.to_string())),
+        [card] => Ok(card.id.clone()),
+        _ => {
+            let mut candidates: Vec<String> = matches
+                .iter()
+                .take(MAX_CANDIDATES)
+                .map(|c| format!("{} ({})", short_id(&c.id), c.title))
+                .collect();
+            if matches.len() > MAX_CANDIDATES {
+                candidates.push(format!("and {} more", matches.len() - MAX_CANDIDATES));
+            }
+            Err(BlazeError::AmbiguousId {
+                prefix: prefix.to_string(),
+                candidates: candidates.join(", "),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn card(id: &str, title: &str) -> Card {
+        testing::card(id, serde_json::json!({ "title": title }))
+    }
+
+    #[test]
+    fn prefixes_resolve_or_explain() {
+        let cards = vec![
+            card("84eb0f2a11b7", "Fix login"),
+            card("84eb9c0d3e21", "Fix logout"),
+            card("1f00aa000001", "Docs"),
+        ];
+
+        assert_eq!(match_prefix(&cards, "1f").unwrap(), "1f00aa000001");
+        assert_eq!(match_prefix(&cards, "84eb0").unwrap(), "84eb0f2a11b7");
+        assert_eq!(match_prefix(&cards, "ff").unwrap_err().to_string(), "Card not found: ff");
+        assert_eq!(
+            match_prefix(&cards, "84eb").unwrap_err().to_string(),
+            "Ambiguous card ID '84eb' matches: 84eb0f2a (Fix login), 84eb9c0d (Fix logout)"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Rhai scripts with bindings to the board.
+//!
+//! Scripts can only reach the board through the functions registered here
+//! (no file, process or network access):
+//!
+//! ```rhai
+//! // cards() / cards(query): active cards, optionally filtered like `list --filter`
+//! for card in cards("column:review AND priority:high,urgent") {
+//!     print(`${card.id}  ${card.title}`);
+//! }
+//! let card = card("84eb");                  // by ID or unique prefix
+//! update(card.id, #{ column: "done", tags: card.tags + ["reviewed"] });
+//! print(ARGS);                              // arguments after the script path
+//! ```
+//!
+//! The script runs on its own thread; each binding sends a [`Request`] to the
+//! command, which answers it with the shared client.
+
+use crate::error::{BlazeError, Result};
+use crate::types::{CardUpdate, CardUpdateBuilder, Column, Priority};
+use chrono::{DateTime, Utc};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde::Deserialize;
+use tokio::sync::{mpsc, oneshot};
+
+/// A board operation requested by a script
+#[derive(Debug)]
+pub enum Op {
+    /// Active cards, with an optional filter query
+    Cards(Option<String>),
+    /// One card by ID or prefix
+    Card(String),
+    /// Apply changes to a card
+    Update(String, Box<CardUpdate>),
+}
+
+/// An operation and where to send its result (a card or list of cards as JSON)
+pub struct Request {
+    pub op: Op,
+    pub reply: oneshot::Sender<std::result::Result<serde_json::Value, String>>,
+}
+
+/// The fields a script may change (same names as the card JSON)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptUpdate {
+    title: Option<String>,
+    description: Option<String>,
+    priority: Option<Priority>,
+    column: Option<Column>,
+    due_date: Option<DateTime<Utc>>,
+    tags: Option<Vec<String>>,
+    agent_assignable: Option<bool>,
+    estimate: Option<f64>,
+}
+
+/// Compile and run `source`, blocking until it finishes; call from a blocking thread
+pub fn run(source: &str, args: Vec<String>, requests: mpsc::UnboundedSender<Request>) -> Result<()> {
+    let engine = engine(requests);
+    let ast = engine.compile(source).map_err(|e| BlazeError::InvalidInput(format!("Script: {}", e)))?;
+
+    let mut scope = Scope::new();
+    scope.push_constant("ARGS", args.into_iter().map(Dynamic::from).collect::<Array>());
+    engine
+        .run_ast_with_scope(&mut scope, &ast)
+        .map_err(|e| BlazeError::Other(format!("Script failed: {}", e)))
+}
+
+fn engine(requests: mpsc::UnboundedSender<Request>) -> Engine {
+    let mut engine = Engine::new();
+
+    let tx = requests.clone();
+    engine.register_fn("cards", move || call(&tx, Op::Cards(None)));
+    let tx = requests.clone();
+    engine.register_fn("cards", move |query: &str| call(&tx, Op::Cards(Some(query.to_string()))));
+    let tx = requests.clone();
+    engine.register_fn("card", move |id: &str| call(&tx, Op::Card(id.to_string())));
+    let tx = requests;
+    engine.register_fn("update", move |id: &str, changes: Map| {
+        let update = to_update(changes)?;
+        call(&tx, Op::Update(id.to_string(), Box::new(update)))
+    });
+
+    engine
+}
+
+/// Send an operation to the command and wait for the answer
+fn call(requests: &mpsc::UnboundedSender<Request>, op: Op) -> std::result::Result<Dynamic, Box<EvalAltResult>> {
+    let (reply, answer) = oneshot::channel();
+    requests
+        .send(Request { op, reply })
+        .map_err(|_| "blaze is no longer answering requests".to_string())?;
+    let value = answer
+        .blocking_recv()
+        .map_err(|_| "blaze dropped the request".to_string())??;
+    rhai::serde::to_dynamic(value)
+}
+
+fn to_update(changes: Map) -> std::result::Result<CardUpdate, Box<EvalAltResult>> {
+    let value: serde_json::Value = rhai::serde::from_dynamic(&Dynamic::from_map(changes))?;
+    let fields: ScriptUpdate = serde_json::from_value(value).map_err(|e| format!("update: {}", e))?;
+    CardUpdateBuilder::default()
+        .title(fields.title)
+        .description(fields.description)
+        .priority(fields.priority)
+        .column(fields.column)
+        .due_date(fields.due_date)
+        .tags(fields.tags)
+        .agent_assignable(fields.agent_assignable)
+        .estimate(fields.estimate)
+        .build()
+        .map_err(|e| format!("update: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_round_trip_through_requests() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
+        let answer = std::thread::spawn(move || {
+            let mut seen = Vec::new();
+            while let Some(req) = rx.blocking_recv() {
+                let reply = match &req.op {
+                    Op::Cards(query) => Ok(serde_json::json!([{ "id": "a1", "title": query }])),
+                    Op::Card(id) => Ok(serde_json::json!({ "id": id, "tags": ["x"] })),
+                    Op::Update(_, update) => Ok(serde_json::to_value(update).unwrap()),
+                };
+                seen.push(format!("{:?}", req.op));
+                let _ = req.reply.send(reply);
+            }
+            seen
+        });
+
+        let script = r#"
+            let list = cards("tag:x");
+            if list[0].title != "tag:x" { throw "query not passed"; }
+            let c = card(ARGS[0]);
+            let updated = update(c.id, #{ column: "done", tags: c.tags + ["y"] });
+            if updated.tags != ["x", "y"] { throw "bad update"; }
+        "#;
+        run(script, vec!["abc".into()], tx).unwrap();
+        let seen = answer.join().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert!(seen[2].contains("Done"));
+
+        let (tx, _rx) = mpsc::unbounded_channel::<Request>();
+        let err = run(r#"update("a1", #{ colour: "red" })"#, vec![], tx).unwrap_err();
+        assert!(err.to_string().contains("unknown field `colour`"), "{err}");
+    }
+}
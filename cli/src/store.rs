@@ -0,0 +1,496 @@
+//! Local card store for working without the server.
+//!
+//! Every card the server sends back is kept in ~/.config/blaze/store.sqlite3
+//! (per server URL), so reads can be answered locally when the server is
+//! unreachable or `--offline` is given. Mutations made with `--offline` are
+//! queued together with the card's `updated_at` at that moment; `blaze sync`
+//! replays them and reports a conflict when the card changed on the server
+//! in the meantime.
+
+use crate::client::Page;
+use crate::config::Config;
+use crate::daemon::RawResponse;
+use crate::error::{BlazeError, Result};
+use crate::types::{Card, Column, Priority};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use rayon::prelude::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS cards (
+        server TEXT NOT NULL,
+        id TEXT NOT NULL,
+        archived INTEGER NOT NULL,
+        body TEXT NOT NULL,
+        PRIMARY KEY (server, id)
+    );
+    CREATE TABLE IF NOT EXISTS synced (
+        server TEXT PRIMARY KEY,
+        at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS queue (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        server TEXT NOT NULL,
+        method TEXT NOT NULL,
+        path TEXT NOT NULL,
+        body TEXT,
+        card_id TEXT,
+        base_updated_at TEXT,
+        queued_at TEXT NOT NULL
+    );
+";
+
+/// A mutation waiting for `blaze sync`
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedRequest {
+    pub seq: i64,
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// Card the request changes, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<String>,
+    /// The card's `updated_at` when the request was queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_updated_at: Option<DateTime<Utc>>,
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Cached cards and queued mutations for one server
+pub struct Store {
+    conn: Mutex<Connection>,
+    server: String,
+}
+
+impl Store {
+    /// Get the store file path
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|d| d.join("store.sqlite3"))
+    }
+
+    /// Open (or create) the store, scoped to `server`
+    pub fn open(server: &str) -> Result<Self> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+
+        let conn = Connection::open(Self::path().unwrap())?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            server: server.to_string(),
+        })
+    }
+
+    /// When the full card list was last fetched
+    pub fn synced_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let at: Option<String> = conn
+            .query_row("SELECT at FROM synced WHERE server = ?1", [&self.server], |r| r.get(0))
+            .optional()?;
+        Ok(at.and_then(|at| at.parse().ok()))
+    }
+
+    /// Keep whatever a successful response says about cards
+    pub fn observe(&self, method: &str, path: &str, body: &str) -> Result<()> {
+        let (route, query) = split_query(path);
+        if route == "/api/cards" && method == "GET" {
+            let page = Page::parse(serde_json::from_str(body)?)?;
+            // Only a whole, unfiltered list proves that cards missing from it are gone
+            let partial = ["page", "cursor", "limit", "priority", "tags", "overdue", "updated_since"]
+                .iter()
+                .any(|p| query_param(query, p).is_some());
+            if partial || page.next.is_some() {
+                return self.upsert_all(&page.items);
+            }
+            return self.replace_list(&page.items, query);
+        }
+
+        match card_route(route) {
+            Some((id, "")) if method == "DELETE" => {
+                let conn = self.conn.lock().unwrap();
+                conn.execute("DELETE FROM cards WHERE server = ?1 AND id = ?2", params![self.server, id])?;
+            }
+            Some((id, _)) => {
+                // Comments, threads and the like live under the card's route too
+                if let Ok(card) = serde_json::from_str::<Value>(body) {
+                    if card["id"] == id {
+                        let conn = self.conn.lock().unwrap();
+                        self.upsert(&conn, &card)?;
+                    }
+                }
+            }
+            None if route == "/api/cards" && method == "POST" => {
+                let card: Value = serde_json::from_str(body)?;
+                let conn = self.conn.lock().unwrap();
+                self.upsert(&conn, &card)?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Keep the cards of one page of a longer list (the server sent a Link to the next one)
+    pub fn observe_page(&self, path: &str, body: &str) -> Result<()> {
+        if split_query(path).0 != "/api/cards" {
+            return Ok(());
+        }
+        self.upsert_all(&Page::parse(serde_json::from_str(body)?)?.items)
+    }
+
+    fn upsert_all(&self, cards: &[Value]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for card in cards {
+            self.upsert(&tx, card)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store a card list, dropping cached cards the list proves are gone
+    fn replace_list(&self, cards: &[Value], query: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let column = query_param(query, "column");
+        if column.is_none() {
+            if query_param(query, "include_archived") == Some("true") {
+                tx.execute("DELETE FROM cards WHERE server = ?1", [&self.server])?;
+            } else {
+                tx.execute("DELETE FROM cards WHERE server = ?1 AND archived = 0", [&self.server])?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO synced (server, at) VALUES (?1, ?2)",
+                params![self.server, Utc::now().to_rfc3339()],
+            )?;
+        }
+        for card in cards {
+            self.upsert(&tx, card)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn upsert(&self, conn: &Connection, card: &Value) -> Result<()> {
+        let Some(id) = card.get("id").and_then(Value::as_str) else {
+            return Ok(());
+        };
+        let archived = card.get("archived").and_then(Value::as_bool).unwrap_or(false);
+        conn.execute(
+            "INSERT OR REPLACE INTO cards (server, id, archived, body) VALUES (?1, ?2, ?3, ?4)",
+            params![self.server, id, archived, card.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Answer a GET from the cache, like the server would (None if it can't)
+    pub fn answer(&self, path: &str) -> Result<Option<RawResponse>> {
+        let (route, query) = split_query(path);
+        let body = match route {
+            "/api/cards" => {
+                let archived = query_param(query, "include_archived") == Some("true");
+                let column = query_param(query, "column");
+                let cards: Vec<Value> = self
+                    .cards(archived)?
+                    .into_iter()
+                    .filter(|c| column.is_none_or(|col| c["column"] == col))
+                    .collect();
+                Value::Array(cards)
+            }
+            "/api/board/stats" => self.stats()?,
+            "/api/agent/ready" => {
+                let cards: Vec<Value> = self
+                    .cards(false)?
+                    .into_iter()
+                    .filter(|c| c["agent_assignable"] == true && c["agent_status"] == "ready")
+                    .collect();
+                Value::Array(cards)
+            }
+            _ => match card_route(route) {
+                Some((id, "")) => match self.card(id)? {
+                    Some(card) => card,
+                    None => return Ok(Some(not_cached(&format!("Card {} not found", id)))),
+                },
+                _ if route.starts_with("/api/") => return Ok(Some(not_cached("Not in the offline store"))),
+                _ => return Ok(None),
+            },
+        };
+        Ok(Some(RawResponse {
+            status: 200,
+            body: body.to_string(),
+            next_link: None,
+        }))
+    }
+
+    /// Cached cards in board order
+    fn cards(&self, include_archived: bool) -> Result<Vec<Value>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT body FROM cards WHERE server = ?1 AND (?2 OR archived = 0)
+             ORDER BY json_extract(body, '$.position'), json_extract(body, '$.created_at')",
+        )?;
+        let rows = stmt.query_map(params![self.server, include_archived], |r| r.get::<_, String>(0))?;
+        let mut cards = Vec::new();
+        for row in rows {
+            cards.push(serde_json::from_str(&row?)?);
+        }
+        Ok(cards)
+    }
+
+    /// Cached unarchived cards, decoded in parallel
+    pub fn active_cards(&self) -> Result<Vec<Card>> {
+        Ok(self
+            .cards(false)?
+            .into_par_iter()
+            .filter_map(|c| serde_json::from_value(c).ok())
+            .collect())
+    }
+
+    fn card(&self, id: &str) -> Result<Option<Value>> {
+        let conn = self.conn.lock().unwrap();
+        let body: Option<String> = conn
+            .query_row(
+                "SELECT body FROM cards WHERE server = ?1 AND id = ?2",
+                params![self.server, id],
+                |r| r.get(0),
+            )
+            .optional()?;
+        Ok(match body {
+            Some(body) => Some(serde_json::from_str(&body)?),
+            None => None,
+        })
+    }
+
+    /// The server's `/api/board/stats`, computed from cached cards
+    fn stats(&self) -> Result<Value> {
+        let all = self.cards(true)?;
+        let archived_count = all.iter().filter(|c| c["archived"] == true).count();
+        let active: Vec<Card> = all
+            .into_iter()
+            .filter(|c| c["archived"] != true)
+            .filter_map(|c| serde_json::from_value(c).ok())
+            .collect();
+
+        let mut by_column: BTreeMap<String, usize> =
+            Column::DEFAULTS.iter().map(|c| (c.to_string(), 0)).collect();
+        let mut by_priority: BTreeMap<String, usize> =
+            Priority::value_variants().iter().map(|p| (p.to_string(), 0)).collect();
+        let mut overdue_count = 0;
+        for card in &active {
+            *by_column.entry(card.column.to_string()).or_default() += 1;
+            *by_priority.entry(card.priority.to_string()).or_default() += 1;
+            if card.column != Column::Done && card.due_date.is_some_and(|d| d < Utc::now()) {
+                overdue_count += 1;
+            }
+        }
+
+        Ok(json!({
+            "total_cards": active.len(),
+            "by_column": by_column,
+            "by_priority": by_priority,
+            "overdue_count": overdue_count,
+            "archived_count": archived_count,
+        }))
+    }
+
+    /// Queue a mutation for `blaze sync`
+    pub fn enqueue(&self, method: &str, path: &str, body: Option<&Value>) -> Result<QueuedRequest> {
+        let card_id = card_route(split_query(path).0).map(|(id, _)| id.to_string());
+        let base_updated_at = match card_id {
+            Some(ref id) => self
+                .card(id)?
+                .and_then(|c| c.get("updated_at").and_then(Value::as_str).and_then(|t| t.parse().ok())),
+            None => None,
+        };
+        let queued_at = Utc::now();
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO queue (server, method, path, body, card_id, base_updated_at, queued_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.server,
+                method,
+                path,
+                body.map(|b| b.to_string()),
+                card_id,
+                base_updated_at.map(|t: DateTime<Utc>| t.to_rfc3339()),
+                queued_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(QueuedRequest {
+            seq: conn.last_insert_rowid(),
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.cloned(),
+            card_id,
+            base_updated_at,
+            queued_at,
+        })
+    }
+
+    /// Queued mutations, oldest first
+    pub fn queued(&self) -> Result<Vec<QueuedRequest>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT seq, method, path, body, card_id, base_updated_at, queued_at
+             FROM queue WHERE server = ?1 ORDER BY seq",
+        )?;
+        let rows = stmt.query_map([&self.server], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, Option<String>>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, Option<String>>(5)?,
+                r.get::<_, String>(6)?,
+            ))
+        })?;
+
+        let mut queued = Vec::new();
+        for row in rows {
+            let (seq, method, path, body, card_id, base, queued_at) = row?;
+            queued.push(QueuedRequest {
+                seq,
+                method,
+                path,
+                body: body.map(|b| serde_json::from_str(&b)).transpose()?,
+                card_id,
+                base_updated_at: base.and_then(|t| t.parse().ok()),
+                queued_at: queued_at.parse().unwrap_or_else(|_| Utc::now()),
+            });
+        }
+        Ok(queued)
+    }
+
+    /// Remove a queued mutation; returns true if it existed
+    pub fn dequeue(&self, seq: i64) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute("DELETE FROM queue WHERE server = ?1 AND seq = ?2", params![self.server, seq])?;
+        Ok(removed > 0)
+    }
+}
+
+fn not_cached(detail: &str) -> RawResponse {
+    RawResponse {
+        status: 404,
+        body: json!({ "detail": detail }).to_string(),
+        next_link: None,
+    }
+}
+
+fn split_query(path: &str) -> (&str, &str) {
+    path.split_once('?').unwrap_or((path, ""))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v)
+}
+
+/// `/api/cards/<id>/rest` as `(id, "/rest")`
+fn card_route(route: &str) -> Option<(&str, &str)> {
+    let rest = route.strip_prefix("/api/cards/")?;
+    match rest.find('/') {
+        Some(i) => Some((&rest[..i], &rest[i..])),
+        None => Some((rest, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        Store {
+            conn: Mutex::new(conn),
+            server: "http://test".into(),
+        }
+    }
+
+    fn card(id: &str, column: &str, archived: bool) -> Value {
+        json!({
+            "id": id,
+            "title": id,
+            "priority": "medium",
+            "column": column,
+            "tags": [],
+            "archived": archived,
+            "created_at": "2026-02-02T09:00:00Z",
+            "updated_at": "2026-02-04T09:00:00Z",
+        })
+    }
+
+    fn answer(store: &Store, path: &str) -> Value {
+        serde_json::from_str(&store.answer(path).unwrap().unwrap().body).unwrap()
+    }
+
+    #[test]
+    fn lists_replace_and_mutations_update_the_cache() {
+        let store = store();
+        let all = json!([card("a", "todo", false), card("b", "done", false), card("c", "done", true)]);
+        store.observe("GET", "/api/cards?include_archived=true", &all.to_string()).unwrap();
+
+        // A later active-only list drops active cards that are gone but keeps archived ones
+        let active = json!([card("a", "todo", false)]);
+        store.observe("GET", "/api/cards", &active.to_string()).unwrap();
+        assert_eq!(answer(&store, "/api/cards").as_array().unwrap().len(), 1);
+        assert_eq!(answer(&store, "/api/cards?include_archived=true").as_array().unwrap().len(), 2);
+
+        store.observe("PATCH", "/api/cards/a/move", &card("a", "review", false).to_string()).unwrap();
+        assert_eq!(answer(&store, "/api/cards?column=review")[0]["id"], "a");
+        assert_eq!(answer(&store, "/api/board/stats")["by_column"]["review"], 1);
+
+        store.observe("DELETE", "/api/cards/a", "").unwrap();
+        assert_eq!(store.answer("/api/cards/a").unwrap().unwrap().status, 404);
+        assert!(store.answer("/health").unwrap().is_none());
+    }
+
+    #[test]
+    fn responses_about_other_things_under_a_card_are_not_cached_as_cards() {
+        let store = store();
+        store.observe("GET", "/api/cards", &json!([card("a", "todo", false)]).to_string()).unwrap();
+
+        let comment = json!({
+            "id": "c1",
+            "card_id": "a",
+            "author": "sam",
+            "body": "LGTM",
+            "created_at": "2026-02-05T09:00:00Z",
+        });
+        store.observe("POST", "/api/cards/a/comments", &comment.to_string()).unwrap();
+        let cards = answer(&store, "/api/cards");
+        assert_eq!(cards.as_array().unwrap().len(), 1);
+        assert_eq!(cards[0]["id"], "a");
+        assert_eq!(store.answer("/api/cards/c1").unwrap().unwrap().status, 404);
+    }
+
+    #[test]
+    fn queue_records_the_base_version() {
+        let store = store();
+        store.observe("GET", "/api/cards/a", &card("a", "todo", false).to_string()).unwrap();
+
+        let queued = store.enqueue("PATCH", "/api/cards/a/move", Some(&json!({"column": "done"}))).unwrap();
+        assert_eq!(queued.card_id.as_deref(), Some("a"));
+        assert_eq!(queued.base_updated_at.unwrap().to_rfc3339(), "2026-02-04T09:00:00+00:00");
+        store.enqueue("POST", "/api/cards", Some(&json!({"title": "new"}))).unwrap();
+
+        let seqs: Vec<i64> = store.queued().unwrap().iter().map(|q| q.seq).collect();
+        assert_eq!(seqs.len(), 2);
+        assert!(store.dequeue(seqs[0]).unwrap());
+        assert_eq!(store.queued().unwrap().len(), 1);
+    }
+}
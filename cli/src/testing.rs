@@ -0,0 +1,17 @@
+//! Fixtures shared by the unit tests.
+
+use crate::types::Card;
+use serde_json::{json, Value};
+
+/// A card titled `id`: medium priority, in Todo, created and updated
+/// 2026-01-01 10:00 UTC, with the keys of `fields` (card JSON) on top
+pub fn card(id: &str, fields: Value) -> Card {
+    let mut card = json!({
+        "id": id, "title": id, "description": null, "priority": "medium", "column": "todo",
+        "due_date": null, "tags": [], "created_at": "2026-01-01T10:00:00Z", "updated_at": "2026-01-01T10:00:00Z",
+    });
+    if let Value::Object(fields) = fields {
+        card.as_object_mut().unwrap().extend(fields);
+    }
+    serde_json::from_value(card).unwrap()
+}
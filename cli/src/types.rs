@@ -1,9 +1,17 @@
 //! Type definitions matching the Blaze API.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Maximum title length accepted by the API
+pub const MAX_TITLE_LEN: usize = 200;
+/// Maximum description length accepted by the API
+pub const MAX_DESCRIPTION_LEN: usize = 2000;
 
 /// Card priority levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
@@ -27,7 +35,18 @@ impl fmt::Display for Priority {
 }
 
 impl Priority {
+    /// Weight in urgency totals; each level counts double the one below
+    pub fn weight(&self) -> u32 {
+        match self {
+            Priority::Low => 1,
+            Priority::Medium => 2,
+            Priority::High => 4,
+            Priority::Urgent => 8,
+        }
+    }
+
     /// Get colored emoji representation
+    #[allow(dead_code)]
     pub fn emoji(&self) -> &'static str {
         match self {
             Priority::Low => "🟢",
@@ -61,6 +80,7 @@ impl fmt::Display for AgentStatus {
 
 impl AgentStatus {
     /// Get status emoji
+    #[allow(dead_code)]
     pub fn emoji(&self) -> &'static str {
         match self {
             AgentStatus::Ready => "🟢",
@@ -78,16 +98,58 @@ pub struct AgentProgressEntry {
     pub message: String,
 }
 
-/// Board columns (workflow stages)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
-#[serde(rename_all = "snake_case")]
-#[clap(rename_all = "snake_case")]
+/// Board columns (workflow stages).
+///
+/// The five built-in columns are variants; servers with renamed or extra
+/// columns send other names, which are kept as `Other` (see
+/// `Client::columns` for the server's list).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Column {
     Backlog,
     Todo,
     InProgress,
     Review,
     Done,
+    /// A column only some servers have, by its API name
+    Other(String),
+}
+
+impl Column {
+    /// The columns of a board that doesn't list its own
+    pub const DEFAULTS: [Column; 5] = [
+        Column::Backlog,
+        Column::Todo,
+        Column::InProgress,
+        Column::Review,
+        Column::Done,
+    ];
+
+    /// Position in the default workflow; other columns sort after it
+    pub fn rank(&self) -> usize {
+        Self::DEFAULTS.iter().position(|c| c == self).unwrap_or(Self::DEFAULTS.len())
+    }
+
+    /// Human-readable display name ("In Progress", or "Qa Signoff" for `qa_signoff`)
+    pub fn display_name(&self) -> Cow<'static, str> {
+        match self {
+            Column::Backlog => "Backlog".into(),
+            Column::Todo => "Todo".into(),
+            Column::InProgress => "In Progress".into(),
+            Column::Review => "Review".into(),
+            Column::Done => "Done".into(),
+            Column::Other(name) => name
+                .split('_')
+                .filter(|w| !w.is_empty())
+                .map(|w| {
+                    let mut chars = w.chars();
+                    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+                .into(),
+        }
+    }
 }
 
 impl fmt::Display for Column {
@@ -98,23 +160,49 @@ impl fmt::Display for Column {
             Column::InProgress => write!(f, "in_progress"),
             Column::Review => write!(f, "review"),
             Column::Done => write!(f, "done"),
+            Column::Other(name) => write!(f, "{}", name),
         }
     }
 }
 
-impl Column {
-    /// Human-readable display name
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            Column::Backlog => "Backlog",
-            Column::Todo => "Todo",
-            Column::InProgress => "In Progress",
-            Column::Review => "Review",
-            Column::Done => "Done",
+/// A column name as the API sends it; anything unknown is another column
+impl From<String> for Column {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "backlog" => Column::Backlog,
+            "todo" => Column::Todo,
+            "in_progress" => Column::InProgress,
+            "review" => Column::Review,
+            "done" => Column::Done,
+            _ => Column::Other(name),
         }
     }
 }
 
+impl From<Column> for String {
+    fn from(column: Column) -> Self {
+        column.to_string()
+    }
+}
+
+/// A column name as typed: any case, with spaces or dashes for underscores
+/// ("In Progress", "in-progress"). Whether the board has it is checked later.
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        let name: String = input
+            .trim()
+            .chars()
+            .map(|c| if c == ' ' || c == '-' { '_' } else { c.to_ascii_lowercase() })
+            .collect();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid column name '{}'", input));
+        }
+        Ok(Column::from(name))
+    }
+}
+
 /// Full card model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
@@ -129,6 +217,24 @@ pub struct Card {
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
     pub position: i32,
+    /// IDs of cards that must be done before this one can start
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    /// Size of the work, in whatever unit the team uses (points, days)
+    #[serde(default)]
+    pub estimate: Option<f64>,
+    /// When the card moved into its current column
+    #[serde(default)]
+    pub entered_column_at: Option<DateTime<Utc>>,
+    /// IDs of cards waiting on this one (filled in by `blaze show`, not stored)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<String>,
+    /// When `blaze maintenance expire` may close or archive the card (e.g. a time-boxed spike)
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Who is working on the card (a name, not checked against any user list)
+    #[serde(default)]
+    pub assignee: Option<String>,
     // Agent workflow fields
     #[serde(default)]
     pub agent_assignable: bool,
@@ -140,6 +246,63 @@ pub struct Card {
     #[serde(default)]
     pub acceptance_checked: Vec<bool>,
     pub blocked_reason: Option<String>,
+    /// Checklist of smaller steps, separate from the acceptance criteria
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    /// External links (CI runs, docs, designs)
+    #[serde(default)]
+    pub links: Vec<CardLink>,
+    /// Comments, oldest first (filled in by `blaze show --comments`, not stored)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    /// Attached files (filled in by `blaze show`, not stored)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Review threads, oldest first (filled in by `blaze show`, not stored)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threads: Vec<Thread>,
+}
+
+/// What an external link points at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Ci,
+    Docs,
+    Design,
+    Issue,
+    #[default]
+    Other,
+}
+
+impl fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkKind::Ci => write!(f, "ci"),
+            LinkKind::Docs => write!(f, "docs"),
+            LinkKind::Design => write!(f, "design"),
+            LinkKind::Issue => write!(f, "issue"),
+            LinkKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// An external link on a card
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardLink {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub kind: LinkKind,
+}
+
+/// One checklist item on a card
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subtask {
+    pub title: String,
+    #[serde(default)]
+    pub done: bool,
 }
 
 /// Request body for creating a card
@@ -154,6 +317,22 @@ pub struct CardCreate {
     pub due_date: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub agent_assignable: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub acceptance_criteria: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 /// Request body for updating a card
@@ -167,10 +346,424 @@ pub struct CardUpdate {
     pub priority: Option<Priority>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub column: Option<Column>,
+    /// `Some(None)` is sent as `null`, which clears the due date
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub due_date: Option<DateTime<Utc>>,
+    pub due_date: Option<Option<DateTime<Utc>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_assignable: Option<bool>,
+    /// Replaces all criteria (the server resets their checked state)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acceptance_criteria: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_by: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// `Some(None)` is sent as `null`, which unassigns the card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<Option<String>>,
+    /// Replaces the whole checklist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtasks: Option<Vec<Subtask>>,
+    /// Replaces all external links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<CardLink>>,
+}
+
+/// Validation failures detected before a request is sent
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("title is required")]
+    MissingTitle,
+
+    #[error("title must be at most {max} characters (got {len})")]
+    TitleTooLong { len: usize, max: usize },
+
+    #[error("description must be at most {max} characters (got {len})")]
+    DescriptionTooLong { len: usize, max: usize },
+
+    #[error("tags must not be empty")]
+    EmptyTag,
+
+    #[error("estimate must be a non-negative number (got {0})")]
+    InvalidEstimate(String),
+
+    #[error("assignee must not be empty")]
+    EmptyAssignee,
+
+    #[error("no fields to update; specify at least one option")]
+    NoChanges,
+
+    #[error("cannot move plan from {from} to {to}: {hint}")]
+    PlanTransition {
+        from: PlanStatus,
+        to: PlanStatus,
+        hint: String,
+    },
+}
+
+fn validate_title(title: &str) -> Result<(), ValidationError> {
+    let len = title.trim().chars().count();
+    if len == 0 {
+        return Err(ValidationError::MissingTitle);
+    }
+    if len > MAX_TITLE_LEN {
+        return Err(ValidationError::TitleTooLong { len, max: MAX_TITLE_LEN });
+    }
+    Ok(())
+}
+
+fn validate_description(description: &str) -> Result<(), ValidationError> {
+    let len = description.chars().count();
+    if len > MAX_DESCRIPTION_LEN {
+        return Err(ValidationError::DescriptionTooLong { len, max: MAX_DESCRIPTION_LEN });
+    }
+    Ok(())
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.iter().any(|t| t.trim().is_empty()) {
+        return Err(ValidationError::EmptyTag);
+    }
+    Ok(())
+}
+
+fn validate_estimate(estimate: f64) -> Result<(), ValidationError> {
+    if !estimate.is_finite() || estimate < 0.0 {
+        return Err(ValidationError::InvalidEstimate(estimate.to_string()));
+    }
+    Ok(())
+}
+
+fn validate_assignee(assignee: &str) -> Result<(), ValidationError> {
+    if assignee.trim().is_empty() {
+        return Err(ValidationError::EmptyAssignee);
+    }
+    Ok(())
+}
+
+/// End of day (UTC) `days` from today
+#[allow(dead_code)]
+fn end_of_day_in(days: i64) -> DateTime<Utc> {
+    let date = (Utc::now() + Duration::days(days)).date_naive();
+    Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap())
+}
+
+impl CardCreate {
+    /// Start building a new card (defaults: todo, medium priority)
+    pub fn builder() -> CardCreateBuilder {
+        CardCreateBuilder::default()
+    }
+}
+
+/// Builder for [`CardCreate`]
+///
+/// Deserializes from the same fields as `CardCreate` (all optional but the
+/// title), so batch input gets the same defaults and validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CardCreateBuilder {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    priority: Option<Priority>,
+    #[serde(default)]
+    column: Option<Column>,
+    #[serde(default, deserialize_with = "crate::parsers::deserialize_due_date")]
+    due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    agent_assignable: bool,
+    #[serde(default)]
+    acceptance_criteria: Vec<String>,
+    #[serde(default)]
+    blocked_by: Vec<String>,
+    #[serde(default)]
+    estimate: Option<f64>,
+    #[serde(default, deserialize_with = "crate::parsers::deserialize_due_date")]
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    assignee: Option<String>,
+}
+
+// Not every setter is used by the commands; the full set is kept for library use
+#[allow(dead_code)]
+impl CardCreateBuilder {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn maybe_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn column(mut self, column: Column) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn due_date(mut self, due: DateTime<Utc>) -> Self {
+        self.due_date = Some(due);
+        self
+    }
+
+    pub fn maybe_due_date(mut self, due: Option<DateTime<Utc>>) -> Self {
+        self.due_date = due;
+        self
+    }
+
+    /// Due at the end of the day (UTC), `days` from today
+    pub fn due_in_days(self, days: i64) -> Self {
+        self.due_date(end_of_day_in(days))
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    pub fn tags<I, S>(self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        tags.into_iter().fold(self, |b, t| b.tag(t))
+    }
+
+    /// Let agents pick up the card
+    pub fn agent_assignable(mut self, assignable: bool) -> Self {
+        self.agent_assignable = assignable;
+        self
+    }
+
+    pub fn criterion(mut self, criterion: impl Into<String>) -> Self {
+        self.acceptance_criteria.push(criterion.into());
+        self
+    }
+
+    pub fn criteria<I, S>(self, criteria: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        criteria.into_iter().fold(self, |b, c| b.criterion(c))
+    }
+
+    /// Declare a card that must be done first
+    pub fn blocked_by(mut self, card_id: impl Into<String>) -> Self {
+        let card_id = card_id.into();
+        if !self.blocked_by.contains(&card_id) {
+            self.blocked_by.push(card_id);
+        }
+        self
+    }
+
+    pub fn estimate(mut self, estimate: Option<f64>) -> Self {
+        self.estimate = estimate;
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn assignee(mut self, assignee: Option<String>) -> Self {
+        self.assignee = assignee;
+        self
+    }
+
+    /// Validate and build the request body
+    pub fn build(self) -> Result<CardCreate, ValidationError> {
+        let title = self.title.ok_or(ValidationError::MissingTitle)?;
+        validate_title(&title)?;
+        if let Some(ref desc) = self.description {
+            validate_description(desc)?;
+        }
+        validate_tags(&self.tags)?;
+        if let Some(estimate) = self.estimate {
+            validate_estimate(estimate)?;
+        }
+        if let Some(ref assignee) = self.assignee {
+            validate_assignee(assignee)?;
+        }
+
+        Ok(CardCreate {
+            title,
+            description: self.description,
+            priority: self.priority.unwrap_or(Priority::Medium),
+            column: self.column.unwrap_or(Column::Todo),
+            due_date: self.due_date,
+            tags: self.tags,
+            agent_assignable: self.agent_assignable,
+            acceptance_criteria: self.acceptance_criteria,
+            blocked_by: self.blocked_by,
+            estimate: self.estimate,
+            expires_at: self.expires_at,
+            assignee: self.assignee,
+        })
+    }
+}
+
+impl CardUpdate {
+    /// Start building a partial card update
+    pub fn builder() -> CardUpdateBuilder {
+        CardUpdateBuilder::default()
+    }
+
+    /// True if the update would not change anything
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.priority.is_none()
+            && self.column.is_none()
+            && self.due_date.is_none()
+            && self.tags.is_none()
+            && self.agent_assignable.is_none()
+            && self.acceptance_criteria.is_none()
+            && self.blocked_by.is_none()
+            && self.estimate.is_none()
+            && self.expires_at.is_none()
+            && self.assignee.is_none()
+            && self.subtasks.is_none()
+            && self.links.is_none()
+    }
+}
+
+/// Builder for [`CardUpdate`]
+#[derive(Debug, Default)]
+pub struct CardUpdateBuilder {
+    update: CardUpdate,
+}
+
+impl CardUpdateBuilder {
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.update.title = title;
+        self
+    }
+
+    pub fn description(mut self, description: Option<String>) -> Self {
+        self.update.description = description;
+        self
+    }
+
+    pub fn priority(mut self, priority: Option<Priority>) -> Self {
+        self.update.priority = priority;
+        self
+    }
+
+    pub fn column(mut self, column: Option<Column>) -> Self {
+        self.update.column = column;
+        self
+    }
+
+    pub fn due_date(mut self, due: Option<DateTime<Utc>>) -> Self {
+        self.update.due_date = due.map(Some);
+        self
+    }
+
+    /// Remove the due date (overrides [`due_date`](Self::due_date) when `clear` is true)
+    pub fn clear_due_date(mut self, clear: bool) -> Self {
+        if clear {
+            self.update.due_date = Some(None);
+        }
+        self
+    }
+
+    pub fn tags(mut self, tags: Option<Vec<String>>) -> Self {
+        self.update.tags = tags;
+        self
+    }
+
+    pub fn agent_assignable(mut self, assignable: Option<bool>) -> Self {
+        self.update.agent_assignable = assignable;
+        self
+    }
+
+    pub fn acceptance_criteria(mut self, criteria: Option<Vec<String>>) -> Self {
+        self.update.acceptance_criteria = criteria;
+        self
+    }
+
+    pub fn blocked_by(mut self, card_ids: Option<Vec<String>>) -> Self {
+        self.update.blocked_by = card_ids;
+        self
+    }
+
+    pub fn estimate(mut self, estimate: Option<f64>) -> Self {
+        self.update.estimate = estimate;
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.update.expires_at = expires_at;
+        self
+    }
+
+    pub fn subtasks(mut self, subtasks: Option<Vec<Subtask>>) -> Self {
+        self.update.subtasks = subtasks;
+        self
+    }
+
+    pub fn links(mut self, links: Option<Vec<CardLink>>) -> Self {
+        self.update.links = links;
+        self
+    }
+
+    pub fn assignee(mut self, assignee: Option<String>) -> Self {
+        self.update.assignee = assignee.map(Some);
+        self
+    }
+
+    /// Unassign the card (overrides [`assignee`](Self::assignee) when `clear` is true)
+    pub fn unassign(mut self, clear: bool) -> Self {
+        if clear {
+            self.update.assignee = Some(None);
+        }
+        self
+    }
+
+    /// Validate and build the request body
+    pub fn build(self) -> Result<CardUpdate, ValidationError> {
+        let update = self.update;
+        if let Some(ref title) = update.title {
+            validate_title(title)?;
+        }
+        if let Some(ref desc) = update.description {
+            validate_description(desc)?;
+        }
+        if let Some(ref tags) = update.tags {
+            validate_tags(tags)?;
+        }
+        if let Some(estimate) = update.estimate {
+            validate_estimate(estimate)?;
+        }
+        if let Some(Some(ref assignee)) = update.assignee {
+            validate_assignee(assignee)?;
+        }
+        Ok(update)
+    }
 }
 
 /// Request body for moving a card
@@ -194,6 +787,110 @@ pub struct HealthResponse {
     pub status: String,
 }
 
+/// A change recorded by the server (`/api/events`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardEvent {
+    /// Event type, e.g. `card.created`, `card.moved`
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub card_id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Type-specific payload; moves carry `from` and `to` columns
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// Identity behind the API token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Identity {
+    pub identity: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+// --- Comment types ---
+
+/// A comment on a card (`/api/cards/{id}/comments`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: String,
+    #[serde(default)]
+    pub card_id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for adding a comment
+#[derive(Debug, Serialize)]
+pub struct CommentCreate {
+    pub body: String,
+    /// The server's token has no user behind it, so the client names the author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+// --- Thread types ---
+
+/// A review thread on a card (`/api/cards/{id}/threads`): a topic and its
+/// replies, open until someone resolves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thread {
+    pub id: String,
+    #[serde(default)]
+    pub card_id: String,
+    pub topic: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    /// Oldest first; the opening message, if any, is the first
+    #[serde(default)]
+    pub replies: Vec<Comment>,
+    #[serde(default)]
+    pub resolved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Request body for starting a thread
+#[derive(Debug, Serialize)]
+pub struct ThreadCreate {
+    pub topic: String,
+    /// Opening message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+/// Request body for resolving or reopening a thread
+#[derive(Debug, Serialize)]
+pub struct ThreadUpdate {
+    pub resolved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+}
+
+// --- Attachment types ---
+
+/// A file attached to a card (`/api/cards/{id}/attachments`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    /// Size in bytes
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploaded_at: Option<DateTime<Utc>>,
+    /// Hash of the stored content, if the server reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
 // --- Plan types ---
 
 /// Plan status levels
@@ -216,7 +913,39 @@ impl fmt::Display for PlanStatus {
 }
 
 impl PlanStatus {
+    /// Statuses directly reachable from this one.
+    ///
+    /// Plans move draft -> ready -> approved; a ready plan can go back to
+    /// draft, and an approved plan must be reopened as draft to change.
+    pub fn next_statuses(&self) -> &'static [PlanStatus] {
+        match self {
+            PlanStatus::Draft => &[PlanStatus::Ready],
+            PlanStatus::Ready => &[PlanStatus::Draft, PlanStatus::Approved],
+            PlanStatus::Approved => &[PlanStatus::Draft],
+        }
+    }
+
+    /// Check that a plan may move from this status to `to`
+    pub fn check_transition(&self, to: PlanStatus) -> Result<(), ValidationError> {
+        if *self == to || self.next_statuses().contains(&to) {
+            return Ok(());
+        }
+
+        // Every status is at most two steps from any other, so point at the step in between
+        let hint = match self.next_statuses().iter().find(|s| s.next_statuses().contains(&to)) {
+            Some(step) => format!("must be {} first (use plan edit --status {})", step, step),
+            None => format!("{} is not reachable from {}", to, self),
+        };
+
+        Err(ValidationError::PlanTransition {
+            from: *self,
+            to,
+            hint,
+        })
+    }
+
     /// Get status emoji
+    #[allow(dead_code)]
     pub fn emoji(&self) -> &'static str {
         match self {
             PlanStatus::Draft => "📝",
@@ -279,3 +1008,105 @@ pub struct PlanFileUpdate {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
 }
+
+// --- Saved views ---
+
+/// A named filter query, shared through `/api/views`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_create_builder_defaults() {
+        let card = CardCreate::builder()
+            .title("Fix login")
+            .tag("backend")
+            .tag("backend")
+            .due_in_days(3)
+            .build()
+            .unwrap();
+
+        assert_eq!(card.column, Column::Todo);
+        assert_eq!(card.priority, Priority::Medium);
+        assert_eq!(card.tags, vec!["backend"]);
+        assert_eq!(card.due_date.unwrap().date_naive(), (Utc::now() + Duration::days(3)).date_naive());
+    }
+
+    #[test]
+    fn card_create_builder_validation() {
+        assert_eq!(CardCreate::builder().build().unwrap_err(), ValidationError::MissingTitle);
+        assert_eq!(CardCreate::builder().title("  ").build().unwrap_err(), ValidationError::MissingTitle);
+        assert_eq!(
+            CardCreate::builder().title("x".repeat(201)).build().unwrap_err(),
+            ValidationError::TitleTooLong { len: 201, max: 200 }
+        );
+        assert_eq!(
+            CardCreate::builder().title("ok").tag("").build().unwrap_err(),
+            ValidationError::EmptyTag
+        );
+    }
+
+    #[test]
+    fn card_create_builder_from_json() {
+        let builder: CardCreateBuilder =
+            serde_json::from_str(r#"{"title": "Import", "column": "backlog", "due_date": "2026-03-01"}"#).unwrap();
+        let card = builder.build().unwrap();
+        assert_eq!(card.column, Column::Backlog);
+        assert_eq!(card.priority, Priority::Medium);
+        assert_eq!(card.due_date.unwrap().to_rfc3339(), "2026-03-01T23:59:59+00:00");
+
+        let typo = serde_json::from_str::<CardCreateBuilder>(r#"{"title": "x", "colum": "done"}"#);
+        assert!(typo.unwrap_err().to_string().starts_with("unknown field `colum`"));
+    }
+
+    #[test]
+    fn card_update_due_date_states() {
+        let body = |update: CardUpdate| serde_json::to_value(update).unwrap();
+        let due = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+        let set = CardUpdate::builder().due_date(Some(due)).build().unwrap();
+        assert_eq!(body(set), serde_json::json!({"due_date": "2026-03-01T12:00:00Z"}));
+        let cleared = CardUpdate::builder().clear_due_date(true).build().unwrap();
+        assert!(!cleared.is_empty());
+        assert_eq!(body(cleared), serde_json::json!({"due_date": null}));
+        let untouched = CardUpdate::builder().title(Some("x".into())).clear_due_date(false).build().unwrap();
+        assert_eq!(body(untouched), serde_json::json!({"title": "x"}));
+    }
+
+    #[test]
+    fn card_update_assignee_states() {
+        let body = |update: CardUpdate| serde_json::to_value(update).unwrap();
+
+        let set = CardUpdate::builder().assignee(Some("dana".into())).build().unwrap();
+        assert_eq!(body(set), serde_json::json!({"assignee": "dana"}));
+        let cleared = CardUpdate::builder().unassign(true).build().unwrap();
+        assert!(!cleared.is_empty());
+        assert_eq!(body(cleared), serde_json::json!({"assignee": null}));
+        let blank = CardUpdate::builder().assignee(Some(" ".into())).build();
+        assert_eq!(blank.unwrap_err(), ValidationError::EmptyAssignee);
+    }
+
+    #[test]
+    fn plan_status_transitions() {
+        assert!(PlanStatus::Draft.check_transition(PlanStatus::Ready).is_ok());
+        assert!(PlanStatus::Ready.check_transition(PlanStatus::Approved).is_ok());
+        assert!(PlanStatus::Approved.check_transition(PlanStatus::Approved).is_ok());
+
+        assert_eq!(
+            PlanStatus::Draft.check_transition(PlanStatus::Approved).unwrap_err().to_string(),
+            "cannot move plan from draft to approved: must be ready first (use plan edit --status ready)"
+        );
+        assert_eq!(
+            PlanStatus::Approved.check_transition(PlanStatus::Ready).unwrap_err().to_string(),
+            "cannot move plan from approved to ready: must be draft first (use plan edit --status draft)"
+        );
+    }
+}
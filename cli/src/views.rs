@@ -0,0 +1,123 @@
+//! Saved views (named filter queries).
+//!
+//! Views live in ~/.config/blaze/views.toml:
+//!
+//! ```toml
+//! [views.urgent-backend]
+//! query = "priority:urgent tag:backend"
+//! description = "What's on fire"
+//! ```
+//!
+//! `blaze view push` / `pull` share them through the server's `/api/views`.
+
+use crate::config::Config;
+use crate::error::{BlazeError, Result};
+use crate::parsers::Query;
+use crate::types::SavedView;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ViewDef {
+    query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+/// Local view storage
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ViewStore {
+    #[serde(default)]
+    views: BTreeMap<String, ViewDef>,
+}
+
+impl ViewStore {
+    /// Get the views file path
+    pub fn path() -> Option<PathBuf> {
+        Config::dir().map(|d| d.join("views.toml"))
+    }
+
+    /// Load views from disk (or return an empty store)
+    pub fn load() -> Result<Self> {
+        let path = match Self::path() {
+            Some(p) if p.exists() => p,
+            _ => return Ok(Self::default()),
+        };
+
+        let content = fs::read_to_string(&path).map_err(|e| {
+            BlazeError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Save views to disk
+    pub fn save(&self) -> Result<()> {
+        let dir = Config::dir().ok_or_else(|| BlazeError::Config("No config directory".into()))?;
+        fs::create_dir_all(&dir)?;
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| BlazeError::Config(format!("Failed to serialize views: {}", e)))?;
+        fs::write(Self::path().unwrap(), content)?;
+        Ok(())
+    }
+
+    /// Look up a view by name
+    pub fn get(&self, name: &str) -> Result<SavedView> {
+        self.views
+            .get(name)
+            .map(|def| to_view(name, def))
+            .ok_or_else(|| BlazeError::InvalidInput(format!("Unknown view '{}'", name)))
+    }
+
+    /// Parse the query of a named view
+    pub fn query(&self, name: &str) -> Result<Query> {
+        let view = self.get(name)?;
+        Query::parse(&view.query).map_err(|e| {
+            BlazeError::InvalidInput(format!("View '{}' has an invalid query: {}", name, e))
+        })
+    }
+
+    /// All views, sorted by name
+    pub fn all(&self) -> Vec<SavedView> {
+        self.views.iter().map(|(name, def)| to_view(name, def)).collect()
+    }
+
+    /// Add or replace a view; returns true if it changed
+    pub fn insert(&mut self, view: SavedView) -> bool {
+        let def = ViewDef {
+            query: view.query,
+            description: view.description,
+        };
+        self.views.insert(view.name, def.clone()) != Some(def)
+    }
+
+    /// Remove a view; returns true if it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.views.remove(name).is_some()
+    }
+}
+
+fn to_view(name: &str, def: &ViewDef) -> SavedView {
+    SavedView {
+        name: name.to_string(),
+        query: def.query.clone(),
+        description: def.description.clone(),
+    }
+}
+
+/// Validate a view name (used in URLs and as a TOML key)
+pub fn parse_view_name(input: &str) -> Result<String> {
+    let name = input.trim();
+    if name.is_empty() {
+        return Err(BlazeError::InvalidInput("View name must not be empty".into()));
+    }
+    if let Some(bad) = name.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_')) {
+        return Err(BlazeError::InvalidInput(format!(
+            "Invalid view name '{}': unexpected character '{}'",
+            name, bad
+        )));
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,103 @@
+//! Work-in-progress limits per column.
+//!
+//! Board-wide limits come from `[wip]` in config.toml, a team's from `wip`
+//! in `[teams.<name>]` (see README.md). `blaze board` shows each limited
+//! column as "count/limit", in red when it's over; `blaze move` and `done`
+//! check the target column first and refuse a move that would take it over
+//! (or only warn, per `enforce`). `--force` always lets the move through.
+
+use crate::config::WipConfig;
+use crate::error::{BlazeError, Result};
+use crate::types::Column;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What `blaze move` does about a move over the limit
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WipEnforcement {
+    /// Print a warning and move anyway
+    Warn,
+    /// Fail unless `--force` is given
+    #[default]
+    Refuse,
+}
+
+/// Limits by column, checked against the known columns
+#[derive(Debug, Default, Clone)]
+pub struct WipLimits(Vec<(Column, usize)>);
+
+impl WipLimits {
+    /// Parse limits keyed by column name; the error names the bad column
+    pub fn parse(limits: &BTreeMap<String, usize>) -> std::result::Result<Self, String> {
+        limits
+            .iter()
+            .map(|(name, limit)| {
+                let column: Column = name.parse().map_err(|e| format!("{} in wip", e))?;
+                Ok((column, *limit))
+            })
+            .collect::<std::result::Result<_, _>>()
+            .map(Self)
+    }
+
+    /// The board-wide limits from `[wip]`
+    pub fn from_config(config: &WipConfig) -> Result<Self> {
+        Self::parse(&config.limits).map_err(|e| BlazeError::Config(format!("[wip]: {}", e)))
+    }
+
+    /// Limit for `column`, if it has one
+    pub fn limit(&self, column: &Column) -> Option<usize> {
+        self.0.iter().find(|(c, _)| c == column).map(|(_, limit)| *limit)
+    }
+}
+
+/// Fail (or warn) if `incoming` more cards would take `column` past its limit
+pub fn check_move(
+    limits: &WipLimits,
+    enforce: WipEnforcement,
+    column: &Column,
+    count: usize,
+    incoming: usize,
+    force: bool,
+) -> Result<()> {
+    let Some(limit) = limits.limit(column) else {
+        return Ok(());
+    };
+    if incoming == 0 || count + incoming <= limit {
+        return Ok(());
+    }
+    let message = format!(
+        "{} would have {} card(s) (WIP limit {})",
+        column.display_name(),
+        count + incoming,
+        limit
+    );
+    if enforce == WipEnforcement::Refuse && !force {
+        return Err(BlazeError::InvalidInput(format!("{}; pass --force to move anyway", message)));
+    }
+    eprintln!("Warning: {}", message);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_past_the_limit() {
+        let limits = WipLimits::parse(&[("in_progress".to_string(), 3)].into()).unwrap();
+        assert_eq!(limits.limit(&Column::InProgress), Some(3));
+        assert_eq!(limits.limit(&Column::Review), None);
+
+        let refuse = WipEnforcement::Refuse;
+        assert!(check_move(&limits, refuse, &Column::InProgress, 2, 1, false).is_ok());
+        assert!(check_move(&limits, refuse, &Column::InProgress, 3, 1, false).is_err());
+        assert!(check_move(&limits, refuse, &Column::InProgress, 3, 1, true).is_ok());
+        // Already over, but nothing new arrives
+        assert!(check_move(&limits, refuse, &Column::InProgress, 5, 0, false).is_ok());
+        assert!(check_move(&limits, refuse, &Column::Review, 9, 1, false).is_ok());
+        assert!(check_move(&limits, WipEnforcement::Warn, &Column::InProgress, 3, 2, false).is_ok());
+
+        assert!(WipLimits::parse(&[("on hold!".to_string(), 3)].into()).is_err());
+    }
+}